@@ -14,11 +14,19 @@ use crate::{
         SchemaWithRefs, apply_schema_to_collection, collection_name_from_file_stem,
         config_for_missing_collection, parse_schema_for_load, read_schema_json_file,
     },
-    executor::plan_executor::{Executor, PlanExecutor},
+    executor::{
+        ddl_executor::{CreateCollectionExecutor, DropCollectionExecutor},
+        delete_executor::DeleteExecutor,
+        insert_executor::InsertExecutor,
+        plan_executor::{Executor, PlanExecutor},
+        random::Random,
+        update_executor::UpdateExecutor,
+    },
     parser::{
+        QueryParser,
         aggregators_helper::AggregateRegistry,
-        analyzer::{AnalysisContext, AnalyzerError},
-        ast::Query,
+        analyzer::{AnalysisContext, AnalyzedStatement, AnalyzerError, ScalarResolver},
+        ast::{Script, Statement},
     },
     planner::plan_builder::PlanBuilder,
 };
@@ -27,11 +35,18 @@ use crate::{
 pub(crate) type ProtectedDb = Arc<RwLock<InternalDb>>;
 
 /// Internal database holding configuration and named collections.
-#[derive(Default)]
 pub(crate) struct InternalDb {
     config: DbConfig,
     collections: HashMap<String, Arc<DbCollection>>,
     pub(crate) reference_manager: Arc<RwLock<DbReferences>>,
+    recursion_limit: usize,
+    random_seed: Option<u64>,
+}
+
+impl Default for InternalDb {
+    fn default() -> Self {
+        Self::new_db()
+    }
 }
 
 impl InternalDb {
@@ -49,6 +64,8 @@ impl InternalDb {
             config,
             collections: HashMap::new(),
             reference_manager: Arc::new(RwLock::new(DbReferences::default())),
+            recursion_limit: Db::DEFAULT_RECURSION_LIMIT,
+            random_seed: None,
         }
     }
 
@@ -435,6 +452,93 @@ impl Db {
         self.internal_db.read().unwrap().config.clone()
     }
 
+    /// Default for [`Db::recursion_limit`].
+    pub const DEFAULT_RECURSION_LIMIT: usize = 1000;
+
+    /// Maximum number of rounds a `WITH RECURSIVE` query may run before it fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::Db;
+    ///
+    /// let db = Db::new();
+    ///
+    /// assert_eq!(db.recursion_limit(), Db::DEFAULT_RECURSION_LIMIT);
+    /// ```
+    pub fn recursion_limit(&self) -> usize {
+        self.internal_db.read().unwrap().recursion_limit
+    }
+
+    /// Change the number of rounds a `WITH RECURSIVE` query may run.
+    ///
+    /// Queries that are still adding rows after `limit` rounds fail instead of
+    /// running unbounded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::{Db, DbConfig};
+    /// use serde_json::json;
+    ///
+    /// let db = Db::new_with_config(DbConfig::int("id"));
+    /// db.create("nodes").add_batch(json!([
+    ///     { "id": 1, "next": 2 },
+    ///     { "id": 2, "next": 3 },
+    ///     { "id": 3, "next": null }
+    /// ]));
+    /// db.set_recursion_limit(1);
+    ///
+    /// let walk = "WITH RECURSIVE chain AS (SELECT id, next FROM nodes WHERE id = 1 \
+    ///             UNION ALL SELECT n.id, n.next FROM nodes n JOIN chain c ON n.id = c.next) \
+    ///             SELECT id FROM chain";
+    /// assert!(db.query(walk).is_err());
+    ///
+    /// db.set_recursion_limit(10);
+    /// assert_eq!(db.query(walk).unwrap().len(), 3);
+    /// ```
+    pub fn set_recursion_limit(&self, limit: usize) {
+        self.internal_db.write().unwrap().recursion_limit = limit;
+    }
+
+    /// The seed `random()` starts from in every statement, if one was set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::Db;
+    ///
+    /// let db = Db::new();
+    ///
+    /// assert_eq!(db.random_seed(), None);
+    /// ```
+    pub fn random_seed(&self) -> Option<u64> {
+        self.internal_db.read().unwrap().random_seed
+    }
+
+    /// Make `random()` reproducible.
+    ///
+    /// With a seed, every statement replays the same sequence of values, which
+    /// keeps sampled fixtures stable across test runs. `None` goes back to an
+    /// unpredictable sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::{Db, DbConfig};
+    /// use serde_json::json;
+    ///
+    /// let db = Db::new_with_config(DbConfig::int("id"));
+    /// db.create("dice").add_batch(json!([{ "side": 1 }, { "side": 2 }]));
+    /// db.set_random_seed(Some(42));
+    ///
+    /// let roll = "SELECT random() AS r FROM dice";
+    /// assert_eq!(db.query(roll).unwrap(), db.query(roll).unwrap());
+    /// ```
+    pub fn set_random_seed(&self, seed: Option<u64>) {
+        self.internal_db.write().unwrap().random_seed = seed;
+    }
+
     /// Load multiple collections from a JSON object.
     ///
     /// The root value must be an object whose keys are collection names and
@@ -707,6 +811,28 @@ impl Db {
     /// Use this when the SQL has no positional parameters. The result is a
     /// vector of JSON object rows containing the selected fields.
     ///
+    /// `INSERT INTO collection (columns) VALUES (...)` and
+    /// `INSERT INTO collection SELECT ...` are also accepted. Inserted
+    /// documents follow the same id rules as [`DbCollection::add`] and the
+    /// result is a single `{ "affected_rows": n }` row.
+    ///
+    /// `UPDATE collection SET column = expr, ... [WHERE predicate]` replaces the
+    /// assigned fields of every row whose predicate is true, keeping the others,
+    /// and reports the affected row count the same way. `DELETE FROM collection [WHERE predicate]` removes the
+    /// matching rows; rows whose predicate is unknown because of `NULL` are
+    /// kept, as in standard SQL.
+    ///
+    /// `INSERT ... ON CONFLICT [(id)] DO NOTHING` skips rows whose id is
+    /// already stored, and `ON CONFLICT [(id)] DO UPDATE` merges them like
+    /// [`DbCollection::upsert`]; `SET column = expr, ...` replaces only those columns,
+    /// with expressions that see the stored row by collection name and the incoming
+    /// row as `excluded`.
+    ///
+    /// Any of these statements may end with `RETURNING *` or
+    /// `RETURNING expr [AS name], ...`. The result then holds one row per
+    /// written document, projected like a `SELECT` list, so generated ids and
+    /// the final document shape come back in the same call.
+    ///
     /// # Errors
     ///
     /// Returns an [`AnalyzerError`] when parsing, name resolution, planning,
@@ -738,20 +864,7 @@ impl Db {
     /// # }
     /// ```
     pub fn query(&self, sql: &str) -> Result<Vec<serde_json::Value>, AnalyzerError> {
-        // 1) Parse
-        let q =
-            Query::try_from(sql).map_err(|e| AnalyzerError::Other(format!("parse error: {e}")))?;
-
-        // 2) Analyze (Db implements SchemaProvider)
-        let aggregates = AggregateRegistry::default_aggregate_registry();
-        let analyzed = AnalysisContext::analyze_query(&q, self, &aggregates, Value::Null)?;
-
-        // 3) Plan
-        let plan = PlanBuilder::from_analyzed(&analyzed)?;
-
-        // 4) Execute
-        let exec = PlanExecutor::new(plan);
-        exec.execute(self)
+        self.execute_sql(sql, Value::Null)
     }
 
     /// Execute a SQL query through the parser, analyzer, planner and executor.
@@ -760,10 +873,16 @@ impl Db {
     /// value for one placeholder, or a JSON array for multiple placeholders.
     /// Arrays can also be used inside `IN (?)` predicates.
     ///
+    /// Named `:name` parameters read the keys of a JSON object, and numbered
+    /// `$1`, `$2`, ... parameters the elements of a JSON array. Both can appear
+    /// several times and bind the same value each time.
+    ///
     /// # Errors
     ///
     /// Returns an [`AnalyzerError`] when parsing, parameter binding, name
-    /// resolution, planning, or execution fails.
+    /// resolution, planning, or execution fails. Named and numbered parameters
+    /// without a value, or values no parameter uses, are reported together as
+    /// [`AnalyzerError::ParameterMismatch`].
     ///
     /// # Example
     ///
@@ -787,24 +906,150 @@ impl Db {
     ///     .map_err(|error| format!("{error:?}"))?;
     ///
     /// assert_eq!(rows.len(), 2);
+    ///
+    /// let rows = db
+    ///     .query_with_args(
+    ///         "SELECT name FROM people WHERE id >= :min AND id < :min + 1",
+    ///         json!({ "min": 2 })
+    ///     )
+    ///     .map_err(|error| format!("{error:?}"))?;
+    ///
+    /// assert_eq!(rows[0]["name"], "Grace");
     /// # Ok(())
     /// # }
     /// ```
     pub fn query_with_args(&self, sql: &str, args: Value) -> Result<Vec<Value>, AnalyzerError> {
+        self.execute_sql(sql, args)
+    }
+
+    /// Execute a `;`-separated SQL script, such as the contents of a `.sql` file.
+    ///
+    /// Every statement is parsed before any of them runs, so a syntax error
+    /// leaves the database untouched. Statements then execute in order and the
+    /// script stops at the first failing one; earlier statements stay applied.
+    /// `--` starts a comment that runs to the end of the line. Returns the
+    /// number of executed statements.
+    ///
+    /// Together with `CREATE COLLECTION` this bootstraps a whole database from
+    /// one script. Column types use the compact schema syntax (`Int`, `String!`,
+    /// ...), and an `Id`, `Uuid` or `None:Type` marker picks the collection id
+    /// field and id generation; without a marker the database config is used.
+    /// References between the created collections are inferred the same way as
+    /// for loaded schema files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AnalyzerError`] when the script does not parse or a
+    /// statement fails to analyze or execute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::Db;
+    ///
+    /// # fn main() -> Result<(), String> {
+    /// let db = Db::new();
+    /// let executed = db
+    ///     .execute_script(
+    ///         "
+    ///         -- schema
+    ///         CREATE COLLECTION users (user_id Id, name String!, age Int);
+    ///         CREATE COLLECTION orders (id Id, user_id Int!, total Float);
+    ///
+    ///         -- seed data
+    ///         INSERT INTO users (name, age) VALUES ('Ada', 37), ('Grace', 45);
+    ///         INSERT INTO orders (user_id, total) VALUES (1, 9.5);
+    ///         ",
+    ///     )
+    ///     .map_err(|error| format!("{error:?}"))?;
+    ///
+    /// assert_eq!(executed, 4);
+    /// assert!(db.get_collection_column_ref("orders", "user_id").is_some());
+    ///
+    /// let rows = db
+    ///     .query("SELECT user_id FROM users WHERE name = 'Grace'")
+    ///     .map_err(|error| format!("{error:?}"))?;
+    /// assert_eq!(rows[0]["user_id"], 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn execute_script(&self, script: &str) -> Result<usize, AnalyzerError> {
+        let statements =
+            Script::parse(script).map_err(|e| AnalyzerError::Other(format!("parse error: {e}")))?;
+
+        for statement in &statements {
+            self.execute_statement(statement, Value::Null)?;
+        }
+
+        Ok(statements.len())
+    }
+
+    /// Execute a SQL script file with [`Db::execute_script`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fosk::Db;
+    /// use std::ffi::OsString;
+    ///
+    /// let db = Db::new();
+    /// let status = db.execute_script_file(&OsString::from("bootstrap.sql"))?;
+    ///
+    /// println!("{status}");
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn execute_script_file(&self, file_path: &OsString) -> Result<String, String> {
+        let file_path_lossy = file_path.to_string_lossy();
+        let script = fs::read_to_string(file_path)
+            .map_err(|_| format!("Could not read script file {file_path_lossy}"))?;
+        let executed = self
+            .execute_script(&script)
+            .map_err(|error| format!("Script file {file_path_lossy} failed: {error:?}"))?;
+        Ok(format!(
+            "Executed {executed} statements from {file_path_lossy}"
+        ))
+    }
+
+    fn execute_sql(&self, sql: &str, args: Value) -> Result<Vec<Value>, AnalyzerError> {
         // 1) Parse
-        let q =
-            Query::try_from(sql).map_err(|e| AnalyzerError::Other(format!("parse error: {e}")))?;
+        let mut parser = QueryParser::new(sql);
+        let statement = Statement::parse(&mut parser)
+            .map_err(|e| AnalyzerError::Other(format!("parse error: {e}")))?;
+        ScalarResolver::check_named_parameters(&parser.parameters, &args)?;
+
+        self.execute_statement(&statement, args)
+    }
+
+    fn execute_statement(
+        &self,
+        statement: &Statement,
+        args: Value,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        Random::reseed(self.random_seed());
 
         // 2) Analyze (Db implements SchemaProvider)
         let aggregates = AggregateRegistry::default_aggregate_registry();
-        let analyzed = AnalysisContext::analyze_query(&q, self, &aggregates, args)?;
+        let analyzed = AnalysisContext::analyze_statement(statement, self, &aggregates, args)?;
 
-        // 3) Plan
-        let plan = PlanBuilder::from_analyzed(&analyzed)?;
+        match analyzed {
+            AnalyzedStatement::Select(analyzed) => {
+                // 3) Plan
+                let plan = PlanBuilder::from_analyzed(&analyzed)?;
 
-        // 4) Execute
-        let exec = PlanExecutor::new(plan);
-        exec.execute(self)
+                // 4) Execute
+                let exec = PlanExecutor::new(plan);
+                exec.execute(self)
+            }
+            AnalyzedStatement::Insert(insert) => InsertExecutor::new(insert).execute(self),
+            AnalyzedStatement::Update(update) => UpdateExecutor::new(update).execute(self),
+            AnalyzedStatement::Delete(delete) => DeleteExecutor::new(delete).execute(self),
+            AnalyzedStatement::CreateCollection(create) => {
+                CreateCollectionExecutor::new(create).execute(self)
+            }
+            AnalyzedStatement::DropCollection(drop) => {
+                DropCollectionExecutor::new(drop).execute(self)
+            }
+        }
     }
 
     /// Declare a bidirectional relationship between two collections.
@@ -993,7 +1238,7 @@ impl Db {
         Some(SchemaWithRefs::new(collection_name, &schema, self))
     }
 
-    fn infer_all_references(&self) -> usize {
+    pub(crate) fn infer_all_references(&self) -> usize {
         let names = self.list_collections();
         let mut inferred = 0;
         for collection_name in &names {
@@ -1023,6 +1268,7 @@ impl SchemaProvider for Db {
 mod tests {
     use super::*;
     use crate::database::{DbConfig, IdType};
+    use crate::parser::analyzer::{Temporal, TemporalResolver};
     use serde_json::json;
 
     fn add_batch_or_panic(
@@ -1175,330 +1421,1613 @@ mod tests {
     }
 
     #[test]
-    fn db_runner_supports_from_list_cross_join() {
+    fn db_runner_select_distinct_with_order_by_and_limit() {
         let db = mk_db();
-        // trivial cross join + count(*) just to exercise the path
-        let sql = r#"
-            SELECT COUNT(*) AS n
-            FROM t a, t b
-        "#;
-        let rows = db.query(sql).expect("query should succeed");
-        assert_eq!(rows.len(), 1);
-        // t has 5 rows -> t × t has 25 rows
-        assert_eq!(rows[0]["n"].as_i64().unwrap(), 25);
+
+        let rows = db
+            .query("SELECT DISTINCT cat FROM t ORDER BY cat DESC")
+            .expect("query should succeed");
+        assert_eq!(rows, vec![json!({ "cat": "b" }), json!({ "cat": "a" })]);
+
+        // LIMIT applies to the distinct rows, not to the scanned ones
+        let rows = db
+            .query("SELECT DISTINCT t.cat AS c FROM t ORDER BY c LIMIT 2")
+            .expect("query should succeed");
+        assert_eq!(rows, vec![json!({ "c": "a" }), json!({ "c": "b" })]);
+
+        let err = db.query("SELECT DISTINCT cat FROM t ORDER BY amt");
+        assert!(matches!(err, Err(AnalyzerError::Other(message)) if message.contains("DISTINCT")));
     }
 
     #[test]
-    fn db_runner_order_by_aggregate_not_projected() {
+    fn db_runner_select_distinct_over_aggregates_and_nulls() {
         let db = mk_db();
+        let t = db.get("t").unwrap();
+        t.add(json!({ "id": 6, "cat": "c", "amt": 7.5 })).unwrap();
+
         let rows = db
-            .query(
-                r#"
-                SELECT t.cat AS cat
-                FROM t
-                GROUP BY t.cat
-                ORDER BY SUM(t.amt) DESC
-            "#,
-            )
-            .unwrap();
+            .query("SELECT DISTINCT COUNT(*) AS n FROM t GROUP BY cat ORDER BY n")
+            .expect("query should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "n": 1 }), json!({ "n": 2 }), json!({ "n": 3 })]
+        );
 
-        assert_eq!(string_values(&rows, "cat"), vec!["a", "b"]);
-        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+        // NULLs compare equal, Int and Float by value
+        t.add(json!({ "id": 7, "cat": "c", "amt": null })).unwrap();
+        t.add(json!({ "id": 8, "cat": "c", "amt": 10 })).unwrap();
+        let rows = db
+            .query("SELECT DISTINCT amt FROM t ORDER BY amt")
+            .expect("query should succeed");
+        let amounts = rows
+            .iter()
+            .map(|row| row["amt"].as_f64())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            amounts,
+            vec![Some(7.5), Some(10.0), Some(15.0), Some(22.5), None]
+        );
+    }
+
+    fn mk_orders_payments_db() -> Db {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        add_batch_or_panic(
+            &db.create("orders"),
+            json!([
+                { "id": 1, "customer": "ada", "total": 30 },
+                { "id": 2, "customer": "grace", "total": 12 },
+                { "id": 3, "customer": "ada", "total": 8 }
+            ]),
+            3,
+        );
+        add_batch_or_panic(
+            &db.create("payments"),
+            json!([
+                { "id": 10, "order_id": 1, "payer": "ada", "amount": 30.0 },
+                { "id": 11, "order_id": 2, "payer": "linus", "amount": 5.5 },
+                { "id": 12, "order_id": 2, "payer": "grace", "amount": 6.5 }
+            ]),
+            3,
+        );
+        db
     }
 
     #[test]
-    fn db_runner_order_by_aggregate_not_projected_preserves_projection_alias() {
-        let db = mk_db();
+    fn db_runner_union_and_union_all_with_trailing_order_and_limit() {
+        let db = mk_orders_payments_db();
+
         let rows = db
             .query(
-                r#"
-                SELECT t.cat AS g
-                FROM t
-                GROUP BY t.cat
-                ORDER BY SUM(t.amt) DESC
-            "#,
+                "SELECT customer AS name FROM orders \
+                 UNION SELECT payer FROM payments \
+                 ORDER BY name",
             )
-            .unwrap();
+            .expect("union should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "ada" }),
+                json!({ "name": "grace" }),
+                json!({ "name": "linus" })
+            ]
+        );
 
-        assert_eq!(string_values(&rows, "g"), vec!["a", "b"]);
-        assert!(rows.iter().all(|row| row.get("cat").is_none()));
-        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+        let rows = db
+            .query(
+                "SELECT id, total FROM orders \
+                 UNION ALL SELECT order_id, amount FROM payments \
+                 ORDER BY 2 DESC, id LIMIT 3",
+            )
+            .expect("union all should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "total": 30 }),
+                json!({ "id": 1, "total": 30.0 }),
+                json!({ "id": 2, "total": 12 })
+            ]
+        );
     }
 
     #[test]
-    fn db_runner_global_count_star_over_empty_table_returns_one_zero_row() {
-        let db = mk_empty_agg_db();
-        let rows = db.query("SELECT COUNT(*) AS n FROM empty_table").unwrap();
+    fn db_runner_intersect_and_except() {
+        let db = mk_orders_payments_db();
 
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["n"], json!(0));
-    }
+        let rows = db
+            .query("SELECT id FROM orders INTERSECT SELECT order_id FROM payments ORDER BY id")
+            .expect("intersect should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 2 })]);
 
-    #[test]
-    fn db_runner_global_sum_over_empty_table_returns_one_null_row() {
-        let db = mk_empty_agg_db();
-        let rows = db.query("SELECT SUM(v) AS s FROM empty_table").unwrap();
+        let rows = db
+            .query("SELECT id FROM orders EXCEPT SELECT order_id FROM payments")
+            .expect("except should succeed");
+        assert_eq!(rows, vec![json!({ "id": 3 })]);
 
-        assert_eq!(rows.len(), 1);
-        assert!(rows[0]["s"].is_null());
+        // INTERSECT binds tighter: orders EXCEPT (payments INTERSECT {2})
+        let rows = db
+            .query(
+                "SELECT id FROM orders \
+                 EXCEPT SELECT order_id FROM payments \
+                 INTERSECT SELECT id FROM orders WHERE total < 20 \
+                 ORDER BY id",
+            )
+            .expect("compound should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
+
+        let err = db.query("SELECT id, total FROM orders UNION SELECT payer FROM payments");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("same number of columns"))
+        );
     }
 
     #[test]
-    fn db_runner_global_avg_min_max_over_empty_table_return_nulls() {
-        let db = mk_empty_agg_db();
-        let rows = db
-            .query("SELECT AVG(v) AS a, MIN(v) AS mn, MAX(v) AS mx FROM empty_table")
-            .unwrap();
+    fn db_runner_compound_query_as_subquery() {
+        let db = mk_orders_payments_db();
 
-        assert_eq!(rows.len(), 1);
-        assert!(rows[0]["a"].is_null());
-        assert!(rows[0]["mn"].is_null());
-        assert!(rows[0]["mx"].is_null());
+        let rows = db
+            .query(
+                "SELECT COUNT(*) AS n FROM \
+                 (SELECT customer AS name FROM orders UNION SELECT payer FROM payments) people",
+            )
+            .expect("compound subquery should succeed");
+        assert_eq!(rows, vec![json!({ "n": 3 })]);
     }
 
     #[test]
-    fn db_runner_empty_grouped_aggregate_returns_zero_rows() {
-        let db = mk_empty_agg_db();
+    fn db_runner_compound_query_numbers_parameters_across_blocks() {
+        let db = mk_db();
+
         let rows = db
-            .query("SELECT grp, COUNT(*) AS n FROM empty_table GROUP BY grp")
-            .unwrap();
+            .query_with_args(
+                "SELECT id FROM t WHERE id = ? UNION ALL SELECT id FROM t WHERE id = ? ORDER BY id",
+                json!([1, 3]),
+            )
+            .expect("each block should bind its own parameter");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
 
-        assert!(rows.is_empty());
+        let rows = db
+            .query_with_args(
+                "SELECT s.id FROM (SELECT id FROM t WHERE id > ?) s WHERE s.id < ? ORDER BY s.id",
+                json!([1, 4]),
+            )
+            .expect("a FROM subquery should continue the numbering");
+        assert_eq!(rows, vec![json!({ "id": 2 }), json!({ "id": 3 })]);
+
+        let err = db.query_with_args(
+            "SELECT id FROM t WHERE id = ? EXCEPT SELECT id FROM t WHERE id = ?",
+            json!([1]),
+        );
+        assert_eq!(err, Err(AnalyzerError::InvalidParameterValue));
     }
 
     #[test]
-    fn db_runner_multiple_sum_calls_receive_distinct_internal_names() {
-        let db = mk_phase2_agg_db();
+    fn db_runner_with_cte_used_in_from_and_join() {
+        let db = mk_orders_payments_db();
+
         let rows = db
-            .query("SELECT SUM(v) AS sv, SUM(w) AS sw FROM metrics")
-            .unwrap();
+            .query(
+                "WITH paid AS (SELECT order_id, SUM(amount) AS paid FROM payments GROUP BY order_id) \
+                 SELECT o.id, o.total, p.paid \
+                 FROM orders o LEFT JOIN paid p ON p.order_id = o.id \
+                 ORDER BY o.id",
+            )
+            .expect("cte query should succeed");
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["paid"].as_f64(), Some(30.0));
+        assert_eq!(rows[1]["paid"].as_f64(), Some(12.0));
+        assert_eq!(rows[2]["paid"], Value::Null);
 
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["sv"], json!(18.0));
-        assert_eq!(rows[0]["sw"], json!(10.0));
+        // the same CTE read twice under different aliases
+        let rows = db
+            .query(
+                "WITH big AS (SELECT id, customer FROM orders WHERE total > 10) \
+                 SELECT a.id AS left_id, b.id AS right_id \
+                 FROM big a JOIN big b ON a.id < b.id",
+            )
+            .expect("self-joined cte should succeed");
+        assert_eq!(rows, vec![json!({ "left_id": 1, "right_id": 2 })]);
     }
 
     #[test]
-    fn db_runner_aggregate_name_does_not_collide_with_group_key_named_sum() {
-        let db = mk_phase2_agg_db();
+    fn db_runner_with_cte_numbers_parameters_in_text_order() {
+        let db = mk_db();
+
         let rows = db
-            .query(
-                r#"
-                SELECT sum AS bucket, SUM(v) AS total
-                FROM metrics
-                GROUP BY sum
-                ORDER BY SUM(v) DESC
-            "#,
+            .query_with_args(
+                "WITH c AS (SELECT id FROM t WHERE id > ?) SELECT id FROM c WHERE id < ? ORDER BY id",
+                json!([1, 3]),
             )
-            .unwrap();
+            .expect("the CTE and the body should bind different parameters");
+        assert_eq!(rows, vec![json!({ "id": 2 })]);
 
-        assert_eq!(string_values(&rows, "bucket"), vec!["z", "y"]);
-        assert_eq!(rows[0]["total"], json!(13.0));
-        assert_eq!(rows[1]["total"], json!(5.0));
+        let rows = db
+            .query_with_args(
+                "WITH a AS (SELECT id FROM t WHERE id >= ?), b AS (SELECT id FROM a WHERE id <= ?) \
+                 SELECT id FROM b WHERE id <> ? ORDER BY id",
+                json!([2, 4, 3]),
+            )
+            .expect("each CTE should continue the numbering");
+        assert_eq!(rows, vec![json!({ "id": 2 }), json!({ "id": 4 })]);
     }
 
     #[test]
-    fn db_runner_having_can_use_hidden_aggregate() {
-        let db = mk_phase2_agg_db();
+    fn db_runner_with_chained_ctes_and_column_list() {
+        let db = mk_orders_payments_db();
+
         let rows = db
             .query(
-                r#"
-                SELECT grp
-                FROM metrics
-                GROUP BY grp
-                HAVING SUM(v) > 6
-                ORDER BY grp
-            "#,
+                "WITH spent (who, amount) AS (SELECT customer, SUM(total) FROM orders GROUP BY customer), \
+                 top AS (SELECT who FROM spent WHERE amount > 20) \
+                 SELECT s.who, s.amount FROM spent s JOIN top t ON t.who = s.who",
             )
-            .unwrap();
+            .expect("chained ctes should succeed");
+        assert_eq!(rows, vec![json!({ "who": "ada", "amount": 38 })]);
 
-        assert_eq!(string_values(&rows, "grp"), vec!["a"]);
-        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+        // CTEs shadow stored collections and reach nested subqueries and set operations
+        let rows = db
+            .query(
+                "WITH payments AS (SELECT customer AS payer FROM orders) \
+                 SELECT n.payer FROM (SELECT payer FROM payments) n \
+                 UNION SELECT payer FROM payments ORDER BY payer",
+            )
+            .expect("shadowing cte should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "payer": "ada" }), json!({ "payer": "grace" })]
+        );
+    }
+
+    fn mk_customers_db() -> Db {
+        let db = mk_orders_payments_db();
+        add_batch_or_panic(
+            &db.create("customers"),
+            json!([
+                { "id": 1, "name": "ada" },
+                { "id": 2, "name": "grace" },
+                { "id": 3, "name": "linus" }
+            ]),
+            3,
+        );
+        db
     }
 
     #[test]
-    fn db_runner_right_join_emits_unmatched_right_rows_with_null_left_fields() {
-        let db = mk_outer_join_db();
+    fn db_runner_exists_and_not_exists_with_correlated_columns() {
+        let db = mk_customers_db();
+
         let rows = db
             .query(
-                r#"
-                SELECT pe.name AS person, p.name AS pet
-                FROM people pe
-                RIGHT JOIN pets p ON pe.id = p.owner_id
-                ORDER BY p.id
-            "#,
+                "SELECT c.name FROM customers c \
+                 WHERE NOT EXISTS (SELECT o.id FROM orders o WHERE o.customer = c.name)",
             )
-            .unwrap();
+            .expect("not exists should succeed");
+        assert_eq!(rows, vec![json!({ "name": "linus" })]);
+
+        // unqualified outer column, nested two levels deep
+        let rows = db
+            .query(
+                "SELECT name FROM customers c WHERE EXISTS ( \
+                     SELECT o.id FROM orders o WHERE o.customer = name AND EXISTS ( \
+                         SELECT p.id FROM payments p WHERE p.order_id = o.id AND p.payer = c.name)) \
+                 ORDER BY name",
+            )
+            .expect("nested exists should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "name": "ada" }), json!({ "name": "grace" })]
+        );
 
+        // an inner alias shadows the outer one
+        let rows = db
+            .query(
+                "SELECT c.name FROM customers c \
+                 WHERE EXISTS (SELECT c.id FROM customers c WHERE c.id = 3) AND c.id < 3",
+            )
+            .expect("uncorrelated exists should succeed");
         assert_eq!(rows.len(), 2);
-        assert_eq!(rows[0]["person"], json!("Ada"));
-        assert_eq!(rows[0]["pet"], json!("Milo"));
-        assert!(rows[1]["person"].is_null());
-        assert_eq!(rows[1]["pet"], json!("Ghost"));
     }
 
     #[test]
-    fn db_runner_full_join_emits_matched_and_unmatched_rows() {
-        let db = mk_outer_join_db();
+    fn db_runner_exists_subquery_continues_parameter_numbering() {
+        let db = mk_db();
+        let sql = "SELECT id FROM t WHERE id = ? OR EXISTS ( \
+                   SELECT x.id FROM t x WHERE x.id = ? AND t.id = 3) ORDER BY id";
+
         let rows = db
-            .query(
-                r#"
-                SELECT pe.name AS person, p.name AS pet
-                FROM people pe
-                FULL JOIN pets p ON pe.id = p.owner_id
-                ORDER BY pe.id
-            "#,
-            )
-            .unwrap();
+            .query_with_args(sql, json!([1, 9]))
+            .expect("exists with parameters should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 })]);
 
-        assert_eq!(rows.len(), 3);
-        assert_eq!(rows[0]["person"], json!("Ada"));
-        assert_eq!(rows[0]["pet"], json!("Milo"));
-        assert_eq!(rows[1]["person"], json!("Bob"));
-        assert!(rows[1]["pet"].is_null());
-        assert!(rows[2]["person"].is_null());
-        assert_eq!(rows[2]["pet"], json!("Ghost"));
+        let rows = db
+            .query_with_args(sql, json!([1, 5]))
+            .expect("exists with parameters should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
     }
 
     #[test]
-    fn db_runner_order_by_non_projected_column() {
-        let db = mk_people_order_db();
-        let rows = db.query("SELECT name FROM people ORDER BY age").unwrap();
-        let names = string_values(&rows, "name");
-        let mut tied = names[1..3].to_vec();
-        tied.sort();
+    fn db_runner_exists_in_join_and_delete() {
+        let db = mk_customers_db();
 
-        assert_eq!(names.first().unwrap(), "Bob");
-        assert_eq!(names.last().unwrap(), "Ada");
-        assert_eq!(tied, vec!["Carla", "Grace"]);
-        assert!(
-            rows.iter().all(|row| row.get("age").is_none()),
-            "hidden sort key leaked"
+        let rows = db
+            .query(
+                "SELECT c.name, o.id FROM customers c LEFT JOIN orders o ON o.customer = c.name \
+                 AND EXISTS (SELECT p.id FROM payments p WHERE p.order_id = o.id) \
+                 ORDER BY c.name, o.id",
+            )
+            .expect("exists in join should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "ada", "id": 1 }),
+                json!({ "name": "grace", "id": 2 }),
+                json!({ "name": "linus", "id": null })
+            ]
         );
+
+        let deleted = db
+            .query(
+                "DELETE FROM customers \
+                 WHERE NOT EXISTS (SELECT o.id FROM orders o WHERE o.customer = customers.name)",
+            )
+            .expect("delete should succeed");
+        assert_eq!(deleted, vec![json!({ "affected_rows": 1 })]);
+        assert!(db.get("customers").unwrap().get("3").unwrap().is_none());
+
+        let err = db
+            .query("SELECT c.name FROM customers c WHERE EXISTS (SELECT o.id FROM orders o WHERE o.customer = x.name)")
+            .unwrap_err();
+        assert_eq!(err, AnalyzerError::UnknownCollection("x".into()));
     }
 
     #[test]
-    fn db_runner_order_by_non_projected_column_with_alias_desc() {
-        let db = mk_people_order_db();
+    fn db_runner_in_and_not_in_subqueries() {
+        let db = mk_customers_db();
+
         let rows = db
-            .query("SELECT name AS n FROM people ORDER BY age DESC")
-            .unwrap();
-        let names = string_values(&rows, "n");
-        let mut tied = names[1..3].to_vec();
-        tied.sort();
+            .query(
+                "SELECT c.name FROM customers c \
+                 WHERE c.name IN (SELECT o.customer FROM orders o WHERE o.total > 10) \
+                 ORDER BY c.name",
+            )
+            .expect("in subquery should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "name": "ada" }), json!({ "name": "grace" })]
+        );
 
-        assert_eq!(names.first().unwrap(), "Ada");
-        assert_eq!(names.last().unwrap(), "Bob");
-        assert_eq!(tied, vec!["Carla", "Grace"]);
+        let rows = db
+            .query("SELECT name FROM customers WHERE name NOT IN (SELECT customer FROM orders)")
+            .expect("not in subquery should succeed");
+        assert_eq!(rows, vec![json!({ "name": "linus" })]);
+
+        // a NULL in the subquery makes NOT IN unknown for every non-matching row
+        let rows = db
+            .query(
+                "SELECT name FROM customers WHERE name NOT IN ( \
+                     SELECT o.customer FROM customers x LEFT JOIN orders o ON o.customer = x.name)",
+            )
+            .expect("not in with null should succeed");
+        assert!(rows.is_empty());
+
+        // correlated, and usable in UPDATE criteria
+        let updated = db
+            .query(
+                "UPDATE customers SET name = 'ADA' WHERE id IN ( \
+                     SELECT x.id FROM customers x WHERE x.name = customers.name AND x.name = 'ada')",
+            )
+            .expect("update should succeed");
+        assert_eq!(updated, vec![json!({ "affected_rows": 1 })]);
+
+        let err = db
+            .query("SELECT name FROM customers WHERE id IN (SELECT id, total FROM orders)")
+            .unwrap_err();
         assert!(
-            rows.iter().all(|row| row.get("age").is_none()),
-            "hidden sort key leaked"
+            matches!(err, AnalyzerError::Other(message) if message.contains("only one column"))
         );
     }
 
     #[test]
-    fn db_runner_order_by_hidden_key_with_expression_projection() {
-        let db = mk_people_order_db();
+    fn db_runner_scalar_subqueries() {
+        let db = mk_customers_db();
+
         let rows = db
-            .query("SELECT UPPER(name) AS n FROM people ORDER BY age")
+            .query("SELECT id FROM orders WHERE total > (SELECT AVG(total) FROM orders)")
+            .expect("scalar subquery in where should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 })]);
+
+        // correlated in the projection; no rows yields NULL
+        let rows = db
+            .query(
+                "SELECT c.name, \
+                     (SELECT COUNT(*) FROM orders o WHERE o.customer = c.name) AS placed, \
+                     (SELECT o.total FROM orders o WHERE o.customer = c.name AND o.total > 20) AS big \
+                 FROM customers c ORDER BY c.name",
+            )
+            .expect("scalar subquery in projection should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "ada", "placed": 2, "big": 30 }),
+                json!({ "name": "grace", "placed": 1, "big": null }),
+                json!({ "name": "linus", "placed": 0, "big": null })
+            ]
+        );
+
+        db.query(
+            "UPDATE orders SET customer = (SELECT p.payer FROM payments p \
+             WHERE p.order_id = orders.id AND p.amount < 6) WHERE id = 2",
+        )
+        .expect("scalar subquery in update should succeed");
+        let rows = db
+            .query("SELECT customer FROM orders WHERE id = 2")
             .unwrap();
-        let names = string_values(&rows, "n");
-        let mut tied = names[1..3].to_vec();
-        tied.sort();
+        assert_eq!(rows, vec![json!({ "customer": "linus" })]);
 
-        assert_eq!(names.first().unwrap(), "BOB");
-        assert_eq!(names.last().unwrap(), "ADA");
-        assert_eq!(tied, vec!["CARLA", "GRACE"]);
+        let err = db
+            .query("SELECT id FROM orders WHERE total = (SELECT total FROM orders)")
+            .unwrap_err();
         assert!(
-            rows.iter().all(|row| row.get("age").is_none()),
-            "hidden sort key leaked"
+            matches!(err, AnalyzerError::Other(message) if message.contains("more than one row"))
+        );
+
+        let err = db
+            .query("SELECT (SELECT id, total FROM orders) AS pair FROM customers")
+            .unwrap_err();
+        assert!(
+            matches!(err, AnalyzerError::Other(message) if message.contains("only one column"))
         );
     }
 
     #[test]
-    fn db_runner_order_by_hidden_multi_key() {
-        let db = mk_people_order_db();
+    fn db_runner_subqueries_continue_parameter_numbering() {
+        let db = mk_db();
+
         let rows = db
-            .query("SELECT name FROM people ORDER BY age ASC, id DESC")
-            .unwrap();
+            .query_with_args(
+                "SELECT id FROM t WHERE id = ? OR id IN (SELECT id FROM t WHERE id = ?) ORDER BY id",
+                json!([1, 3]),
+            )
+            .expect("in subquery with parameters should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
 
-        assert_eq!(
-            string_values(&rows, "name"),
-            vec!["Bob", "Grace", "Carla", "Ada"]
-        );
-        assert!(rows.iter().all(|row| row.get("age").is_none()));
-        assert!(rows.iter().all(|row| row.get("id").is_none()));
+        let rows = db
+            .query_with_args(
+                "SELECT id FROM t WHERE id > ? AND amt < (SELECT amt FROM t WHERE id = ?) ORDER BY id",
+                json!([1, 5]),
+            )
+            .expect("scalar subquery with parameters should succeed");
+        assert_eq!(rows, vec![json!({ "id": 2 }), json!({ "id": 3 })]);
+
+        // the projection comes before WHERE in the text, so it binds first
+        let rows = db
+            .query_with_args(
+                "SELECT id, (SELECT cat FROM t x WHERE x.id = ?) AS other, ? AS tag \
+                 FROM t WHERE id = ?",
+                json!([3, "x", 1]),
+            )
+            .expect("parameters in the projection should bind first");
+        assert_eq!(rows, vec![json!({ "id": 1, "other": "b", "tag": "x" })]);
     }
 
     #[test]
-    fn db_runner_order_by_projected_position_still_works() {
-        let db = mk_people_order_db();
-        let rows = db.query("SELECT name FROM people ORDER BY 1").unwrap();
+    fn db_runner_case_when_in_projection_aggregates_and_group_by() {
+        let db = mk_orders_payments_db();
 
+        let rows = db
+            .query(
+                "SELECT id, CASE WHEN total >= 20 THEN 'big' WHEN total >= 10 THEN 'medium' END AS size, \
+                     CASE customer WHEN 'ada' THEN 1 ELSE 2.5 END AS tier \
+                 FROM orders ORDER BY id",
+            )
+            .expect("case in projection should succeed");
         assert_eq!(
-            string_values(&rows, "name"),
-            vec!["Ada", "Bob", "Carla", "Grace"]
+            rows,
+            vec![
+                json!({ "id": 1, "size": "big", "tier": 1 }),
+                json!({ "id": 2, "size": "medium", "tier": 2.5 }),
+                json!({ "id": 3, "size": null, "tier": 1 })
+            ]
         );
-    }
 
-    #[test]
-    fn db_runner_order_by_position_out_of_range_errors() {
-        let db = mk_people_order_db();
-        let err = db.query("SELECT name FROM people ORDER BY 2").unwrap_err();
-        let msg = format!("{err:?}").to_lowercase();
+        let rows = db
+            .query(
+                "SELECT SUM(CASE WHEN customer = 'ada' THEN total ELSE 0 END) AS ada_total, \
+                     COUNT(CASE WHEN total > 10 THEN 1 END) AS over_ten \
+                 FROM orders",
+            )
+            .expect("case inside aggregates should succeed");
+        assert_eq!(rows, vec![json!({ "ada_total": 38, "over_ten": 2 })]);
 
-        assert!(
-            msg.contains("order by position"),
-            "unexpected error: {err:?}"
+        let rows = db
+            .query(
+                "SELECT CASE WHEN total >= 10 THEN 'high' ELSE 'low' END AS band, COUNT(*) AS n \
+                 FROM orders \
+                 GROUP BY CASE WHEN total >= 10 THEN 'high' ELSE 'low' END \
+                 ORDER BY band",
+            )
+            .expect("group by case should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "band": "high", "n": 2 }),
+                json!({ "band": "low", "n": 1 })
+            ]
         );
-    }
 
-    #[test]
-    fn db_runner_order_by_missing_column_errors() {
-        let db = mk_people_order_db();
         let err = db
-            .query("SELECT name FROM people ORDER BY missing_column")
+            .query("SELECT customer FROM orders GROUP BY CASE WHEN total > 10 THEN 1 END")
             .unwrap_err();
-        let msg = format!("{err:?}").to_lowercase();
-
+        assert!(matches!(err, AnalyzerError::Other(_)));
+        let err = db
+            .query("SELECT CASE WHEN total > 10 THEN 'big' ELSE total END AS size FROM orders")
+            .expect_err("mismatched branch types should be rejected");
         assert!(
-            msg.contains("unknowncolumn") || msg.contains("missing_column"),
-            "unexpected error: {err:?}"
+            matches!(err, AnalyzerError::Other(message) if message.contains("cannot be matched"))
+        );
+    }
+
+    fn mk_order_items_db() -> Db {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        add_batch_or_panic(
+            &db.create("order_items"),
+            json!([
+                { "id": 1, "order_id": 1, "sku": "pen", "price": 2.5, "quantity": 4 },
+                { "id": 2, "order_id": 1, "sku": "ink", "price": 10.0, "quantity": 1 },
+                { "id": 3, "order_id": 2, "sku": "pad", "price": 3.0, "quantity": 0 }
+            ]),
+            3,
         );
+        db
     }
 
     #[test]
-    fn db_runner_selects_from_subquery_with_order_by() {
-        let db = mk_people_order_db();
+    fn db_runner_arithmetic_and_concatenation() {
+        let db = mk_order_items_db();
+
         let rows = db
             .query(
-                r#"
-                SELECT p.name
-                FROM (SELECT name FROM people) p
-                ORDER BY p.name
-            "#,
+                "SELECT id, price * quantity AS line_total, -quantity AS neg, \
+                     (price + 1) * 2 AS bumped, sku || '#' || id AS label \
+                 FROM order_items ORDER BY id",
             )
-            .unwrap();
+            .expect("arithmetic in projection should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "line_total": 10.0, "neg": -4, "bumped": 7.0, "label": "pen#1" }),
+                json!({ "id": 2, "line_total": 10.0, "neg": -1, "bumped": 22.0, "label": "ink#2" }),
+                json!({ "id": 3, "line_total": 0.0, "neg": 0, "bumped": 8.0, "label": "pad#3" })
+            ]
+        );
 
+        // integer division; division by zero yields NULL
+        let rows = db
+            .query("SELECT id, 10 / quantity AS per, quantity % 3 AS rest FROM order_items ORDER BY id")
+            .expect("division should succeed");
         assert_eq!(
-            string_values(&rows, "name"),
-            vec!["Ada", "Bob", "Carla", "Grace"]
+            rows,
+            vec![
+                json!({ "id": 1, "per": 2, "rest": 1 }),
+                json!({ "id": 2, "per": 10, "rest": 1 }),
+                json!({ "id": 3, "per": null, "rest": 0 })
+            ]
         );
-    }
 
-    #[test]
-    fn db_runner_select_star_from_subquery() {
-        let db = mk_people_order_db();
         let rows = db
             .query(
-                r#"
-                SELECT *
-                FROM (SELECT name, age FROM people WHERE id = 1) p
-            "#,
+                "SELECT order_id, SUM(price * quantity) AS total FROM order_items \
+                 WHERE quantity * 2 >= 0 GROUP BY order_id \
+                 ORDER BY SUM(price * quantity) - 1 DESC",
             )
-            .unwrap();
+            .expect("arithmetic in aggregates should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "order_id": 1, "total": 20.0 }),
+                json!({ "order_id": 2, "total": 0.0 })
+            ]
+        );
 
-        assert_eq!(rows.len(), 1);
+        let rows = db
+            .query(
+                "SELECT quantity % 2 AS parity, COUNT(*) AS n FROM order_items \
+                 GROUP BY quantity % 2 ORDER BY parity",
+            )
+            .expect("group by arithmetic should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "parity": 0, "n": 2 }),
+                json!({ "parity": 1, "n": 1 })
+            ]
+        );
+
+        let err = db
+            .query("SELECT sku + 1 AS bad FROM order_items")
+            .unwrap_err();
+        assert!(
+            matches!(err, AnalyzerError::Other(message) if message.contains("expects numeric operands"))
+        );
+    }
+
+    #[test]
+    fn db_runner_not_between_and_grouped_predicates() {
+        let db = mk_order_items_db();
+
+        let rows = db
+            .query("SELECT id FROM order_items WHERE quantity BETWEEN 1 AND 4 AND price < 5 ORDER BY id")
+            .expect("between should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 })]);
+
+        let rows = db
+            .query("SELECT id FROM order_items WHERE quantity NOT BETWEEN 1 AND 3 ORDER BY id")
+            .expect("not between should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
+
+        // AND binds tighter than OR, a leading group and NOT over a group
+        let rows = db
+            .query(
+                "SELECT id FROM order_items \
+                 WHERE (sku = 'pen' OR sku = 'pad') AND NOT (quantity = 0 OR price > 5) \
+                    OR sku NOT LIKE 'p%' AND id = 2 ORDER BY id",
+            )
+            .expect("grouped predicates should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 2 })]);
+
+        // NOT of an unknown comparison (division by zero) stays unknown and filters the row out
+        let rows = db
+            .query("SELECT id FROM order_items WHERE NOT price / (quantity - 1) > 3 ORDER BY id")
+            .expect("not should succeed");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 3 })]);
+    }
+
+    fn mk_profiles_db() -> Db {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        add_batch_or_panic(
+            &db.create("people"),
+            json!([
+                { "id": 1, "name": "Ana", "profile": { "city": "Paris", "zip": 75001 }, "tags": ["admin", "ops"],
+                  "items": [{ "sku": "pen" }] },
+                { "id": 2, "name": "Bo", "profile": { "city": "Lima" }, "tags": ["dev"], "items": [] },
+                { "id": 3, "name": "Cy", "profile": { "city": "Paris" }, "tags": [], "items": [{ "sku": "ink" }] },
+                { "id": 4, "name": "Di", "profile": {}, "tags": ["dev"], "items": [] }
+            ]),
+            4,
+        );
+        db
+    }
+
+    #[test]
+    fn db_runner_cast_functions() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        db.execute_script(
+            "CREATE COLLECTION accounts (id Id, name String!);
+             CREATE COLLECTION legacy (id Id, account String!, amount String);
+             INSERT INTO accounts (name) VALUES ('Ada'), ('Bob');
+             INSERT INTO legacy (account, amount) VALUES ('1', '10'), ('2', 'n/a'), ('9', '5')",
+        )
+        .expect("cast fixture should load");
+
+        // String and Int ids never compare equal until one side is cast
+        let rows = db
+            .query("SELECT a.name FROM legacy l JOIN accounts a ON l.account = a.id")
+            .expect("uncast join should succeed");
+        assert!(rows.is_empty());
+
+        let rows = db
+            .query(
+                "SELECT a.name, TRY_CAST(l.amount AS Int) AS amount, a.id::text AS ref \
+                 FROM legacy l JOIN accounts a ON CAST(l.account AS Int) = a.id ORDER BY a.name",
+            )
+            .expect("cast join should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "Ada", "amount": 10, "ref": "1" }),
+                json!({ "name": "Bob", "amount": null, "ref": "2" })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT id, l.amount::float * 2 AS doubled FROM legacy l \
+                 WHERE TRY_CAST(l.amount AS Int) IS NOT NULL ORDER BY id",
+            )
+            .expect("try_cast filter should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "doubled": 20.0 }),
+                json!({ "id": 3, "doubled": 10.0 })
+            ]
+        );
+
+        let rows = db
+            .query("SELECT TRY_CAST('n/a' AS Int) AS n FROM accounts WHERE id = 1")
+            .expect("try_cast of a bad constant should succeed");
+        assert_eq!(rows, vec![json!({ "n": null })]);
+
+        let err = db
+            .query("SELECT CAST('n/a' AS Int) AS n FROM accounts")
+            .expect_err("cast of a bad constant should be rejected");
+        assert!(matches!(err, AnalyzerError::Other(msg) if msg.contains("cannot cast")));
+
+        let rows = db
+            .query("SELECT 1::String AS s, 2.5::Int AS i FROM accounts WHERE id = 1::Int")
+            .expect("casts of numeric literals should succeed");
+        assert_eq!(rows, vec![json!({ "s": "1", "i": 3 })]);
+
+        // a row value that does not convert fails the query, unlike TRY_CAST
+        let err = db
+            .query("SELECT CAST(l.amount AS Int) AS amount FROM legacy l")
+            .expect_err("cast of a bad row value should fail");
+        assert!(matches!(err, AnalyzerError::Other(msg) if msg == "cannot cast \"n/a\" to Int"));
+        let err = db
+            .query("SELECT id FROM accounts WHERE CAST(name AS Date) IS NULL")
+            .expect_err("cast of a bad row value in WHERE should fail");
+        assert!(
+            matches!(err, AnalyzerError::Other(msg) if msg.starts_with("cannot cast") && msg.ends_with("to Date"))
+        );
+    }
+
+    fn mk_payments_db() -> Db {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        db.execute_script(
+            "CREATE COLLECTION payments (id Id, due Date!, paid_at DateTime, amount Int!);
+             INSERT INTO payments (due, paid_at, amount) VALUES
+                ('2024-01-31', '2024-01-30T22:00:00-05:00', 10),
+                ('2024-02-15', '2024-02-13T09:00:00Z', 20),
+                ('2024-02-29', NULL, 30),
+                ('2024-03-10', '2024-03-09T23:30:00+01:00', 40)",
+        )
+        .expect("payments fixture should load");
+        db
+    }
+
+    #[test]
+    fn db_runner_date_functions() {
+        let db = mk_payments_db();
+
+        // offsets make these sort differently from their text
+        let rows = db
+            .query(
+                "SELECT id FROM payments WHERE paid_at >= '2024-01-31T03:00:00Z' \
+                 ORDER BY paid_at DESC",
+            )
+            .expect("temporal comparisons should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "id": 4 }), json!({ "id": 2 }), json!({ "id": 1 })]
+        );
+
+        let rows = db
+            .query(
+                "SELECT date_trunc('month', due) AS month, COUNT(*) AS n, SUM(amount) AS total \
+                 FROM payments GROUP BY date_trunc('month', due) ORDER BY month",
+            )
+            .expect("grouping by month should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "month": "2024-01-01", "n": 1, "total": 10 }),
+                json!({ "month": "2024-02-01", "n": 2, "total": 50 }),
+                json!({ "month": "2024-03-01", "n": 1, "total": 40 })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT id, extract(month FROM due) AS m, date_add(due, 1, 'month') AS next_due, \
+                 date_diff('day', due, paid_at) AS early, to_char(paid_at, '%Y-%m-%d %H:%M') AS paid \
+                 FROM payments WHERE id <= 3 ORDER BY id",
+            )
+            .expect("date functions should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "m": 1, "next_due": "2024-02-29", "early": 0, "paid": "2024-01-31 03:00" }),
+                json!({ "id": 2, "m": 2, "next_due": "2024-03-15", "early": -1, "paid": "2024-02-13 09:00" }),
+                json!({ "id": 3, "m": 2, "next_due": "2024-03-29", "early": null, "paid": null })
+            ]
+        );
+
+        let rows = db
+            .query("SELECT MIN(paid_at) AS first, MAX(due) AS last FROM payments")
+            .expect("MIN/MAX over dates should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "first": "2024-01-30T22:00:00-05:00", "last": "2024-03-10" })]
+        );
+
+        let rows = db
+            .query("SELECT COUNT(*) AS n FROM payments WHERE due < date_add(now(), 1, 'day')")
+            .expect("now() should succeed");
+        assert_eq!(rows, vec![json!({ "n": 4 })]);
+
+        let err = db
+            .query("SELECT date_trunc('fortnight', due) FROM payments")
+            .expect_err("unknown units should be rejected");
+        assert!(matches!(err, AnalyzerError::Other(msg) if msg.contains("unknown date unit")));
+    }
+
+    #[test]
+    fn db_runner_temporal_columns_only_take_dates() {
+        let db = mk_payments_db();
+
+        for (sql, message) in [
+            (
+                "INSERT INTO payments (due, amount) VALUES ('not a date', 1)",
+                "cannot assign \"not a date\" to column payments.due of type Date",
+            ),
+            (
+                "INSERT INTO payments (due, amount) VALUES ('2024-02-30', 1)",
+                "cannot assign \"2024-02-30\" to column payments.due of type Date",
+            ),
+            (
+                "INSERT INTO payments (due, amount) VALUES ('2024-03-01T10:00:00Z', 1)",
+                "cannot assign \"2024-03-01T10:00:00Z\" to column payments.due of type Date",
+            ),
+            (
+                "UPDATE payments SET paid_at = 'soon'",
+                "cannot assign \"soon\" to column payments.paid_at of type DateTime",
+            ),
+        ] {
+            let err = db.query(sql).expect_err("invalid dates should be rejected");
+            assert_eq!(err, AnalyzerError::Other(message.into()), "{sql}");
+        }
+
+        // values computed from rows are checked when they are written
+        let err = db
+            .query("UPDATE payments SET due = to_char(due, '%d/%m/%Y') WHERE id = 1")
+            .expect_err("a row value that is not a date should be rejected");
+        assert_eq!(
+            err,
+            AnalyzerError::Other(
+                "cannot assign \"31/01/2024\" to column payments.due of type Date".into()
+            )
+        );
+        let err = db
+            .query("INSERT INTO payments (due, amount) SELECT to_char(due, '%d/%m/%Y'), 1 FROM payments")
+            .expect_err("a selected value that is not a date should be rejected");
+        assert!(matches!(err, AnalyzerError::Other(msg) if msg.starts_with("cannot assign")));
+
+        // a date-time written to a Date column keeps its date
+        let rows = db
+            .query("UPDATE payments SET due = CAST(paid_at AS String) WHERE id = 2 RETURNING due")
+            .expect("a date-time string from a row should be converted");
+        assert_eq!(rows, vec![json!({ "due": "2024-02-13" })]);
+        let rows = db
+            .query("INSERT INTO payments (due, amount) VALUES (now(), 50) RETURNING due, amount")
+            .expect("now() should be written to a Date column");
+        let due = rows[0]["due"].as_str().unwrap();
+        assert!(matches!(Temporal::parse(due), Some(Temporal::Date(_))));
+
+        let rows = db
+            .query("SELECT COUNT(*) AS n FROM payments")
+            .expect("count should succeed");
+        assert_eq!(rows, vec![json!({ "n": 5 })]);
+    }
+
+    #[test]
+    fn db_runner_order_by_mixed_date_and_text_strings() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        db.execute_script("CREATE COLLECTION notes (id Id, s String)")
+            .expect("collection should be created");
+        // digits sort dates between plain strings by text, and offsets reorder dates
+        let mut values = Vec::new();
+        for i in 0..10 {
+            values.push(format!("'2024-01-{:02}T0{}:00:00+05:00'", 10 + i, i % 3));
+            values.push(format!("'{}'", 1 + i * 331));
+            values.push(format!("'2024-01-{:02}'", 10 + i));
+        }
+        db.query(&format!(
+            "INSERT INTO notes (s) VALUES ({})",
+            values.join("), (")
+        ))
+        .expect("rows should be inserted");
+
+        let rows = db
+            .query("SELECT s FROM notes ORDER BY s")
+            .expect("mixed strings should sort");
+        let sorted = string_values(&rows, "s");
+        assert_eq!(sorted.len(), 30);
+        let (dates, text) = sorted.split_at(20);
+        assert!(
+            dates
+                .windows(2)
+                .all(|pair| TemporalResolver::order(&pair[0], &pair[1]).is_le())
+        );
+        assert_eq!(dates[0], "2024-01-10T00:00:00+05:00");
+        assert!(text.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(text[0], "1");
+
+        let rows = db
+            .query("SELECT s, row_number() OVER (ORDER BY s DESC) AS n FROM notes ORDER BY n")
+            .expect("mixed strings should sort inside a window");
+        let mut reversed = string_values(&rows, "s");
+        reversed.reverse();
+        assert_eq!(reversed, sorted);
+    }
+
+    #[test]
+    fn db_runner_math_functions() {
+        let db = mk_db();
+
+        let rows = db
+            .query(
+                "SELECT cat, round(avg(amt), 1) AS avg_amt, round(sum(amt) * 1.075, 2) AS taxed, \
+                 mod(sum(id), 4) AS bucket FROM t GROUP BY cat ORDER BY cat",
+            )
+            .expect("math over aggregates should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "cat": "a", "avg_amt": 15.8, "taxed": 51.06, "bucket": 0 }),
+                json!({ "cat": "b", "avg_amt": 7.5, "taxed": 8.06, "bucket": 3 })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT id, abs(id - 3) AS distance, sign(id - 3) AS side, floor(amt) AS whole, \
+                 greatest(amt, 12) AS at_least FROM t WHERE id >= 3 ORDER BY id",
+            )
+            .expect("row math should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 3, "distance": 0, "side": 0, "whole": 7.0, "at_least": 12 }),
+                json!({ "id": 4, "distance": 1, "side": 1, "whole": null, "at_least": 12 }),
+                json!({ "id": 5, "distance": 2, "side": 1, "whole": 22.0, "at_least": 22.5 })
+            ]
+        );
+
+        db.set_random_seed(Some(11));
+        let sample = "SELECT id, random() AS r FROM t ORDER BY id";
+        let first = db.query(sample).expect("random() should succeed");
+        assert_eq!(first, db.query(sample).unwrap());
+        assert!(
+            first
+                .iter()
+                .all(|row| (0.0..1.0).contains(&row["r"].as_f64().unwrap()))
+        );
+        assert_ne!(first[0]["r"], first[1]["r"]);
+    }
+
+    #[test]
+    fn db_runner_string_functions() {
+        let db = mk_people_order_db();
+
+        let rows = db
+            .query(
+                "SELECT concat(id, '-', upper(left(name, 2))) AS code, \
+                 strpos(name, 'a') AS first_a, replace(name, 'a', '_') AS masked \
+                 FROM people WHERE starts_with(name, 'G') = false ORDER BY id",
+            )
+            .expect("string functions should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "code": "1-AD", "first_a": 3, "masked": "Ad_" }),
+                json!({ "code": "2-BO", "first_a": 0, "masked": "Bob" }),
+                json!({ "code": "3-CA", "first_a": 2, "masked": "C_rl_" })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT substr(name, 2, 3) AS middle, split_part('x,y,z', ',', id) AS part, \
+                 regexp_replace(name, '[aeiou]', '*', 'g') AS masked FROM people \
+                 WHERE id > 2 ORDER BY id",
+            )
+            .expect("substr, split_part and regexp_replace should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "middle": "arl", "part": "z", "masked": "C*rl*" }),
+                json!({ "middle": "rac", "part": "", "masked": "Gr*c*" })
+            ]
+        );
+
+        let err = db
+            .query("SELECT lpad(id, 3, '0') FROM people")
+            .expect_err("lpad over an Int column should be rejected");
+        assert!(matches!(
+            err,
+            AnalyzerError::FunctionArgMismatch { expected, .. } if expected == "LPAD(string, length[, fill])"
+        ));
+
+        let err = db
+            .query("SELECT lpad(name, 1000000000000) AS padded FROM people")
+            .expect_err("padding to a huge length should fail");
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("limit")));
+
+        let err = db
+            .query("SELECT regexp_replace(name, '(a', '*') AS masked FROM people")
+            .expect_err("an invalid constant pattern should be rejected");
+        assert!(
+            matches!(err, AnalyzerError::Other(message) if message.contains("invalid regular expression"))
+        );
+        let err = db
+            .query("SELECT regexp_replace(name, 'a', '*', 'q') AS masked FROM people")
+            .expect_err("an unknown constant flag should be rejected");
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("unknown flag")));
+    }
+
+    #[test]
+    fn db_runner_json_functions() {
+        let db = mk_profiles_db();
+
+        let rows = db
+            .query(
+                "SELECT id, json_extract(profile, '$.city') AS city, json_array_length(tags) AS n_tags, \
+                 json_keys(profile) AS keys, json_type(items) AS kind FROM people ORDER BY id",
+            )
+            .expect("json inspection functions should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "city": "Paris", "n_tags": 2, "keys": ["city", "zip"], "kind": "array" }),
+                json!({ "id": 2, "city": "Lima", "n_tags": 1, "keys": ["city"], "kind": "array" }),
+                json!({ "id": 3, "city": "Paris", "n_tags": 0, "keys": ["city"], "kind": "array" }),
+                json!({ "id": 4, "city": null, "n_tags": 1, "keys": [], "kind": "array" })
+            ]
+        );
+
+        let rows = db
+            .query("SELECT name FROM people WHERE json_contains(tags, 'dev') = true ORDER BY name")
+            .expect("json_contains in WHERE should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Bo" }), json!({ "name": "Di" })]);
+
+        let rows = db
+            .query(
+                "SELECT json_object('name', name, 'city', profile.city) AS card, \
+                 json_array(id, name) AS pair FROM people WHERE id = 2",
+            )
+            .expect("json builders should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "card": { "name": "Bo", "city": "Lima" }, "pair": [2, "Bo"] })]
+        );
+
+        let err = db
+            .query("SELECT json_keys(name) FROM people")
+            .expect_err("json_keys over a string column should be rejected");
+        assert!(matches!(err, AnalyzerError::FunctionArgMismatch { .. }));
+    }
+
+    #[test]
+    fn db_runner_json_path_access() {
+        let db = mk_profiles_db();
+
+        let rows = db
+            .query(
+                "SELECT p.id, p.profile.city, tags[0] AS first_tag, items[0].sku, profile.zip + 1 AS next_zip \
+                 FROM people p ORDER BY p.id",
+            )
+            .expect("json paths in projection should succeed");
+        // missing keys and indexes read as NULL
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "city": "Paris", "first_tag": "admin", "sku": "pen", "next_zip": 75002 }),
+                json!({ "id": 2, "city": "Lima", "first_tag": "dev", "sku": null, "next_zip": null }),
+                json!({ "id": 3, "city": "Paris", "first_tag": null, "sku": "ink", "next_zip": null }),
+                json!({ "id": 4, "city": null, "first_tag": "dev", "sku": null, "next_zip": null })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT name FROM people WHERE profile.city = 'Paris' AND tags[1] IS NULL ORDER BY name",
+            )
+            .expect("json paths in WHERE should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Cy" })]);
+
+        let rows = db
+            .query(
+                "SELECT profile.city AS city, COUNT(*) AS n FROM people \
+                 WHERE profile.city IS NOT NULL GROUP BY profile.city ORDER BY profile.city",
+            )
+            .expect("json paths in GROUP BY should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "city": "Lima", "n": 1 }),
+                json!({ "city": "Paris", "n": 2 })
+            ]
+        );
+
+        // a path over an outer column inside a correlated subquery
+        let rows = db
+            .query(
+                "SELECT name FROM people p WHERE EXISTS (SELECT 1 FROM people q \
+                 WHERE q.profile.city = p.profile.city AND q.id <> p.id) ORDER BY name",
+            )
+            .expect("correlated json paths should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "name": "Ana" }), json!({ "name": "Cy" })]
+        );
+
+        let err = db
+            .query("SELECT name.first FROM people")
+            .expect_err("a path into a string column should be rejected");
+        assert!(
+            matches!(err, AnalyzerError::Other(msg) if msg.contains("cannot read path .first"))
+        );
+    }
+
+    #[test]
+    fn db_runner_unnest_expands_arrays() {
+        let db = mk_profiles_db();
+
+        // people without tags produce no rows
+        let rows = db
+            .query(
+                "SELECT p.name, t.tag FROM people p CROSS JOIN UNNEST(p.tags) AS t(tag) \
+                 ORDER BY p.id, t.tag",
+            )
+            .expect("CROSS JOIN UNNEST should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "Ana", "tag": "admin" }),
+                json!({ "name": "Ana", "tag": "ops" }),
+                json!({ "name": "Bo", "tag": "dev" }),
+                json!({ "name": "Di", "tag": "dev" })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT t.tag, COUNT(*) AS n FROM people p CROSS JOIN UNNEST(p.tags) t(tag) \
+                 GROUP BY t.tag ORDER BY t.tag",
+            )
+            .expect("grouping on array elements should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "tag": "admin", "n": 1 }),
+                json!({ "tag": "dev", "n": 2 }),
+                json!({ "tag": "ops", "n": 1 })
+            ]
+        );
+
+        // a FROM item works too, and elements can be read with JSON paths
+        let rows = db
+            .query("SELECT name FROM people p, UNNEST(p.items) i(item) WHERE i.item.sku = 'ink'")
+            .expect("UNNEST in FROM should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Cy" })]);
+
+        // without a column list the element is named after the alias
+        let rows = db
+            .query("SELECT COUNT(*) AS n FROM people, UNNEST(tags) tag WHERE tag = 'dev'")
+            .expect("UNNEST without a column list should succeed");
+        assert_eq!(rows, vec![json!({ "n": 2 })]);
+
+        let err = db
+            .query("SELECT name FROM people p CROSS JOIN UNNEST(p.name) AS n(letter)")
+            .expect_err("only arrays can be unnested");
+        assert!(
+            matches!(err, AnalyzerError::Other(msg) if msg.contains("UNNEST expects an array"))
+        );
+    }
+
+    fn mk_regions_db() -> Db {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        add_batch_or_panic(
+            &db.create("geo_regions"),
+            json!([
+                { "id": 1, "name": "Europe", "parent_id": null },
+                { "id": 2, "name": "Portugal", "parent_id": 1 },
+                { "id": 3, "name": "Porto", "parent_id": 2 },
+                { "id": 4, "name": "Matosinhos", "parent_id": 3 },
+                { "id": 5, "name": "Spain", "parent_id": 1 },
+                { "id": 6, "name": "Asia", "parent_id": null }
+            ]),
+            6,
+        );
+        db
+    }
+
+    #[test]
+    fn db_runner_with_recursive_walks_a_tree() {
+        let db = mk_regions_db();
+
+        let rows = db
+            .query(
+                "WITH RECURSIVE below (id, name, root) AS ( \
+                     SELECT id, name, name FROM geo_regions WHERE id = 2 \
+                     UNION ALL \
+                     SELECT g.id, g.name, b.root FROM geo_regions g JOIN below b ON g.parent_id = b.id \
+                 ) \
+                 SELECT name, root FROM below ORDER BY id",
+            )
+            .expect("recursive cte should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "Portugal", "root": "Portugal" }),
+                json!({ "name": "Porto", "root": "Portugal" }),
+                json!({ "name": "Matosinhos", "root": "Portugal" })
+            ]
+        );
+
+        // walk up from a leaf and join the result back to the collection
+        let rows = db
+            .query(
+                "WITH RECURSIVE up AS ( \
+                     SELECT id, parent_id FROM geo_regions WHERE name = 'Matosinhos' \
+                     UNION SELECT g.id, g.parent_id FROM geo_regions g JOIN up u ON g.id = u.parent_id \
+                 ) \
+                 SELECT g.name FROM up JOIN geo_regions g ON g.id = up.id WHERE up.parent_id IS NULL",
+            )
+            .expect("upward walk should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Europe" })]);
+    }
+
+    #[test]
+    fn db_runner_with_recursive_cycles_and_iteration_limit() {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        add_batch_or_panic(
+            &db.create("comments"),
+            json!([
+                { "id": 1, "reply_to": 3 },
+                { "id": 2, "reply_to": 1 },
+                { "id": 3, "reply_to": 2 }
+            ]),
+            3,
+        );
+        let thread = |op: &str| {
+            format!(
+                "WITH RECURSIVE thread AS (SELECT id, reply_to FROM comments WHERE id = 1 \
+                 {op} SELECT c.id, c.reply_to FROM comments c JOIN thread t ON c.reply_to = t.id) \
+                 SELECT id FROM thread"
+            )
+        };
+
+        // UNION stops once the cycle only yields rows already produced
+        let rows = db.query(&thread("UNION")).expect("union should end");
+        assert_eq!(rows.len(), 3);
+
+        let err = db.query(&thread("UNION ALL")).unwrap_err();
+        assert!(
+            matches!(err, AnalyzerError::Other(message) if message.contains("contains a cycle"))
+        );
+
+        db.set_recursion_limit(2);
+        let err = db.query(&thread("UNION")).unwrap_err();
+        assert!(
+            matches!(err, AnalyzerError::Other(message) if message.contains("within 2 iterations"))
+        );
+    }
+
+    #[test]
+    fn db_runner_supports_from_list_cross_join() {
+        let db = mk_db();
+        // trivial cross join + count(*) just to exercise the path
+        let sql = r#"
+            SELECT COUNT(*) AS n
+            FROM t a, t b
+        "#;
+        let rows = db.query(sql).expect("query should succeed");
+        assert_eq!(rows.len(), 1);
+        // t has 5 rows -> t × t has 25 rows
+        assert_eq!(rows[0]["n"].as_i64().unwrap(), 25);
+    }
+
+    #[test]
+    fn db_runner_order_by_aggregate_not_projected() {
+        let db = mk_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT t.cat AS cat
+                FROM t
+                GROUP BY t.cat
+                ORDER BY SUM(t.amt) DESC
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(string_values(&rows, "cat"), vec!["a", "b"]);
+        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+    }
+
+    #[test]
+    fn db_runner_order_by_aggregate_not_projected_preserves_projection_alias() {
+        let db = mk_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT t.cat AS g
+                FROM t
+                GROUP BY t.cat
+                ORDER BY SUM(t.amt) DESC
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(string_values(&rows, "g"), vec!["a", "b"]);
+        assert!(rows.iter().all(|row| row.get("cat").is_none()));
+        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+    }
+
+    #[test]
+    fn db_runner_global_count_star_over_empty_table_returns_one_zero_row() {
+        let db = mk_empty_agg_db();
+        let rows = db.query("SELECT COUNT(*) AS n FROM empty_table").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["n"], json!(0));
+    }
+
+    #[test]
+    fn db_runner_global_sum_over_empty_table_returns_one_null_row() {
+        let db = mk_empty_agg_db();
+        let rows = db.query("SELECT SUM(v) AS s FROM empty_table").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0]["s"].is_null());
+    }
+
+    #[test]
+    fn db_runner_global_avg_min_max_over_empty_table_return_nulls() {
+        let db = mk_empty_agg_db();
+        let rows = db
+            .query("SELECT AVG(v) AS a, MIN(v) AS mn, MAX(v) AS mx FROM empty_table")
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0]["a"].is_null());
+        assert!(rows[0]["mn"].is_null());
+        assert!(rows[0]["mx"].is_null());
+    }
+
+    #[test]
+    fn db_runner_empty_grouped_aggregate_returns_zero_rows() {
+        let db = mk_empty_agg_db();
+        let rows = db
+            .query("SELECT grp, COUNT(*) AS n FROM empty_table GROUP BY grp")
+            .unwrap();
+
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn db_runner_multiple_sum_calls_receive_distinct_internal_names() {
+        let db = mk_phase2_agg_db();
+        let rows = db
+            .query("SELECT SUM(v) AS sv, SUM(w) AS sw FROM metrics")
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["sv"], json!(18.0));
+        assert_eq!(rows[0]["sw"], json!(10.0));
+    }
+
+    #[test]
+    fn db_runner_aggregate_name_does_not_collide_with_group_key_named_sum() {
+        let db = mk_phase2_agg_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT sum AS bucket, SUM(v) AS total
+                FROM metrics
+                GROUP BY sum
+                ORDER BY SUM(v) DESC
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(string_values(&rows, "bucket"), vec!["z", "y"]);
+        assert_eq!(rows[0]["total"], json!(13.0));
+        assert_eq!(rows[1]["total"], json!(5.0));
+    }
+
+    #[test]
+    fn db_runner_having_can_use_hidden_aggregate() {
+        let db = mk_phase2_agg_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT grp
+                FROM metrics
+                GROUP BY grp
+                HAVING SUM(v) > 6
+                ORDER BY grp
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(string_values(&rows, "grp"), vec!["a"]);
+        assert!(rows.iter().all(|row| row.get("sum").is_none()));
+    }
+
+    #[test]
+    fn db_runner_right_join_emits_unmatched_right_rows_with_null_left_fields() {
+        let db = mk_outer_join_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT pe.name AS person, p.name AS pet
+                FROM people pe
+                RIGHT JOIN pets p ON pe.id = p.owner_id
+                ORDER BY p.id
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["person"], json!("Ada"));
+        assert_eq!(rows[0]["pet"], json!("Milo"));
+        assert!(rows[1]["person"].is_null());
+        assert_eq!(rows[1]["pet"], json!("Ghost"));
+    }
+
+    #[test]
+    fn db_runner_full_join_emits_matched_and_unmatched_rows() {
+        let db = mk_outer_join_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT pe.name AS person, p.name AS pet
+                FROM people pe
+                FULL JOIN pets p ON pe.id = p.owner_id
+                ORDER BY pe.id
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["person"], json!("Ada"));
+        assert_eq!(rows[0]["pet"], json!("Milo"));
+        assert_eq!(rows[1]["person"], json!("Bob"));
+        assert!(rows[1]["pet"].is_null());
+        assert!(rows[2]["person"].is_null());
+        assert_eq!(rows[2]["pet"], json!("Ghost"));
+    }
+
+    #[test]
+    fn db_runner_order_by_non_projected_column() {
+        let db = mk_people_order_db();
+        let rows = db.query("SELECT name FROM people ORDER BY age").unwrap();
+        let names = string_values(&rows, "name");
+        let mut tied = names[1..3].to_vec();
+        tied.sort();
+
+        assert_eq!(names.first().unwrap(), "Bob");
+        assert_eq!(names.last().unwrap(), "Ada");
+        assert_eq!(tied, vec!["Carla", "Grace"]);
+        assert!(
+            rows.iter().all(|row| row.get("age").is_none()),
+            "hidden sort key leaked"
+        );
+    }
+
+    #[test]
+    fn db_runner_order_by_non_projected_column_with_alias_desc() {
+        let db = mk_people_order_db();
+        let rows = db
+            .query("SELECT name AS n FROM people ORDER BY age DESC")
+            .unwrap();
+        let names = string_values(&rows, "n");
+        let mut tied = names[1..3].to_vec();
+        tied.sort();
+
+        assert_eq!(names.first().unwrap(), "Ada");
+        assert_eq!(names.last().unwrap(), "Bob");
+        assert_eq!(tied, vec!["Carla", "Grace"]);
+        assert!(
+            rows.iter().all(|row| row.get("age").is_none()),
+            "hidden sort key leaked"
+        );
+    }
+
+    #[test]
+    fn db_runner_order_by_hidden_key_with_expression_projection() {
+        let db = mk_people_order_db();
+        let rows = db
+            .query("SELECT UPPER(name) AS n FROM people ORDER BY age")
+            .unwrap();
+        let names = string_values(&rows, "n");
+        let mut tied = names[1..3].to_vec();
+        tied.sort();
+
+        assert_eq!(names.first().unwrap(), "BOB");
+        assert_eq!(names.last().unwrap(), "ADA");
+        assert_eq!(tied, vec!["CARLA", "GRACE"]);
+        assert!(
+            rows.iter().all(|row| row.get("age").is_none()),
+            "hidden sort key leaked"
+        );
+    }
+
+    #[test]
+    fn db_runner_order_by_hidden_multi_key() {
+        let db = mk_people_order_db();
+        let rows = db
+            .query("SELECT name FROM people ORDER BY age ASC, id DESC")
+            .unwrap();
+
+        assert_eq!(
+            string_values(&rows, "name"),
+            vec!["Bob", "Grace", "Carla", "Ada"]
+        );
+        assert!(rows.iter().all(|row| row.get("age").is_none()));
+        assert!(rows.iter().all(|row| row.get("id").is_none()));
+    }
+
+    #[test]
+    fn db_runner_order_by_projected_position_still_works() {
+        let db = mk_people_order_db();
+        let rows = db.query("SELECT name FROM people ORDER BY 1").unwrap();
+
+        assert_eq!(
+            string_values(&rows, "name"),
+            vec!["Ada", "Bob", "Carla", "Grace"]
+        );
+    }
+
+    #[test]
+    fn db_runner_order_by_position_out_of_range_errors() {
+        let db = mk_people_order_db();
+        let err = db.query("SELECT name FROM people ORDER BY 2").unwrap_err();
+        let msg = format!("{err:?}").to_lowercase();
+
+        assert!(
+            msg.contains("order by position"),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn db_runner_order_by_missing_column_errors() {
+        let db = mk_people_order_db();
+        let err = db
+            .query("SELECT name FROM people ORDER BY missing_column")
+            .unwrap_err();
+        let msg = format!("{err:?}").to_lowercase();
+
+        assert!(
+            msg.contains("unknowncolumn") || msg.contains("missing_column"),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn db_runner_selects_from_subquery_with_order_by() {
+        let db = mk_people_order_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT p.name
+                FROM (SELECT name FROM people) p
+                ORDER BY p.name
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            string_values(&rows, "name"),
+            vec!["Ada", "Bob", "Carla", "Grace"]
+        );
+    }
+
+    #[test]
+    fn db_runner_select_star_from_subquery() {
+        let db = mk_people_order_db();
+        let rows = db
+            .query(
+                r#"
+                SELECT *
+                FROM (SELECT name, age FROM people WHERE id = 1) p
+            "#,
+            )
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
         assert_eq!(rows[0]["name"], json!("Ada"));
         assert_eq!(rows[0]["age"], json!(37));
     }
@@ -1634,10 +3163,323 @@ mod tests {
             "#,
                 serde_json::json!([2, "a"]),
             )
-            .expect("query should succeed");
-        // Expect rows with id >= 2 and cat='a' -> ids 2 and 5 in mk_db fixture
-        let ids: Vec<i64> = rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
-        assert_eq!(ids, vec![2, 5]);
+            .expect("query should succeed");
+        // Expect rows with id >= 2 and cat='a' -> ids 2 and 5 in mk_db fixture
+        let ids: Vec<i64> = rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![2, 5]);
+    }
+
+    #[test]
+    fn db_runner_named_and_numbered_params() {
+        let db = mk_db();
+
+        // a named parameter can repeat, including in a subquery and an IN list
+        let rows = db
+            .query_with_args(
+                "SELECT id FROM t WHERE cat = :cat AND id IN (:ids) \
+                 AND amt < (SELECT MAX(amt) FROM t WHERE cat = :cat) ORDER BY id",
+                json!({ "cat": "a", "ids": [1, 2, 3, 5] }),
+            )
+            .expect("named parameters should bind");
+        assert_eq!(rows, vec![json!({ "id": 1 }), json!({ "id": 2 })]);
+
+        let rows = db
+            .query_with_args(
+                "SELECT id, $2 AS label FROM t WHERE id >= $1 AND id <= $1 + 1 ORDER BY id",
+                json!([3, "x"]),
+            )
+            .expect("numbered parameters should bind");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 3, "label": "x" }),
+                json!({ "id": 4, "label": "x" })
+            ]
+        );
+
+        let err = db
+            .query_with_args(
+                "SELECT id FROM t WHERE cat = :cat AND id > :min_id AND amt < :max_amt",
+                json!({ "cat": "a", "limit": 2, "max": 1 }),
+            )
+            .expect_err("missing and unused names should be reported");
+        assert_eq!(
+            err,
+            AnalyzerError::ParameterMismatch {
+                missing: vec![":min_id".into(), ":max_amt".into()],
+                unused: vec![":limit".into(), ":max".into()],
+            }
+        );
+
+        let err = db
+            .query_with_args("SELECT id FROM t WHERE id = $2", json!([1, 2]))
+            .expect_err("unused numbered values should be reported");
+        assert_eq!(
+            err,
+            AnalyzerError::ParameterMismatch {
+                missing: vec![],
+                unused: vec!["$1".into()],
+            }
+        );
+
+        // positional placeholders keep their own binding
+        let rows = db
+            .query_with_args("SELECT id FROM t WHERE id = ?", json!([4]))
+            .expect("positional parameters should still bind");
+        assert_eq!(rows, vec![json!({ "id": 4 })]);
+    }
+
+    #[test]
+    fn db_runner_named_params_inside_subqueries_and_ctes() {
+        let db = mk_db();
+
+        let rows = db
+            .query_with_args(
+                "WITH c AS (SELECT id FROM t WHERE id > :a) SELECT id FROM c WHERE id < :b",
+                json!({ "a": 1, "b": 3 }),
+            )
+            .expect("names in a WITH query should be checked with the body's");
+        assert_eq!(rows, vec![json!({ "id": 2 })]);
+
+        let rows = db
+            .query_with_args(
+                "SELECT id FROM t WHERE id IN (SELECT id FROM t WHERE cat = :cat) \
+                 AND amt > :min ORDER BY id",
+                json!({ "cat": "b", "min": 5 }),
+            )
+            .expect("names in an IN subquery should be checked");
+        assert_eq!(rows, vec![json!({ "id": 3 })]);
+
+        // names used only by a subquery still report unused values
+        let err = db
+            .query_with_args(
+                "SELECT s.id FROM (SELECT id FROM t WHERE id = :id) s",
+                json!({ "id": 1, "zzz": 2 }),
+            )
+            .expect_err("unused names should be reported");
+        assert_eq!(
+            err,
+            AnalyzerError::ParameterMismatch {
+                missing: vec![],
+                unused: vec![":zzz".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn db_runner_window_functions() {
+        let db = mk_db();
+
+        let rows = db
+            .query(
+                "SELECT id, ROW_NUMBER() OVER (PARTITION BY cat ORDER BY id) AS rn, \
+                 RANK() OVER (ORDER BY cat) AS rk, DENSE_RANK() OVER (ORDER BY cat) AS drk \
+                 FROM t ORDER BY id",
+            )
+            .expect("ranking functions should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "rn": 1, "rk": 1, "drk": 1 }),
+                json!({ "id": 2, "rn": 2, "rk": 1, "drk": 1 }),
+                json!({ "id": 3, "rn": 1, "rk": 4, "drk": 2 }),
+                json!({ "id": 4, "rn": 2, "rk": 4, "drk": 2 }),
+                json!({ "id": 5, "rn": 3, "rk": 1, "drk": 1 })
+            ]
+        );
+
+        // running total per category, and a moving sum over neighbouring rows
+        let rows = db
+            .query(
+                "SELECT id, SUM(amt) OVER (PARTITION BY cat ORDER BY id) AS running, \
+                 SUM(amt) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) AS moving \
+                 FROM t ORDER BY id",
+            )
+            .expect("aggregates over windows should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "running": 10.0, "moving": 25.0 }),
+                json!({ "id": 2, "running": 25.0, "moving": 32.5 }),
+                json!({ "id": 3, "running": 7.5, "moving": 22.5 }),
+                json!({ "id": 4, "running": 7.5, "moving": 30.0 }),
+                json!({ "id": 5, "running": 47.5, "moving": 22.5 })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT id, LAG(id) OVER (ORDER BY id) AS prev, \
+                 LEAD(id, 2, 0) OVER (ORDER BY id) AS next2, \
+                 FIRST_VALUE(id) OVER (PARTITION BY cat ORDER BY id DESC) AS newest, \
+                 NTILE(2) OVER (ORDER BY id) AS half \
+                 FROM t ORDER BY id",
+            )
+            .expect("offset functions should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "prev": null, "next2": 3, "newest": 5, "half": 1 }),
+                json!({ "id": 2, "prev": 1, "next2": 4, "newest": 5, "half": 1 }),
+                json!({ "id": 3, "prev": 2, "next2": 5, "newest": 4, "half": 1 }),
+                json!({ "id": 4, "prev": 3, "next2": 0, "newest": 4, "half": 2 }),
+                json!({ "id": 5, "prev": 4, "next2": 0, "newest": 5, "half": 2 })
+            ]
+        );
+
+        // offsets too large for the partition fall back to the default instead of overflowing
+        let rows = db
+            .query(
+                "SELECT id, LAG(id, -9223372036854775808, 0) OVER (ORDER BY id) AS far, \
+                 LEAD(id, 9223372036854775807) OVER (ORDER BY id) AS farther, \
+                 COUNT(*) OVER (ORDER BY id ROWS BETWEEN CURRENT ROW AND 9223372036854775807 FOLLOWING) AS rest, \
+                 COUNT(*) OVER (ORDER BY id ROWS BETWEEN 18446744073709551615 PRECEDING AND CURRENT ROW) AS seen \
+                 FROM t ORDER BY id",
+            )
+            .expect("huge offsets should run");
+        assert_eq!(
+            rows,
+            (1..=5)
+                .map(
+                    |id| json!({ "id": id, "far": 0, "farther": null, "rest": 6 - id, "seen": id })
+                )
+                .collect::<Vec<_>>()
+        );
+
+        // a bucket count below 1 fails, before running when it is a constant
+        for sql in [
+            "SELECT NTILE(0) OVER (ORDER BY id) AS b FROM t",
+            "SELECT NTILE(id - 4) OVER (ORDER BY id) AS b FROM t",
+        ] {
+            let err = db
+                .query(sql)
+                .expect_err("a non-positive bucket count should fail");
+            assert!(matches!(err, AnalyzerError::Other(msg) if msg.contains("must be positive")));
+        }
+
+        // windows run after GROUP BY, over the grouped rows
+        let rows = db
+            .query(
+                "SELECT cat, SUM(amt) AS total, RANK() OVER (ORDER BY SUM(amt) DESC) AS pos, \
+                 COUNT(*) OVER () AS groups FROM t GROUP BY cat ORDER BY pos",
+            )
+            .expect("windows over groups should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "cat": "a", "total": 47.5, "pos": 1, "groups": 2 }),
+                json!({ "cat": "b", "total": 7.5, "pos": 2, "groups": 2 })
+            ]
+        );
+
+        // top row per category through a derived table
+        let rows = db
+            .query(
+                "SELECT r.id FROM (SELECT id, ROW_NUMBER() OVER \
+                 (PARTITION BY cat ORDER BY amt DESC) AS rn FROM t) r WHERE r.rn = 1 ORDER BY r.id",
+            )
+            .expect("filtering on a window through a subquery should run");
+        assert_eq!(rows, vec![json!({ "id": 3 }), json!({ "id": 5 })]);
+
+        let rows = db
+            .query("SELECT id FROM t ORDER BY ROW_NUMBER() OVER (ORDER BY amt DESC)")
+            .expect("ordering by a window should run");
+        let ids: Vec<i64> = rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![5, 2, 1, 3, 4]);
+
+        for sql in [
+            "SELECT id FROM t WHERE ROW_NUMBER() OVER (ORDER BY id) = 1",
+            "SELECT cat FROM t GROUP BY RANK() OVER (ORDER BY cat)",
+            "SELECT cat, SUM(ROW_NUMBER() OVER ()) FROM t GROUP BY cat",
+            "SELECT MEDIAN(amt) OVER () FROM t",
+        ] {
+            assert!(db.query(sql).is_err(), "{sql} should be rejected");
+        }
+    }
+
+    #[test]
+    fn db_runner_rollup_cube_and_grouping_sets() {
+        let db = Db::new_with_config(DbConfig {
+            id_type: IdType::None,
+            id_key: "id".into(),
+        });
+        let sales = db.create("sales");
+        add_batch_or_panic(
+            &sales,
+            json!([
+                { "id": 1, "region": "north", "product": "tea", "qty": 3 },
+                { "id": 2, "region": "north", "product": "coffee", "qty": 5 },
+                { "id": 3, "region": "south", "product": "tea", "qty": 2 },
+                { "id": 4, "region": "north", "product": "tea", "qty": 1 }
+            ]),
+            4,
+        );
+
+        // subtotals per region and a grand total, which sorts last
+        let rows = db
+            .query(
+                "SELECT region, product, SUM(qty) AS total, GROUPING(region, product) AS g \
+                 FROM sales GROUP BY ROLLUP(region, product) ORDER BY region, product",
+            )
+            .expect("ROLLUP should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "region": "north", "product": "coffee", "total": 5, "g": 0 }),
+                json!({ "region": "north", "product": "tea", "total": 4, "g": 0 }),
+                json!({ "region": "north", "product": null, "total": 9, "g": 1 }),
+                json!({ "region": "south", "product": "tea", "total": 2, "g": 0 }),
+                json!({ "region": "south", "product": null, "total": 2, "g": 1 }),
+                json!({ "region": null, "product": null, "total": 11, "g": 3 })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT product, COUNT(*) AS n FROM sales \
+                 GROUP BY CUBE(region, product) HAVING GROUPING(region) = 1 ORDER BY n DESC",
+            )
+            .expect("CUBE should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "product": null, "n": 4 }),
+                json!({ "product": "tea", "n": 3 }),
+                json!({ "product": "coffee", "n": 1 })
+            ]
+        );
+
+        let rows = db
+            .query(
+                "SELECT s.region, product, SUM(qty) AS total FROM sales s \
+                 GROUP BY GROUPING SETS ((region), (s.product), ()) ORDER BY total DESC, region, product",
+            )
+            .expect("GROUPING SETS should run");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "region": null, "product": null, "total": 11 }),
+                json!({ "region": "north", "product": null, "total": 9 }),
+                json!({ "region": null, "product": "tea", "total": 6 }),
+                json!({ "region": null, "product": "coffee", "total": 5 }),
+                json!({ "region": "south", "product": null, "total": 2 })
+            ]
+        );
+
+        // the grand total row is kept even when no rows match
+        let rows = db
+            .query("SELECT region, COUNT(*) AS n FROM sales WHERE qty > 10 GROUP BY ROLLUP(region)")
+            .expect("ROLLUP over no rows should run");
+        assert_eq!(rows, vec![json!({ "region": null, "n": 0 })]);
+
+        for sql in [
+            "SELECT GROUPING(product) FROM sales GROUP BY ROLLUP(region)",
+            "SELECT region FROM sales GROUP BY ROLLUP(region) HAVING GROUPING() = 0",
+            "SELECT region FROM sales WHERE GROUPING(region) = 0 GROUP BY region",
+            "SELECT region FROM sales GROUP BY ROLLUP(region, 1)",
+        ] {
+            assert!(db.query(sql).is_err(), "{sql} should be rejected");
+        }
     }
 
     #[test]
@@ -1692,6 +3534,474 @@ mod tests {
         assert_eq!(rows[0]["n"].as_i64().unwrap(), 25);
     }
 
+    #[test]
+    fn db_runner_insert_values_with_args() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        let people = db.create("people");
+
+        let rows = db
+            .query_with_args(
+                "INSERT INTO people (name, age) VALUES (?, ?), ('Grace', 29)",
+                json!(["Ada", 37]),
+            )
+            .expect("insert should succeed");
+        assert_eq!(rows, vec![json!({ "affected_rows": 2 })]);
+
+        assert_eq!(
+            people.get("1").unwrap().unwrap(),
+            json!({ "id": 1, "name": "Ada", "age": 37 })
+        );
+        let rows = db
+            .query("SELECT name FROM people WHERE age < 30")
+            .expect("query should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Grace" })]);
+    }
+
+    #[test]
+    fn db_runner_insert_none_id_requires_id_and_is_all_or_nothing() {
+        let db = mk_db();
+
+        let err = db
+            .query("INSERT INTO t (id, cat) VALUES (6, 'c'), (7, 'c'), (1, 'dup')")
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("duplicate")));
+
+        let err = db.query("INSERT INTO t (cat) VALUES ('c')").unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("missing")));
+
+        assert_eq!(db.get("t").unwrap().count().unwrap(), 5);
+    }
+
+    #[test]
+    fn db_runner_insert_select_copies_rows() {
+        let db = mk_people_order_db();
+        let _archive = db.create("archive");
+
+        let rows = db
+            .query(
+                "INSERT INTO archive (id, label) SELECT p.id, p.name FROM people p WHERE p.age >= 30",
+            )
+            .expect("insert should succeed");
+        assert_eq!(rows[0]["affected_rows"], 3);
+
+        let rows = db
+            .query("SELECT label FROM archive ORDER BY id")
+            .expect("query should succeed");
+        let labels: Vec<&str> = rows.iter().map(|r| r["label"].as_str().unwrap()).collect();
+        assert_eq!(labels, vec!["Ada", "Carla", "Grace"]);
+    }
+
+    #[test]
+    fn db_runner_insert_rejects_schema_type_mismatch() {
+        let db = mk_people_order_db();
+
+        let err = db
+            .query("INSERT INTO people (id, age) VALUES (9, 'old')")
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("people.age")));
+
+        let err = db.query("INSERT INTO nobody (id) VALUES (1)").unwrap_err();
+        assert_eq!(err, AnalyzerError::UnknownCollection("nobody".into()));
+    }
+
+    #[test]
+    fn db_runner_update_with_where_reports_row_count() {
+        let db = mk_people_order_db();
+
+        let rows = db
+            .query_with_args(
+                "UPDATE people SET age = ?, name = upper(name) WHERE age = 30",
+                json!([31]),
+            )
+            .expect("update should succeed");
+        assert_eq!(rows, vec![json!({ "affected_rows": 2 })]);
+
+        let rows = db
+            .query("SELECT name, age FROM people ORDER BY id")
+            .expect("query should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "name": "Ada", "age": 37 }),
+                json!({ "name": "Bob", "age": 25 }),
+                json!({ "name": "CARLA", "age": 31 }),
+                json!({ "name": "GRACE", "age": 31 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn db_runner_update_without_where_touches_every_row_and_keeps_other_fields() {
+        let db = mk_db();
+
+        let rows = db
+            .query("UPDATE t SET flag = TRUE")
+            .expect("update should succeed");
+        assert_eq!(rows[0]["affected_rows"], 5);
+
+        let row = db.get("t").unwrap().get("4").unwrap().unwrap();
+        assert_eq!(
+            row,
+            json!({ "id": 4, "cat": "b", "amt": null, "flag": true })
+        );
+    }
+
+    #[test]
+    fn db_runner_update_replaces_object_columns() {
+        let db = mk_profiles_db();
+
+        let rows = db
+            .query("UPDATE people SET profile = json_object('a', 1) WHERE id = 1 RETURNING profile")
+            .expect("update should succeed");
+        assert_eq!(rows, vec![json!({ "profile": { "a": 1 } })]);
+        assert_eq!(
+            db.get("people").unwrap().get("1").unwrap().unwrap()["profile"],
+            json!({ "a": 1 })
+        );
+
+        db.query(
+            "INSERT INTO people (id, name) VALUES (2, 'Bo') \
+             ON CONFLICT (id) DO UPDATE SET profile = json_object('b', 2)",
+        )
+        .expect("upsert should succeed");
+        assert_eq!(
+            db.get("people").unwrap().get("2").unwrap().unwrap()["profile"],
+            json!({ "b": 2 })
+        );
+    }
+
+    #[test]
+    fn db_runner_update_skips_unknown_predicate_rows() {
+        let db = mk_db();
+
+        let rows = db
+            .query("UPDATE t SET cat = 'z' WHERE amt < 100")
+            .expect("update should succeed");
+        assert_eq!(rows[0]["affected_rows"], 4);
+
+        let rows = db
+            .query("SELECT id FROM t WHERE cat = 'b'")
+            .expect("query should succeed");
+        assert_eq!(rows, vec![json!({ "id": 4 })]);
+    }
+
+    #[test]
+    fn db_runner_delete_with_where_keeps_unknown_rows() {
+        let db = mk_db();
+
+        // amt is NULL for id 4, so `amt < 12` is Unknown there and the row stays
+        let rows = db
+            .query("DELETE FROM t WHERE amt < 12 OR cat = 'x'")
+            .expect("delete should succeed");
+        assert_eq!(rows, vec![json!({ "affected_rows": 2 })]);
+
+        let rows = db
+            .query("SELECT id FROM t ORDER BY id")
+            .expect("query should succeed");
+        let ids: Vec<i64> = rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn db_runner_delete_with_args_and_without_where() {
+        let db = mk_people_order_db();
+
+        let rows = db
+            .query_with_args("DELETE FROM orders WHERE person_id IN (?)", json!([[1, 2]]))
+            .expect("delete should succeed");
+        assert_eq!(rows[0]["affected_rows"], 2);
+
+        let rows = db
+            .query("DELETE FROM people")
+            .expect("delete should succeed");
+        assert_eq!(rows[0]["affected_rows"], 4);
+        assert_eq!(db.get("people").unwrap().count().unwrap(), 0);
+
+        let err = db.query("DELETE FROM missing").unwrap_err();
+        assert_eq!(err, AnalyzerError::UnknownCollection("missing".into()));
+    }
+
+    #[test]
+    fn db_runner_insert_returning_echoes_generated_ids() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        let people = db.create("people");
+
+        let rows = db
+            .query("INSERT INTO people (name, age) VALUES ('Ada', 37), ('Grace', NULL) RETURNING *")
+            .expect("insert should succeed");
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "name": "Ada", "age": 37 }),
+                json!({ "id": 2, "name": "Grace", "age": null })
+            ]
+        );
+
+        let rows = db
+            .query_with_args(
+                "INSERT INTO people (name) VALUES (?) RETURNING id, upper(name) AS shout",
+                json!("Linus"),
+            )
+            .expect("insert should succeed");
+        assert_eq!(rows, vec![json!({ "id": 3, "shout": "LINUS" })]);
+        assert_eq!(
+            people.get("3").unwrap().unwrap(),
+            json!({ "id": 3, "name": "Linus" })
+        );
+
+        let uuids = db.create_with_config("sessions", DbConfig::uuid("session_id"));
+        let rows = db
+            .query("INSERT INTO sessions (user) VALUES ('ada') RETURNING session_id")
+            .expect("insert should succeed");
+        let id = rows[0]["session_id"].as_str().expect("uuid id").to_string();
+        assert_eq!(uuids.get(&id).unwrap().unwrap()["user"], "ada");
+    }
+
+    #[test]
+    fn db_runner_update_and_delete_returning_final_documents() {
+        let db = mk_people_order_db();
+
+        let mut rows = db
+            .query("UPDATE people SET age = 31 WHERE age = 30 RETURNING *")
+            .expect("update should succeed");
+        rows.sort_by_key(|row| row["id"].as_i64());
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 3, "name": "Carla", "age": 31 }),
+                json!({ "id": 4, "name": "Grace", "age": 31 })
+            ]
+        );
+
+        let rows = db
+            .query("DELETE FROM people WHERE id = 1 RETURNING name, age AS was")
+            .expect("delete should succeed");
+        assert_eq!(rows, vec![json!({ "name": "Ada", "was": 37 })]);
+
+        let rows = db
+            .query("DELETE FROM people WHERE id = 1 RETURNING *")
+            .expect("delete should succeed");
+        assert!(rows.is_empty());
+
+        let err = db
+            .query("UPDATE people SET age = 1 RETURNING missing")
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::UnknownColumn { .. }));
+        assert_eq!(
+            db.get("people").unwrap().get("2").unwrap().unwrap()["age"],
+            25
+        );
+    }
+
+    #[test]
+    fn db_runner_insert_on_conflict_do_update_merges_rows() {
+        let db = mk_people_order_db();
+
+        let rows = db
+            .query(
+                "INSERT INTO people (id, name, city) VALUES (1, 'Ada L.', 'London'), (9, 'Edsger', 'Austin') \
+                 ON CONFLICT (id) DO UPDATE",
+            )
+            .expect("upsert should succeed");
+        assert_eq!(rows, vec![json!({ "affected_rows": 2 })]);
+
+        let people = db.get("people").unwrap();
+        assert_eq!(
+            people.get("1").unwrap().unwrap(),
+            json!({ "id": 1, "name": "Ada L.", "age": 37, "city": "London" })
+        );
+        assert_eq!(
+            people.get("9").unwrap().unwrap(),
+            json!({ "id": 9, "name": "Edsger", "city": "Austin" })
+        );
+
+        let rows = db
+            .query_with_args(
+                "INSERT INTO people (id, name, age) VALUES (?, ?, ?) \
+                 ON CONFLICT (id) DO UPDATE SET age = excluded.age, nick = people.name \
+                 RETURNING *",
+                json!([2, "ignored", 26]),
+            )
+            .expect("upsert should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "id": 2, "name": "Bob", "age": 26, "city": null, "nick": "Bob" })]
+        );
+    }
+
+    #[test]
+    fn db_runner_insert_on_conflict_do_nothing_skips_existing_rows() {
+        let db = mk_people_order_db();
+
+        let rows = db
+            .query(
+                "INSERT INTO people (id, name) VALUES (1, 'Other'), (7, 'New'), (7, 'Again') \
+                 ON CONFLICT DO NOTHING RETURNING id, name",
+            )
+            .expect("insert should succeed");
+        assert_eq!(rows, vec![json!({ "id": 7, "name": "New" })]);
+        assert_eq!(
+            db.get("people").unwrap().get("1").unwrap().unwrap()["name"],
+            "Ada"
+        );
+
+        let err = db
+            .query("INSERT INTO people (id, name) VALUES (1, 'x') ON CONFLICT (name) DO NOTHING")
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("id column")));
+
+        let err = db
+            .query("INSERT INTO people (name) VALUES ('y') ON CONFLICT DO UPDATE")
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("missing")));
+    }
+
+    #[test]
+    fn db_runner_insert_select_on_conflict_reseeds_int_collection() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        let staging = db.create("staging");
+        add_batch_or_panic(
+            &staging,
+            json!([
+                { "id": 1, "name": "Ada" },
+                { "id": 5, "name": "Linus" }
+            ]),
+            2,
+        );
+        let people = db.create("people");
+        add_batch_or_panic(&people, json!([{ "id": 1, "name": "Old", "age": 37 }]), 1);
+
+        let rows = db
+            .query("INSERT INTO people SELECT id, name FROM staging ON CONFLICT (id) DO UPDATE")
+            .expect("upsert should succeed");
+        assert_eq!(rows[0]["affected_rows"], 2);
+        assert_eq!(
+            people.get("1").unwrap().unwrap(),
+            json!({ "id": 1, "name": "Ada", "age": 37 })
+        );
+        assert_eq!(people.get("5").unwrap().unwrap()["name"], "Linus");
+
+        let rows = db
+            .query("INSERT INTO people (name) VALUES ('Grace') RETURNING id")
+            .expect("insert should succeed");
+        assert_eq!(rows, vec![json!({ "id": 6 })]);
+    }
+
+    #[test]
+    fn db_runner_create_collection_uses_id_marker_and_schema() {
+        let db = Db::new();
+        db.query("CREATE COLLECTION users (user_id Id, name String!, age Int)")
+            .expect("create should succeed");
+
+        let users = db.get("users").expect("users should exist");
+        assert_eq!(users.get_config().unwrap(), DbConfig::int("user_id"));
+
+        let rows = db
+            .query("INSERT INTO users (name, age) VALUES ('Ada', 37) RETURNING user_id")
+            .expect("insert should succeed");
+        assert_eq!(rows, vec![json!({ "user_id": 1 })]);
+
+        let schema = db.schema_of("users").unwrap();
+        assert!(!schema.fields["name"].nullable);
+        assert!(schema.fields["age"].nullable);
+
+        let err = db.query("CREATE COLLECTION users (user_id Id)");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("already exists"))
+        );
+        db.query("CREATE COLLECTION IF NOT EXISTS users (user_id Id)")
+            .expect("IF NOT EXISTS should skip the existing collection");
+        assert_eq!(users.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn db_runner_drop_collection() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        db.create("people");
+
+        assert_eq!(
+            db.query("DROP COLLECTION people").unwrap(),
+            Vec::<Value>::new()
+        );
+        assert!(db.get("people").is_none());
+
+        let err = db.query("DROP COLLECTION people");
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "people"));
+        db.query("DROP COLLECTION IF EXISTS people")
+            .expect("IF EXISTS should ignore missing collections");
+    }
+
+    #[test]
+    fn db_runner_script_bootstraps_database() {
+        let db = Db::new();
+        let executed = db
+            .execute_script(
+                "
+                -- schema
+                CREATE COLLECTION users (user_id Id, name String!);
+                CREATE COLLECTION orders (order_id Uuid, user_id Int!, total Float);
+
+                INSERT INTO users (name) VALUES ('Ada'), ('Grace; Hopper');
+                INSERT INTO orders (user_id, total) VALUES (2, 12.5), (2, 3.0);
+                ",
+            )
+            .expect("script should run");
+
+        assert_eq!(executed, 4);
+        assert!(db.get_collection_column_ref("orders", "user_id").is_some());
+        let rows = db
+            .query(
+                "SELECT u.name, SUM(o.total) AS total FROM users u \
+                 JOIN orders o ON o.user_id = u.user_id GROUP BY u.name",
+            )
+            .expect("query should succeed");
+        assert_eq!(
+            rows,
+            vec![json!({ "name": "Grace; Hopper", "total": 15.5 })]
+        );
+    }
+
+    #[test]
+    fn db_runner_script_parses_everything_before_executing() {
+        let db = Db::new();
+
+        let err = db.execute_script("CREATE COLLECTION users (user_id Id); SELEC 1");
+
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.starts_with("parse error"))
+        );
+        assert!(db.get("users").is_none());
+    }
+
+    #[test]
+    fn execute_script_file_reports_success_status() {
+        use std::{ffi::OsString, fs};
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("bootstrap.sql");
+        fs::write(
+            &path,
+            "CREATE COLLECTION people (person_id Id, name String!);\n\
+             INSERT INTO people (name) VALUES ('Ada');\n",
+        )
+        .unwrap();
+
+        let db = Db::new();
+        let status = db
+            .execute_script_file(&OsString::from(path.to_string_lossy().into_owned()))
+            .unwrap();
+
+        assert!(status.starts_with("Executed 2 statements from"));
+        assert_eq!(db.get("people").unwrap().count().unwrap(), 1);
+
+        let missing = db.execute_script_file(&OsString::from("missing.sql"));
+        assert_eq!(
+            missing,
+            Err("Could not read script file missing.sql".to_string())
+        );
+    }
+
     #[test]
     fn test_db_load_from_json() {
         use serde_json::json;
@@ -1,6 +1,6 @@
 use serde_json::{Map, Number, Value};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
     ffi::OsString,
     fmt::{self, Display},
@@ -71,6 +71,11 @@ pub enum AddError {
         /// Duplicate id value.
         id: String,
     },
+    /// A provided id does not fit the collection's id type.
+    InvalidId {
+        /// Provided id value, as JSON.
+        id: String,
+    },
 }
 
 impl Display for AddError {
@@ -80,6 +85,7 @@ impl Display for AddError {
             Self::NonObjectItem => f.write_str("collection items must be JSON objects"),
             Self::MissingId { id_key } => write!(f, "missing required id field '{id_key}'"),
             Self::DuplicateId { id } => write!(f, "duplicate collection id '{id}'"),
+            Self::InvalidId { id } => write!(f, "id {id} does not match the collection id type"),
         }
     }
 }
@@ -117,6 +123,26 @@ pub enum AddBatchError {
         /// Zero-based index of the invalid batch item.
         index: usize,
     },
+    /// One item has an id that does not fit the collection's id type.
+    InvalidId {
+        /// Zero-based index of the invalid batch item.
+        index: usize,
+        /// Provided id value, as JSON.
+        id: String,
+    },
+}
+
+impl AddBatchError {
+    /// Attach the batch index to an error reported for a single item.
+    fn at(index: usize, error: AddError) -> Self {
+        match error {
+            AddError::LockPoisoned => Self::LockPoisoned,
+            AddError::NonObjectItem => Self::NonObjectItem { index },
+            AddError::MissingId { id_key } => Self::MissingId { index, id_key },
+            AddError::DuplicateId { id } => Self::DuplicateId { index, id },
+            AddError::InvalidId { id } => Self::InvalidId { index, id },
+        }
+    }
 }
 
 impl Display for AddBatchError {
@@ -142,6 +168,12 @@ impl Display for AddBatchError {
             Self::InvalidIntId { index } => {
                 write!(f, "batch item at index {index} has an invalid integer id")
             }
+            Self::InvalidId { index, id } => {
+                write!(
+                    f,
+                    "batch item at index {index} has id {id}, which does not match the collection id type"
+                )
+            }
         }
     }
 }
@@ -268,7 +300,9 @@ impl InternalMemoryCollection {
                 nullable: false,
                 ty: match config.id_type {
                     IdType::Int => JsonPrimitive::Int,
-                    _ => JsonPrimitive::String,
+                    IdType::Uuid => JsonPrimitive::String,
+                    // caller-provided ids may be strings or numbers; the first document decides
+                    IdType::None => JsonPrimitive::Null,
                 },
             },
         );
@@ -607,6 +641,140 @@ impl InternalMemoryCollection {
         Ok(added_items)
     }
 
+    /// Add every item following the same id rules as `add`. Ids are validated
+    /// up front so a failing item leaves the collection untouched.
+    pub fn add_all(&mut self, items: Vec<Value>) -> Result<Vec<Value>, AddBatchError> {
+        let mut ids = HashSet::new();
+        for (index, item) in items.iter().enumerate() {
+            if !item.is_object() {
+                return Err(AddBatchError::NonObjectItem { index });
+            }
+
+            if self.config.id_type != IdType::None {
+                continue;
+            }
+
+            let id = match item.get(&self.config.id_key) {
+                Some(Value::String(id_string)) => id_string.clone(),
+                Some(Value::Number(id_number)) => id_number.to_string(),
+                _ => {
+                    return Err(AddBatchError::MissingId {
+                        index,
+                        id_key: self.config.id_key.clone(),
+                    });
+                }
+            };
+            if self.collection.contains_key(&id) || !ids.insert(id.clone()) {
+                return Err(AddBatchError::DuplicateId { index, id });
+            }
+        }
+
+        let mut added_items = Vec::with_capacity(items.len());
+        for (index, item) in items.into_iter().enumerate() {
+            let added = self
+                .add(item)
+                .map_err(|error| AddBatchError::at(index, error))?;
+            added_items.push(added);
+        }
+
+        Ok(added_items)
+    }
+
+    /// Insert `item`, or merge it into the stored document with the same id
+    /// using `merge_json_values`. Provided ids must fit the id type (see
+    /// `provided_id`) and missing ones are generated like `add`.
+    pub fn upsert(&mut self, item: Value) -> Result<Value, AddError> {
+        let Value::Object(mut map) = item else {
+            return Err(AddError::NonObjectItem);
+        };
+
+        let Some(id_value) = self.provided_id(&map)? else {
+            return self.add(Value::Object(map));
+        };
+        let id = match &id_value {
+            Value::String(id) => id.clone(),
+            other => other.to_string(),
+        };
+        map.insert(self.config.id_key.clone(), id_value.clone());
+        let item = Value::Object(map);
+
+        if self.collection.contains_key(&id) {
+            return Ok(self
+                .update_partial(&id, item)
+                .expect("stored id must be present"));
+        }
+
+        // keep generated ids ahead of the ids callers provide
+        if let Some(number) = id_value.as_u64()
+            && self.config.id_type == IdType::Int
+            && !matches!(self.id_manager.current, Some(IdValue::Int(current)) if current >= number)
+        {
+            let _ = self.id_manager.set_current(IdValue::Int(number));
+        }
+
+        self.ensure_update_schema_for_item(&item);
+        self.collection.insert(id, item.clone());
+
+        Ok(item)
+    }
+
+    /// Upsert every item of a JSON array. Items are validated up front so a
+    /// failing item leaves the collection untouched.
+    pub fn upsert_batch(&mut self, items: Value) -> Result<Vec<Value>, AddBatchError> {
+        let Value::Array(items) = items else {
+            return Err(AddBatchError::NonArrayInput);
+        };
+
+        for (index, item) in items.iter().enumerate() {
+            if !item.is_object() {
+                return Err(AddBatchError::NonObjectItem { index });
+            }
+            let provided = item
+                .as_object()
+                .map(|map| self.provided_id(map))
+                .transpose()
+                .map_err(|error| AddBatchError::at(index, error))?
+                .flatten();
+            if self.config.id_type == IdType::None && provided.is_none() {
+                return Err(AddBatchError::MissingId {
+                    index,
+                    id_key: self.config.id_key.clone(),
+                });
+            }
+        }
+
+        let mut stored_items = Vec::with_capacity(items.len());
+        for (index, item) in items.into_iter().enumerate() {
+            let stored = self
+                .upsert(item)
+                .map_err(|error| AddBatchError::at(index, error))?;
+            stored_items.push(stored);
+        }
+
+        Ok(stored_items)
+    }
+
+    /// The id an upserted item provides, in the form the collection stores it:
+    /// Int collections take a non-negative integer or a string holding one,
+    /// Uuid collections a string, and None collections a string or a number.
+    /// `None` when the item has no id.
+    fn provided_id(&self, item: &Map<String, Value>) -> Result<Option<Value>, AddError> {
+        let id = match item.get(&self.config.id_key) {
+            None | Some(Value::Null) => return Ok(None),
+            Some(id) => id,
+        };
+        let normalized = match (self.config.id_type, id) {
+            (IdType::Int, Value::Number(number)) => number.as_u64().map(Value::from),
+            (IdType::Int, Value::String(text)) => text.parse::<u64>().ok().map(Value::from),
+            (IdType::Uuid, Value::String(_))
+            | (IdType::None, Value::String(_) | Value::Number(_)) => Some(id.clone()),
+            _ => None,
+        };
+        normalized
+            .map(Some)
+            .ok_or_else(|| AddError::InvalidId { id: id.to_string() })
+    }
+
     pub fn update(&mut self, id: &str, item: Value) -> Option<Value> {
         let mut item = item;
 
@@ -1078,6 +1246,102 @@ impl DbCollection {
             .add_batch(items)
     }
 
+    /// Insert a document, or merge it into the stored document with the same
+    /// id. Returns the stored document.
+    ///
+    /// Merging follows [`DbCollection::update_partial`]: nested objects are
+    /// merged recursively and other values are replaced. Unlike
+    /// [`DbCollection::add`], a provided id is kept for every id type, so
+    /// fixtures can be re-seeded on top of existing data. Documents without an
+    /// id get a generated one for `IdType::Int` and `IdType::Uuid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AddError::LockPoisoned`] when the collection lock cannot be
+    /// acquired, [`AddError::NonObjectItem`] for non-object JSON values,
+    /// [`AddError::MissingId`] when an `IdType::None` document has no id, or
+    /// [`AddError::InvalidId`] when the id does not fit the id type: `Int`
+    /// collections take a non-negative integer or a string holding one (stored
+    /// as the number), and `Uuid` collections a string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::{DbCollection, DbConfig};
+    /// use serde_json::json;
+    ///
+    /// # fn main() -> Result<(), String> {
+    /// let people = DbCollection::new_coll("people", DbConfig::none("id"));
+    /// let _ada = people
+    ///     .upsert(json!({ "id": "ada", "name": "Ada", "age": 36 }))
+    ///     .map_err(|error| error.to_string())?;
+    ///
+    /// let merged = people
+    ///     .upsert(json!({ "id": "ada", "age": 37 }))
+    ///     .map_err(|error| error.to_string())?;
+    ///
+    /// assert_eq!(merged, json!({ "id": "ada", "name": "Ada", "age": 37 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upsert(&self, item: Value) -> Result<Value, AddError> {
+        self.collection
+            .write()
+            .map_err(|_| AddError::LockPoisoned)?
+            .upsert(item)
+    }
+
+    /// Upsert every item of a JSON array with the rules of
+    /// [`DbCollection::upsert`] and return the stored documents.
+    ///
+    /// Items are validated before anything is written, so a bad item leaves
+    /// the collection untouched instead of failing halfway.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the input is not an array, an item is not an
+    /// object, an `IdType::None` item has no id, or an id does not fit the id
+    /// type. It also returns an error
+    /// when the collection lock cannot be acquired.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fosk::{DbCollection, DbConfig};
+    /// use serde_json::json;
+    ///
+    /// # fn main() -> Result<(), String> {
+    /// let people = DbCollection::new_coll("people", DbConfig::none("id"));
+    /// let seed = json!([
+    ///     { "id": "ada", "name": "Ada" },
+    ///     { "id": "grace", "name": "Grace" }
+    /// ]);
+    ///
+    /// people.upsert_batch(seed.clone()).map_err(|error| error.to_string())?;
+    /// let stored = people.upsert_batch(seed).map_err(|error| error.to_string())?;
+    ///
+    /// assert_eq!(stored.len(), 2);
+    /// let count = people.count().map_err(|error| error.to_string())?;
+    /// assert_eq!(count, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upsert_batch(&self, items: Value) -> Result<Vec<Value>, AddBatchError> {
+        self.collection
+            .write()
+            .map_err(|_| AddBatchError::LockPoisoned)?
+            .upsert_batch(items)
+    }
+
+    /// Add items with the same id rules as [`DbCollection::add`], rejecting
+    /// the whole set when any id is missing or duplicated.
+    pub(crate) fn add_all(&self, items: Vec<Value>) -> Result<Vec<Value>, AddBatchError> {
+        self.collection
+            .write()
+            .map_err(|_| AddBatchError::LockPoisoned)?
+            .add_all(items)
+    }
+
     /// Replace the document with id `id` with `item`. Returns the stored
     /// document on success or `None` if the id was not present.
     ///
@@ -2037,6 +2301,182 @@ mod tests {
         assert!(collection.get("43").is_none());
     }
 
+    #[test]
+    fn test_add_all_int_generates_ids() {
+        let mut collection = create_test_collection();
+
+        let added = collection
+            .add_all(vec![
+                json!({"id": 99, "name": "Ada"}),
+                json!({"name": "Grace"}),
+            ])
+            .unwrap();
+
+        assert_eq!(added[0]["id"], 1);
+        assert_eq!(added[1]["id"], 2);
+        assert_eq!(collection.count(), 2);
+    }
+
+    #[test]
+    fn test_add_all_none_validates_before_inserting() {
+        let mut collection = create_none_collection();
+        add_item(&mut collection, json!({"id": "a", "name": "Existing"}));
+
+        let error = collection
+            .add_all(vec![json!({"id": "b"}), json!({"name": "No id"})])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AddBatchError::MissingId {
+                index: 1,
+                id_key: "id".to_string()
+            }
+        );
+
+        let error = collection
+            .add_all(vec![json!({"id": "b"}), json!({"id": "a"})])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AddBatchError::DuplicateId {
+                index: 1,
+                id: "a".to_string()
+            }
+        );
+
+        let error = collection
+            .add_all(vec![json!({"id": 7}), json!({"id": 7})])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AddBatchError::DuplicateId {
+                index: 1,
+                id: "7".to_string()
+            }
+        );
+
+        assert_eq!(collection.count(), 1);
+        assert!(collection.get("b").is_none());
+    }
+
+    #[test]
+    fn test_upsert_merges_existing_and_inserts_new() {
+        let mut collection = create_none_collection();
+        add_item(
+            &mut collection,
+            json!({"id": "a", "name": "Ada", "profile": {"city": "London", "lang": "en"}}),
+        );
+
+        let merged = collection
+            .upsert(json!({"id": "a", "age": 37, "profile": {"city": "Paris"}}))
+            .unwrap();
+        assert_eq!(
+            merged,
+            json!({"id": "a", "name": "Ada", "age": 37, "profile": {"city": "Paris", "lang": "en"}})
+        );
+
+        let inserted = collection.upsert(json!({"id": 2, "name": "Bob"})).unwrap();
+        assert_eq!(inserted, json!({"id": 2, "name": "Bob"}));
+        assert_eq!(collection.count(), 2);
+
+        assert_eq!(
+            collection.upsert(json!({"name": "No id"})),
+            Err(AddError::MissingId {
+                id_key: "id".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_upsert_int_keeps_provided_ids_ahead_of_generated_ones() {
+        let mut collection = InternalMemoryCollection::new("int_collection", DbConfig::int("id"));
+
+        let stored = collection.upsert(json!({"id": 5, "name": "Five"})).unwrap();
+        assert_eq!(stored["id"], 5);
+
+        let generated = collection.upsert(json!({"name": "Next"})).unwrap();
+        assert_eq!(generated["id"], 6);
+
+        let merged = collection.upsert(json!({"id": 5, "tag": "x"})).unwrap();
+        assert_eq!(merged, json!({"id": 5, "name": "Five", "tag": "x"}));
+        assert_eq!(collection.count(), 2);
+    }
+
+    #[test]
+    fn test_upsert_checks_provided_ids_against_the_id_type() {
+        let mut collection = InternalMemoryCollection::new("int_collection", DbConfig::int("id"));
+
+        // a numeric string is stored as the number and moves the counter past it
+        let stored = collection.upsert(json!({"id": "2", "name": "S"})).unwrap();
+        assert_eq!(stored, json!({"id": 2, "name": "S"}));
+        let generated = collection.add(json!({"name": "Next"})).unwrap();
+        assert_eq!(generated["id"], 3);
+        assert_eq!(collection.get("2").unwrap()["name"], "S");
+
+        for id in [json!(2.7), json!(-1), json!("x"), json!(true)] {
+            assert_eq!(
+                collection.upsert(json!({"id": id.clone()})),
+                Err(AddError::InvalidId { id: id.to_string() })
+            );
+        }
+        assert_eq!(collection.count(), 2);
+
+        let mut collection = InternalMemoryCollection::new("uuid_collection", DbConfig::uuid("id"));
+        assert_eq!(
+            collection.upsert(json!({"id": 7})),
+            Err(AddError::InvalidId {
+                id: "7".to_string()
+            })
+        );
+        assert_eq!(collection.count(), 0);
+
+        // the batch is checked before anything is written
+        let mut collection = InternalMemoryCollection::new("int_collection", DbConfig::int("id"));
+        let error = collection
+            .upsert_batch(json!([{"id": 1}, {"id": "one"}]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AddBatchError::InvalidId {
+                index: 1,
+                id: "\"one\"".to_string()
+            }
+        );
+        assert_eq!(collection.count(), 0);
+    }
+
+    #[test]
+    fn test_upsert_batch_reseeds_and_validates_before_writing() {
+        let mut collection = create_none_collection();
+        let seed = json!([
+            {"id": "a", "name": "Ada"},
+            {"id": "b", "name": "Bob"}
+        ]);
+        collection.upsert_batch(seed.clone()).unwrap();
+        add_item(&mut collection, json!({"id": "c", "name": "Carla"}));
+
+        let stored = collection.upsert_batch(seed).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(collection.count(), 3);
+
+        let error = collection
+            .upsert_batch(json!([{"id": "d"}, {"name": "No id"}]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AddBatchError::MissingId {
+                index: 1,
+                id_key: "id".to_string()
+            }
+        );
+        assert!(collection.get("d").is_none());
+
+        assert_eq!(
+            collection.upsert_batch(json!({"id": "a"})),
+            Err(AddBatchError::NonArrayInput)
+        );
+    }
+
     #[test]
     fn test_add_batch_none_rejects_later_duplicate_but_keeps_following_valid_items() {
         let mut collection = create_none_collection();
@@ -18,21 +18,31 @@ pub(crate) fn parse_compact_schema(value: &Value) -> Result<ParsedCompactSchema,
         return Err("Schema JSON must be an object of field names to type strings".to_string());
     };
 
+    let mut specs = Vec::with_capacity(fields.len());
+    for (field_name, type_value) in fields {
+        let Value::String(type_spec) = type_value else {
+            return Err(format!(
+                "Schema field '{}' must use a string type spec",
+                field_name.trim()
+            ));
+        };
+        specs.push((field_name.as_str(), type_spec.as_str()));
+    }
+
+    parse_compact_fields(&specs)
+}
+
+/// Parse ordered `(field name, type spec)` pairs into a schema and optional ID configuration.
+pub(crate) fn parse_compact_fields(fields: &[(&str, &str)]) -> Result<ParsedCompactSchema, String> {
     let mut schema_fields = IndexMap::new();
     let mut config = None;
 
-    for (field_name, type_value) in fields {
+    for (field_name, type_spec) in fields {
         let field_name = field_name.trim();
         if field_name.is_empty() {
             return Err("Schema field names cannot be empty".to_string());
         }
 
-        let Value::String(type_spec) = type_value else {
-            return Err(format!(
-                "Schema field '{field_name}' must use a string type spec"
-            ));
-        };
-
         let parsed = parse_type_spec(field_name, type_spec)?;
         if let Some(id_config) = parsed.config {
             if config.is_some() {
@@ -201,6 +211,8 @@ fn parse_json_primitive(type_name: &str) -> Result<JsonPrimitive, String> {
         "String" => Ok(JsonPrimitive::String),
         "Object" => Ok(JsonPrimitive::Object),
         "Array" => Ok(JsonPrimitive::Array),
+        "Date" => Ok(JsonPrimitive::Date),
+        "DateTime" => Ok(JsonPrimitive::DateTime),
         _ => Err(format!("Unknown schema type '{type_name}'")),
     }
 }
@@ -223,6 +235,20 @@ mod tests {
         assert!(parsed.schema.fields["email"].nullable);
     }
 
+    #[test]
+    fn parses_temporal_fields() {
+        let parsed =
+            parse_compact_schema(&json!({ "day": "Date!", "created_at": "DateTime" })).unwrap();
+
+        assert_eq!(parsed.schema.fields["day"].ty, JsonPrimitive::Date);
+        assert!(!parsed.schema.fields["day"].nullable);
+        assert_eq!(
+            parsed.schema.fields["created_at"].ty,
+            JsonPrimitive::DateTime
+        );
+        assert!(parsed.schema.fields["created_at"].nullable);
+    }
+
     #[test]
     fn parses_id_markers_with_custom_field_names() {
         let cases = [
@@ -5,7 +5,10 @@ use serde_json::Value;
 ///
 /// This enum represents the coarse-grained primitive kind of a JSON value
 /// encountered while inspecting documents: Null, Bool, Int, Float, String,
-/// Object (map) or Array.
+/// Object (map) or Array. `Date` and `DateTime` are ISO-8601 strings and come
+/// only from declared schemas. `Any` is never inferred from a document; the
+/// query analyzer uses it for values only known at runtime, like nested JSON
+/// paths.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JsonPrimitive {
     /// JSON null
@@ -22,6 +25,12 @@ pub enum JsonPrimitive {
     Object,
     /// JSON array
     Array,
+    /// ISO-8601 calendar date string (`2024-05-01`)
+    Date,
+    /// ISO-8601 date and time string (`2024-05-01T10:30:00Z`)
+    DateTime,
+    /// Any of the above, decided per value at runtime
+    Any,
 }
 
 impl JsonPrimitive {
@@ -58,9 +67,10 @@ impl JsonPrimitive {
 
     /// Promote two primitive types to a common representative for schema merging.
     ///
-    /// Numeric types promote `Int` + `Float` -> `Float`. For different
-    /// non-numeric types the left-hand value is preserved except when it is
-    /// `Null`, in which case the right-hand type is returned.
+    /// Numeric types promote `Int` + `Float` -> `Float`, `Date` + `DateTime`
+    /// -> `DateTime` and `Any` absorbs everything. For different non-numeric types the left-hand value is
+    /// preserved except when it is `Null`, in which case the right-hand type
+    /// is returned.
     ///
     /// # Example
     ///
@@ -79,6 +89,8 @@ impl JsonPrimitive {
         }
         match (a, b) {
             (Int, Float) | (Float, Int) => Float,
+            (Date, DateTime) | (DateTime, Date) => DateTime,
+            (Any, _) | (_, Any) => Any,
             // Different non-numeric types stay as the left (first seen) type.
             // You can change this to a Mixed variant if you prefer.
             (x, y) => {
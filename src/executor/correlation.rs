@@ -0,0 +1,354 @@
+use std::{collections::HashSet, sync::Arc};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    executor::eval::Eval,
+    parser::{
+        analyzer::{
+            AnalyzedCte, AnalyzedIdentifier, AnalyzedJoin, AnalyzedQuery, AnalyzedSetOperation,
+            AnalyzedSource, ScalarResolver,
+        },
+        ast::{Case, Column, Function, Literal, OrderBy, Predicate, ScalarExpr, Subquery, Window},
+    },
+};
+
+/// Replaces the columns a correlated subquery reads from its enclosing query
+/// with their values in the current outer row.
+///
+/// A column is outer when no query level between the subquery root and the
+/// column declares its collection, which mirrors how the analyzer resolved it.
+pub struct Correlation;
+
+impl Correlation {
+    pub fn bind_query(query: &AnalyzedQuery, row: &Map<String, Value>) -> AnalyzedQuery {
+        Self::query(query, row, &HashSet::new())
+    }
+
+    fn query(
+        query: &AnalyzedQuery,
+        row: &Map<String, Value>,
+        inherited: &HashSet<String>,
+    ) -> AnalyzedQuery {
+        let mut bound = inherited.clone();
+        for source in query
+            .collections
+            .iter()
+            .chain(query.joins.iter().map(|j| &j.source))
+        {
+            bound.insert(Self::visible(source).to_string());
+        }
+
+        AnalyzedQuery {
+            ctes: query
+                .ctes
+                .iter()
+                .map(|cte| AnalyzedCte {
+                    query: Self::query(&cte.query, row, inherited),
+                    recursive_terms: Self::set_operations(&cte.recursive_terms, row, inherited),
+                    ..cte.clone()
+                })
+                .collect(),
+            distinct: query.distinct,
+            projection: query
+                .projection
+                .iter()
+                .map(|id| AnalyzedIdentifier {
+                    expression: Self::scalar(&id.expression, row, &bound),
+                    ..id.clone()
+                })
+                .collect(),
+            // FROM/JOIN subqueries do not see their siblings, only enclosing queries
+            collections: query
+                .collections
+                .iter()
+                .map(|source| Self::source(source, row, inherited, &bound))
+                .collect(),
+            joins: query
+                .joins
+                .iter()
+                .map(|join| AnalyzedJoin {
+                    join_type: join.join_type.clone(),
+                    source: Self::source(&join.source, row, inherited, &bound),
+                    predicate: Self::predicate(&join.predicate, row, &bound),
+                })
+                .collect(),
+            criteria: query
+                .criteria
+                .as_ref()
+                .map(|p| Self::predicate(p, row, &bound)),
+            group_by: query
+                .group_by
+                .iter()
+                .map(|e| Self::scalar(e, row, &bound))
+                .collect(),
+            grouping_sets: query.grouping_sets.clone(),
+            having: query
+                .having
+                .as_ref()
+                .map(|p| Self::predicate(p, row, &bound)),
+            order_by: query
+                .order_by
+                .iter()
+                .map(|ob| OrderBy {
+                    expr: Self::scalar(&ob.expr, row, &bound),
+                    ascending: ob.ascending,
+                })
+                .collect(),
+            limit: query.limit,
+            offset: query.offset,
+            set_operations: Self::set_operations(&query.set_operations, row, inherited),
+        }
+    }
+
+    fn set_operations(
+        operations: &[AnalyzedSetOperation],
+        row: &Map<String, Value>,
+        inherited: &HashSet<String>,
+    ) -> Vec<AnalyzedSetOperation> {
+        operations
+            .iter()
+            .map(|operation| AnalyzedSetOperation {
+                query: Self::query(&operation.query, row, inherited),
+                ..operation.clone()
+            })
+            .collect()
+    }
+
+    fn visible(source: &AnalyzedSource) -> &str {
+        match source {
+            AnalyzedSource::Table { visible, .. }
+            | AnalyzedSource::Subquery { visible, .. }
+            | AnalyzedSource::Cte { visible, .. }
+            | AnalyzedSource::Unnest { visible, .. } => visible,
+        }
+    }
+
+    fn source(
+        source: &AnalyzedSource,
+        row: &Map<String, Value>,
+        inherited: &HashSet<String>,
+        bound: &HashSet<String>,
+    ) -> AnalyzedSource {
+        match source {
+            AnalyzedSource::Subquery { visible, query } => AnalyzedSource::Subquery {
+                visible: visible.clone(),
+                query: Box::new(Self::query(query, row, inherited)),
+            },
+            // UNNEST is lateral, so it also sees its sibling sources
+            AnalyzedSource::Unnest {
+                visible,
+                expr,
+                column,
+            } => AnalyzedSource::Unnest {
+                visible: visible.clone(),
+                expr: Self::scalar(expr, row, bound),
+                column: column.clone(),
+            },
+            other => other.clone(),
+        }
+    }
+
+    fn subquery(
+        subquery: &Subquery,
+        row: &Map<String, Value>,
+        bound: &HashSet<String>,
+    ) -> Subquery {
+        Subquery {
+            query: subquery.query.clone(),
+            analyzed: subquery
+                .analyzed
+                .as_ref()
+                .map(|analyzed| Arc::new(Self::query(analyzed, row, bound))),
+        }
+    }
+
+    fn predicate(
+        predicate: &Predicate,
+        row: &Map<String, Value>,
+        bound: &HashSet<String>,
+    ) -> Predicate {
+        let scalar = |e: &ScalarExpr| Self::scalar(e, row, bound);
+        match predicate {
+            Predicate::And(list) => Predicate::And(
+                list.iter()
+                    .map(|p| Self::predicate(p, row, bound))
+                    .collect(),
+            ),
+            Predicate::Or(list) => Predicate::Or(
+                list.iter()
+                    .map(|p| Self::predicate(p, row, bound))
+                    .collect(),
+            ),
+            Predicate::Not(p) => Predicate::Not(Box::new(Self::predicate(p, row, bound))),
+            Predicate::Compare { left, op, right } => Predicate::Compare {
+                left: scalar(left),
+                op: *op,
+                right: scalar(right),
+            },
+            Predicate::IsNull { expr, negated } => Predicate::IsNull {
+                expr: scalar(expr),
+                negated: *negated,
+            },
+            Predicate::InList {
+                expr,
+                list,
+                negated,
+            } => Predicate::InList {
+                expr: scalar(expr),
+                list: list.iter().map(scalar).collect(),
+                negated: *negated,
+            },
+            Predicate::Like {
+                expr,
+                pattern,
+                negated,
+            } => Predicate::Like {
+                expr: scalar(expr),
+                pattern: scalar(pattern),
+                negated: *negated,
+            },
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Predicate::InSubquery {
+                expr: scalar(expr),
+                subquery: Self::subquery(subquery, row, bound),
+                negated: *negated,
+            },
+            Predicate::Exists { subquery, negated } => Predicate::Exists {
+                subquery: Self::subquery(subquery, row, bound),
+                negated: *negated,
+            },
+            Predicate::Const3(t) => Predicate::Const3(*t),
+        }
+    }
+
+    fn scalar(expr: &ScalarExpr, row: &Map<String, Value>, bound: &HashSet<String>) -> ScalarExpr {
+        match expr {
+            ScalarExpr::Column(Column::WithCollection { collection, name })
+                if !bound.contains(collection) =>
+            {
+                match row.get(&format!("{}.{}", collection, name)) {
+                    // arrays and objects have no literal form and compare as NULL
+                    Some(value) => ScalarResolver::value_to_scalar(value)
+                        .unwrap_or(ScalarExpr::Literal(Literal::Null)),
+                    None => expr.clone(),
+                }
+            }
+            ScalarExpr::JsonPath {
+                column: Column::WithCollection { collection, name },
+                path,
+            } if !bound.contains(collection) => {
+                match row.get(&format!("{}.{}", collection, name)) {
+                    Some(value) => Eval::path_value(value, path)
+                        .and_then(ScalarResolver::value_to_scalar)
+                        .unwrap_or(ScalarExpr::Literal(Literal::Null)),
+                    None => expr.clone(),
+                }
+            }
+            ScalarExpr::Function(f) => ScalarExpr::Function(Function {
+                args: f.args.iter().map(|a| Self::scalar(a, row, bound)).collect(),
+                ..f.clone()
+            }),
+            ScalarExpr::Args(args) => {
+                ScalarExpr::Args(args.iter().map(|a| Self::scalar(a, row, bound)).collect())
+            }
+            ScalarExpr::Subquery(subquery) => {
+                ScalarExpr::Subquery(Self::subquery(subquery, row, bound))
+            }
+            ScalarExpr::Case(case) => ScalarExpr::Case(Case {
+                branches: case
+                    .branches
+                    .iter()
+                    .map(|(condition, result)| {
+                        (
+                            Self::predicate(condition, row, bound),
+                            Self::scalar(result, row, bound),
+                        )
+                    })
+                    .collect(),
+                otherwise: case
+                    .otherwise
+                    .as_deref()
+                    .map(|otherwise| Box::new(Self::scalar(otherwise, row, bound))),
+            }),
+            ScalarExpr::Binary { left, op, right } => ScalarExpr::Binary {
+                left: Box::new(Self::scalar(left, row, bound)),
+                op: *op,
+                right: Box::new(Self::scalar(right, row, bound)),
+            },
+            ScalarExpr::Negate(expr) => {
+                ScalarExpr::Negate(Box::new(Self::scalar(expr, row, bound)))
+            }
+            ScalarExpr::Window(window) => ScalarExpr::Window(Window {
+                function: Function {
+                    args: window
+                        .function
+                        .args
+                        .iter()
+                        .map(|a| Self::scalar(a, row, bound))
+                        .collect(),
+                    ..window.function.clone()
+                },
+                partition_by: window
+                    .partition_by
+                    .iter()
+                    .map(|e| Self::scalar(e, row, bound))
+                    .collect(),
+                order_by: window
+                    .order_by
+                    .iter()
+                    .map(|ob| OrderBy {
+                        expr: Self::scalar(&ob.expr, row, bound),
+                        ascending: ob.ascending,
+                    })
+                    .collect(),
+                frame: window.frame,
+            }),
+            _ => expr.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::{Map, Value, json};
+
+    use crate::{
+        executor::correlation::Correlation,
+        parser::ast::{Column, ComparatorOp, Literal, Predicate, ScalarExpr},
+    };
+
+    fn col(collection: &str, name: &str) -> ScalarExpr {
+        ScalarExpr::Column(Column::WithCollection {
+            collection: collection.into(),
+            name: name.into(),
+        })
+    }
+
+    #[test]
+    fn only_columns_of_undeclared_collections_are_bound() {
+        let row: Map<String, Value> = json!({ "c.id": 7, "o.id": 1 }).as_object().unwrap().clone();
+        let bound: HashSet<String> = ["o".to_string()].into();
+        let predicate = Predicate::Compare {
+            left: col("o", "id"),
+            op: ComparatorOp::Eq,
+            right: col("c", "id"),
+        };
+
+        let result = Correlation::predicate(&predicate, &row, &bound);
+
+        assert_eq!(
+            result,
+            Predicate::Compare {
+                left: col("o", "id"),
+                op: ComparatorOp::Eq,
+                right: ScalarExpr::Literal(Literal::Int(7)),
+            }
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use serde_json::Value;
+
+use crate::{
+    Db,
+    database::validate_schema_config,
+    executor::plan_executor::Executor,
+    parser::analyzer::{AnalyzedCreateCollection, AnalyzedDropCollection, AnalyzerError},
+};
+
+pub struct CreateCollectionExecutor {
+    create: AnalyzedCreateCollection,
+}
+
+impl Executor for CreateCollectionExecutor {
+    fn execute(&self, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        if db.get(&self.create.name).is_some() {
+            if self.create.if_not_exists {
+                return Ok(vec![]);
+            }
+            return Err(AnalyzerError::Other(format!(
+                "Collection {} already exists",
+                self.create.name
+            )));
+        }
+
+        let config = self
+            .create
+            .config
+            .clone()
+            .unwrap_or_else(|| db.get_config());
+        validate_schema_config(&self.create.schema, &config).map_err(AnalyzerError::Other)?;
+
+        let collection = db.create_with_config(&self.create.name, config);
+        collection
+            .set_schema(self.create.schema.clone())
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+        db.infer_all_references();
+
+        Ok(vec![])
+    }
+}
+
+impl CreateCollectionExecutor {
+    pub fn new(create: AnalyzedCreateCollection) -> Self {
+        Self { create }
+    }
+}
+
+pub struct DropCollectionExecutor {
+    drop: AnalyzedDropCollection,
+}
+
+impl Executor for DropCollectionExecutor {
+    fn execute(&self, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        if !db.drop_collection(&self.drop.name) && !self.drop.if_exists {
+            return Err(AnalyzerError::UnknownCollection(self.drop.name.clone()));
+        }
+
+        Ok(vec![])
+    }
+}
+
+impl DropCollectionExecutor {
+    pub fn new(drop: AnalyzedDropCollection) -> Self {
+        Self { drop }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DbConfig, IdType, JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+    };
+    use indexmap::IndexMap;
+
+    fn users(config: Option<DbConfig>) -> AnalyzedCreateCollection {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "user_id".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Int,
+                nullable: false,
+            },
+        );
+        fields.insert(
+            "name".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::String,
+                nullable: false,
+            },
+        );
+        AnalyzedCreateCollection {
+            name: "users".into(),
+            if_not_exists: false,
+            schema: SchemaDict { fields },
+            config,
+        }
+    }
+
+    #[test]
+    fn creates_collection_with_config_and_schema() {
+        let db = Db::new();
+
+        let out = CreateCollectionExecutor::new(users(Some(DbConfig::int("user_id"))))
+            .execute(&db)
+            .unwrap();
+
+        assert!(out.is_empty());
+        let collection = db.get("users").unwrap();
+        assert_eq!(collection.get_config().unwrap().id_type, IdType::Int);
+        assert_eq!(db.schema_of("users").unwrap().fields.len(), 2);
+    }
+
+    #[test]
+    fn existing_collection_errors_unless_if_not_exists() {
+        let db = Db::new();
+        db.create("users");
+
+        let err = CreateCollectionExecutor::new(users(None)).execute(&db);
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("already exists"))
+        );
+
+        let mut create = users(None);
+        create.if_not_exists = true;
+        assert!(CreateCollectionExecutor::new(create).execute(&db).is_ok());
+        // the existing collection is left untouched
+        assert!(
+            db.schema_of("users")
+                .is_none_or(|schema| !schema.fields.contains_key("name"))
+        );
+    }
+
+    #[test]
+    fn schema_must_fit_the_database_config() {
+        // default config keys UUID ids on "id", which the schema does not declare
+        let db = Db::new();
+
+        let err = CreateCollectionExecutor::new(users(None)).execute(&db);
+
+        assert!(matches!(err, Err(AnalyzerError::Other(message)) if message.contains("'id'")));
+        assert!(db.get("users").is_none());
+    }
+
+    #[test]
+    fn drop_collection_respects_if_exists() {
+        let db = Db::new();
+        db.create("users");
+
+        let drop = AnalyzedDropCollection {
+            name: "users".into(),
+            if_exists: false,
+        };
+        assert!(
+            DropCollectionExecutor::new(drop.clone())
+                .execute(&db)
+                .is_ok()
+        );
+        assert!(db.get("users").is_none());
+
+        let err = DropCollectionExecutor::new(drop).execute(&db);
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "users"));
+
+        let drop = AnalyzedDropCollection {
+            name: "users".into(),
+            if_exists: true,
+        };
+        assert!(DropCollectionExecutor::new(drop).execute(&db).is_ok());
+    }
+}
@@ -0,0 +1,110 @@
+use serde_json::Value;
+
+use crate::{
+    Db,
+    executor::{helpers::Helpers, plan_executor::Executor},
+    parser::analyzer::{AnalyzedDelete, AnalyzerError},
+};
+
+pub struct DeleteExecutor {
+    delete: AnalyzedDelete,
+}
+
+impl Executor for DeleteExecutor {
+    fn execute(&self, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        let collection = db
+            .get(&self.delete.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(self.delete.collection.clone()))?;
+        let config = collection
+            .get_config()
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+
+        let documents = collection
+            .get_all()
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+        let rows = Helpers::matching_rows(
+            documents,
+            &self.delete.collection,
+            &config.id_key,
+            self.delete.criteria.as_ref(),
+            db,
+        )?;
+
+        let mut deleted = Vec::with_capacity(rows.len());
+        for (id, _) in rows {
+            if let Some(document) = collection
+                .delete(&id)
+                .map_err(|error| AnalyzerError::Other(error.to_string()))?
+            {
+                deleted.push(document);
+            }
+        }
+
+        Helpers::dml_output(&self.delete.collection, &self.delete.returning, &deleted)
+    }
+}
+
+impl DeleteExecutor {
+    pub fn new(delete: AnalyzedDelete) -> Self {
+        Self { delete }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbConfig;
+    use crate::parser::ast::{Column, Predicate, ScalarExpr};
+    use serde_json::json;
+
+    fn mk_db() -> Db {
+        let db = Db::new_with_config(DbConfig::uuid("key"));
+        let people = db.create("people");
+        people
+            .add_batch(json!([
+                { "key": "a", "name": "Ada", "age": 37 },
+                { "key": "g", "name": "Grace", "age": null },
+                { "key": "l", "name": "Linus" }
+            ]))
+            .unwrap();
+        db
+    }
+
+    #[test]
+    fn deletes_rows_where_predicate_is_true() {
+        let db = mk_db();
+        let delete = AnalyzedDelete {
+            collection: "people".into(),
+            criteria: Some(Predicate::IsNull {
+                expr: ScalarExpr::Column(Column::WithCollection {
+                    collection: "people".into(),
+                    name: "age".into(),
+                }),
+                negated: false,
+            }),
+            returning: vec![],
+        };
+
+        let out = DeleteExecutor::new(delete).execute(&db).unwrap();
+
+        assert_eq!(out, vec![json!({ "affected_rows": 2 })]);
+        let people = db.get("people").unwrap();
+        assert_eq!(people.count().unwrap(), 1);
+        assert!(people.exists("a").unwrap());
+    }
+
+    #[test]
+    fn deletes_everything_without_predicate() {
+        let db = mk_db();
+        let delete = AnalyzedDelete {
+            collection: "people".into(),
+            criteria: None,
+            returning: vec![],
+        };
+
+        let out = DeleteExecutor::new(delete).execute(&db).unwrap();
+
+        assert_eq!(out, vec![json!({ "affected_rows": 3 })]);
+        assert_eq!(db.get("people").unwrap().count().unwrap(), 0);
+    }
+}
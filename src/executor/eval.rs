@@ -1,68 +1,484 @@
 use std::cmp::Ordering;
 
+use ordered_float::NotNan;
 use serde_json::{Map, Value};
 
-use crate::parser::{
-    analyzer::LiteralResolver,
-    ast::{Column, ComparatorOp, Function, Literal, Predicate, ScalarExpr, Truth},
+use crate::{
+    JsonPrimitive,
+    executor::{helpers::Helpers, random::Random},
+    parser::{
+        QueryParser,
+        analyzer::{
+            AnalyzerError, CastResolver, DateUnit, LiteralResolver, Temporal, TemporalResolver,
+        },
+        ast::{
+            ArithmeticOp, Column, ComparatorOp, Function, JsonPath, Literal, PathSegment,
+            Predicate, ScalarExpr, Truth,
+        },
+    },
 };
 
 pub struct Eval;
 
 impl Eval {
-    pub fn eval_scalar(expr: &ScalarExpr, row: &Map<String, Value>) -> Value {
-        match expr {
-            ScalarExpr::Literal(l) => match l {
-                Literal::Null => Value::Null,
-                Literal::Bool(b) => Value::Bool(*b),
-                Literal::Int(i) => Self::json_i(*i),
-                Literal::Float(f) => Self::json_f(f.into_inner()),
-                Literal::String(s) => Value::String(s.clone()),
-            },
-            ScalarExpr::Column(c) => {
-                let key = match c {
-                    Column::WithCollection { collection, name } => {
-                        format!("{}.{}", collection, name)
-                    }
-                    Column::Name { name } => name.clone(), // analyzer should qualify earlier; kept for safety
-                };
-                row.get(&key).cloned().unwrap_or(Value::Null)
-            }
-            ScalarExpr::Function(f) => Self::eval_scalar_function(f, row),
+    /// Longest string LPAD and RPAD will build.
+    pub const MAX_PAD_LENGTH: i64 = 10_000_000;
+
+    pub fn eval_scalar(
+        expr: &ScalarExpr,
+        row: &Map<String, Value>,
+    ) -> Result<Value, AnalyzerError> {
+        Ok(match expr {
+            ScalarExpr::Literal(l) => Self::literal_value(l),
+            ScalarExpr::Column(c) => Self::column_value(c, row).cloned().unwrap_or(Value::Null),
+            ScalarExpr::JsonPath { column, path } => Self::column_value(column, row)
+                .and_then(|value| Self::path_value(value, path))
+                .cloned()
+                .unwrap_or(Value::Null),
+            ScalarExpr::Function(f) => Self::eval_scalar_function(f, row)?,
             ScalarExpr::WildCard
             | ScalarExpr::WildCardWithCollection(_)
             | ScalarExpr::Parameter
+            | ScalarExpr::NamedParameter(_)
             | ScalarExpr::Args(_) => Value::Null, // should not appear after analysis
+            // the plan executor replaces subqueries with their result before evaluation,
+            // and windows with the column their `Window` node computes
+            ScalarExpr::Subquery(_) | ScalarExpr::Window(_) => Value::Null,
+            ScalarExpr::Case(case) => {
+                for (condition, result) in &case.branches {
+                    if Self::eval_predicate3(condition, row)? == Truth::True {
+                        return Self::eval_scalar(result, row);
+                    }
+                }
+                match case.otherwise.as_deref() {
+                    Some(otherwise) => Self::eval_scalar(otherwise, row)?,
+                    None => Value::Null,
+                }
+            }
+            ScalarExpr::Binary { left, op, right } => {
+                let l = Self::value_literal(&Self::eval_scalar(left, row)?);
+                let r = Self::value_literal(&Self::eval_scalar(right, row)?);
+                Self::literal_value(&LiteralResolver::eval_arithmetic(&l, *op, &r))
+            }
+            ScalarExpr::Negate(expr) => {
+                let value = Self::value_literal(&Self::eval_scalar(expr, row)?);
+                Self::literal_value(&LiteralResolver::eval_negate(&value))
+            }
+        })
+    }
+
+    fn column_value<'a>(column: &Column, row: &'a Map<String, Value>) -> Option<&'a Value> {
+        match column {
+            Column::WithCollection { collection, name } => {
+                row.get(&format!("{}.{}", collection, name))
+            }
+            Column::Name { name } => row.get(name), // analyzer should qualify earlier; kept for safety
+        }
+    }
+
+    /// Walks `path` into `value`; `None` when a key or index is missing.
+    pub fn path_value<'a>(value: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+        path.iter().try_fold(value, |value, segment| match segment {
+            PathSegment::Key(key) => value.as_object()?.get(key),
+            PathSegment::Index(index) => value.as_array()?.get(*index),
+        })
+    }
+
+    fn literal_value(literal: &Literal) -> Value {
+        match literal {
+            Literal::Null => Value::Null,
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Int(i) => Self::json_i(*i),
+            Literal::Float(f) => Self::json_f(f.into_inner()),
+            Literal::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    /// Literal for a scalar JSON value; arrays and objects become NULL.
+    fn value_literal(value: &Value) -> Literal {
+        match value {
+            Value::Bool(b) => Literal::Bool(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => Literal::Int(i),
+                None => n
+                    .as_f64()
+                    .and_then(|f| NotNan::new(f).ok())
+                    .map_or(Literal::Null, Literal::Float),
+            },
+            Value::String(s) => Literal::String(s.clone()),
+            Value::Null | Value::Array(_) | Value::Object(_) => Literal::Null,
         }
     }
 
-    fn eval_scalar_function(f: &Function, row: &Map<String, Value>) -> Value {
+    fn eval_scalar_function(
+        f: &Function,
+        row: &Map<String, Value>,
+    ) -> Result<Value, AnalyzerError> {
         let lname = f.name.to_ascii_lowercase();
-        let args: Vec<Value> = f.args.iter().map(|a| Self::eval_scalar(a, row)).collect();
-        match (lname.as_str(), args.as_slice()) {
+        let args: Vec<Value> = f
+            .args
+            .iter()
+            .map(|a| Self::eval_scalar(a, row))
+            .collect::<Result<_, _>>()?;
+        Ok(match (lname.as_str(), args.as_slice()) {
             ("upper", [Value::String(s)]) => Value::String(s.to_uppercase()),
             ("lower", [Value::String(s)]) => Value::String(s.to_lowercase()),
             ("trim", [Value::String(s)]) => Value::String(s.trim().to_string()),
             ("length", [Value::String(s)]) => Self::json_i(s.chars().count() as i64),
+            (
+                "abs" | "sign" | "floor" | "ceil" | "ceiling" | "round" | "mod" | "sqrt" | "power"
+                | "pow",
+                args,
+            ) => Self::eval_math_function(&lname, args).unwrap_or(Value::Null),
+            ("random", []) => Self::json_f(Random::next_f64()),
+            ("greatest" | "least", args) => {
+                let ordering = match lname.as_str() {
+                    "greatest" => Ordering::Greater,
+                    _ => Ordering::Less,
+                };
+                args.iter()
+                    .filter(|value| !value.is_null())
+                    .reduce(|best, value| {
+                        match Helpers::cmp_json_for_sort(value, best, true) == ordering {
+                            true => value,
+                            false => best,
+                        }
+                    })
+                    .cloned()
+                    .unwrap_or(Value::Null)
+            }
+            ("now", []) => Value::String(Temporal::now().format()),
+            (
+                "date_trunc" | "extract" | "date_part" | "date_add" | "date_diff" | "to_char"
+                | "strftime",
+                args,
+            ) => Self::eval_temporal_function(&lname, args).unwrap_or(Value::Null),
+            // CAST of a constant is checked up front, a row value that does not convert fails
+            ("cast", [value, Value::String(ty)]) => match CastResolver::target(ty) {
+                Some(to) => CastResolver::cast(value, to).ok_or_else(|| {
+                    AnalyzerError::Other(format!("cannot cast {} to {:?}", value, to))
+                })?,
+                None => Value::Null,
+            },
+            ("try_cast", [value, Value::String(ty)]) => CastResolver::target(ty)
+                .and_then(|to| CastResolver::cast(value, to))
+                .unwrap_or(Value::Null),
+            ("coalesce", args) => args
+                .iter()
+                .find(|value| !value.is_null())
+                .cloned()
+                .unwrap_or(Value::Null),
+            // CONCAT follows `||`: numbers and booleans print as text, NULL wins
+            ("concat", args) => Self::literal_value(&args.iter().fold(
+                Literal::String(String::new()),
+                |text, value| {
+                    LiteralResolver::eval_arithmetic(
+                        &text,
+                        ArithmeticOp::Concat,
+                        &Self::value_literal(value),
+                    )
+                },
+            )),
+            ("lpad" | "rpad", [_, Value::Number(length), ..])
+                if length.as_i64().is_some_and(|n| n > Self::MAX_PAD_LENGTH) =>
+            {
+                return Err(AnalyzerError::Other(format!(
+                    "{} length {} is larger than the limit of {}",
+                    lname,
+                    length,
+                    Self::MAX_PAD_LENGTH
+                )));
+            }
+            (
+                "substr" | "substring" | "replace" | "position" | "strpos" | "ltrim" | "rtrim"
+                | "lpad" | "rpad" | "left" | "right" | "split_part" | "starts_with" | "ends_with"
+                | "regexp_replace",
+                args,
+            ) => Self::eval_string_function(&lname, args).unwrap_or(Value::Null),
+            ("json_extract", [doc, Value::String(path)]) => Self::parse_json_path(path)
+                .and_then(|path| Self::path_value(doc, &path))
+                .cloned()
+                .unwrap_or(Value::Null),
+            ("json_array_length", [Value::Array(items)]) => Self::json_i(items.len() as i64),
+            ("json_keys", [Value::Object(map)]) => {
+                Value::Array(map.keys().cloned().map(Value::String).collect())
+            }
+            ("json_type", [value]) => Value::String(Self::json_type_name(value).into()),
+            ("json_contains", [Value::Array(_), Value::Null]) => Value::Null,
+            ("json_contains", [Value::Array(items), value]) => {
+                Value::Bool(items.iter().any(|item| Self::value_equal(item, value)))
+            }
+            ("json_object", pairs) if pairs.len() % 2 == 0 => {
+                let mut object = Map::new();
+                for pair in pairs.chunks(2) {
+                    let Value::String(key) = &pair[0] else {
+                        return Ok(Value::Null);
+                    };
+                    object.insert(key.clone(), pair[1].clone());
+                }
+                Value::Object(object)
+            }
+            ("json_array", items) => Value::Array(items.to_vec()),
             _ => Value::Null, // aggregates are not evaluated here; they are handled by Aggregate executor
+        })
+    }
+
+    /// Math functions keep Int or Float like the analyzer types them; `None`
+    /// (NULL) for NULL or non-numeric arguments, overflow and undefined results.
+    fn eval_math_function(name: &str, args: &[Value]) -> Option<Value> {
+        let literals: Vec<Literal> = args.iter().map(Self::value_literal).collect();
+        let float = |literal: &Literal| match literal {
+            Literal::Int(i) => Some(*i as f64),
+            Literal::Float(f) => Some(f.into_inner()),
+            _ => None,
+        };
+
+        let result = match (name, literals.as_slice()) {
+            ("abs", [Literal::Int(i)]) => Literal::Int(i.checked_abs()?),
+            ("sign", [Literal::Int(i)]) => Literal::Int(i.signum()),
+            ("floor" | "ceil" | "ceiling" | "round", [Literal::Int(i)]) => Literal::Int(*i),
+            ("round", [Literal::Int(i), Literal::Int(digits)]) => match *digits {
+                0.. => Literal::Int(*i),
+                // round half away from zero to tens, hundreds, ...
+                _ => {
+                    let unit = 10i64.checked_pow(digits.unsigned_abs() as u32)?;
+                    let quotient = i / unit
+                        + match (i % unit).unsigned_abs() * 2 >= unit.unsigned_abs() {
+                            true => i.signum(),
+                            false => 0,
+                        };
+                    Literal::Int(quotient.checked_mul(unit)?)
+                }
+            },
+            ("mod", [a, b]) => LiteralResolver::eval_arithmetic(a, ArithmeticOp::Mod, b),
+            (_, [Literal::Float(f)]) => {
+                let f = f.into_inner();
+                Literal::Float(
+                    NotNan::new(match name {
+                        "abs" => f.abs(),
+                        "sign" if f == 0.0 => 0.0,
+                        "sign" => f.signum(),
+                        "floor" => f.floor(),
+                        "ceil" | "ceiling" => f.ceil(),
+                        "round" => f.round(),
+                        "sqrt" if f >= 0.0 => f.sqrt(),
+                        _ => return None,
+                    })
+                    .ok()?,
+                )
+            }
+            ("round", [Literal::Float(f), Literal::Int(digits)]) => {
+                let scale = 10f64.powi(i32::try_from(*digits).ok()?);
+                Literal::Float(NotNan::new((f.into_inner() * scale).round() / scale).ok()?)
+            }
+            ("sqrt", [value]) => {
+                let f = float(value)?;
+                Literal::Float(NotNan::new(f.sqrt()).ok().filter(|_| f >= 0.0)?)
+            }
+            ("power" | "pow", [base, exponent]) => {
+                let result = float(base)?.powf(float(exponent)?);
+                Literal::Float(NotNan::new(result).ok().filter(|r| r.is_finite())?)
+            }
+            _ => return None,
+        };
+        Some(Self::literal_value(&result))
+    }
+
+    /// Date functions over ISO-8601 strings; `None` (NULL) for NULL arguments,
+    /// strings that are not dates, unknown units and invalid formats.
+    fn eval_temporal_function(name: &str, args: &[Value]) -> Option<Value> {
+        let text = |i: usize| args.get(i).and_then(Value::as_str);
+        let temporal = |i: usize| text(i).and_then(Temporal::parse);
+        let unit = |i: usize| text(i).and_then(DateUnit::parse);
+
+        let result = match name {
+            "date_trunc" => Value::String(temporal(1)?.truncate(unit(0)?)?.format()),
+            "extract" | "date_part" => Self::json_i(temporal(1)?.extract(unit(0)?)),
+            "date_add" => {
+                let unit = match args.get(2) {
+                    Some(_) => unit(2)?,
+                    None => DateUnit::Day,
+                };
+                let amount = args.get(1)?.as_i64()?;
+                Value::String(temporal(0)?.add(amount, unit)?.format())
+            }
+            "date_diff" => Self::json_i(Temporal::diff(unit(0)?, temporal(1)?, temporal(2)?)?),
+            "to_char" => Value::String(temporal(0)?.format_with(text(1)?)?),
+            "strftime" => Value::String(temporal(1)?.format_with(text(0)?)?),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// String functions with fixed signatures; `None` (NULL) when an argument
+    /// is NULL or not of the expected type. Positions count characters from 1.
+    fn eval_string_function(name: &str, args: &[Value]) -> Option<Value> {
+        let text = |i: usize| args.get(i).and_then(Value::as_str);
+        let int = |i: usize| args.get(i).and_then(Value::as_i64);
+        // an optional trailing argument that is present must still be valid
+        let optional_text = |i: usize, default: &'static str| match args.get(i) {
+            Some(value) => value.as_str(),
+            None => Some(default),
+        };
+
+        let s = text(0)?;
+        let result = match name {
+            "substr" | "substring" => {
+                let start = int(1)?;
+                let end = match args.get(2) {
+                    Some(_) if int(2)? < 0 => return None,
+                    Some(_) => start.saturating_add(int(2)?),
+                    None => i64::MAX,
+                };
+                let from = start.max(1);
+                Value::String(
+                    s.chars()
+                        .skip((from - 1) as usize)
+                        .take((end - from).max(0) as usize)
+                        .collect(),
+                )
+            }
+            "replace" => match text(1)? {
+                "" => Value::String(s.to_string()),
+                from => Value::String(s.replace(from, text(2)?)),
+            },
+            "position" => Self::json_i(Self::char_position(s, text(1)?)),
+            "strpos" => Self::json_i(Self::char_position(text(1)?, s)),
+            "ltrim" | "rtrim" => {
+                let trim = match args.get(1) {
+                    Some(_) => {
+                        let characters = text(1)?;
+                        let matches = |c: char| characters.contains(c);
+                        match name {
+                            "ltrim" => s.trim_start_matches(matches),
+                            _ => s.trim_end_matches(matches),
+                        }
+                    }
+                    None if name == "ltrim" => s.trim_start(),
+                    None => s.trim_end(),
+                };
+                Value::String(trim.to_string())
+            }
+            "lpad" | "rpad" => {
+                let length = int(1)?.max(0) as usize;
+                let fill = optional_text(2, " ")?;
+                let mut chars: Vec<char> = s.chars().take(length).collect();
+                let padding: Vec<char> = fill
+                    .chars()
+                    .cycle()
+                    .take(match fill.is_empty() {
+                        true => 0,
+                        false => length - chars.len(),
+                    })
+                    .collect();
+                if name == "lpad" {
+                    chars.splice(0..0, padding);
+                } else {
+                    chars.extend(padding);
+                }
+                Value::String(chars.into_iter().collect())
+            }
+            "left" | "right" => {
+                let count = int(1)?;
+                let total = s.chars().count() as i64;
+                // a negative count drops that many characters from the other end
+                let keep = match count {
+                    0.. => count.min(total),
+                    _ => (total + count).max(0),
+                } as usize;
+                let skip = match name {
+                    "left" => 0,
+                    _ => total as usize - keep,
+                };
+                Value::String(s.chars().skip(skip).take(keep).collect())
+            }
+            "split_part" => {
+                let delimiter = text(1)?;
+                let n = int(2)?;
+                let parts: Vec<&str> = match delimiter {
+                    "" => vec![s],
+                    _ => s.split(delimiter).collect(),
+                };
+                // negative positions count from the end, 0 has no part
+                let index = match n {
+                    0 => return None,
+                    1.. => n - 1,
+                    _ => parts.len() as i64 + n,
+                };
+                let part = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| parts.get(index))
+                    .copied()
+                    .unwrap_or_default();
+                Value::String(part.to_string())
+            }
+            "starts_with" => Value::Bool(s.starts_with(text(1)?)),
+            "ends_with" => Value::Bool(s.ends_with(text(1)?)),
+            "regexp_replace" => {
+                let flags = optional_text(3, "")?;
+                if !flags.chars().all(|flag| matches!(flag, 'g' | 'i')) {
+                    return None;
+                }
+                let regex = regex::RegexBuilder::new(text(1)?)
+                    .case_insensitive(flags.contains('i'))
+                    .build()
+                    .ok()?;
+                let replacement = text(2)?;
+                Value::String(match flags.contains('g') {
+                    true => regex.replace_all(s, replacement).into_owned(),
+                    false => regex.replace(s, replacement).into_owned(),
+                })
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// 1-based character position of `needle` in `haystack`, 0 when absent.
+    fn char_position(needle: &str, haystack: &str) -> i64 {
+        haystack
+            .find(needle)
+            .map_or(0, |at| haystack[..at].chars().count() as i64 + 1)
+    }
+
+    /// `$`, `$.a.b`, `$.items[0]`; `None` for anything else.
+    fn parse_json_path(path: &str) -> Option<Vec<PathSegment>> {
+        let mut parser = QueryParser::new(path.strip_prefix('$')?);
+        let segments = JsonPath::parse_segments(&mut parser).ok()?;
+        parser.eof().then_some(segments)
+    }
+
+    fn json_type_name(value: &Value) -> &'static str {
+        match JsonPrimitive::of_value(value) {
+            JsonPrimitive::Null | JsonPrimitive::Any => "null",
+            JsonPrimitive::Bool => "bool",
+            JsonPrimitive::Int => "int",
+            JsonPrimitive::Float => "float",
+            JsonPrimitive::String | JsonPrimitive::Date | JsonPrimitive::DateTime => "string",
+            JsonPrimitive::Object => "object",
+            JsonPrimitive::Array => "array",
         }
     }
 
-    pub fn eval_predicate3(predicate: &Predicate, row: &Map<String, Value>) -> Truth {
-        match predicate {
-            Predicate::And(v) => v
-                .iter()
-                .fold(Truth::True, |acc, x| acc.and(Self::eval_predicate3(x, row))),
-            Predicate::Or(v) => v
-                .iter()
-                .fold(Truth::False, |acc, x| acc.or(Self::eval_predicate3(x, row))),
+    pub fn eval_predicate3(
+        predicate: &Predicate,
+        row: &Map<String, Value>,
+    ) -> Result<Truth, AnalyzerError> {
+        Ok(match predicate {
+            Predicate::And(v) => v.iter().try_fold(Truth::True, |acc, x| {
+                Ok::<_, AnalyzerError>(acc.and(Self::eval_predicate3(x, row)?))
+            })?,
+            Predicate::Or(v) => v.iter().try_fold(Truth::False, |acc, x| {
+                Ok::<_, AnalyzerError>(acc.or(Self::eval_predicate3(x, row)?))
+            })?,
+            Predicate::Not(p) => Self::eval_predicate3(p, row)?.not(),
             Predicate::Compare { left, op, right } => {
-                let l = Self::eval_scalar(left, row);
-                let r = Self::eval_scalar(right, row);
+                let l = Self::eval_scalar(left, row)?;
+                let r = Self::eval_scalar(right, row)?;
                 Self::lit_cmp3(&l, *op, &r)
             }
             Predicate::IsNull { expr, negated } => {
-                let v = Self::eval_scalar(expr, row);
+                let v = Self::eval_scalar(expr, row)?;
                 let t = if v.is_null() {
                     Truth::True
                 } else {
@@ -75,27 +491,12 @@ impl Eval {
                 list,
                 negated,
             } => {
-                let v = Self::eval_scalar(expr, row);
-                let mut found = false;
-                let mut has_null = false;
-                for e in list {
-                    let ev = Self::eval_scalar(e, row);
-                    if ev.is_null() {
-                        has_null = true;
-                        continue;
-                    }
-                    if Self::value_equal(&v, &ev) {
-                        found = true;
-                        break;
-                    }
-                }
-                let t = if found {
-                    Truth::True
-                } else if has_null {
-                    Truth::Unknown
-                } else {
-                    Truth::False
-                };
+                let v = Self::eval_scalar(expr, row)?;
+                let candidates: Vec<Value> = list
+                    .iter()
+                    .map(|e| Self::eval_scalar(e, row))
+                    .collect::<Result<_, _>>()?;
+                let t = Self::eval_in3(&v, &candidates);
                 if *negated { t.not() } else { t }
             }
             Predicate::Like {
@@ -103,8 +504,8 @@ impl Eval {
                 pattern,
                 negated,
             } => {
-                let v = Self::eval_scalar(expr, row);
-                let p = Self::eval_scalar(pattern, row);
+                let v = Self::eval_scalar(expr, row)?;
+                let p = Self::eval_scalar(pattern, row)?;
                 let t = match (v, p) {
                     (Value::String(s), Value::String(pat)) => LiteralResolver::eval_like(&s, &pat),
                     (Value::Null, _) | (_, Value::Null) => Truth::Unknown,
@@ -112,7 +513,27 @@ impl Eval {
                 };
                 if *negated { t.not() } else { t }
             }
+            // the plan executor replaces subqueries with their result before evaluation
+            Predicate::Exists { .. } | Predicate::InSubquery { .. } => Truth::Unknown,
             Predicate::Const3(t) => *t,
+        })
+    }
+
+    /// SQL `value IN (candidates)`: Unknown instead of False when the value or a
+    /// candidate is NULL and nothing matched.
+    pub fn eval_in3(value: &Value, candidates: &[Value]) -> Truth {
+        let mut has_null = value.is_null() && !candidates.is_empty();
+        for candidate in candidates {
+            if candidate.is_null() {
+                has_null = true;
+            } else if Self::value_equal(value, candidate) {
+                return Truth::True;
+            }
+        }
+        if has_null {
+            Truth::Unknown
+        } else {
+            Truth::False
         }
     }
 
@@ -120,6 +541,12 @@ impl Eval {
         if l.is_null() || r.is_null() {
             return Truth::Unknown;
         }
+        // ISO-8601 strings compare chronologically
+        if let (Value::String(a), Value::String(b)) = (l, r)
+            && let Some(truth) = TemporalResolver::compare3(a, op, b)
+        {
+            return truth;
+        }
         // Only numeric, bool eq/neq, string eq/neq — mirrors analyzer folding
         match (l, r) {
             (Value::Bool(a), Value::Bool(b)) => match op {
@@ -183,13 +610,21 @@ impl Eval {
             .map(Value::Number)
             .unwrap_or(Value::Null)
     }
-    fn value_equal(a: &Value, b: &Value) -> bool {
+    pub fn value_equal(a: &Value, b: &Value) -> bool {
         use serde_json::Value::*;
         match (a, b) {
             (Null, Null) => true,
             (Bool(x), Bool(y)) => x == y,
             (Number(x), Number(y)) => x.as_f64() == y.as_f64(),
             (String(x), String(y)) => x == y,
+            (Array(x), Array(y)) => {
+                x.len() == y.len() && x.iter().zip(y).all(|(a, b)| Self::value_equal(a, b))
+            }
+            (Object(x), Object(y)) => {
+                x.len() == y.len()
+                    && x.iter()
+                        .all(|(k, a)| y.get(k).is_some_and(|b| Self::value_equal(a, b)))
+            }
             _ => false,
         }
     }
@@ -203,8 +638,11 @@ mod tests {
     use serde_json::{Map, Value};
 
     use crate::{
-        executor::eval::Eval,
-        parser::ast::{Column, ComparatorOp, Function, Literal, Predicate, ScalarExpr, Truth},
+        executor::{eval::Eval, random::Random},
+        parser::{
+            analyzer::{AnalyzerError, Temporal},
+            ast::{Column, ComparatorOp, Function, Literal, Predicate, ScalarExpr, Truth},
+        },
     };
 
     fn row(pairs: &[(&str, Value)]) -> Map<String, Value> {
@@ -251,15 +689,21 @@ mod tests {
     #[test]
     fn scalar_literals_eval_correctly() {
         let m = Map::new();
-        assert_eq!(Eval::eval_scalar(&lit_null(), &m), Value::Null);
-        assert_eq!(Eval::eval_scalar(&lit_b(true), &m), Value::Bool(true));
-        assert_eq!(Eval::eval_scalar(&lit_i(42), &m), Value::Number(42.into()));
+        assert_eq!(Eval::eval_scalar(&lit_null(), &m).unwrap(), Value::Null);
         assert_eq!(
-            Eval::eval_scalar(&lit_f(1.5), &m),
+            Eval::eval_scalar(&lit_b(true), &m).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            Eval::eval_scalar(&lit_i(42), &m).unwrap(),
+            Value::Number(42.into())
+        );
+        assert_eq!(
+            Eval::eval_scalar(&lit_f(1.5), &m).unwrap(),
             Value::Number(serde_json::Number::from_f64(1.5).unwrap())
         );
         assert_eq!(
-            Eval::eval_scalar(&lit_s("x"), &m),
+            Eval::eval_scalar(&lit_s("x"), &m).unwrap(),
             Value::String("x".into())
         );
     }
@@ -271,26 +715,29 @@ mod tests {
             ("name", Value::String("Ana".into())),
         ]);
         assert_eq!(
-            Eval::eval_scalar(&col_q("t", "id"), &m),
+            Eval::eval_scalar(&col_q("t", "id"), &m).unwrap(),
             Value::Number(1.into())
         );
         assert_eq!(
-            Eval::eval_scalar(&col_u("name"), &m),
+            Eval::eval_scalar(&col_u("name"), &m).unwrap(),
             Value::String("Ana".into())
         );
         // missing -> Null
-        assert_eq!(Eval::eval_scalar(&col_q("t", "missing"), &m), Value::Null);
+        assert_eq!(
+            Eval::eval_scalar(&col_q("t", "missing"), &m).unwrap(),
+            Value::Null
+        );
     }
 
     #[test]
     fn scalar_wildcard_is_never_evaluated_but_returns_null_if_seen() {
         let m = Map::new();
         assert!(matches!(
-            Eval::eval_scalar(&ScalarExpr::WildCard, &m),
+            Eval::eval_scalar(&ScalarExpr::WildCard, &m).unwrap(),
             Value::Null
         ));
         assert!(matches!(
-            Eval::eval_scalar(&ScalarExpr::WildCardWithCollection("t".into()), &m),
+            Eval::eval_scalar(&ScalarExpr::WildCardWithCollection("t".into()), &m).unwrap(),
             Value::Null
         ));
     }
@@ -299,26 +746,421 @@ mod tests {
     fn scalar_functions_work_upper_lower_trim_length() {
         let m = Map::new();
         assert_eq!(
-            Eval::eval_scalar(&fun("upper", vec![lit_s("aBc")]), &m),
+            Eval::eval_scalar(&fun("upper", vec![lit_s("aBc")]), &m).unwrap(),
             Value::String("ABC".into())
         );
         assert_eq!(
-            Eval::eval_scalar(&fun("lower", vec![lit_s("aBc")]), &m),
+            Eval::eval_scalar(&fun("lower", vec![lit_s("aBc")]), &m).unwrap(),
             Value::String("abc".into())
         );
         assert_eq!(
-            Eval::eval_scalar(&fun("trim", vec![lit_s("  hi  ")]), &m),
+            Eval::eval_scalar(&fun("trim", vec![lit_s("  hi  ")]), &m).unwrap(),
             Value::String("hi".into())
         );
         assert_eq!(
-            Eval::eval_scalar(&fun("length", vec![lit_s("hé")]), &m),
+            Eval::eval_scalar(&fun("length", vec![lit_s("hé")]), &m).unwrap(),
             Value::Number(2.into()) // grapheme vs chars: we use chars(), so 2 here
         );
         // non-supported sigs -> Null
         assert_eq!(
-            Eval::eval_scalar(&fun("upper", vec![lit_i(1)]), &m),
+            Eval::eval_scalar(&fun("upper", vec![lit_i(1)]), &m).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn string_functions_slice_pad_and_search() {
+        let m = Map::new();
+        let eval =
+            |name: &str, args: Vec<ScalarExpr>| Eval::eval_scalar(&fun(name, args), &m).unwrap();
+        let s = |text: &str| Value::String(text.into());
+
+        assert_eq!(
+            eval("substr", vec![lit_s("héllo"), lit_i(2), lit_i(3)]),
+            s("éll")
+        );
+        assert_eq!(eval("substring", vec![lit_s("hello"), lit_i(3)]), s("llo"));
+        // positions before the first character still count towards the length
+        assert_eq!(
+            eval("substr", vec![lit_s("hello"), lit_i(0), lit_i(2)]),
+            s("h")
+        );
+        assert_eq!(
+            eval("substr", vec![lit_s("hello"), lit_i(1), lit_i(-1)]),
             Value::Null
         );
+        assert_eq!(
+            eval("concat", vec![lit_s("a"), lit_i(1), lit_b(true)]),
+            s("a1true")
+        );
+        assert_eq!(eval("concat", vec![lit_s("a"), lit_null()]), Value::Null);
+        assert_eq!(
+            eval("replace", vec![lit_s("a-b-c"), lit_s("-"), lit_s("+")]),
+            s("a+b+c")
+        );
+        assert_eq!(
+            eval("position", vec![lit_s("lo"), lit_s("héllo")]),
+            serde_json::json!(4)
+        );
+        assert_eq!(
+            eval("strpos", vec![lit_s("hello"), lit_s("z")]),
+            serde_json::json!(0)
+        );
+        assert_eq!(eval("ltrim", vec![lit_s("  hi  ")]), s("hi  "));
+        assert_eq!(eval("rtrim", vec![lit_s("xhix"), lit_s("x")]), s("xhi"));
+        assert_eq!(
+            eval("lpad", vec![lit_s("7"), lit_i(3), lit_s("0")]),
+            s("007")
+        );
+        assert_eq!(
+            eval("rpad", vec![lit_s("ab"), lit_i(5), lit_s("xy")]),
+            s("abxyx")
+        );
+        assert_eq!(eval("lpad", vec![lit_s("hello"), lit_i(2)]), s("he"));
+        // the result is built in memory, so huge lengths fail instead of allocating
+        let err = Eval::eval_scalar(&fun("rpad", vec![lit_s("x"), lit_i(1_000_000_000_000)]), &m)
+            .unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("limit")));
+        assert_eq!(eval("left", vec![lit_s("hello"), lit_i(2)]), s("he"));
+        assert_eq!(eval("left", vec![lit_s("hello"), lit_i(-2)]), s("hel"));
+        assert_eq!(eval("right", vec![lit_s("hello"), lit_i(2)]), s("lo"));
+        assert_eq!(eval("right", vec![lit_s("hello"), lit_i(-4)]), s("o"));
+        assert_eq!(
+            eval("split_part", vec![lit_s("a,b,c"), lit_s(","), lit_i(2)]),
+            s("b")
+        );
+        assert_eq!(
+            eval("split_part", vec![lit_s("a,b,c"), lit_s(","), lit_i(-1)]),
+            s("c")
+        );
+        assert_eq!(
+            eval("split_part", vec![lit_s("a,b,c"), lit_s(","), lit_i(9)]),
+            s("")
+        );
+        assert_eq!(
+            eval("starts_with", vec![lit_s("hello"), lit_s("he")]),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            eval("ends_with", vec![lit_s("hello"), lit_s("he")]),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            eval(
+                "regexp_replace",
+                vec![lit_s("a1b22"), lit_s("[0-9]+"), lit_s("#")]
+            ),
+            s("a#b22")
+        );
+        assert_eq!(
+            eval(
+                "regexp_replace",
+                vec![lit_s("A1b22"), lit_s("([a-z])"), lit_s("<$1>"), lit_s("gi")]
+            ),
+            s("<A>1<b>22")
+        );
+        // invalid patterns and NULL arguments -> Null
+        assert_eq!(
+            eval("regexp_replace", vec![lit_s("a"), lit_s("("), lit_s("")]),
+            Value::Null
+        );
+        assert_eq!(eval("left", vec![lit_null(), lit_i(1)]), Value::Null);
+        assert_eq!(eval("strpos", vec![lit_s("a"), lit_i(1)]), Value::Null);
+    }
+
+    #[test]
+    fn math_functions_keep_int_and_float_apart() {
+        let m = Map::new();
+        let eval =
+            |name: &str, args: Vec<ScalarExpr>| Eval::eval_scalar(&fun(name, args), &m).unwrap();
+
+        assert_eq!(eval("abs", vec![lit_i(-3)]), serde_json::json!(3));
+        assert_eq!(eval("abs", vec![lit_f(-2.5)]), serde_json::json!(2.5));
+        assert_eq!(eval("sign", vec![lit_f(-0.5)]), serde_json::json!(-1.0));
+        assert_eq!(eval("sign", vec![lit_i(0)]), serde_json::json!(0));
+        assert_eq!(eval("floor", vec![lit_f(-1.5)]), serde_json::json!(-2.0));
+        assert_eq!(eval("ceil", vec![lit_f(1.2)]), serde_json::json!(2.0));
+        assert_eq!(eval("ceil", vec![lit_i(7)]), serde_json::json!(7));
+        assert_eq!(eval("round", vec![lit_f(2.5)]), serde_json::json!(3.0));
+        assert_eq!(
+            eval("round", vec![lit_f(1.23456), lit_i(2)]),
+            serde_json::json!(1.23)
+        );
+        assert_eq!(
+            eval("round", vec![lit_i(-1250), lit_i(-2)]),
+            serde_json::json!(-1300)
+        );
+        assert_eq!(
+            eval("round", vec![lit_i(1249), lit_i(-2)]),
+            serde_json::json!(1200)
+        );
+        assert_eq!(eval("mod", vec![lit_i(7), lit_i(3)]), serde_json::json!(1));
+        assert_eq!(eval("mod", vec![lit_i(7), lit_i(0)]), Value::Null);
+        assert_eq!(eval("sqrt", vec![lit_i(9)]), serde_json::json!(3.0));
+        assert_eq!(eval("sqrt", vec![lit_f(-1.0)]), Value::Null);
+        assert_eq!(
+            eval("power", vec![lit_i(2), lit_i(10)]),
+            serde_json::json!(1024.0)
+        );
+        assert_eq!(eval("power", vec![lit_i(10), lit_i(400)]), Value::Null);
+        assert_eq!(
+            eval("greatest", vec![lit_i(1), lit_null(), lit_f(2.5)]),
+            serde_json::json!(2.5)
+        );
+        assert_eq!(
+            eval("least", vec![lit_s("b"), lit_s("a")]),
+            serde_json::json!("a")
+        );
+        assert_eq!(eval("least", vec![lit_null()]), Value::Null);
+        // overflow and non-numeric arguments -> Null
+        assert_eq!(eval("abs", vec![lit_i(i64::MIN)]), Value::Null);
+        assert_eq!(eval("floor", vec![lit_s("1")]), Value::Null);
+    }
+
+    #[test]
+    fn random_follows_the_seeded_sequence() {
+        let m = Map::new();
+        let random = fun("random", vec![]);
+
+        Random::reseed(Some(3));
+        let first = Eval::eval_scalar(&random, &m).unwrap();
+        Random::reseed(Some(3));
+        assert_eq!(Eval::eval_scalar(&random, &m).unwrap(), first);
+        assert!((0.0..1.0).contains(&first.as_f64().unwrap()));
+    }
+
+    #[test]
+    fn date_functions_read_and_write_iso_strings() {
+        let m = row(&[("e.at", serde_json::json!("2024-05-15T13:45:30Z"))]);
+        let at = col_q("e", "at");
+        let eval =
+            |name: &str, args: Vec<ScalarExpr>| Eval::eval_scalar(&fun(name, args), &m).unwrap();
+        let s = |text: &str| Value::String(text.into());
+
+        assert_eq!(
+            eval("date_trunc", vec![lit_s("month"), at.clone()]),
+            s("2024-05-01T00:00:00Z")
+        );
+        assert_eq!(
+            eval("extract", vec![lit_s("year"), at.clone()]),
+            serde_json::json!(2024)
+        );
+        assert_eq!(
+            eval("date_part", vec![lit_s("hour"), at.clone()]),
+            serde_json::json!(13)
+        );
+        assert_eq!(
+            eval(
+                "date_add",
+                vec![lit_s("2024-01-31"), lit_i(1), lit_s("month")]
+            ),
+            s("2024-02-29")
+        );
+        assert_eq!(
+            eval("date_add", vec![lit_s("2024-01-31"), lit_i(-31)]),
+            s("2023-12-31")
+        );
+        assert_eq!(
+            eval(
+                "date_diff",
+                vec![lit_s("day"), lit_s("2024-05-01"), at.clone()]
+            ),
+            serde_json::json!(14)
+        );
+        assert_eq!(
+            eval("to_char", vec![at.clone(), lit_s("%d/%m/%Y")]),
+            s("15/05/2024")
+        );
+        assert_eq!(
+            eval("strftime", vec![lit_s("%H:%M"), at.clone()]),
+            s("13:45")
+        );
+        assert!(Temporal::parse(eval("now", vec![]).as_str().unwrap()).is_some());
+
+        // NULLs, non-dates, unknown units and bad formats -> Null
+        assert_eq!(
+            eval("date_trunc", vec![lit_s("month"), lit_null()]),
+            Value::Null
+        );
+        assert_eq!(
+            eval("extract", vec![lit_s("year"), lit_s("soon")]),
+            Value::Null
+        );
+        assert_eq!(
+            eval("date_trunc", vec![lit_s("dow"), at.clone()]),
+            Value::Null
+        );
+        assert_eq!(eval("to_char", vec![at, lit_s("%Q")]), Value::Null);
+    }
+
+    #[test]
+    fn iso_strings_compare_chronologically() {
+        let m = row(&[("e.at", serde_json::json!("2024-05-15T13:45:30+02:00"))]);
+        let compare = |op, text: &str| {
+            Eval::eval_predicate3(
+                &Predicate::Compare {
+                    left: col_q("e", "at"),
+                    op,
+                    right: lit_s(text),
+                },
+                &m,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            compare(ComparatorOp::Gt, "2024-05-15T11:00:00Z"),
+            Truth::True
+        );
+        assert_eq!(compare(ComparatorOp::Lt, "2024-05-16"), Truth::True);
+        assert_eq!(
+            compare(ComparatorOp::Eq, "2024-05-15T11:45:30Z"),
+            Truth::True
+        );
+        assert_eq!(compare(ComparatorOp::Lt, "tomorrow"), Truth::Unknown);
+    }
+
+    #[test]
+    fn cast_converts_row_values_and_fails_on_bad_ones() {
+        let m = row(&[
+            ("a.id", serde_json::json!("42")),
+            ("b.id", serde_json::json!(42)),
+            ("a.code", serde_json::json!("x1")),
+        ]);
+        let cast =
+            |expr: ScalarExpr, ty: &str| Eval::eval_scalar(&fun("cast", vec![expr, lit_s(ty)]), &m);
+
+        assert_eq!(
+            cast(col_q("a", "id"), "int").unwrap(),
+            serde_json::json!(42)
+        );
+        assert_eq!(
+            cast(col_q("b", "id"), "text").unwrap(),
+            serde_json::json!("42")
+        );
+        assert!(matches!(
+            cast(col_q("a", "code"), "int"),
+            Err(AnalyzerError::Other(msg)) if msg == "cannot cast \"x1\" to Int"
+        ));
+        assert_eq!(cast(col_q("a", "missing"), "date").unwrap(), Value::Null);
+        assert_eq!(
+            Eval::eval_scalar(
+                &fun("try_cast", vec![col_q("a", "code"), lit_s("bool")]),
+                &m
+            )
+            .unwrap(),
+            Value::Null
+        );
+
+        // the string id only equals the number once cast
+        let join = |left: ScalarExpr| {
+            Eval::eval_predicate3(
+                &Predicate::Compare {
+                    left,
+                    op: ComparatorOp::Eq,
+                    right: col_q("b", "id"),
+                },
+                &m,
+            )
+            .unwrap()
+        };
+        assert_eq!(join(col_q("a", "id")), Truth::Unknown);
+        assert_eq!(
+            join(fun("cast", vec![col_q("a", "id"), lit_s("int")])),
+            Truth::True
+        );
+    }
+
+    #[test]
+    fn coalesce_returns_the_first_non_null_argument() {
+        let m = row(&[("t.a", Value::Null), ("t.b", serde_json::json!(2))]);
+        assert_eq!(
+            Eval::eval_scalar(
+                &fun("coalesce", vec![col_q("t", "a"), col_q("t", "b"), lit_i(3)]),
+                &m
+            )
+            .unwrap(),
+            serde_json::json!(2)
+        );
+        assert_eq!(
+            Eval::eval_scalar(&fun("coalesce", vec![lit_null(), lit_null()]), &m).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn json_functions_inspect_and_build_documents() {
+        let m = row(&[(
+            "p.doc",
+            serde_json::json!({ "a": { "b": [10, 20] }, "tags": ["x", "y"] }),
+        )]);
+        let doc = col_q("p", "doc");
+        let eval =
+            |name: &str, args: Vec<ScalarExpr>| Eval::eval_scalar(&fun(name, args), &m).unwrap();
+
+        assert_eq!(
+            eval("json_extract", vec![doc.clone(), lit_s("$.a.b[1]")]),
+            serde_json::json!(20)
+        );
+        assert_eq!(
+            eval("json_extract", vec![doc.clone(), lit_s("$")]),
+            m["p.doc"]
+        );
+        // missing paths and malformed paths -> Null
+        assert_eq!(
+            eval("json_extract", vec![doc.clone(), lit_s("$.a.c")]),
+            Value::Null
+        );
+        assert_eq!(
+            eval("json_extract", vec![doc.clone(), lit_s("a.b")]),
+            Value::Null
+        );
+
+        let tags = fun("json_extract", vec![doc.clone(), lit_s("$.tags")]);
+        assert_eq!(
+            eval("json_array_length", vec![tags.clone()]),
+            serde_json::json!(2)
+        );
+        assert_eq!(eval("json_array_length", vec![doc.clone()]), Value::Null);
+        assert_eq!(
+            eval("json_keys", vec![doc.clone()]),
+            serde_json::json!(["a", "tags"])
+        );
+        assert_eq!(
+            eval("json_type", vec![doc.clone()]),
+            serde_json::json!("object")
+        );
+        assert_eq!(
+            eval("json_type", vec![lit_f(1.5)]),
+            serde_json::json!("float")
+        );
+        assert_eq!(
+            eval("json_type", vec![lit_null()]),
+            serde_json::json!("null")
+        );
+
+        assert_eq!(
+            eval("json_contains", vec![tags.clone(), lit_s("y")]),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            eval("json_contains", vec![tags.clone(), lit_s("z")]),
+            Value::Bool(false)
+        );
+        assert_eq!(eval("json_contains", vec![tags, lit_null()]), Value::Null);
+
+        assert_eq!(
+            eval(
+                "json_object",
+                vec![lit_s("id"), lit_i(1), lit_s("ok"), lit_b(true)]
+            ),
+            serde_json::json!({ "id": 1, "ok": true })
+        );
+        assert_eq!(eval("json_object", vec![lit_i(1), lit_i(1)]), Value::Null);
+        assert_eq!(
+            eval("json_array", vec![lit_i(1), lit_s("a"), lit_null()]),
+            serde_json::json!([1, "a", null])
+        );
     }
 
     // --- predicate: comparisons & 3VL ---------------------------------------
@@ -333,19 +1175,19 @@ mod tests {
         };
 
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_i(2), ComparatorOp::Gt, lit_i(1)), &m),
+            Eval::eval_predicate3(&make(lit_i(2), ComparatorOp::Gt, lit_i(1)), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_f(2.0), ComparatorOp::Lt, lit_f(3.0)), &m),
+            Eval::eval_predicate3(&make(lit_f(2.0), ComparatorOp::Lt, lit_f(3.0)), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_i(2), ComparatorOp::Eq, lit_f(2.0)), &m),
+            Eval::eval_predicate3(&make(lit_i(2), ComparatorOp::Eq, lit_f(2.0)), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_f(2.0), ComparatorOp::NotEq, lit_f(2.1)), &m),
+            Eval::eval_predicate3(&make(lit_f(2.0), ComparatorOp::NotEq, lit_f(2.1)), &m).unwrap(),
             Truth::True
         ));
     }
@@ -361,35 +1203,35 @@ mod tests {
 
         // strings: only Eq/NotEq True/False; others Unknown
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::Eq, lit_s("a")), &m),
+            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::Eq, lit_s("a")), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::NotEq, lit_s("b")), &m),
+            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::NotEq, lit_s("b")), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::Lt, lit_s("b")), &m),
+            Eval::eval_predicate3(&make(lit_s("a"), ComparatorOp::Lt, lit_s("b")), &m).unwrap(),
             Truth::Unknown
         ));
 
         // bools: only Eq/NotEq; others Unknown
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_b(true), ComparatorOp::Eq, lit_b(true)), &m),
+            Eval::eval_predicate3(&make(lit_b(true), ComparatorOp::Eq, lit_b(true)), &m).unwrap(),
             Truth::True
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_b(true), ComparatorOp::Gt, lit_b(false)), &m),
+            Eval::eval_predicate3(&make(lit_b(true), ComparatorOp::Gt, lit_b(false)), &m).unwrap(),
             Truth::Unknown
         ));
 
         // null involvement => Unknown
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_null(), ComparatorOp::Eq, lit_i(1)), &m),
+            Eval::eval_predicate3(&make(lit_null(), ComparatorOp::Eq, lit_i(1)), &m).unwrap(),
             Truth::Unknown
         ));
         assert!(matches!(
-            Eval::eval_predicate3(&make(lit_i(1), ComparatorOp::Lt, lit_null()), &m),
+            Eval::eval_predicate3(&make(lit_i(1), ComparatorOp::Lt, lit_null()), &m).unwrap(),
             Truth::Unknown
         ));
     }
@@ -407,9 +1249,12 @@ mod tests {
             expr: col_q("t", "b"),
             negated: true,
         };
-        assert!(matches!(Eval::eval_predicate3(&is_null_a, &m), Truth::True));
         assert!(matches!(
-            Eval::eval_predicate3(&is_not_null_b, &m),
+            Eval::eval_predicate3(&is_null_a, &m).unwrap(),
+            Truth::True
+        ));
+        assert!(matches!(
+            Eval::eval_predicate3(&is_not_null_b, &m).unwrap(),
             Truth::True
         ));
     }
@@ -426,7 +1271,10 @@ mod tests {
             list: vec![lit_i(1), lit_null(), lit_i(2)],
             negated: false,
         };
-        assert!(matches!(Eval::eval_predicate3(&p_true, &m), Truth::True));
+        assert!(matches!(
+            Eval::eval_predicate3(&p_true, &m).unwrap(),
+            Truth::True
+        ));
 
         // 3 IN (1,NULL,2) -> Unknown (no match but NULL present)
         let p_unknown = Predicate::InList {
@@ -435,7 +1283,7 @@ mod tests {
             negated: false,
         };
         assert!(matches!(
-            Eval::eval_predicate3(&p_unknown, &m),
+            Eval::eval_predicate3(&p_unknown, &m).unwrap(),
             Truth::Unknown
         ));
 
@@ -446,7 +1294,7 @@ mod tests {
             negated: true,
         };
         assert!(matches!(
-            Eval::eval_predicate3(&p_notin_true, &m),
+            Eval::eval_predicate3(&p_notin_true, &m).unwrap(),
             Truth::True
         ));
 
@@ -457,9 +1305,21 @@ mod tests {
             negated: true,
         };
         assert!(matches!(
-            Eval::eval_predicate3(&p_notin_unknown, &m),
+            Eval::eval_predicate3(&p_notin_unknown, &m).unwrap(),
             Truth::Unknown
         ));
+
+        // NULL NOT IN (1,2) -> Unknown, but NULL NOT IN () -> True
+        let p_null_notin = Predicate::InList {
+            expr: lit_null(),
+            list: vec![lit_i(1), lit_i(2)],
+            negated: true,
+        };
+        assert!(matches!(
+            Eval::eval_predicate3(&p_null_notin, &m).unwrap(),
+            Truth::Unknown
+        ));
+        assert_eq!(Eval::eval_in3(&Value::Null, &[]), Truth::False);
     }
 
     // --- LIKE / NOT LIKE -----------------------------------------------------
@@ -475,7 +1335,10 @@ mod tests {
             pattern: ScalarExpr::Literal(Literal::String("he%2_".into())),
             negated: false,
         };
-        assert!(matches!(Eval::eval_predicate3(&like_p, &m), Truth::True));
+        assert!(matches!(
+            Eval::eval_predicate3(&like_p, &m).unwrap(),
+            Truth::True
+        ));
 
         // Escaping '_' -> must match literal underscore
         let like_escape = Predicate::Like {
@@ -484,7 +1347,7 @@ mod tests {
             negated: false,
         };
         assert!(matches!(
-            Eval::eval_predicate3(&like_escape, &m),
+            Eval::eval_predicate3(&like_escape, &m).unwrap(),
             Truth::True
         ));
 
@@ -494,7 +1357,10 @@ mod tests {
             pattern: ScalarExpr::Literal(Literal::String("a_c".into())),
             negated: true,
         };
-        assert!(matches!(Eval::eval_predicate3(&not_like, &m), Truth::False));
+        assert!(matches!(
+            Eval::eval_predicate3(&not_like, &m).unwrap(),
+            Truth::False
+        ));
 
         // NULL involvement -> Unknown
         let like_null = Predicate::Like {
@@ -503,7 +1369,7 @@ mod tests {
             negated: false,
         };
         assert!(matches!(
-            Eval::eval_predicate3(&like_null, &m),
+            Eval::eval_predicate3(&like_null, &m).unwrap(),
             Truth::Unknown
         ));
     }
@@ -531,23 +1397,32 @@ mod tests {
 
         // True AND Unknown -> Unknown
         assert!(matches!(
-            Eval::eval_predicate3(&Predicate::And(vec![t.clone(), u.clone()]), &m),
+            Eval::eval_predicate3(&Predicate::And(vec![t.clone(), u.clone()]), &m).unwrap(),
             Truth::Unknown
         ));
         // False AND Unknown -> False (short-circuit semantics in our fold/eval combination)
         assert!(matches!(
-            Eval::eval_predicate3(&Predicate::And(vec![f.clone(), u.clone()]), &m),
+            Eval::eval_predicate3(&Predicate::And(vec![f.clone(), u.clone()]), &m).unwrap(),
             Truth::False
         ));
         // True OR Unknown -> True
         assert!(matches!(
-            Eval::eval_predicate3(&Predicate::Or(vec![t, u.clone()]), &m),
+            Eval::eval_predicate3(&Predicate::Or(vec![t, u.clone()]), &m).unwrap(),
             Truth::True
         ));
         // False OR Unknown -> Unknown
         assert!(matches!(
-            Eval::eval_predicate3(&Predicate::Or(vec![f, u]), &m),
+            Eval::eval_predicate3(&Predicate::Or(vec![f.clone(), u.clone()]), &m).unwrap(),
             Truth::Unknown
         ));
+        // NOT flips True/False and keeps Unknown
+        assert_eq!(
+            Eval::eval_predicate3(&Predicate::Not(Box::new(f)), &m).unwrap(),
+            Truth::True
+        );
+        assert_eq!(
+            Eval::eval_predicate3(&Predicate::Not(Box::new(u)), &m).unwrap(),
+            Truth::Unknown
+        );
     }
 }
@@ -1,7 +1,24 @@
-use serde_json::Value;
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Db,
+    executor::{
+        eval::Eval,
+        plan_executor::{CteRows, PlanExecutor},
+    },
+    parser::{
+        analyzer::{AnalyzedIdentifier, AnalyzerError, TemporalResolver},
+        ast::{Predicate, SetOperator, Truth},
+    },
+};
 
 pub struct Helpers;
 
+/// Stored id of a document paired with its scan row.
+pub type MatchedRow = (String, Map<String, Value>);
+
 impl Helpers {
     pub fn canonical_tuple(vals: &[Value]) -> String {
         // Note: serde_json::to_string preserves map key order by default; your input objects
@@ -32,7 +49,8 @@ impl Helpers {
                 if ascending { ord } else { ord.reverse() }
             }
             (String(x), String(y)) => {
-                let ord = x.cmp(y);
+                // ISO-8601 strings sort chronologically before other strings
+                let ord = TemporalResolver::order(x, y);
                 if ascending { ord } else { ord.reverse() }
             }
             // fallback: compare type tags to keep total order stable
@@ -50,6 +68,164 @@ impl Helpers {
             }
         }
     }
+
+    /// Prefix document keys with the visible collection name, like a Scan row.
+    pub fn scan_row(visible: &str, document: &Value) -> Map<String, Value> {
+        let mut row = Map::new();
+        if let Value::Object(map) = document {
+            for (k, v) in map {
+                row.insert(format!("{}.{}", visible, k), v.clone());
+            }
+        }
+        row
+    }
+
+    /// Key a stored document is registered under in its collection.
+    pub fn document_id(document: &Value, id_key: &str) -> Option<String> {
+        match document.get(id_key) {
+            Some(Value::String(id)) => Some(id.clone()),
+            Some(Value::Number(id)) => Some(id.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Scan rows of the documents selected by an UPDATE/DELETE predicate,
+    /// paired with the id each document is stored under. Rows whose predicate
+    /// is False or Unknown are skipped.
+    pub fn matching_rows(
+        documents: Vec<Value>,
+        visible: &str,
+        id_key: &str,
+        criteria: Option<&Predicate>,
+        db: &Db,
+    ) -> Result<Vec<MatchedRow>, AnalyzerError> {
+        let mut out = Vec::new();
+        for document in documents {
+            let row = Self::scan_row(visible, &document);
+            if let Some(criteria) = criteria
+                && !matches!(
+                    PlanExecutor::eval_row_predicate(criteria, &row, db, &mut CteRows::new())?,
+                    Truth::True
+                )
+            {
+                continue;
+            }
+
+            let id = Self::document_id(&document, id_key)
+                .ok_or_else(|| AnalyzerError::Other(format!("document without id {id_key}")))?;
+            out.push((id, row));
+        }
+        Ok(out)
+    }
+
+    /// Single result row reported by DML statements without RETURNING.
+    pub fn affected_rows(count: usize) -> Value {
+        json!({ "affected_rows": count })
+    }
+
+    /// Result of a DML statement: the affected row count, or the RETURNING
+    /// projection of every written document.
+    pub fn dml_output(
+        visible: &str,
+        returning: &[AnalyzedIdentifier],
+        documents: &[Value],
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        if returning.is_empty() {
+            return Ok(vec![Helpers::affected_rows(documents.len())]);
+        }
+        documents
+            .iter()
+            .map(|document| {
+                PlanExecutor::project_row(returning, &Helpers::scan_row(visible, document))
+            })
+            .collect()
+    }
+
+    /// Keep the first row of every group of rows equal under `Eval::value_equal`,
+    /// preserving input order (so an earlier ORDER BY still holds).
+    pub fn distinct_rows(rows: Vec<Value>) -> Vec<Value> {
+        let mut buckets: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut out: Vec<Value> = Vec::new();
+        for row in rows {
+            let bucket = buckets.entry(Self::row_bucket(&row)).or_default();
+            if bucket.iter().any(|&i| Eval::value_equal(&out[i], &row)) {
+                continue;
+            }
+            bucket.push(out.len());
+            out.push(row);
+        }
+        out
+    }
+
+    /// Combine the rows of two compound query blocks. Without ALL the result is
+    /// distinct; with ALL, INTERSECT and EXCEPT match rows one-for-one.
+    pub fn set_operation(
+        op: SetOperator,
+        all: bool,
+        left: Vec<Value>,
+        right: Vec<Value>,
+    ) -> Vec<Value> {
+        if op == SetOperator::Union {
+            let mut rows = left;
+            rows.extend(right);
+            return if all { rows } else { Self::distinct_rows(rows) };
+        }
+
+        // right rows grouped into (row, remaining matches) per bucket
+        let mut matches: HashMap<String, Vec<(Value, usize)>> = HashMap::new();
+        for row in right {
+            let bucket = matches.entry(Self::row_bucket(&row)).or_default();
+            match bucket.iter_mut().find(|(v, _)| Eval::value_equal(v, &row)) {
+                Some((_, count)) => *count += 1,
+                None => bucket.push((row, 1)),
+            }
+        }
+
+        let left = if all { left } else { Self::distinct_rows(left) };
+        let mut out = Vec::new();
+        for row in left {
+            let remaining = matches
+                .get_mut(&Self::row_bucket(&row))
+                .and_then(|bucket| bucket.iter_mut().find(|(v, _)| Eval::value_equal(v, &row)))
+                .map(|(_, count)| count);
+            let matched = match remaining {
+                Some(count) if *count > 0 => {
+                    if all {
+                        *count -= 1;
+                    }
+                    true
+                }
+                _ => false,
+            };
+            if matched == (op == SetOperator::Intersect) {
+                out.push(row);
+            }
+        }
+        out
+    }
+
+    // equal for value-equal rows (1 and 1.0 share one)
+    fn row_bucket(row: &Value) -> String {
+        Self::canonical_tuple(&[Self::numbers_as_f64(row)])
+    }
+
+    fn numbers_as_f64(v: &Value) -> Value {
+        match v {
+            Value::Number(n) => {
+                let f = n.as_f64().unwrap_or_default();
+                // -0.0 equals 0.0
+                json!(if f == 0.0 { 0.0 } else { f })
+            }
+            Value::Array(items) => Value::Array(items.iter().map(Self::numbers_as_f64).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), Self::numbers_as_f64(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
     fn type_rank(v: &Value) -> u8 {
         match v {
             Value::Null => 0,
@@ -68,6 +244,86 @@ mod tests {
     use serde_json::{Value, json};
     use std::cmp::Ordering::*;
 
+    // ---------- dml helpers ----------
+
+    #[test]
+    fn scan_row_prefixes_document_keys() {
+        let row = Helpers::scan_row("p", &json!({ "id": 1, "name": "Ada" }));
+        assert_eq!(Value::Object(row), json!({ "p.id": 1, "p.name": "Ada" }));
+        assert!(Helpers::scan_row("p", &json!(1)).is_empty());
+    }
+
+    #[test]
+    fn document_id_accepts_string_and_number_ids() {
+        assert_eq!(
+            Helpers::document_id(&json!({ "id": "a" }), "id"),
+            Some("a".into())
+        );
+        assert_eq!(
+            Helpers::document_id(&json!({ "key": 7 }), "key"),
+            Some("7".into())
+        );
+        assert_eq!(Helpers::document_id(&json!({ "id": null }), "id"), None);
+    }
+
+    #[test]
+    fn distinct_rows_keeps_first_of_value_equal_rows() {
+        let rows = vec![
+            json!({ "a": 1, "b": "x" }),
+            json!({ "a": 1.0, "b": "x" }),
+            json!({ "a": null, "b": "x" }),
+            json!({ "a": null, "b": "x" }),
+            json!({ "a": [1, { "k": 2 }], "b": "y" }),
+            json!({ "a": [1.0, { "k": 2 }], "b": "y" }),
+            json!({ "a": 2, "b": "x" }),
+        ];
+
+        let out = Helpers::distinct_rows(rows);
+
+        assert_eq!(
+            out,
+            vec![
+                json!({ "a": 1, "b": "x" }),
+                json!({ "a": null, "b": "x" }),
+                json!({ "a": [1, { "k": 2 }], "b": "y" }),
+                json!({ "a": 2, "b": "x" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_operations_follow_distinct_and_all_semantics() {
+        use crate::parser::ast::SetOperator::*;
+        let rows = |values: &[i64]| values.iter().map(|v| json!({ "a": v })).collect::<Vec<_>>();
+        let left = rows(&[1, 1, 1, 2, 3]);
+        let right = vec![json!({ "a": 1.0 }), json!({ "a": 1 }), json!({ "a": 4 })];
+
+        assert_eq!(
+            Helpers::set_operation(Union, false, left.clone(), right.clone()),
+            rows(&[1, 2, 3, 4])
+        );
+        assert_eq!(
+            Helpers::set_operation(Union, true, left.clone(), right.clone()).len(),
+            8
+        );
+        assert_eq!(
+            Helpers::set_operation(Intersect, false, left.clone(), right.clone()),
+            rows(&[1])
+        );
+        assert_eq!(
+            Helpers::set_operation(Intersect, true, left.clone(), right.clone()),
+            rows(&[1, 1])
+        );
+        assert_eq!(
+            Helpers::set_operation(Except, false, left.clone(), right.clone()),
+            rows(&[2, 3])
+        );
+        assert_eq!(
+            Helpers::set_operation(Except, true, left, right),
+            rows(&[1, 2, 3])
+        );
+    }
+
     // ---------- canonical_tuple ----------
 
     #[test]
@@ -127,6 +383,19 @@ mod tests {
         assert_eq!(Helpers::cmp_json_for_sort(&a, &a, true), Equal);
     }
 
+    #[test]
+    fn sort_iso_strings_chronologically() {
+        // lexicographically "2024-01-01T10:00:00+05:00" sorts after the Z value
+        let early = json!("2024-01-01T10:00:00+05:00");
+        let late = json!("2024-01-01T06:00:00Z");
+        assert_eq!(Helpers::cmp_json_for_sort(&early, &late, true), Less);
+        assert_eq!(Helpers::cmp_json_for_sort(&early, &late, false), Greater);
+        assert_eq!(
+            Helpers::cmp_json_for_sort(&json!("2024-01-01"), &json!("2024-01-01T00:00:00Z"), true),
+            Equal
+        );
+    }
+
     #[test]
     fn sort_bools_false_before_true_in_ascending() {
         let f = json!(false);
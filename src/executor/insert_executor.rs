@@ -0,0 +1,242 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    Db, DbCollection, IdType, SchemaDict,
+    database::{AddBatchError, SchemaProvider},
+    executor::{
+        helpers::Helpers,
+        plan_executor::{CteRows, Executor, PlanExecutor},
+    },
+    parser::analyzer::{
+        AnalyzedConflictAction, AnalyzedInsert, AnalyzedInsertSource, AnalyzedOnConflict,
+        AnalyzerError, AssignmentResolver, EXCLUDED,
+    },
+    planner::plan_builder::PlanBuilder,
+};
+
+pub struct InsertExecutor {
+    insert: AnalyzedInsert,
+}
+
+impl Executor for InsertExecutor {
+    fn execute(&self, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        let collection = db
+            .get(&self.insert.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(self.insert.collection.clone()))?;
+
+        let schema = db.schema_of(&self.insert.collection);
+        let documents = self.documents(db, schema.as_ref())?;
+        let added = match &self.insert.on_conflict {
+            Some(on_conflict) => {
+                self.upsert(db, &collection, schema.as_ref(), on_conflict, documents)?
+            }
+            None => collection
+                .add_all(documents)
+                .map_err(|error| AnalyzerError::Other(error.to_string()))?,
+        };
+
+        Helpers::dml_output(&self.insert.collection, &self.insert.returning, &added)
+    }
+}
+
+impl InsertExecutor {
+    pub fn new(insert: AnalyzedInsert) -> Self {
+        Self { insert }
+    }
+
+    fn documents(&self, db: &Db, schema: Option<&SchemaDict>) -> Result<Vec<Value>, AnalyzerError> {
+        match &self.insert.source {
+            AnalyzedInsertSource::Values(rows) => {
+                let empty = Map::new();
+                let mut ctes = CteRows::new();
+                rows.iter()
+                    .map(|row| {
+                        let values = row
+                            .iter()
+                            .map(|expr| PlanExecutor::eval_row_scalar(expr, &empty, db, &mut ctes))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        self.document(schema, values.into_iter())
+                    })
+                    .collect()
+            }
+            AnalyzedInsertSource::Query(query) => {
+                let plan = PlanBuilder::from_analyzed(query)?;
+                let rows = PlanExecutor::new(plan).execute(db)?;
+                rows.iter()
+                    .map(|row| {
+                        let values = query
+                            .projection
+                            .iter()
+                            .map(|id| row.get(&id.output_name).cloned().unwrap_or(Value::Null));
+                        self.document(schema, values)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Write every document, resolving id conflicts with the stored row one
+    /// document at a time, so a row repeated in the statement sees its earlier copy.
+    fn upsert(
+        &self,
+        db: &Db,
+        collection: &DbCollection,
+        schema: Option<&SchemaDict>,
+        on_conflict: &AnalyzedOnConflict,
+        documents: Vec<Value>,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        let config = collection
+            .get_config()
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+        if let Some(target) = &on_conflict.target
+            && *target != config.id_key
+        {
+            return Err(AnalyzerError::Other(format!(
+                "ON CONFLICT target must be the id column {}",
+                config.id_key
+            )));
+        }
+
+        // validate up front so a bad row leaves the collection untouched
+        if config.id_type == IdType::None
+            && let Some(index) = documents
+                .iter()
+                .position(|document| Helpers::document_id(document, &config.id_key).is_none())
+        {
+            let error = AddBatchError::MissingId {
+                index,
+                id_key: config.id_key.clone(),
+            };
+            return Err(AnalyzerError::Other(error.to_string()));
+        }
+
+        let mut written = Vec::with_capacity(documents.len());
+        for document in documents {
+            let id = Helpers::document_id(&document, &config.id_key);
+            let existing = match &id {
+                Some(id) => collection
+                    .get(id)
+                    .map_err(|error| AnalyzerError::Other(error.to_string()))?,
+                None => None,
+            };
+
+            let item = match (existing, &on_conflict.action) {
+                (None, _) => document,
+                (Some(_), AnalyzedConflictAction::DoNothing) => continue,
+                (Some(_), AnalyzedConflictAction::DoUpdate(assignments))
+                    if assignments.is_empty() =>
+                {
+                    document
+                }
+                (Some(stored), AnalyzedConflictAction::DoUpdate(assignments)) => {
+                    let mut row = Helpers::scan_row(&self.insert.collection, &stored);
+                    row.extend(Helpers::scan_row(EXCLUDED, &document));
+
+                    // like UPDATE, an assignment replaces the stored value instead of merging
+                    let (Some(id), Value::Object(mut updated)) = (&id, stored) else {
+                        continue;
+                    };
+                    for (column, expr) in assignments {
+                        let value =
+                            PlanExecutor::eval_row_scalar(expr, &row, db, &mut CteRows::new())?;
+                        let value = AssignmentResolver::write_value(
+                            &self.insert.collection,
+                            schema,
+                            column,
+                            value,
+                        )?;
+                        updated.insert(column.clone(), value);
+                    }
+                    if let Some(updated) = collection
+                        .update(id, Value::Object(updated))
+                        .map_err(|error| AnalyzerError::Other(error.to_string()))?
+                    {
+                        written.push(updated);
+                    }
+                    continue;
+                }
+            };
+
+            written.push(
+                collection
+                    .upsert(item)
+                    .map_err(|error| AnalyzerError::Other(error.to_string()))?,
+            );
+        }
+
+        Ok(written)
+    }
+
+    fn document(
+        &self,
+        schema: Option<&SchemaDict>,
+        values: impl Iterator<Item = Value>,
+    ) -> Result<Value, AnalyzerError> {
+        let mut document = Map::new();
+        for (column, value) in self.insert.columns.iter().zip(values) {
+            let value =
+                AssignmentResolver::write_value(&self.insert.collection, schema, column, value)?;
+            document.insert(column.clone(), value);
+        }
+        Ok(Value::Object(document))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbConfig;
+    use crate::parser::ast::{Literal, ScalarExpr};
+    use serde_json::json;
+
+    #[test]
+    fn values_are_added_with_generated_ids() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+        let people = db.create("people");
+
+        let insert = AnalyzedInsert {
+            collection: "people".into(),
+            columns: vec!["name".into(), "age".into()],
+            source: AnalyzedInsertSource::Values(vec![
+                vec![
+                    ScalarExpr::Literal(Literal::String("Ada".into())),
+                    ScalarExpr::Literal(Literal::Int(37)),
+                ],
+                vec![
+                    ScalarExpr::Literal(Literal::String("Grace".into())),
+                    ScalarExpr::Literal(Literal::Null),
+                ],
+            ]),
+            on_conflict: None,
+            returning: vec![],
+        };
+
+        let out = InsertExecutor::new(insert).execute(&db).unwrap();
+
+        assert_eq!(out, vec![json!({ "affected_rows": 2 })]);
+        assert_eq!(
+            people.get("1").unwrap().unwrap(),
+            json!({ "id": 1, "name": "Ada", "age": 37 })
+        );
+        assert_eq!(
+            people.get("2").unwrap().unwrap(),
+            json!({ "id": 2, "name": "Grace", "age": null })
+        );
+    }
+
+    #[test]
+    fn unknown_collection_is_reported() {
+        let db = Db::new_with_config(DbConfig::int("id"));
+
+        let insert = AnalyzedInsert {
+            collection: "missing".into(),
+            columns: vec![],
+            source: AnalyzedInsertSource::Values(vec![]),
+            on_conflict: None,
+            returning: vec![],
+        };
+
+        let err = InsertExecutor::new(insert).execute(&db).unwrap_err();
+        assert_eq!(err, AnalyzerError::UnknownCollection("missing".into()));
+    }
+}
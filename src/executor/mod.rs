@@ -1,6 +1,12 @@
+pub mod correlation;
+pub mod ddl_executor;
+pub mod delete_executor;
 pub mod eval;
 pub mod helpers;
+pub mod insert_executor;
 pub mod plan_executor;
+pub mod random;
 pub mod row;
+pub mod update_executor;
 
 pub mod _tests;
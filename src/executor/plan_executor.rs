@@ -1,17 +1,23 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use serde_json::{Map, Value};
 
 use crate::{
     Db,
     database::SchemaProvider,
-    executor::{eval::Eval, helpers::Helpers},
+    executor::{correlation::Correlation, eval::Eval, helpers::Helpers},
     parser::{
         aggregators_helper::{Accumulator as AggAcc, AggregateRegistry},
-        analyzer::AnalyzerError,
-        ast::{Column, JoinType, ScalarExpr, Truth},
+        analyzer::{AnalyzedIdentifier, AnalyzerError, ColumnKey, ScalarResolver},
+        ast::{
+            Case, Column, FrameBound, Function, JoinType, JsonPath, Literal, Predicate, ScalarExpr,
+            SetOperator, Subquery, Truth, Window,
+        },
+    },
+    planner::{
+        aggregate_call::AggregateCall, logical_plan::LogicalPlan, plan_builder::PlanBuilder,
+        window_call::WindowCall,
     },
-    planner::{aggregate_call::AggregateCall, logical_plan::LogicalPlan},
 };
 
 pub trait Executor {
@@ -28,7 +34,10 @@ impl Executor for PlanExecutor {
     }
 }
 
-type GroupEntry = (Vec<Value>, Vec<Box<dyn AggAcc>>);
+type GroupEntry = (Vec<Value>, Vec<Option<Box<dyn AggAcc>>>); // None for grouping()
+
+/// Rows of the WITH queries in scope, by name.
+pub type CteRows = HashMap<String, Vec<Value>>;
 
 impl PlanExecutor {
     pub fn new(plan: LogicalPlan) -> Self {
@@ -36,6 +45,15 @@ impl PlanExecutor {
     }
 
     pub fn run_plan(plan: &LogicalPlan, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        Self::run_plan_with(plan, db, &mut CteRows::new())
+    }
+
+    /// Run `plan` with the rows of the WITH queries already evaluated by enclosing plans.
+    pub fn run_plan_with(
+        plan: &LogicalPlan,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Vec<Value>, AnalyzerError> {
         match plan {
             LogicalPlan::Scan { backing, visible } => {
                 let coll = db
@@ -58,15 +76,80 @@ impl PlanExecutor {
                 Ok(out)
             }
             LogicalPlan::Subquery { input, visible } => {
-                let rows = Self::run_plan(input, db)?;
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                Self::prefix_rows(rows, visible)
+            }
+            LogicalPlan::With {
+                ctes: definitions,
+                input,
+            } => {
+                // inner definitions shadow outer ones only while `input` runs
+                let mut shadowed = Vec::new();
+                for (name, definition) in definitions {
+                    let rows = Self::run_plan_with(definition, db, ctes)?;
+                    shadowed.push((name.clone(), ctes.insert(name.clone(), rows)));
+                }
+                let result = Self::run_plan_with(input, db, ctes);
+                for (name, previous) in shadowed.into_iter().rev() {
+                    match previous {
+                        Some(rows) => ctes.insert(name, rows),
+                        None => ctes.remove(&name),
+                    };
+                }
+                result
+            }
+            LogicalPlan::RecursiveCte {
+                name,
+                anchor,
+                step,
+                all,
+            } => {
+                let rows = Self::run_plan_with(anchor, db, ctes)?;
+                let previous = ctes.remove(name);
+                let result = Self::run_recursive(name, rows, step, *all, db, ctes);
+                match previous {
+                    Some(rows) => ctes.insert(name.clone(), rows),
+                    None => ctes.remove(name),
+                };
+                result
+            }
+            LogicalPlan::CteScan { name, visible, .. } => {
+                let rows = ctes.get(name).cloned().ok_or_else(|| {
+                    AnalyzerError::Other(format!("Executor: WITH query {name} was not evaluated"))
+                })?;
                 Self::prefix_rows(rows, visible)
             }
+            LogicalPlan::Unnest {
+                input,
+                expr,
+                visible,
+                column,
+            } => {
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                let key = format!("{}.{}", visible, column);
+                let mut out = Vec::new();
+                for v in rows {
+                    let Value::Object(row) = v else { continue };
+                    // NULL and non-array values produce no rows, like an empty array
+                    if let Value::Array(elements) = Self::eval_row_scalar(expr, &row, db, ctes)? {
+                        for element in elements {
+                            let mut expanded = row.clone();
+                            expanded.insert(key.clone(), element);
+                            out.push(Value::Object(expanded));
+                        }
+                    }
+                }
+                Ok(out)
+            }
             LogicalPlan::Filter { input, predicate } => {
-                let rows = Self::run_plan(input, db)?;
+                let rows = Self::run_plan_with(input, db, ctes)?;
                 let mut out = Vec::new();
                 for v in rows {
                     if let Value::Object(m) = &v
-                        && matches!(Eval::eval_predicate3(predicate, m), Truth::True)
+                        && matches!(
+                            Self::eval_row_predicate(predicate, m, db, ctes)?,
+                            Truth::True
+                        )
                     {
                         out.push(v);
                     }
@@ -76,49 +159,72 @@ impl PlanExecutor {
             LogicalPlan::Aggregate {
                 input,
                 group_keys,
+                grouping_sets,
                 aggs,
             } => {
-                let rows = Self::run_plan(input, db)?;
-                Self::aggregate_rows(rows, group_keys, aggs)
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                Self::aggregate_rows(rows, group_keys, grouping_sets, aggs)
+            }
+            LogicalPlan::Window { input, windows } => {
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                Self::window_rows(rows, windows, db, ctes)
             }
             LogicalPlan::Project { input, exprs } => {
-                let rows = Self::run_plan(input, db)?;
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                let correlated = exprs.iter().any(|id| id.expression.has_subquery());
                 let mut out = Vec::new();
                 for v in rows {
-                    let mut proj = Map::new();
-                    let obj = v.as_object().unwrap();
-                    for id in exprs {
-                        let val = Eval::eval_scalar(&id.expression, obj);
-                        proj.insert(id.output_name.clone(), val);
+                    let row = v.as_object().unwrap();
+                    if correlated {
+                        out.push(Self::project_row_with(exprs, row, db, ctes)?);
+                    } else {
+                        out.push(Self::project_row(exprs, row)?);
                     }
-                    out.push(Value::Object(proj));
                 }
                 Ok(out)
             }
+            LogicalPlan::SetOperation {
+                left,
+                right,
+                op,
+                all,
+            } => {
+                let left_rows = Self::run_plan_with(left, db, ctes)?;
+                let right_rows = Self::run_plan_with(right, db, ctes)?;
+                Ok(Helpers::set_operation(*op, *all, left_rows, right_rows))
+            }
+            LogicalPlan::Distinct { input } => {
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                Ok(Helpers::distinct_rows(rows))
+            }
             LogicalPlan::Sort { input, keys } => {
-                let mut rows = Self::run_plan(input, db)?;
-                // stable sort
-                rows.sort_by(|a, b| {
-                    let ao = a.as_object().unwrap();
-                    let bo = b.as_object().unwrap();
-                    for k in keys {
-                        let av = Eval::eval_scalar(&k.expr, ao);
-                        let bv = Eval::eval_scalar(&k.expr, bo);
-                        let ord = Helpers::cmp_json_for_sort(&av, &bv, k.ascending);
+                let rows = Self::run_plan_with(input, db, ctes)?;
+                // evaluate the keys once per row, then stable sort
+                let mut keyed = Vec::with_capacity(rows.len());
+                for row in rows {
+                    let values = keys
+                        .iter()
+                        .map(|k| Self::eval_row_scalar(&k.expr, row.as_object().unwrap(), db, ctes))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    keyed.push((values, row));
+                }
+                keyed.sort_by(|(a, _), (b, _)| {
+                    for ((av, bv), k) in a.iter().zip(b).zip(keys) {
+                        let ord = Helpers::cmp_json_for_sort(av, bv, k.ascending);
                         if !ord.is_eq() {
                             return ord;
                         }
                     }
                     std::cmp::Ordering::Equal
                 });
-                Ok(rows)
+                Ok(keyed.into_iter().map(|(_, row)| row).collect())
             }
             LogicalPlan::Limit {
                 input,
                 limit,
                 offset,
             } => {
-                let rows = Self::run_plan(input, db)?;
+                let rows = Self::run_plan_with(input, db, ctes)?;
                 let start = offset.unwrap_or(0).max(0) as usize;
                 let mut end = rows.len();
                 if let Some(lim) = limit {
@@ -133,8 +239,8 @@ impl PlanExecutor {
                 on,
             } => {
                 // Execute children
-                let left_rows = Self::run_plan(left, db)?;
-                let right_rows = Self::run_plan(right, db)?;
+                let left_rows = Self::run_plan_with(left, db, ctes)?;
+                let right_rows = Self::run_plan_with(right, db, ctes)?;
 
                 // Collect key sets for null-extension (derived from observed rows)
                 let left_keys = Self::keyset_for_side(left, &left_rows, db);
@@ -177,7 +283,7 @@ impl PlanExecutor {
                                 let merged = merge_objs(lo, ro);
                                 let mref = merged.as_object().unwrap();
                                 if matches!(
-                                    crate::executor::eval::Eval::eval_predicate3(on, mref),
+                                    Self::eval_row_predicate(on, mref, db, ctes)?,
                                     Truth::True
                                 ) {
                                     out.push(merged);
@@ -194,7 +300,7 @@ impl PlanExecutor {
                                 let merged = merge_objs(lo, ro);
                                 let mref = merged.as_object().unwrap();
                                 if matches!(
-                                    crate::executor::eval::Eval::eval_predicate3(on, mref),
+                                    Self::eval_row_predicate(on, mref, db, ctes)?,
                                     Truth::True
                                 ) {
                                     out.push(merged);
@@ -219,7 +325,7 @@ impl PlanExecutor {
                                 let merged = merge_objs(lo, ro);
                                 let mref = merged.as_object().unwrap();
                                 if matches!(
-                                    crate::executor::eval::Eval::eval_predicate3(on, mref),
+                                    Self::eval_row_predicate(on, mref, db, ctes)?,
                                     Truth::True
                                 ) {
                                     out.push(merged);
@@ -245,7 +351,7 @@ impl PlanExecutor {
                                 let merged = merge_objs(lo, ro);
                                 let mref = merged.as_object().unwrap();
                                 if matches!(
-                                    crate::executor::eval::Eval::eval_predicate3(on, mref),
+                                    Self::eval_row_predicate(on, mref, db, ctes)?,
                                     Truth::True
                                 ) {
                                     out.push(merged);
@@ -279,6 +385,244 @@ impl PlanExecutor {
         }
     }
 
+    /// Fixpoint of a WITH RECURSIVE query: `step` reads the rows added by the previous
+    /// round under `name`. UNION drops rows already produced, so cycles end on their
+    /// own; UNION ALL fails when a round repeats an earlier one, since it would never end.
+    fn run_recursive(
+        name: &str,
+        anchor_rows: Vec<Value>,
+        step: &LogicalPlan,
+        all: bool,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        let limit = db.recursion_limit();
+        let mut result = if all {
+            anchor_rows
+        } else {
+            Helpers::distinct_rows(anchor_rows)
+        };
+        let mut working = result.clone();
+        let mut rounds = HashSet::new();
+        let mut iterations = 0usize;
+
+        while !working.is_empty() {
+            if all && !rounds.insert(Helpers::canonical_tuple(&working)) {
+                return Err(AnalyzerError::Other(format!(
+                    "WITH RECURSIVE query {name} contains a cycle; use UNION to skip rows already produced"
+                )));
+            }
+            if iterations == limit {
+                return Err(AnalyzerError::Other(format!(
+                    "WITH RECURSIVE query {name} did not finish within {limit} iterations"
+                )));
+            }
+            iterations += 1;
+
+            ctes.insert(name.to_string(), working);
+            let mut rows = Self::run_plan_with(step, db, ctes)?;
+            if !all {
+                rows = Helpers::set_operation(SetOperator::Except, false, rows, result.clone());
+            }
+            result.extend(rows.iter().cloned());
+            working = rows;
+        }
+
+        Ok(result)
+    }
+
+    /// Evaluate `predicate` over `row`, first running the subqueries it contains.
+    pub fn eval_row_predicate(
+        predicate: &Predicate,
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Truth, AnalyzerError> {
+        if !predicate.has_subquery() {
+            return Eval::eval_predicate3(predicate, row);
+        }
+        let bound = Self::bind_subqueries(predicate, row, db, ctes)?;
+        Eval::eval_predicate3(&bound, row)
+    }
+
+    fn bind_subqueries(
+        predicate: &Predicate,
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Predicate, AnalyzerError> {
+        Ok(match predicate {
+            Predicate::And(list) => Predicate::And(
+                list.iter()
+                    .map(|p| Self::bind_subqueries(p, row, db, ctes))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Predicate::Or(list) => Predicate::Or(
+                list.iter()
+                    .map(|p| Self::bind_subqueries(p, row, db, ctes))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Predicate::Not(p) => Predicate::Not(Box::new(Self::bind_subqueries(p, row, db, ctes)?)),
+            Predicate::Exists { subquery, negated } => {
+                let found = !Self::run_subquery(subquery, row, db, ctes)?.is_empty();
+                Predicate::Const3(if found != *negated {
+                    Truth::True
+                } else {
+                    Truth::False
+                })
+            }
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                let value = Self::eval_row_scalar(expr, row, db, ctes)?;
+                let candidates = Self::run_subquery(subquery, row, db, ctes)?
+                    .iter()
+                    .map(Self::single_column_value)
+                    .collect::<Vec<_>>();
+                let t = Eval::eval_in3(&value, &candidates);
+                Predicate::Const3(if *negated { t.not() } else { t })
+            }
+            Predicate::Compare { left, op, right } => Predicate::Compare {
+                left: Self::bind_scalar_subqueries(left, row, db, ctes)?,
+                op: *op,
+                right: Self::bind_scalar_subqueries(right, row, db, ctes)?,
+            },
+            Predicate::IsNull { expr, negated } => Predicate::IsNull {
+                expr: Self::bind_scalar_subqueries(expr, row, db, ctes)?,
+                negated: *negated,
+            },
+            Predicate::InList {
+                expr,
+                list,
+                negated,
+            } => Predicate::InList {
+                expr: Self::bind_scalar_subqueries(expr, row, db, ctes)?,
+                list: list
+                    .iter()
+                    .map(|e| Self::bind_scalar_subqueries(e, row, db, ctes))
+                    .collect::<Result<_, _>>()?,
+                negated: *negated,
+            },
+            Predicate::Like {
+                expr,
+                pattern,
+                negated,
+            } => Predicate::Like {
+                expr: Self::bind_scalar_subqueries(expr, row, db, ctes)?,
+                pattern: Self::bind_scalar_subqueries(pattern, row, db, ctes)?,
+                negated: *negated,
+            },
+            Predicate::Const3(t) => Predicate::Const3(*t),
+        })
+    }
+
+    /// Evaluate `expr` over `row`, first running the scalar subqueries it contains.
+    pub fn eval_row_scalar(
+        expr: &ScalarExpr,
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Value, AnalyzerError> {
+        if !expr.has_subquery() {
+            return Eval::eval_scalar(expr, row);
+        }
+        let bound = Self::bind_scalar_subqueries(expr, row, db, ctes)?;
+        Eval::eval_scalar(&bound, row)
+    }
+
+    /// Replace each scalar subquery with the single value it returns for `row`,
+    /// or NULL when it returns no rows.
+    fn bind_scalar_subqueries(
+        expr: &ScalarExpr,
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<ScalarExpr, AnalyzerError> {
+        Ok(match expr {
+            ScalarExpr::Subquery(subquery) => {
+                let rows = Self::run_subquery(subquery, row, db, ctes)?;
+                if rows.len() > 1 {
+                    return Err(AnalyzerError::Other(
+                        "more than one row returned by a subquery used as an expression".into(),
+                    ));
+                }
+                let value = rows
+                    .first()
+                    .map(Self::single_column_value)
+                    .unwrap_or(Value::Null);
+                // arrays and objects have no literal form and compare as NULL
+                ScalarResolver::value_to_scalar(&value)
+                    .unwrap_or(ScalarExpr::Literal(Literal::Null))
+            }
+            ScalarExpr::Function(f) => ScalarExpr::Function(Function {
+                args: f
+                    .args
+                    .iter()
+                    .map(|a| Self::bind_scalar_subqueries(a, row, db, ctes))
+                    .collect::<Result<_, _>>()?,
+                ..f.clone()
+            }),
+            ScalarExpr::Args(args) => ScalarExpr::Args(
+                args.iter()
+                    .map(|a| Self::bind_scalar_subqueries(a, row, db, ctes))
+                    .collect::<Result<_, _>>()?,
+            ),
+            ScalarExpr::Case(case) => {
+                let mut branches = Vec::with_capacity(case.branches.len());
+                for (condition, result) in &case.branches {
+                    branches.push((
+                        Self::bind_subqueries(condition, row, db, ctes)?,
+                        Self::bind_scalar_subqueries(result, row, db, ctes)?,
+                    ));
+                }
+                let otherwise = match &case.otherwise {
+                    Some(otherwise) => Some(Box::new(Self::bind_scalar_subqueries(
+                        otherwise, row, db, ctes,
+                    )?)),
+                    None => None,
+                };
+                ScalarExpr::Case(Case {
+                    branches,
+                    otherwise,
+                })
+            }
+            ScalarExpr::Binary { left, op, right } => ScalarExpr::Binary {
+                left: Box::new(Self::bind_scalar_subqueries(left, row, db, ctes)?),
+                op: *op,
+                right: Box::new(Self::bind_scalar_subqueries(right, row, db, ctes)?),
+            },
+            ScalarExpr::Negate(expr) => {
+                ScalarExpr::Negate(Box::new(Self::bind_scalar_subqueries(expr, row, db, ctes)?))
+            }
+            other => other.clone(),
+        })
+    }
+
+    /// The value of a one-column subquery row.
+    fn single_column_value(row: &Value) -> Value {
+        row.as_object()
+            .and_then(|map| map.values().next())
+            .cloned()
+            .unwrap_or(Value::Null)
+    }
+
+    /// Run a subquery for one row of its enclosing query.
+    pub fn run_subquery(
+        subquery: &Subquery,
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        let analyzed = subquery
+            .analyzed
+            .as_ref()
+            .ok_or_else(|| AnalyzerError::Other("Executor: subquery was not analyzed".into()))?;
+        let plan = PlanBuilder::from_analyzed(&Correlation::bind_query(analyzed, row))?;
+        Self::run_plan_with(&plan, db, ctes)
+    }
+
     fn prefix_rows(rows: Vec<Value>, visible: &str) -> Result<Vec<Value>, AnalyzerError> {
         rows.into_iter()
             .map(|row| match row {
@@ -330,11 +674,31 @@ impl PlanExecutor {
                         .map(|key| format!("{}.{}", visible, key)),
                 );
             }
+            LogicalPlan::CteScan {
+                visible, columns, ..
+            } => {
+                for col in columns {
+                    keys.insert(format!("{}.{}", visible, col));
+                }
+            }
             LogicalPlan::Filter { input, .. }
+            | LogicalPlan::With { input, .. }
+            | LogicalPlan::RecursiveCte { anchor: input, .. }
+            | LogicalPlan::Distinct { input }
+            | LogicalPlan::SetOperation { left: input, .. }
             | LogicalPlan::Sort { input, .. }
             | LogicalPlan::Limit { input, .. } => {
                 keys.extend(Self::keyset_for_plan(input, db));
             }
+            LogicalPlan::Unnest {
+                input,
+                visible,
+                column,
+                ..
+            } => {
+                keys.extend(Self::keyset_for_plan(input, db));
+                keys.insert(format!("{}.{}", visible, column));
+            }
             LogicalPlan::Project { exprs, .. } => {
                 for id in exprs {
                     keys.insert(id.output_name.clone());
@@ -343,14 +707,8 @@ impl PlanExecutor {
             LogicalPlan::Aggregate {
                 group_keys, aggs, ..
             } => {
-                for c in group_keys {
-                    let key = match c {
-                        Column::WithCollection { collection, name } => {
-                            format!("{}.{}", collection, name)
-                        }
-                        Column::Name { name } => name.clone(),
-                    };
-                    keys.insert(key);
+                for expr in group_keys {
+                    keys.insert(LogicalPlan::group_key_name(expr));
                 }
 
                 let mut used = keys.clone();
@@ -366,6 +724,10 @@ impl PlanExecutor {
                     keys.insert(name);
                 }
             }
+            LogicalPlan::Window { input, windows } => {
+                keys.extend(Self::keyset_for_plan(input, db));
+                keys.extend(windows.iter().map(|call| call.name.clone()));
+            }
             LogicalPlan::Join { left, right, .. } => {
                 keys.extend(Self::keyset_for_plan(left, db));
                 keys.extend(Self::keyset_for_plan(right, db));
@@ -376,25 +738,45 @@ impl PlanExecutor {
 
     // ---- Aggregation runner ----
 
+    /// Group `rows` by every key, or once per grouping set when there are any, and
+    /// compute the aggregate calls per group. Keys a set leaves out are NULL in its
+    /// rows and `grouping(...)` reports them as bits, the first argument highest.
     fn aggregate_rows(
         rows: Vec<Value>,
-        group_keys: &[Column],
+        group_keys: &[ScalarExpr],
+        grouping_sets: &[Vec<usize>],
         calls: &[AggregateCall],
     ) -> Result<Vec<Value>, AnalyzerError> {
         use std::collections::{HashMap, HashSet};
-        let mut groups: HashMap<String, GroupEntry> = HashMap::new();
         let registry = AggregateRegistry::default_aggregate_registry();
-        let mut distinct: HashMap<(String, usize), HashSet<String>> = HashMap::new();
+        let accumulators = || -> Vec<Option<Box<dyn AggAcc>>> {
+            calls
+                .iter()
+                .map(|call| registry.get(&call.func).map(|imp| imp.create_accumulator()))
+                .collect()
+        };
 
-        if rows.is_empty() && group_keys.is_empty() {
-            let accs: Vec<Box<dyn AggAcc>> = calls
+        // keys each grouping() call asks about, as indexes into group_keys
+        let key_index = |arg: &ScalarExpr| {
+            let key = ColumnKey::of_expr(arg);
+            group_keys
                 .iter()
-                .map(|call| registry.get(&call.func).unwrap().create_accumulator())
-                .collect();
-            let mut m = Map::new();
-            let mut used = HashSet::new();
+                .position(|g| ColumnKey::of_expr(g) == key)
+                .expect("grouping() arguments are GROUP BY keys")
+        };
+        let grouping_args: Vec<Option<Vec<usize>>> = calls
+            .iter()
+            .map(|call| {
+                (call.func == "grouping").then(|| call.args.iter().map(key_index).collect())
+            })
+            .collect();
 
-            for (call, acc) in calls.iter().zip(accs.iter()) {
+        // output names: group keys first, then base or base_1, base_2, ... per call
+        let mut used: HashSet<String> =
+            group_keys.iter().map(LogicalPlan::group_key_name).collect();
+        let names: Vec<String> = calls
+            .iter()
+            .map(|call| {
                 let base = call.func.to_ascii_lowercase();
                 let mut name = base.clone();
                 let mut k = 1usize;
@@ -403,99 +785,361 @@ impl PlanExecutor {
                     k += 1;
                 }
                 used.insert(name.clone());
-                m.insert(name, acc.finalize());
+                name
+            })
+            .collect();
+
+        let every_key: Vec<usize> = (0..group_keys.len()).collect();
+        let sets: Vec<&[usize]> = match grouping_sets.is_empty() {
+            true => vec![&every_key],
+            false => grouping_sets.iter().map(Vec::as_slice).collect(),
+        };
+
+        let mut out = Vec::new();
+        for set in sets {
+            let mut groups: HashMap<String, GroupEntry> = HashMap::new();
+            let mut distinct: HashMap<(String, usize), HashSet<String>> = HashMap::new();
+
+            for v in &rows {
+                let obj = v.as_object().unwrap();
+
+                // eval group key values
+                let gb_vals: Vec<Value> = set
+                    .iter()
+                    .map(|i| Eval::eval_scalar(&group_keys[*i], obj))
+                    .collect::<Result<_, _>>()?;
+                let gk = Helpers::canonical_tuple(&gb_vals);
+
+                // create group tuple (and accumulators per call) on first sight
+                let entry = groups
+                    .entry(gk.clone())
+                    .or_insert_with(|| (gb_vals.clone(), accumulators()));
+
+                // feed each aggregate
+                for (i, call) in calls.iter().enumerate() {
+                    let Some(acc) = entry.1[i].as_mut() else {
+                        continue;
+                    };
+                    // COUNT(*) special-case: increment per-row regardless of Nulls
+                    let args: Vec<Value> = if call.func.eq_ignore_ascii_case("count")
+                        && call.args.len() == 1
+                        && matches!(call.args[0], ScalarExpr::WildCard)
+                    {
+                        // pass a definite non-null sentinel so CountImpl "counts" it
+                        vec![Value::Bool(true)]
+                    } else {
+                        call.args
+                            .iter()
+                            .map(|a| Eval::eval_scalar(a, obj))
+                            .collect::<Result<_, _>>()?
+                    };
+
+                    if call.distinct {
+                        let key = Helpers::canonical_tuple(&args);
+                        let set = distinct.entry((gk.clone(), i)).or_default();
+                        if set.insert(key) {
+                            acc.update(&args)?;
+                        }
+                    } else {
+                        acc.update(&args)?;
+                    }
+                }
+            }
+
+            // without keys there is always one group, even over no rows
+            if groups.is_empty() && set.is_empty() {
+                groups.insert(String::new(), (vec![], accumulators()));
             }
 
-            return Ok(vec![Value::Object(m)]);
+            // build output rows: group keys first, then aggregates
+            for (_gk, (gb_vals, accs)) in groups.into_iter() {
+                let mut m = Map::new();
+
+                // materialize group keys
+                for (idx, expr) in group_keys.iter().enumerate() {
+                    let value = match set.iter().position(|i| *i == idx) {
+                        Some(at) => gb_vals[at].clone(),
+                        None => Value::Null,
+                    };
+                    m.insert(LogicalPlan::group_key_name(expr), value);
+                }
+
+                for ((name, acc), args) in names.iter().zip(&accs).zip(&grouping_args) {
+                    let value = match (acc, args) {
+                        (Some(acc), _) => acc.finalize(),
+                        (None, Some(args)) => Value::from(
+                            args.iter()
+                                .fold(0i64, |bits, i| bits << 1 | i64::from(!set.contains(i))),
+                        ),
+                        (None, None) => unreachable!("aggregate calls are registered"),
+                    };
+                    m.insert(name.clone(), value);
+                }
+
+                out.push(Value::Object(m));
+            }
         }
+        Ok(out)
+    }
 
-        for v in rows {
-            let obj = v.as_object().unwrap();
+    // ---- Window runner ----
 
-            // eval group key values
-            let gb_vals: Vec<Value> = group_keys
-                .iter()
-                .map(|c| {
-                    let expr = ScalarExpr::Column(c.clone());
-                    Eval::eval_scalar(&expr, obj)
-                })
-                .collect();
-            let gk = Helpers::canonical_tuple(&gb_vals);
+    /// Add one column per window to each row. Rows are split into partitions and
+    /// sorted within each, but come out in their input order.
+    fn window_rows(
+        mut rows: Vec<Value>,
+        calls: &[WindowCall],
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        let registry = AggregateRegistry::default_aggregate_registry();
 
-            // create group tuple on first sight
-            let entry = groups.entry(gk.clone()).or_insert_with(|| {
-                // create accumulators per call
-                let accs: Vec<Box<dyn AggAcc>> = calls
-                    .iter()
-                    .map(|call| registry.get(&call.func).unwrap().create_accumulator())
-                    .collect();
-                (gb_vals.clone(), accs)
-            });
+        for call in calls {
+            let window = &call.window;
+            let count_star = window.function.name.eq_ignore_ascii_case("count")
+                && matches!(window.function.args.as_slice(), [ScalarExpr::WildCard]);
 
-            // feed each aggregate
-            for (i, call) in calls.iter().enumerate() {
-                // COUNT(*) special-case: increment per-row regardless of Nulls
-                let args: Vec<Value> = if call.func.eq_ignore_ascii_case("count")
-                    && call.args.len() == 1
-                    && matches!(call.args[0], ScalarExpr::WildCard)
-                {
+            let order: Vec<ScalarExpr> = window.order_by.iter().map(|ob| ob.expr.clone()).collect();
+
+            // evaluate partition keys, sort keys and arguments once per row
+            let mut partitions: HashMap<String, Vec<usize>> = HashMap::new();
+            let mut sort_keys = Vec::with_capacity(rows.len());
+            let mut args = Vec::with_capacity(rows.len());
+            for (i, row) in rows.iter().enumerate() {
+                let obj = row.as_object().unwrap();
+                let key = Self::eval_row_scalars(&window.partition_by, obj, db, ctes)?;
+                partitions
+                    .entry(Helpers::canonical_tuple(&key))
+                    .or_default()
+                    .push(i);
+                sort_keys.push(Self::eval_row_scalars(&order, obj, db, ctes)?);
+                args.push(match count_star {
                     // pass a definite non-null sentinel so CountImpl "counts" it
-                    vec![Value::Bool(true)]
-                } else {
-                    call.args
-                        .iter()
-                        .map(|a| Eval::eval_scalar(a, obj))
-                        .collect()
-                };
+                    true => vec![Value::Bool(true)],
+                    false => Self::eval_row_scalars(&window.function.args, obj, db, ctes)?,
+                });
+            }
 
-                if call.distinct {
-                    let key = Helpers::canonical_tuple(&args);
-                    let set = distinct.entry((gk.clone(), i)).or_default();
-                    if set.insert(key) {
-                        entry.1[i].update(&args)?;
+            let mut results = vec![Value::Null; rows.len()];
+            for mut members in partitions.into_values() {
+                members.sort_by(|a, b| {
+                    for ((av, bv), ob) in sort_keys[*a]
+                        .iter()
+                        .zip(&sort_keys[*b])
+                        .zip(&window.order_by)
+                    {
+                        let ord = Helpers::cmp_json_for_sort(av, bv, ob.ascending);
+                        if !ord.is_eq() {
+                            return ord;
+                        }
                     }
-                } else {
-                    entry.1[i].update(&args)?;
+                    std::cmp::Ordering::Equal
+                });
+                let sorted_keys: Vec<&Vec<Value>> =
+                    members.iter().map(|&i| &sort_keys[i]).collect();
+                let sorted_args: Vec<&Vec<Value>> = members.iter().map(|&i| &args[i]).collect();
+                let values = Self::window_partition(window, &sorted_keys, &sorted_args, &registry)?;
+                for (i, value) in members.into_iter().zip(values) {
+                    results[i] = value;
                 }
             }
-        }
 
-        // build output rows: group keys first, then aggregates
-        let mut out = Vec::new();
-        for (_gk, (gb_vals, accs)) in groups.into_iter() {
-            let mut m = Map::new();
-
-            // materialize group keys
-            for (idx, c) in group_keys.iter().enumerate() {
-                let key = match c {
-                    Column::WithCollection { collection, name } => {
-                        format!("{}.{}", collection, name)
-                    }
-                    Column::Name { name } => name.clone(),
-                };
-                m.insert(key, gb_vals[idx].clone());
+            for (row, value) in rows.iter_mut().zip(results) {
+                if let Value::Object(m) = row {
+                    m.insert(call.name.clone(), value);
+                }
             }
+        }
+        Ok(rows)
+    }
 
-            // keep track of used names so we can assign base or base_1, base_2, ...
-            let mut used: HashSet<String> = m.keys().cloned().collect();
+    fn eval_row_scalars(
+        exprs: &[ScalarExpr],
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        exprs
+            .iter()
+            .map(|e| Self::eval_row_scalar(e, row, db, ctes))
+            .collect()
+    }
 
-            for (call, acc) in calls.iter().zip(accs.iter()) {
-                let base = call.func.to_ascii_lowercase();
-                let mut name = base.clone();
-                let mut k = 1usize;
-                while used.contains(&name) {
-                    name = format!("{}_{}", base, k);
-                    k += 1;
+    /// Values of `window` for the rows of one sorted partition, given their sort
+    /// keys and evaluated function arguments.
+    fn window_partition(
+        window: &Window,
+        keys: &[&Vec<Value>],
+        args: &[&Vec<Value>],
+        registry: &AggregateRegistry,
+    ) -> Result<Vec<Value>, AnalyzerError> {
+        let n = keys.len();
+        let peers = |a: usize, b: usize| {
+            keys[a]
+                .iter()
+                .zip(keys[b])
+                .zip(&window.order_by)
+                .all(|((av, bv), ob)| Helpers::cmp_json_for_sort(av, bv, ob.ascending).is_eq())
+        };
+
+        // first and last position of the peers of each row (rows that sort equal)
+        let mut peer_start = Vec::with_capacity(n);
+        let mut peer_end = vec![0; n];
+        for p in 0..n {
+            peer_start.push(match p > 0 && peers(p - 1, p) {
+                true => peer_start[p - 1],
+                false => p,
+            });
+        }
+        for p in (0..n).rev() {
+            peer_end[p] = match p + 1 < n && peers(p, p + 1) {
+                true => peer_end[p + 1],
+                false => p,
+            };
+        }
+
+        // frame of each row as positions in the partition; empty when start > end
+        let frame = |p: usize| -> (isize, isize) {
+            let (start, end) = match window.frame {
+                Some(frame) => (frame.start.offset(p, n), frame.end.offset(p, n)),
+                None => (0, peer_end[p] as isize),
+            };
+            (start.max(0), end.min(n as isize - 1))
+        };
+        let int_arg = |p: usize, i: usize, default: i64| match args[p].get(i) {
+            None => Some(default),
+            Some(value) => value.as_i64(),
+        };
+
+        let lname = window.function.name.to_ascii_lowercase();
+        let mut out = Vec::with_capacity(n);
+        match lname.as_str() {
+            "row_number" => out.extend((1..=n).map(Value::from)),
+            "rank" => out.extend(peer_start.iter().map(|&s| Value::from(s + 1))),
+            "dense_rank" => {
+                let mut rank = 0;
+                for (p, &start) in peer_start.iter().enumerate() {
+                    if start == p {
+                        rank += 1;
+                    }
+                    out.push(Value::from(rank));
+                }
+            }
+            // the first `n % buckets` buckets hold one row more than the others
+            "ntile" => {
+                for p in 0..n {
+                    out.push(match int_arg(p, 0, 1) {
+                        Some(buckets) if buckets > 0 => {
+                            let buckets = buckets as usize;
+                            let (size, larger) = (n / buckets, n % buckets);
+                            let bucket = match p < larger * (size + 1) {
+                                true => p / (size + 1),
+                                false => larger + (p - larger * (size + 1)) / size,
+                            };
+                            Value::from(bucket + 1)
+                        }
+                        Some(buckets) => {
+                            return Err(AnalyzerError::Other(format!(
+                                "NTILE bucket count must be positive, got {}",
+                                buckets
+                            )));
+                        }
+                        None => Value::Null,
+                    });
+                }
+            }
+            "lag" | "lead" => {
+                for p in 0..n {
+                    let Some(offset) = int_arg(p, 1, 1) else {
+                        out.push(Value::Null);
+                        continue;
+                    };
+                    // an offset that overflows points outside the partition too
+                    let target = match lname.as_str() {
+                        "lag" => (p as i64).checked_sub(offset),
+                        _ => (p as i64).checked_add(offset),
+                    };
+                    out.push(match target.filter(|t| (0..n as i64).contains(t)) {
+                        Some(target) => args[target as usize][0].clone(),
+                        None => args[p].get(2).cloned().unwrap_or(Value::Null),
+                    });
+                }
+            }
+            "first_value" | "last_value" => {
+                for p in 0..n {
+                    let (start, end) = frame(p);
+                    let at = match lname.as_str() {
+                        "first_value" => start,
+                        _ => end,
+                    };
+                    out.push(match start <= end {
+                        true => args[at as usize][0].clone(),
+                        false => Value::Null,
+                    });
+                }
+            }
+            _ => {
+                let imp = registry
+                    .get(&lname)
+                    .ok_or_else(|| AnalyzerError::FunctionNotFound(lname.clone()))?;
+                let running = window
+                    .frame
+                    .is_none_or(|frame| frame.start == FrameBound::UnboundedPreceding);
+                if running {
+                    // frames only grow: feed each row once to a single accumulator
+                    let mut acc = imp.create_accumulator();
+                    let mut fed = 0;
+                    for p in 0..n {
+                        let (_, end) = frame(p);
+                        while (fed as isize) <= end {
+                            acc.update(args[fed])?;
+                            fed += 1;
+                        }
+                        out.push(acc.finalize());
+                    }
+                } else {
+                    for p in 0..n {
+                        let (start, end) = frame(p);
+                        let mut acc = imp.create_accumulator();
+                        for q in start..=end {
+                            acc.update(args[q as usize])?;
+                        }
+                        out.push(acc.finalize());
+                    }
                 }
-                used.insert(name.clone());
-                m.insert(name, acc.finalize());
             }
-
-            out.push(Value::Object(m));
         }
         Ok(out)
     }
 
+    /// Evaluate a projection list over one row, keyed by each output name.
+    pub fn project_row(
+        exprs: &[AnalyzedIdentifier],
+        row: &Map<String, Value>,
+    ) -> Result<Value, AnalyzerError> {
+        let mut proj = Map::new();
+        for id in exprs {
+            let val = Eval::eval_scalar(&id.expression, row)?;
+            proj.insert(id.output_name.clone(), val);
+        }
+        Ok(Value::Object(proj))
+    }
+
+    /// [`Self::project_row`] for projections that contain subqueries.
+    pub fn project_row_with(
+        exprs: &[AnalyzedIdentifier],
+        row: &Map<String, Value>,
+        db: &Db,
+        ctes: &mut CteRows,
+    ) -> Result<Value, AnalyzerError> {
+        let mut proj = Map::new();
+        for id in exprs {
+            let val = Self::eval_row_scalar(&id.expression, row, db, ctes)?;
+            proj.insert(id.output_name.clone(), val);
+        }
+        Ok(Value::Object(proj))
+    }
+
     // Simple default naming when no alias is set (used by Project)
     pub fn default_name_for_expr(e: &ScalarExpr) -> String {
         match e {
@@ -503,11 +1147,20 @@ impl PlanExecutor {
                 format!("{}.{}", collection, name)
             }
             ScalarExpr::Column(Column::Name { name }) => name.clone(),
+            ScalarExpr::JsonPath {
+                column: Column::WithCollection { name, .. } | Column::Name { name },
+                path,
+            } => JsonPath::default_name(name, path),
             ScalarExpr::Function(f) => f.name.to_ascii_lowercase(),
             ScalarExpr::Literal(_) => "_lit".into(),
             ScalarExpr::WildCard | ScalarExpr::WildCardWithCollection(_) => "*".into(),
             ScalarExpr::Parameter => "?".into(),
+            ScalarExpr::NamedParameter(name) => name.clone(),
             ScalarExpr::Args(_) => "(...)".into(),
+            ScalarExpr::Subquery(_) => "_subquery".into(),
+            ScalarExpr::Case(_) => "case".into(),
+            ScalarExpr::Window(window) => window.function.name.to_ascii_lowercase(),
+            ScalarExpr::Binary { .. } | ScalarExpr::Negate(_) => "_expr".into(),
         }
     }
 }
@@ -561,6 +1214,8 @@ mod tests {
 
     fn analyzed_sum_by_cat() -> AnalyzedQuery {
         AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![
                 AnalyzedIdentifier {
                     expression: ScalarExpr::Column(Column::WithCollection {
@@ -599,10 +1254,11 @@ mod tests {
                 op: ComparatorOp::Gt,
                 right: ScalarExpr::Literal(Literal::Int(1)),
             }),
-            group_by: vec![Column::WithCollection {
+            group_by: vec![ScalarExpr::Column(Column::WithCollection {
                 collection: "t".into(),
                 name: "cat".into(),
-            }],
+            })],
+            grouping_sets: vec![],
             having: Some(Predicate::Compare {
                 left: ScalarExpr::Function(Function {
                     name: "sum".into(),
@@ -623,6 +1279,7 @@ mod tests {
             }],
             limit: Some(10),
             offset: None,
+            set_operations: vec![],
             joins: vec![],
         }
     }
@@ -943,13 +1600,14 @@ mod tests {
                 distinct: false,
             },
         ];
-        let group_keys = vec![Column::WithCollection {
+        let group_keys = vec![ScalarExpr::Column(Column::WithCollection {
             collection: "t".into(),
             name: "cat".into(),
-        }];
+        })];
         let agg = LogicalPlan::Aggregate {
             input: Box::new(filter),
             group_keys: group_keys.clone(),
+            grouping_sets: vec![],
             aggs,
         };
 
@@ -1061,13 +1719,14 @@ mod tests {
                 distinct: true,
             },
         ];
-        let group_keys = vec![Column::WithCollection {
+        let group_keys = vec![ScalarExpr::Column(Column::WithCollection {
             collection: "t".into(),
             name: "x".into(),
-        }];
+        })];
         let plan = LogicalPlan::Aggregate {
             input: Box::new(scan),
             group_keys: group_keys.clone(),
+            grouping_sets: vec![],
             aggs,
         };
         let out = PlanExecutor::run_plan(&plan, &db).unwrap();
@@ -1136,14 +1795,15 @@ mod tests {
                 distinct: false,
             },
         ];
-        let group_keys = vec![Column::WithCollection {
+        let group_keys = vec![ScalarExpr::Column(Column::WithCollection {
             collection: "t".into(),
             name: "g".into(),
-        }];
+        })];
         let plan = LogicalPlan::Aggregate {
             input: Box::new(scan),
             group_keys,
             aggs,
+            grouping_sets: vec![],
         };
         let mut out = PlanExecutor::run_plan(&plan, &db).unwrap();
 
@@ -1485,12 +2145,13 @@ mod tests {
         let aggregate = LogicalPlan::Aggregate {
             input: Box::new(project),
             group_keys: vec![
-                Column::WithCollection {
+                ScalarExpr::Column(Column::WithCollection {
                     collection: "t".into(),
                     name: "grp".into(),
-                },
-                Column::Name { name: "sum".into() },
+                }),
+                ScalarExpr::Column(Column::Name { name: "sum".into() }),
             ],
+            grouping_sets: vec![],
             aggs: vec![
                 AggregateCall {
                     func: "sum".into(),
@@ -0,0 +1,55 @@
+use std::cell::Cell;
+
+thread_local! {
+    static STATE: Cell<u64> = Cell::new(Random::entropy());
+}
+
+/// Generator behind `random()`.
+///
+/// `Eval` keeps no state, so each statement reseeds the generator of the thread
+/// running it, either from [`crate::Db::random_seed`] or from fresh entropy.
+pub struct Random;
+
+impl Random {
+    /// Restart the sequence; `None` picks an unpredictable seed.
+    pub fn reseed(seed: Option<u64>) {
+        STATE.with(|state| state.set(seed.unwrap_or_else(Self::entropy)));
+    }
+
+    /// Next value in `[0, 1)`, from a splitmix64 sequence.
+    pub fn next_f64() -> f64 {
+        let bits = STATE.with(|state| {
+            let next = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+            state.set(next);
+            let mut z = next;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        });
+        // the top 53 bits fill the mantissa exactly
+        (bits >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn entropy() -> u64 {
+        uuid::Uuid::new_v4().as_u64_pair().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::executor::random::Random;
+
+    #[test]
+    fn seeded_sequences_repeat_and_stay_in_range() {
+        Random::reseed(Some(7));
+        let first: Vec<f64> = (0..5).map(|_| Random::next_f64()).collect();
+        Random::reseed(Some(7));
+        let again: Vec<f64> = (0..5).map(|_| Random::next_f64()).collect();
+        Random::reseed(Some(8));
+        let other: Vec<f64> = (0..5).map(|_| Random::next_f64()).collect();
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        assert!(first.iter().all(|value| (0.0..1.0).contains(value)));
+    }
+}
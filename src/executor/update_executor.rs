@@ -0,0 +1,167 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    Db,
+    database::SchemaProvider,
+    executor::{
+        helpers::Helpers,
+        plan_executor::{CteRows, Executor, PlanExecutor},
+    },
+    parser::analyzer::{AnalyzedUpdate, AnalyzerError, AssignmentResolver},
+};
+
+pub struct UpdateExecutor {
+    update: AnalyzedUpdate,
+}
+
+impl Executor for UpdateExecutor {
+    fn execute(&self, db: &Db) -> Result<Vec<Value>, AnalyzerError> {
+        let collection = db
+            .get(&self.update.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(self.update.collection.clone()))?;
+        let config = collection
+            .get_config()
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+
+        if self
+            .update
+            .assignments
+            .iter()
+            .any(|(column, _)| *column == config.id_key)
+        {
+            return Err(AnalyzerError::Other(format!(
+                "cannot update id column {}",
+                config.id_key
+            )));
+        }
+
+        // evaluate every change against the pre-update snapshot before writing
+        let documents = collection
+            .get_all()
+            .map_err(|error| AnalyzerError::Other(error.to_string()))?;
+        let rows = Helpers::matching_rows(
+            documents,
+            &self.update.collection,
+            &config.id_key,
+            self.update.criteria.as_ref(),
+            db,
+        )?;
+
+        let schema = db.schema_of(&self.update.collection);
+        let mut ctes = CteRows::new();
+        let mut changes = Vec::with_capacity(rows.len());
+        for (id, row) in rows {
+            let mut assigned = Map::new();
+            for (column, expr) in &self.update.assignments {
+                let value = PlanExecutor::eval_row_scalar(expr, &row, db, &mut ctes)?;
+                let value = AssignmentResolver::write_value(
+                    &self.update.collection,
+                    schema.as_ref(),
+                    column,
+                    value,
+                )?;
+                assigned.insert(column.clone(), value);
+            }
+            changes.push((id, assigned));
+        }
+
+        let mut updated = Vec::with_capacity(changes.len());
+        for (id, assigned) in changes {
+            let Some(Value::Object(mut document)) = collection
+                .get(&id)
+                .map_err(|error| AnalyzerError::Other(error.to_string()))?
+            else {
+                continue;
+            };
+            // an assignment replaces the column's value, objects are not merged into it
+            document.extend(assigned);
+            if let Some(document) = collection
+                .update(&id, Value::Object(document))
+                .map_err(|error| AnalyzerError::Other(error.to_string()))?
+            {
+                updated.push(document);
+            }
+        }
+
+        Helpers::dml_output(&self.update.collection, &self.update.returning, &updated)
+    }
+}
+
+impl UpdateExecutor {
+    pub fn new(update: AnalyzedUpdate) -> Self {
+        Self { update }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbConfig;
+    use crate::parser::ast::{Column, ComparatorOp, Function, Literal, Predicate, ScalarExpr};
+    use serde_json::json;
+
+    fn mk_db() -> Db {
+        let db = Db::new_with_config(DbConfig::none("id"));
+        let people = db.create("people");
+        people
+            .add_batch(json!([
+                { "id": 1, "name": "ada", "age": 37 },
+                { "id": 2, "name": "grace", "age": null },
+                { "id": 3, "name": "linus", "age": 20 }
+            ]))
+            .unwrap();
+        db
+    }
+
+    fn col(name: &str) -> ScalarExpr {
+        ScalarExpr::Column(Column::WithCollection {
+            collection: "people".into(),
+            name: name.into(),
+        })
+    }
+
+    #[test]
+    fn updates_only_rows_where_predicate_is_true() {
+        let db = mk_db();
+        let update = AnalyzedUpdate {
+            collection: "people".into(),
+            assignments: vec![(
+                "name".into(),
+                ScalarExpr::Function(Function {
+                    name: "upper".into(),
+                    args: vec![col("name")],
+                    distinct: false,
+                }),
+            )],
+            // age > 18 is Unknown for grace (NULL age), so she is left untouched
+            criteria: Some(Predicate::Compare {
+                left: col("age"),
+                op: ComparatorOp::Gt,
+                right: ScalarExpr::Literal(Literal::Int(18)),
+            }),
+            returning: vec![],
+        };
+
+        let out = UpdateExecutor::new(update).execute(&db).unwrap();
+
+        assert_eq!(out, vec![json!({ "affected_rows": 2 })]);
+        let people = db.get("people").unwrap();
+        assert_eq!(people.get("1").unwrap().unwrap()["name"], "ADA");
+        assert_eq!(people.get("2").unwrap().unwrap()["name"], "grace");
+        assert_eq!(people.get("3").unwrap().unwrap()["name"], "LINUS");
+    }
+
+    #[test]
+    fn id_column_cannot_be_updated() {
+        let db = mk_db();
+        let update = AnalyzedUpdate {
+            collection: "people".into(),
+            assignments: vec![("id".into(), ScalarExpr::Literal(Literal::Int(9)))],
+            criteria: None,
+            returning: vec![],
+        };
+
+        let err = UpdateExecutor::new(update).execute(&db).unwrap_err();
+        assert!(matches!(err, AnalyzerError::Other(message) if message.contains("id column")));
+    }
+}
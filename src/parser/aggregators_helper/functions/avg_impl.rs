@@ -22,7 +22,9 @@ impl AggregateImpl for AvgImpl {
         if let Some(arg) = fun.args.first() {
             let (t, _n) = crate::parser::analyzer::TypeInference::infer_scalar(arg, ctx)?;
             match t {
-                JsonPrimitive::Int | JsonPrimitive::Float => Ok((JsonPrimitive::Float, true)),
+                JsonPrimitive::Int | JsonPrimitive::Float | JsonPrimitive::Any => {
+                    Ok((JsonPrimitive::Float, true))
+                }
                 other => Err(AnalyzerError::FunctionArgMismatch {
                     name: fun.name.clone(),
                     expected: "numeric".into(),
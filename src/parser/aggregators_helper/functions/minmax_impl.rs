@@ -4,7 +4,7 @@ use crate::{
     JsonPrimitive,
     parser::{
         aggregators_helper::{Accumulator, AggregateImpl},
-        analyzer::{AnalysisContext, AnalyzerError, TypeInference},
+        analyzer::{AnalysisContext, AnalyzerError, TemporalResolver, TypeInference},
         ast::Function,
     },
 };
@@ -99,7 +99,8 @@ impl ExtremaAcc {
                     _ => return Err(AnalyzerError::Other("MIN/MAX mixed numeric kinds".into())),
                 }
             }
-            (String(x), String(y)) => x.cmp(y),
+            // ISO-8601 strings compare chronologically, in the same order as ORDER BY
+            (String(x), String(y)) => TemporalResolver::order(x, y),
             // Arrays/Objects: usually unsupported in SQL; keep strict and error
             (Array(_), _) | (Object(_), _) | (_, Array(_)) | (_, Object(_)) => {
                 return Err(AnalyzerError::Other("MIN/MAX unsupported type".into()));
@@ -25,6 +25,8 @@ impl AggregateImpl for SumImpl {
             match t {
                 JsonPrimitive::Int => Ok((JsonPrimitive::Int, true)),
                 JsonPrimitive::Float => Ok((JsonPrimitive::Float, true)),
+                // Int or Float is decided by the values summed
+                JsonPrimitive::Any => Ok((JsonPrimitive::Any, true)),
                 other => Err(AnalyzerError::FunctionArgMismatch {
                     name: fun.name.clone(),
                     expected: "numeric".into(),
@@ -3,15 +3,17 @@ use once_cell::sync::Lazy;
 use serde_json::Value;
 
 use crate::{
+    JsonPrimitive,
     database::{FieldInfo, SchemaDict, SchemaProvider},
     parser::{
         aggregators_helper::AggregateRegistry,
         analyzer::{
             AggregateResolver, AnalyzedIdentifier, AnalyzedJoin, AnalyzedQuery, AnalyzedSource,
-            AnalyzerError, ColumnKey, ColumnResolver, IdentifierResolver, OrderByResolver,
-            PredicateResolver, ScalarResolver, TypeInference,
+            AnalyzedStatement, AnalyzerError, CollectionDdlResolver, ColumnKey, CteResolver,
+            DeleteResolver, IdentifierResolver, InsertResolver, OrderByResolver, PredicateResolver,
+            ScalarResolver, SetOperationResolver, TypeInference, UpdateResolver,
         },
-        ast::{Collection, Column, Query, ScalarExpr},
+        ast::{Collection, Column, Identifier, JsonPath, Query, ScalarExpr, Statement},
     },
 };
 
@@ -19,6 +21,15 @@ use crate::{
 static DEFAULT_REGISTRY: Lazy<AggregateRegistry> =
     Lazy::new(AggregateRegistry::default_aggregate_registry);
 
+/// Names a nested query can read from the queries around it.
+#[derive(Debug, Clone, Default)]
+pub struct QueryScope {
+    /// output schemas of the WITH queries in scope, by name
+    pub ctes: IndexMap<String, SchemaDict>,
+    /// visible collections of the enclosing queries, for correlated subqueries
+    pub outer: IndexMap<String, SchemaDict>,
+}
+
 pub struct AnalysisContext<'a> {
     /// map visible name -> underlying collection ref (alias or table)
     pub collections: IndexMap<String, String>,
@@ -31,6 +42,12 @@ pub struct AnalysisContext<'a> {
     pub parameters: Value,
     /// current parameter position
     pub current_param: usize,
+    /// output schemas of the WITH queries in scope, by name
+    pub ctes: IndexMap<String, SchemaDict>,
+    /// visible collections of the enclosing queries, by visible name
+    pub outer: IndexMap<String, SchemaDict>,
+    /// window functions are allowed in what is being qualified (SELECT list and ORDER BY)
+    pub windows_allowed: bool,
 }
 
 impl<'a> AnalysisContext<'a> {
@@ -45,6 +62,9 @@ impl<'a> AnalysisContext<'a> {
             aggregates,
             parameters: Value::Null,
             current_param: 0,
+            ctes: IndexMap::new(),
+            outer: IndexMap::new(),
+            windows_allowed: false,
         }
     }
 
@@ -75,6 +95,49 @@ impl<'a> AnalysisContext<'a> {
             .or_else(|| self.schemas.schema_of(collection_ref))
     }
 
+    /// Scope for a subquery nested in an expression of this query: it sees the
+    /// WITH queries and every collection visible here or in enclosing queries.
+    pub fn nested_scope(&self) -> QueryScope {
+        let mut outer = self.outer.clone();
+        for (visible, backing) in &self.collections {
+            if let Some(schema) = self.schema_of_collection_ref(backing) {
+                outer.insert(visible.clone(), schema);
+            }
+        }
+        QueryScope {
+            ctes: self.ctes.clone(),
+            outer,
+        }
+    }
+
+    /// Scope for FROM/JOIN subqueries: siblings are not visible, enclosing queries are.
+    fn source_scope(&self) -> QueryScope {
+        QueryScope {
+            ctes: self.ctes.clone(),
+            outer: self.outer.clone(),
+        }
+    }
+
+    /// Analyze a query nested in the one this context analyzes; its `?` placeholders
+    /// continue the numbering of the enclosing statement.
+    pub fn analyze_nested_query(
+        &mut self,
+        query: &Query,
+        scope: &QueryScope,
+    ) -> Result<AnalyzedQuery, AnalyzerError> {
+        let mut current_param = self.current_param;
+        let analyzed = Self::analyze_query_in_scope(
+            query,
+            self.schemas,
+            self.aggregates,
+            self.parameters.clone(),
+            &mut current_param,
+            scope,
+        )?;
+        self.current_param = current_param;
+        Ok(analyzed)
+    }
+
     pub fn build_context_from_query(
         q: &Query,
         sp: &'a dyn SchemaProvider,
@@ -95,6 +158,9 @@ impl<'a> AnalysisContext<'a> {
                     })?;
                     ctx.add_collection(visible.clone(), visible);
                 }
+                Collection::Unnest { alias, .. } => {
+                    ctx.add_collection(alias.clone(), alias.clone())
+                }
             }
         }
         // Joins often introduce new visible names too (if you allow `JOIN t AS x`)
@@ -110,6 +176,9 @@ impl<'a> AnalysisContext<'a> {
                     })?;
                     ctx.add_collection(visible.clone(), visible);
                 }
+                Collection::Unnest { alias, .. } => {
+                    ctx.add_collection(alias.clone(), alias.clone())
+                }
             }
         }
 
@@ -122,10 +191,20 @@ impl<'a> AnalysisContext<'a> {
                 format!("{}.{}", collection, name)
             }
             ScalarExpr::Column(Column::Name { name }) => name.clone(),
+            ScalarExpr::JsonPath {
+                column: Column::WithCollection { name, .. } | Column::Name { name },
+                path,
+            } => JsonPath::default_name(name, path),
             ScalarExpr::Function(f) => f.name.to_ascii_lowercase(),
             ScalarExpr::Literal(_) => "_lit".into(),
             ScalarExpr::WildCard | ScalarExpr::WildCardWithCollection(_) => "*".into(),
-            ScalarExpr::Parameter | ScalarExpr::Args(_) => "_param".into(),
+            ScalarExpr::Parameter | ScalarExpr::NamedParameter(_) | ScalarExpr::Args(_) => {
+                "_param".into()
+            }
+            ScalarExpr::Subquery(_) => "_subquery".into(),
+            ScalarExpr::Case(_) => "case".into(),
+            ScalarExpr::Window(window) => window.function.name.to_ascii_lowercase(),
+            ScalarExpr::Binary { .. } | ScalarExpr::Negate(_) => "_expr".into(),
         }
     }
 
@@ -164,31 +243,124 @@ impl<'a> AnalysisContext<'a> {
         }
     }
 
+    /// Expand wildcards, then qualify, fold, type and name each projected identifier
+    /// against the collections already registered in this context.
+    pub fn analyze_projection(
+        &mut self,
+        projection: &[Identifier],
+    ) -> Result<Vec<AnalyzedIdentifier>, AnalyzerError> {
+        // expand wildcards in projection
+        let expanded_proj = IdentifierResolver::expand_projection_idents(projection, self)?;
+
+        // qualify + fold + type inference
+        let mut analyzed_proj = Vec::with_capacity(expanded_proj.len());
+        for id in expanded_proj {
+            // qualify (no wildcards remain)
+            let qexpr = ScalarResolver::qualify_scalar(&id.expression, self, false)?;
+            // fold constants
+            let fexpr = ScalarResolver::fold_scalar(&qexpr);
+            // infer type
+            let (ty, nullable) = TypeInference::infer_scalar(&fexpr, self)?;
+            analyzed_proj.push(AnalyzedIdentifier {
+                expression: fexpr,
+                alias: id.alias.clone(),
+                ty,
+                nullable,
+                output_name: String::new(),
+            });
+        }
+        AnalysisContext::assign_output_names(&mut analyzed_proj);
+
+        Ok(analyzed_proj)
+    }
+
     pub fn analyze_query(
         query: &Query,
         schema_provider: &'a dyn SchemaProvider,
         aggregates: &'a AggregateRegistry,
         parameters: Value,
     ) -> Result<AnalyzedQuery, AnalyzerError> {
+        Self::analyze_query_in_scope(
+            query,
+            schema_provider,
+            aggregates,
+            parameters,
+            &mut 0,
+            &QueryScope::default(),
+        )
+    }
+
+    /// Analyze a query that can also read the WITH queries and columns of its enclosing queries.
+    /// Its `?` placeholders start at `current_param`, which is left past the last one it used.
+    pub fn analyze_query_in_scope(
+        query: &Query,
+        schema_provider: &'a dyn SchemaProvider,
+        aggregates: &'a AggregateRegistry,
+        parameters: Value,
+        current_param: &mut usize,
+        scope: &QueryScope,
+    ) -> Result<AnalyzedQuery, AnalyzerError> {
+        if !query.ctes.is_empty() {
+            let mut scope = scope.clone();
+            let analyzed_ctes = CteResolver::analyze_ctes(
+                &query.ctes,
+                schema_provider,
+                aggregates,
+                parameters.clone(),
+                current_param,
+                &mut scope,
+            )?;
+            let body = Query {
+                ctes: vec![],
+                ..query.clone()
+            };
+            let mut analyzed = Self::analyze_query_in_scope(
+                &body,
+                schema_provider,
+                aggregates,
+                parameters,
+                current_param,
+                &scope,
+            )?;
+            analyzed.ctes = analyzed_ctes;
+            return Ok(analyzed);
+        }
+
+        if !query.set_operations.is_empty() {
+            return SetOperationResolver::analyze_compound(
+                query,
+                schema_provider,
+                aggregates,
+                parameters,
+                current_param,
+                scope,
+            );
+        }
+
         let mut ctx = Self::new_with_aggregates(schema_provider, aggregates);
         ctx.parameters = parameters.clone();
+        ctx.current_param = *current_param;
+        ctx.ctes = scope.ctes.clone();
+        ctx.outer = scope.outer.clone();
+
+        // the projection is written before FROM, so its `?` come first even though it is
+        // analyzed once the sources are known
+        let projection_param = ctx.current_param;
+        ctx.current_param += query
+            .projection
+            .iter()
+            .map(|id| id.expression.parameter_count())
+            .sum::<usize>();
 
         let mut from_collections: Vec<AnalyzedSource> = Vec::with_capacity(query.collections.len());
         for c in &query.collections {
-            let source =
-                Self::analyze_source(c, schema_provider, aggregates, parameters.clone(), &mut ctx)?;
+            let source = Self::analyze_source(c, &mut ctx)?;
             from_collections.push(source);
         }
 
         let mut analyzed_joins = Vec::with_capacity(query.joins.len());
         for join in &query.joins {
-            let source = Self::analyze_source(
-                &join.collection,
-                schema_provider,
-                aggregates,
-                parameters.clone(),
-                &mut ctx,
-            )?;
+            let source = Self::analyze_source(&join.collection, &mut ctx)?;
             let qp = PredicateResolver::qualify_predicate(&join.predicate, &mut ctx)?;
             let fp = PredicateResolver::fold_predicate(&qp);
             analyzed_joins.push(AnalyzedJoin {
@@ -198,27 +370,12 @@ impl<'a> AnalysisContext<'a> {
             });
         }
 
-        // expand wildcards in projection
-        let expanded_proj = IdentifierResolver::expand_projection_idents(&query.projection, &ctx)?;
-
-        // qualify + fold + type inference
-        let mut analyzed_proj = Vec::with_capacity(expanded_proj.len());
-        for id in expanded_proj {
-            // qualify (no wildcards remain)
-            let qexpr = ScalarResolver::qualify_scalar(&id.expression, &mut ctx, false)?;
-            // fold constants
-            let fexpr = ScalarResolver::fold_scalar(&qexpr);
-            // infer type
-            let (ty, nullable) = TypeInference::infer_scalar(&fexpr, &ctx)?;
-            analyzed_proj.push(AnalyzedIdentifier {
-                expression: fexpr,
-                alias: id.alias.clone(),
-                ty,
-                nullable,
-                output_name: String::new(),
-            });
-        }
-        AnalysisContext::assign_output_names(&mut analyzed_proj);
+        let sources_param = ctx.current_param;
+        ctx.current_param = projection_param;
+        ctx.windows_allowed = true;
+        let mut analyzed_proj = ctx.analyze_projection(&query.projection)?;
+        ctx.windows_allowed = false;
+        ctx.current_param = sources_param;
 
         // qualify + fold predicates
         let criteria_qualified = match &query.criteria {
@@ -229,6 +386,41 @@ impl<'a> AnalysisContext<'a> {
             .as_ref()
             .map(PredicateResolver::fold_predicate);
 
+        // qualify group_by columns and expressions
+        let mut group_by = Vec::with_capacity(query.group_by.len());
+        let mut group_set = std::collections::HashSet::<ColumnKey>::new();
+        let mut group_index = Vec::with_capacity(query.group_by.len());
+        for expr in &query.group_by {
+            let qualified = ScalarResolver::qualify_scalar(expr, &mut ctx, false)?;
+            if AggregateResolver::contains_aggregate(&qualified) {
+                return Err(AnalyzerError::Other(
+                    "Aggregates are not allowed in GROUP BY".into(),
+                ));
+            }
+            let folded = ScalarResolver::fold_scalar(&qualified);
+            // `a` and `t.a` are one key once qualified, so grouping sets share its index
+            let key = ColumnKey::of_expr(&folded);
+            let index = match group_by.iter().position(|g| ColumnKey::of_expr(g) == key) {
+                Some(index) => index,
+                None => {
+                    group_by.push(folded);
+                    group_by.len() - 1
+                }
+            };
+            group_set.insert(key);
+            group_index.push(index);
+        }
+        let grouping_sets: Vec<Vec<usize>> = query
+            .grouping_sets
+            .iter()
+            .map(|set| {
+                let mut set: Vec<usize> = set.iter().map(|i| group_index[*i]).collect();
+                set.sort_unstable();
+                set.dedup();
+                set
+            })
+            .collect();
+
         let having_qualified = match &query.having {
             Some(predicate) => Some(PredicateResolver::qualify_predicate(predicate, &mut ctx)?),
             None => None,
@@ -237,15 +429,6 @@ impl<'a> AnalysisContext<'a> {
             .as_ref()
             .map(PredicateResolver::fold_predicate);
 
-        // qualify group_by columns
-        let mut group_by = Vec::with_capacity(query.group_by.len());
-        let mut group_set = std::collections::HashSet::<ColumnKey>::new();
-        for c in &query.group_by {
-            let (qc, _) = ColumnResolver::qualify_column(c, &ctx)?;
-            group_set.insert(ColumnKey::of(&qc));
-            group_by.push(qc);
-        }
-
         // detect aggregate query
         let is_agg_query = !group_by.is_empty()
             || analyzed_proj
@@ -289,6 +472,19 @@ impl<'a> AnalysisContext<'a> {
             }
         }
 
+        // a key left out of some grouping set is NULL in that set's rows
+        if !grouping_sets.is_empty() {
+            let kept: std::collections::HashSet<ColumnKey> = group_by
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| grouping_sets.iter().all(|set| set.contains(i)))
+                .map(|(_, expr)| ColumnKey::of_expr(expr))
+                .collect();
+            for id in &mut analyzed_proj {
+                id.nullable |= !AggregateResolver::uses_only_group_by(&id.expression, &kept, false);
+            }
+        }
+
         // ORDER BY resolution (aliases, positional indexes, qualification, folding, validation)
         // Decide if the query is an aggregate
         let needs_agg = !group_by.is_empty()
@@ -301,6 +497,7 @@ impl<'a> AnalysisContext<'a> {
                 .unwrap_or(false);
 
         // Resolve ORDER BY
+        ctx.windows_allowed = true;
         let order_by = if needs_agg {
             // existing path: resolve + validate against group set
             OrderByResolver::qualify_order_by(
@@ -313,30 +510,94 @@ impl<'a> AnalysisContext<'a> {
             // NEW: non-aggregate path (no group validation)
             OrderByResolver::qualify_order_by_non_agg(&query.order_by, &analyzed_proj, &mut ctx)?
         };
+        ctx.windows_allowed = false;
+
+        // DISTINCT dedups projected rows, so sort keys must be among them
+        if query.distinct
+            && order_by
+                .iter()
+                .any(|ob| !analyzed_proj.iter().any(|id| id.expression == ob.expr))
+        {
+            return Err(AnalyzerError::Other(
+                "For SELECT DISTINCT, ORDER BY expressions must appear in the select list".into(),
+            ));
+        }
 
+        *current_param = ctx.current_param;
         Ok(AnalyzedQuery {
+            ctes: vec![],
+            distinct: query.distinct,
             projection: analyzed_proj,
             collections: from_collections,
             joins: analyzed_joins,
             criteria,
             group_by,
+            grouping_sets,
             having,
             order_by,
             limit: query.limit,
             offset: query.offset,
+            set_operations: vec![],
         })
     }
 
-    fn analyze_source(
-        collection: &Collection,
+    pub fn analyze_statement(
+        statement: &Statement,
         schema_provider: &'a dyn SchemaProvider,
         aggregates: &'a AggregateRegistry,
         parameters: Value,
+    ) -> Result<AnalyzedStatement, AnalyzerError> {
+        match statement {
+            Statement::Select(query) => Ok(AnalyzedStatement::Select(Box::new(
+                Self::analyze_query(query, schema_provider, aggregates, parameters)?,
+            ))),
+            Statement::Insert(insert) => {
+                let mut ctx = Self::new_with_aggregates(schema_provider, aggregates);
+                ctx.parameters = parameters;
+                Ok(AnalyzedStatement::Insert(InsertResolver::analyze_insert(
+                    insert, &mut ctx,
+                )?))
+            }
+            Statement::Update(update) => {
+                let mut ctx = Self::new_with_aggregates(schema_provider, aggregates);
+                ctx.parameters = parameters;
+                Ok(AnalyzedStatement::Update(UpdateResolver::analyze_update(
+                    update, &mut ctx,
+                )?))
+            }
+            Statement::Delete(delete) => {
+                let mut ctx = Self::new_with_aggregates(schema_provider, aggregates);
+                ctx.parameters = parameters;
+                Ok(AnalyzedStatement::Delete(DeleteResolver::analyze_delete(
+                    delete, &mut ctx,
+                )?))
+            }
+            Statement::CreateCollection(create) => Ok(AnalyzedStatement::CreateCollection(
+                CollectionDdlResolver::analyze_create_collection(create)?,
+            )),
+            Statement::DropCollection(drop) => Ok(AnalyzedStatement::DropCollection(
+                CollectionDdlResolver::analyze_drop_collection(drop),
+            )),
+        }
+    }
+
+    fn analyze_source(
+        collection: &Collection,
         ctx: &mut AnalysisContext<'a>,
     ) -> Result<AnalyzedSource, AnalyzerError> {
         match collection {
             Collection::Table { name, alias } => {
                 let visible = alias.clone().unwrap_or_else(|| name.clone());
+                // WITH queries shadow stored collections of the same name
+                if let Some(schema) = ctx.ctes.get(name).cloned() {
+                    let columns = schema.fields.keys().cloned().collect();
+                    ctx.add_subquery(visible.clone(), schema);
+                    return Ok(AnalyzedSource::Cte {
+                        visible,
+                        name: name.clone(),
+                        columns,
+                    });
+                }
                 ctx.add_collection(visible.clone(), name.clone());
                 Ok(AnalyzedSource::Table {
                     visible,
@@ -347,7 +608,8 @@ impl<'a> AnalysisContext<'a> {
                 let visible = alias
                     .clone()
                     .ok_or_else(|| AnalyzerError::Other("subquery requires an alias".into()))?;
-                let analyzed = Self::analyze_query(query, schema_provider, aggregates, parameters)?;
+                let scope = ctx.source_scope();
+                let analyzed = ctx.analyze_nested_query(query, &scope)?;
                 let schema = Self::schema_from_projection(&analyzed.projection);
                 ctx.add_subquery(visible.clone(), schema);
                 Ok(AnalyzedSource::Subquery {
@@ -355,10 +617,45 @@ impl<'a> AnalysisContext<'a> {
                     query: Box::new(analyzed),
                 })
             }
+            // lateral: the array expression reads the sources registered before it
+            Collection::Unnest {
+                expr,
+                alias,
+                column,
+            } => {
+                let qualified = ScalarResolver::qualify_scalar(expr, ctx, false)?;
+                let folded = ScalarResolver::fold_scalar(&qualified);
+                let (ty, _) = TypeInference::infer_scalar(&folded, ctx)?;
+                if !matches!(
+                    ty,
+                    JsonPrimitive::Array | JsonPrimitive::Any | JsonPrimitive::Null
+                ) {
+                    return Err(AnalyzerError::Other(format!(
+                        "UNNEST expects an array, got {:?}",
+                        ty
+                    )));
+                }
+
+                // elements are not typed by the schema
+                let mut fields = IndexMap::new();
+                fields.insert(
+                    column.clone(),
+                    FieldInfo {
+                        ty: JsonPrimitive::Any,
+                        nullable: true,
+                    },
+                );
+                ctx.add_subquery(alias.clone(), SchemaDict { fields });
+                Ok(AnalyzedSource::Unnest {
+                    visible: alias.clone(),
+                    expr: folded,
+                    column: column.clone(),
+                })
+            }
         }
     }
 
-    fn schema_from_projection(projection: &[AnalyzedIdentifier]) -> SchemaDict {
+    pub fn schema_from_projection(projection: &[AnalyzedIdentifier]) -> SchemaDict {
         let mut fields = IndexMap::new();
         for id in projection {
             fields.insert(
@@ -436,7 +733,8 @@ mod tests {
             }],
             joins: vec![],
             criteria,
-            group_by,
+            group_by: group_by.into_iter().map(ScalarExpr::Column).collect(),
+            grouping_sets: vec![],
             having,
             order_by,
             ..Default::default()
@@ -734,6 +1032,7 @@ mod tests {
             joins: vec![],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             ..Default::default()
@@ -856,6 +1155,7 @@ mod tests {
             joins: vec![],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             ..Default::default()
@@ -892,6 +1192,7 @@ mod tests {
             }],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             ..Default::default()
@@ -931,6 +1232,7 @@ mod tests {
             }],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             ..Default::default()
@@ -1002,6 +1304,7 @@ mod tests {
             joins: vec![],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             ..Default::default()
@@ -1,6 +1,6 @@
 use crate::parser::{
-    analyzer::AnalyzedIdentifier,
-    ast::{Column, JoinType, OrderBy, Predicate},
+    analyzer::{AnalyzedCte, AnalyzedIdentifier, AnalyzedSetOperation},
+    ast::{JoinType, OrderBy, Predicate, ScalarExpr},
 };
 
 #[derive(Debug, Clone)]
@@ -13,6 +13,18 @@ pub enum AnalyzedSource {
         visible: String,
         query: Box<AnalyzedQuery>,
     },
+    /// Reference to a WITH query evaluated once by the enclosing query.
+    Cte {
+        visible: String,
+        name: String,
+        columns: Vec<String>,
+    },
+    /// One row per element of an array, read from the sources before it.
+    Unnest {
+        visible: String,
+        expr: ScalarExpr, // qualified + folded
+        column: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -24,13 +36,17 @@ pub struct AnalyzedJoin {
 
 #[derive(Debug, Clone)]
 pub struct AnalyzedQuery {
+    pub ctes: Vec<AnalyzedCte>, // in definition order, each sees the previous ones
+    pub distinct: bool,
     pub projection: Vec<AnalyzedIdentifier>, // qualified + typed
     pub collections: Vec<AnalyzedSource>,
     pub joins: Vec<AnalyzedJoin>,
-    pub criteria: Option<Predicate>, // qualified + folded
-    pub group_by: Vec<Column>,       // qualified
-    pub having: Option<Predicate>,   // qualified + folded
-    pub order_by: Vec<OrderBy>,      // OrderBy
+    pub criteria: Option<Predicate>,    // qualified + folded
+    pub group_by: Vec<ScalarExpr>,      // qualified
+    pub grouping_sets: Vec<Vec<usize>>, // indexes into group_by; empty groups by all of it
+    pub having: Option<Predicate>,      // qualified + folded
+    pub order_by: Vec<OrderBy>,         // OrderBy
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub set_operations: Vec<AnalyzedSetOperation>, // ORDER BY/LIMIT then apply to the compound
 }
@@ -0,0 +1,73 @@
+use crate::{
+    DbConfig, SchemaDict,
+    parser::{
+        analyzer::{AnalyzedIdentifier, AnalyzedQuery},
+        ast::{Predicate, ScalarExpr},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub enum AnalyzedInsertSource {
+    Values(Vec<Vec<ScalarExpr>>), // folded literals, one vec per row
+    Query(Box<AnalyzedQuery>),
+}
+
+#[derive(Debug, Clone)]
+pub enum AnalyzedConflictAction {
+    DoNothing,
+    DoUpdate(Vec<(String, ScalarExpr)>), // empty merges the whole incoming row
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedOnConflict {
+    pub target: Option<String>,
+    pub action: AnalyzedConflictAction,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedInsert {
+    pub collection: String,
+    pub columns: Vec<String>, // target field per source position
+    pub source: AnalyzedInsertSource,
+    pub on_conflict: Option<AnalyzedOnConflict>,
+    pub returning: Vec<AnalyzedIdentifier>, // empty without RETURNING
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedUpdate {
+    pub collection: String,
+    pub assignments: Vec<(String, ScalarExpr)>, // qualified + folded
+    pub criteria: Option<Predicate>,            // qualified + folded
+    pub returning: Vec<AnalyzedIdentifier>,     // empty without RETURNING
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedDelete {
+    pub collection: String,
+    pub criteria: Option<Predicate>,        // qualified + folded
+    pub returning: Vec<AnalyzedIdentifier>, // empty without RETURNING
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedCreateCollection {
+    pub name: String,
+    pub if_not_exists: bool,
+    pub schema: SchemaDict,
+    pub config: Option<DbConfig>, // from the id marker; None uses the database config
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedDropCollection {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum AnalyzedStatement {
+    Select(Box<AnalyzedQuery>),
+    Insert(AnalyzedInsert),
+    Update(AnalyzedUpdate),
+    Delete(AnalyzedDelete),
+    CreateCollection(AnalyzedCreateCollection),
+    DropCollection(AnalyzedDropCollection),
+}
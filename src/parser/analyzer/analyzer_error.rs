@@ -21,5 +21,11 @@ pub enum AnalyzerError {
     NonConstInConstFold,
     InvalidLikePattern,
     InvalidParameterValue,
+    /// named (`:name`) or numbered (`$1`) parameters without a value, and
+    /// bound values no parameter refers to
+    ParameterMismatch {
+        missing: Vec<String>,
+        unused: Vec<String>,
+    },
     Other(String),
 }
@@ -7,6 +7,9 @@ pub use analyzed_identifier::*;
 pub mod analyzed_query;
 pub use analyzed_query::*;
 
+pub mod analyzed_statement;
+pub use analyzed_statement::*;
+
 pub mod analyzer_error;
 pub use analyzer_error::*;
 
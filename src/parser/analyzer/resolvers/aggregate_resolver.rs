@@ -2,16 +2,18 @@ use std::collections::HashSet;
 
 use crate::parser::{
     analyzer::ColumnKey,
-    ast::{Predicate, ScalarExpr},
+    ast::{Predicate, ScalarExpr, Window},
 };
 
 pub struct AggregateResolver;
 
 impl AggregateResolver {
+    /// Aggregates computed per group; `grouping(...)` reports which GROUP BY keys a
+    /// `ROLLUP` / `CUBE` / `GROUPING SETS` row leaves out.
     pub fn is_aggregate_name(name: &str) -> bool {
         matches!(
             name.to_ascii_lowercase().as_str(),
-            "count" | "sum" | "avg" | "min" | "max"
+            "count" | "sum" | "avg" | "min" | "max" | "grouping"
         )
     }
 
@@ -19,6 +21,21 @@ impl AggregateResolver {
         match expr {
             ScalarExpr::Function(f) if Self::is_aggregate_name(&f.name) => true,
             ScalarExpr::Function(f) => f.args.iter().any(Self::contains_aggregate),
+            // an aggregate over a window is computed per row, not per group
+            ScalarExpr::Window(window) => Self::window_parts(window).any(Self::contains_aggregate),
+            ScalarExpr::Case(case) => {
+                case.branches.iter().any(|(condition, result)| {
+                    Self::predicate_contains_aggregate(condition)
+                        || Self::contains_aggregate(result)
+                }) || case
+                    .otherwise
+                    .as_deref()
+                    .is_some_and(Self::contains_aggregate)
+            }
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::contains_aggregate(left) || Self::contains_aggregate(right)
+            }
+            ScalarExpr::Negate(expr) => Self::contains_aggregate(expr),
             _ => false,
         }
     }
@@ -28,9 +45,14 @@ impl AggregateResolver {
         group: &HashSet<ColumnKey>,
         inside_agg_arg: bool,
     ) -> bool {
+        // the whole expression is a GROUP BY entry
+        if !inside_agg_arg && group.contains(&ColumnKey::of_expr(expr)) {
+            return true;
+        }
+
         match expr {
             ScalarExpr::Literal(_) => true,
-            ScalarExpr::Column(c) => {
+            ScalarExpr::Column(c) | ScalarExpr::JsonPath { column: c, .. } => {
                 if inside_agg_arg {
                     true
                 } else {
@@ -38,6 +60,14 @@ impl AggregateResolver {
                     group.contains(&ColumnKey::of(c))
                 }
             }
+            // each argument must be a GROUP BY entry itself
+            ScalarExpr::Function(f) if f.name.eq_ignore_ascii_case("grouping") => {
+                !inside_agg_arg
+                    && !f.args.is_empty()
+                    && f.args
+                        .iter()
+                        .all(|a| group.contains(&ColumnKey::of_expr(a)))
+            }
             ScalarExpr::Function(f) => {
                 if Self::is_aggregate_name(&f.name) {
                     // args are inside aggregate
@@ -54,26 +84,61 @@ impl AggregateResolver {
             ScalarExpr::WildCard
             | ScalarExpr::WildCardWithCollection(_)
             | ScalarExpr::Parameter
+            | ScalarExpr::NamedParameter(_)
             | ScalarExpr::Args(_) => inside_agg_arg,
+            // the subquery is evaluated on its own, once per output row
+            ScalarExpr::Subquery(_) => true,
+            ScalarExpr::Case(case) => {
+                case.branches.iter().all(|(condition, result)| {
+                    (inside_agg_arg || Self::predicate_uses_only_group_by_or_agg(condition, group))
+                        && Self::uses_only_group_by(result, group, inside_agg_arg)
+                }) && case.otherwise.as_deref().is_none_or(|otherwise| {
+                    Self::uses_only_group_by(otherwise, group, inside_agg_arg)
+                })
+            }
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::uses_only_group_by(left, group, inside_agg_arg)
+                    && Self::uses_only_group_by(right, group, inside_agg_arg)
+            }
+            ScalarExpr::Negate(expr) => Self::uses_only_group_by(expr, group, inside_agg_arg),
+            // windows run over the grouped rows; `COUNT(*) OVER` counts them
+            ScalarExpr::Window(window) => Self::window_parts(window).all(|part| {
+                matches!(part, ScalarExpr::WildCard)
+                    || Self::uses_only_group_by(part, group, inside_agg_arg)
+            }),
         }
     }
 
+    /// Function arguments, PARTITION BY and ORDER BY expressions of a window.
+    fn window_parts(window: &Window) -> impl Iterator<Item = &ScalarExpr> {
+        window
+            .function
+            .args
+            .iter()
+            .chain(&window.partition_by)
+            .chain(window.order_by.iter().map(|ob| &ob.expr))
+    }
+
     pub fn predicate_contains_aggregate(predicate: &Predicate) -> bool {
         match predicate {
             Predicate::And(predicates) | Predicate::Or(predicates) => {
                 predicates.iter().any(Self::predicate_contains_aggregate)
             }
+            Predicate::Not(predicate) => Self::predicate_contains_aggregate(predicate),
             Predicate::Compare { left, right, .. } => {
                 Self::contains_aggregate(left) || Self::contains_aggregate(right)
             }
-            Predicate::IsNull { expr, .. } => Self::contains_aggregate(expr),
+            Predicate::IsNull { expr, .. } | Predicate::InSubquery { expr, .. } => {
+                Self::contains_aggregate(expr)
+            }
             Predicate::InList { expr, list, .. } => {
                 Self::contains_aggregate(expr) || list.iter().any(Self::contains_aggregate)
             }
             Predicate::Like { expr, pattern, .. } => {
                 Self::contains_aggregate(expr) || Self::contains_aggregate(pattern)
             }
-            Predicate::Const3(_) => false,
+            // aggregates inside a subquery belong to the subquery
+            Predicate::Exists { .. } | Predicate::Const3(_) => false,
         }
     }
 
@@ -85,11 +150,14 @@ impl AggregateResolver {
             Predicate::And(v) | Predicate::Or(v) => v
                 .iter()
                 .all(|x| Self::predicate_uses_only_group_by_or_agg(x, group)),
+            Predicate::Not(p) => Self::predicate_uses_only_group_by_or_agg(p, group),
             Predicate::Compare { left, right, .. } => {
                 Self::uses_only_group_by(left, group, false)
                     && Self::uses_only_group_by(right, group, false)
             }
-            Predicate::IsNull { expr, .. } => Self::uses_only_group_by(expr, group, false),
+            Predicate::IsNull { expr, .. } | Predicate::InSubquery { expr, .. } => {
+                Self::uses_only_group_by(expr, group, false)
+            }
             Predicate::InList { expr, list, .. } => {
                 Self::uses_only_group_by(expr, group, false)
                     && list
@@ -100,7 +168,7 @@ impl AggregateResolver {
                 Self::uses_only_group_by(expr, group, false)
                     && Self::uses_only_group_by(pattern, group, false)
             }
-            Predicate::Const3(_) => true,
+            Predicate::Exists { .. } | Predicate::Const3(_) => true,
         }
     }
 }
@@ -277,6 +345,40 @@ mod tests {
         assert!(!AggregateResolver::predicate_contains_aggregate(&p5));
     }
 
+    #[test]
+    fn uses_only_group_by_requires_grouping_args_to_be_group_keys() {
+        let mut group = HashSet::new();
+        group.insert(ColumnKey {
+            column: "t".into(),
+            name: "a".into(),
+        });
+
+        let grouping = fun("grouping", vec![ScalarExpr::Column(qc("t", "a"))]);
+        assert!(AggregateResolver::contains_aggregate(&grouping));
+        assert!(AggregateResolver::uses_only_group_by(
+            &grouping, &group, false
+        ));
+
+        let grouping_bad = fun(
+            "GROUPING",
+            vec![
+                ScalarExpr::Column(qc("t", "a")),
+                ScalarExpr::Column(qc("t", "b")),
+            ],
+        );
+        assert!(!AggregateResolver::uses_only_group_by(
+            &grouping_bad,
+            &group,
+            false
+        ));
+
+        // grouping() cannot be the argument of another aggregate
+        let nested = agg("sum", vec![grouping]);
+        assert!(!AggregateResolver::uses_only_group_by(
+            &nested, &group, false
+        ));
+    }
+
     // --- predicate_uses_only_group_by_or_agg ---------------------------------
     #[test]
     fn predicate_group_by_validation() {
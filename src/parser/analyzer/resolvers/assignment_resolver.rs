@@ -0,0 +1,260 @@
+use serde_json::Value;
+
+use crate::{
+    JsonPrimitive,
+    database::SchemaDict,
+    parser::{
+        analyzer::{AnalyzerError, Temporal},
+        ast::{Literal, ScalarExpr},
+    },
+};
+
+pub struct AssignmentResolver;
+
+impl AssignmentResolver {
+    /// Check that a value of type `ty` can be written to `column`.
+    /// Fields missing from the schema are accepted as new document fields.
+    pub fn check_assignable(
+        collection: &str,
+        schema: &SchemaDict,
+        column: &str,
+        ty: JsonPrimitive,
+    ) -> Result<(), AnalyzerError> {
+        let Some(field) = schema.get(column) else {
+            return Ok(());
+        };
+
+        let assignable = match (field.ty, ty) {
+            (_, JsonPrimitive::Null) => field.nullable,
+            (JsonPrimitive::Null, _) => true,
+            // dynamic values are only known at runtime
+            (JsonPrimitive::Any, _) | (_, JsonPrimitive::Any) => true,
+            (JsonPrimitive::Float, JsonPrimitive::Int) => true,
+            // temporal values are written as ISO-8601 strings
+            (JsonPrimitive::Date | JsonPrimitive::DateTime, JsonPrimitive::String) => true,
+            // a DateTime written to a Date column keeps its date, like CAST
+            (JsonPrimitive::Date, JsonPrimitive::DateTime)
+            | (JsonPrimitive::DateTime, JsonPrimitive::Date) => true,
+            (expected, got) => expected == got,
+        };
+
+        if assignable {
+            Ok(())
+        } else if ty == JsonPrimitive::Null {
+            Err(AnalyzerError::Other(format!(
+                "column {collection}.{column} is not nullable"
+            )))
+        } else {
+            Err(AnalyzerError::Other(format!(
+                "cannot assign {ty:?} to column {collection}.{column} of type {:?}",
+                field.ty
+            )))
+        }
+    }
+
+    /// Check a constant string written to a `Date` or `DateTime` column: it
+    /// must be an ISO-8601 value, and a `Date` takes only the date-only form.
+    pub fn check_constant(
+        collection: &str,
+        schema: &SchemaDict,
+        column: &str,
+        expr: &ScalarExpr,
+    ) -> Result<(), AnalyzerError> {
+        let (Some(field), ScalarExpr::Literal(Literal::String(text))) = (schema.get(column), expr)
+        else {
+            return Ok(());
+        };
+
+        let valid = match (field.ty, Temporal::parse(text)) {
+            (JsonPrimitive::Date, Some(Temporal::Date(_))) => true,
+            (JsonPrimitive::DateTime, Some(_)) => true,
+            (JsonPrimitive::Date | JsonPrimitive::DateTime, _) => false,
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            let value = Value::String(text.clone());
+            Err(Self::invalid_value(collection, column, field.ty, &value))
+        }
+    }
+
+    /// Value actually written to `column` at runtime. Strings and dynamic
+    /// values bound for a `Date` or `DateTime` column must parse as ISO-8601;
+    /// a date-time written to a `Date` column keeps only its date.
+    pub fn write_value(
+        collection: &str,
+        schema: Option<&SchemaDict>,
+        column: &str,
+        value: Value,
+    ) -> Result<Value, AnalyzerError> {
+        let Some(ty @ (JsonPrimitive::Date | JsonPrimitive::DateTime)) = schema
+            .and_then(|schema| schema.get(column))
+            .map(|field| field.ty)
+        else {
+            return Ok(value);
+        };
+
+        let parsed = match &value {
+            Value::Null => return Ok(value),
+            Value::String(text) => Temporal::parse(text),
+            _ => None,
+        };
+        match (ty, parsed) {
+            (JsonPrimitive::Date, Some(Temporal::DateTime(datetime))) => {
+                Ok(Value::String(Temporal::Date(datetime.date()).format()))
+            }
+            (_, Some(_)) => Ok(value),
+            (_, None) => Err(Self::invalid_value(collection, column, ty, &value)),
+        }
+    }
+
+    fn invalid_value(
+        collection: &str,
+        column: &str,
+        ty: JsonPrimitive,
+        value: &Value,
+    ) -> AnalyzerError {
+        AnalyzerError::Other(format!(
+            "cannot assign {value} to column {collection}.{column} of type {ty:?}"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::database::FieldInfo;
+
+    fn schema() -> SchemaDict {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "age".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Int,
+                nullable: false,
+            },
+        );
+        fields.insert(
+            "score".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Float,
+                nullable: true,
+            },
+        );
+        fields.insert(
+            "unknown".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Null,
+                nullable: true,
+            },
+        );
+        fields.insert(
+            "due".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Date,
+                nullable: true,
+            },
+        );
+        fields.insert(
+            "at".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::DateTime,
+                nullable: true,
+            },
+        );
+        SchemaDict { fields }
+    }
+
+    #[test]
+    fn matching_and_promotable_types_are_assignable() {
+        let schema = schema();
+
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "age", JsonPrimitive::Int).is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "score", JsonPrimitive::Int).is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "score", JsonPrimitive::Null)
+                .is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "unknown", JsonPrimitive::String)
+                .is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "new_field", JsonPrimitive::Bool)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn mismatched_types_and_nulls_are_rejected() {
+        let schema = schema();
+
+        let err = AssignmentResolver::check_assignable("t", &schema, "age", JsonPrimitive::Float);
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("t.age of type Int"))
+        );
+
+        let err = AssignmentResolver::check_assignable("t", &schema, "age", JsonPrimitive::Null);
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("not nullable"))
+        );
+    }
+
+    #[test]
+    fn temporal_columns_check_constants_and_written_values() {
+        let schema = schema();
+        let text = |s: &str| ScalarExpr::Literal(Literal::String(s.into()));
+
+        assert!(
+            AssignmentResolver::check_assignable("t", &schema, "due", JsonPrimitive::DateTime)
+                .is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_constant("t", &schema, "due", &text("2024-02-29")).is_ok()
+        );
+        assert!(
+            AssignmentResolver::check_constant("t", &schema, "at", &text("2024-02-29T10:00:00Z"))
+                .is_ok()
+        );
+        for (column, value) in [
+            ("due", "2024-02-30"),
+            ("due", "2024-02-29T10:00:00Z"),
+            ("at", "yesterday"),
+        ] {
+            let err = AssignmentResolver::check_constant("t", &schema, column, &text(value));
+            assert!(
+                matches!(err, Err(AnalyzerError::Other(message)) if message.contains(value)),
+                "{value} should be rejected for {column}"
+            );
+        }
+
+        let write = |column: &str, value: Value| {
+            AssignmentResolver::write_value("t", Some(&schema), column, value)
+        };
+        assert_eq!(
+            write("due", json!("2024-02-29T23:30:00Z")),
+            Ok(json!("2024-02-29"))
+        );
+        assert_eq!(
+            write("at", json!("2024-02-29T23:30:00+02:00")),
+            Ok(json!("2024-02-29T23:30:00+02:00"))
+        );
+        assert_eq!(write("due", Value::Null), Ok(Value::Null));
+        assert_eq!(write("age", json!("not a date")), Ok(json!("not a date")));
+        assert_eq!(
+            write("at", json!(20240229)),
+            Err(AnalyzerError::Other(
+                "cannot assign 20240229 to column t.at of type DateTime".into()
+            ))
+        );
+    }
+}
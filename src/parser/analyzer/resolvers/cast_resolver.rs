@@ -0,0 +1,208 @@
+use serde_json::{Number, Value};
+
+use crate::{
+    JsonPrimitive,
+    parser::{analyzer::Temporal, ast::Literal},
+};
+
+/// Conversions behind `CAST(x AS type)`, `x::type` and `TRY_CAST(x AS type)`.
+pub struct CastResolver;
+
+impl CastResolver {
+    /// Type named in a cast; names are case-insensitive and accept the usual
+    /// SQL aliases (`integer`, `text`, `boolean`, `timestamp`, ...).
+    pub fn target(name: &str) -> Option<JsonPrimitive> {
+        match name.to_ascii_lowercase().as_str() {
+            "int" | "integer" | "bigint" => Some(JsonPrimitive::Int),
+            "float" | "double" | "real" | "numeric" | "decimal" => Some(JsonPrimitive::Float),
+            "string" | "text" | "varchar" => Some(JsonPrimitive::String),
+            "bool" | "boolean" => Some(JsonPrimitive::Bool),
+            "date" => Some(JsonPrimitive::Date),
+            "datetime" | "timestamp" => Some(JsonPrimitive::DateTime),
+            _ => None,
+        }
+    }
+
+    /// Can some value of type `from` convert to `to`?
+    pub fn castable(from: JsonPrimitive, to: JsonPrimitive) -> bool {
+        use JsonPrimitive::*;
+        match (from, to) {
+            (Null | Any, _) | (_, String) => true,
+            _ if from == to => true,
+            (Int | Float | Bool | String, Int | Float | Bool) => true,
+            (String | Date | DateTime, Date | DateTime) => true,
+            _ => false,
+        }
+    }
+
+    /// Does every value of type `from` convert to `to`?
+    pub fn infallible(from: JsonPrimitive, to: JsonPrimitive) -> bool {
+        use JsonPrimitive::*;
+        from == to
+            || matches!(
+                (from, to),
+                (Int | Bool, Float)
+                    | (Bool, Int)
+                    | (Int, Bool)
+                    | (Date, DateTime)
+                    | (
+                        Int | Float | Bool | Date | DateTime | Object | Array,
+                        String
+                    )
+            )
+    }
+
+    /// Converts `value` to `to`; `None` when it does not convert. NULL stays NULL.
+    ///
+    /// Floats round to the nearest Int, Ints are true unless zero, and strings
+    /// are trimmed before they are read as numbers, booleans (`true`, `t`,
+    /// `yes`, `on`, `1`, ...) or ISO-8601 dates. Anything converts to a String;
+    /// objects and arrays as JSON text.
+    pub fn cast(value: &Value, to: JsonPrimitive) -> Option<Value> {
+        if value.is_null() {
+            return Some(Value::Null);
+        }
+        match to {
+            JsonPrimitive::Int => match value {
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => Some(Value::from(i)),
+                    None => Self::float_to_int(n.as_f64()?),
+                },
+                Value::Bool(b) => Some(Value::from(*b as i64)),
+                Value::String(s) => s.trim().parse::<i64>().ok().map(Value::from),
+                _ => None,
+            },
+            JsonPrimitive::Float => {
+                let f = match value {
+                    Value::Number(n) => n.as_f64()?,
+                    Value::Bool(b) => *b as i64 as f64,
+                    Value::String(s) => s.trim().parse::<f64>().ok()?,
+                    _ => return None,
+                };
+                Number::from_f64(f).map(Value::Number)
+            }
+            JsonPrimitive::String => match value {
+                Value::String(s) => Some(Value::String(s.clone())),
+                other => Some(Value::String(other.to_string())),
+            },
+            JsonPrimitive::Bool => match value {
+                Value::Bool(b) => Some(Value::Bool(*b)),
+                Value::Number(n) => Some(Value::Bool(n.as_f64()? != 0.0)),
+                Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                    "true" | "t" | "yes" | "y" | "on" | "1" => Some(Value::Bool(true)),
+                    "false" | "f" | "no" | "n" | "off" | "0" => Some(Value::Bool(false)),
+                    _ => None,
+                },
+                _ => None,
+            },
+            JsonPrimitive::Date | JsonPrimitive::DateTime => {
+                let Value::String(s) = value else {
+                    return None;
+                };
+                let datetime = Temporal::parse(s)?.datetime();
+                let temporal = match to {
+                    JsonPrimitive::Date => Temporal::Date(datetime.date()),
+                    _ => Temporal::DateTime(datetime),
+                };
+                Some(Value::String(temporal.format()))
+            }
+            JsonPrimitive::Null | JsonPrimitive::Object | JsonPrimitive::Array => None,
+            JsonPrimitive::Any => Some(value.clone()),
+        }
+    }
+
+    /// JSON form of a literal, for casts the analyzer checks up front.
+    pub fn literal_value(literal: &Literal) -> Value {
+        match literal {
+            Literal::Null => Value::Null,
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Int(i) => Value::from(*i),
+            Literal::Float(f) => {
+                Number::from_f64(f.into_inner()).map_or(Value::Null, Value::Number)
+            }
+            Literal::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    fn float_to_int(f: f64) -> Option<Value> {
+        let rounded = f.round();
+        // i64::MIN is -2^63, exactly representable; 2^63 is already out of range
+        (rounded >= i64::MIN as f64 && rounded < -(i64::MIN as f64))
+            .then(|| Value::from(rounded as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use crate::{JsonPrimitive, parser::analyzer::CastResolver};
+
+    fn cast(value: Value, to: &str) -> Option<Value> {
+        CastResolver::cast(&value, CastResolver::target(to).expect("known type"))
+    }
+
+    #[test]
+    fn target_reads_type_names_and_aliases() {
+        assert_eq!(CastResolver::target("INT"), Some(JsonPrimitive::Int));
+        assert_eq!(CastResolver::target("text"), Some(JsonPrimitive::String));
+        assert_eq!(CastResolver::target("Boolean"), Some(JsonPrimitive::Bool));
+        assert_eq!(
+            CastResolver::target("timestamp"),
+            Some(JsonPrimitive::DateTime)
+        );
+        assert_eq!(CastResolver::target("object"), None);
+    }
+
+    #[test]
+    fn cast_converts_between_scalar_types() {
+        assert_eq!(cast(json!(" 42 "), "int"), Some(json!(42)));
+        assert_eq!(cast(json!(2.5), "int"), Some(json!(3)));
+        assert_eq!(cast(json!(-2.5), "int"), Some(json!(-3)));
+        assert_eq!(cast(json!(true), "int"), Some(json!(1)));
+        assert_eq!(cast(json!("1.5"), "int"), None);
+        assert_eq!(cast(json!(1e30), "int"), None);
+
+        assert_eq!(cast(json!(3), "float"), Some(json!(3.0)));
+        assert_eq!(cast(json!("1e3"), "float"), Some(json!(1000.0)));
+        assert_eq!(cast(json!("NaN"), "float"), None);
+
+        assert_eq!(cast(json!(7), "string"), Some(json!("7")));
+        assert_eq!(cast(json!(1.5), "string"), Some(json!("1.5")));
+        assert_eq!(cast(json!([1, "a"]), "string"), Some(json!("[1,\"a\"]")));
+
+        assert_eq!(cast(json!("Yes"), "bool"), Some(json!(true)));
+        assert_eq!(cast(json!("off"), "bool"), Some(json!(false)));
+        assert_eq!(cast(json!(0), "bool"), Some(json!(false)));
+        assert_eq!(cast(json!("maybe"), "bool"), None);
+
+        assert_eq!(
+            cast(json!("2024-03-01T23:30:00-02:00"), "date"),
+            Some(json!("2024-03-02"))
+        );
+        assert_eq!(
+            cast(json!("2024-03-01"), "datetime"),
+            Some(json!("2024-03-01T00:00:00Z"))
+        );
+        assert_eq!(cast(json!(20240301), "date"), None);
+
+        assert_eq!(cast(Value::Null, "int"), Some(Value::Null));
+        assert_eq!(cast(json!({ "a": 1 }), "int"), None);
+    }
+
+    #[test]
+    fn castable_rejects_conversions_that_can_never_succeed() {
+        use JsonPrimitive::*;
+        assert!(CastResolver::castable(String, Int));
+        assert!(CastResolver::castable(Object, String));
+        assert!(CastResolver::castable(Any, Date));
+        assert!(!CastResolver::castable(Object, Int));
+        assert!(!CastResolver::castable(Bool, Date));
+        assert!(!CastResolver::castable(Date, Int));
+
+        assert!(CastResolver::infallible(Int, Float));
+        assert!(CastResolver::infallible(Date, String));
+        assert!(!CastResolver::infallible(String, Int));
+        assert!(!CastResolver::infallible(Float, Int));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    database::parse_compact_fields,
+    parser::{
+        analyzer::{AnalyzedCreateCollection, AnalyzedDropCollection, AnalyzerError},
+        ast::{CreateCollection, DropCollection},
+    },
+};
+
+pub struct CollectionDdlResolver;
+
+impl CollectionDdlResolver {
+    /// Turn the column list into a compact schema; the id marker (if any) decides the config.
+    pub fn analyze_create_collection(
+        create: &CreateCollection,
+    ) -> Result<AnalyzedCreateCollection, AnalyzerError> {
+        let mut fields = Vec::with_capacity(create.columns.len());
+        for column in &create.columns {
+            if fields.iter().any(|(name, _)| *name == column.name) {
+                return Err(AnalyzerError::Other(format!(
+                    "Duplicate column '{}' in CREATE COLLECTION {}",
+                    column.name, create.name
+                )));
+            }
+            fields.push((column.name.as_str(), column.type_spec.as_str()));
+        }
+
+        let parsed = parse_compact_fields(&fields).map_err(AnalyzerError::Other)?;
+
+        Ok(AnalyzedCreateCollection {
+            name: create.name.clone(),
+            if_not_exists: create.if_not_exists,
+            schema: parsed.schema,
+            config: parsed.config,
+        })
+    }
+
+    pub fn analyze_drop_collection(drop: &DropCollection) -> AnalyzedDropCollection {
+        AnalyzedDropCollection {
+            name: drop.name.clone(),
+            if_exists: drop.if_exists,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DbConfig, JsonPrimitive,
+        parser::{
+            QueryParser,
+            analyzer::{AnalyzerError, CollectionDdlResolver},
+            ast::CreateCollection,
+        },
+    };
+
+    fn analyze(
+        text: &str,
+    ) -> Result<crate::parser::analyzer::AnalyzedCreateCollection, AnalyzerError> {
+        let create = CreateCollection::parse(&mut QueryParser::new(text)).unwrap();
+        CollectionDdlResolver::analyze_create_collection(&create)
+    }
+
+    #[test]
+    fn id_marker_becomes_collection_config() {
+        let analyzed =
+            analyze("CREATE COLLECTION users (user_id Id, name String!, age Int)").unwrap();
+
+        assert_eq!(analyzed.config, Some(DbConfig::int("user_id")));
+        let names = analyzed.schema.fields.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(names, vec!["user_id", "name", "age"]);
+        assert!(!analyzed.schema.fields["name"].nullable);
+        assert!(analyzed.schema.fields["age"].nullable);
+        assert_eq!(analyzed.schema.fields["age"].ty, JsonPrimitive::Int);
+    }
+
+    #[test]
+    fn without_marker_config_is_left_to_the_database() {
+        let analyzed = analyze("CREATE COLLECTION tags (id String!, label String)").unwrap();
+
+        assert_eq!(analyzed.config, None);
+    }
+
+    #[test]
+    fn invalid_specs_and_duplicates_are_rejected() {
+        let err = analyze("CREATE COLLECTION t (a Id, b Uuid)");
+        assert!(matches!(err, Err(AnalyzerError::Other(message)) if message.contains("ID marker")));
+
+        let err = analyze("CREATE COLLECTION t (a Int, a String)");
+        assert!(matches!(err, Err(AnalyzerError::Other(message)) if message.contains("Duplicate")));
+
+        assert!(analyze("CREATE COLLECTION t (a Banana)").is_err());
+    }
+}
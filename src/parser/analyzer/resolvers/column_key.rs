@@ -1,4 +1,4 @@
-use crate::parser::ast::Column;
+use crate::parser::ast::{Column, ScalarExpr};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ColumnKey {
@@ -19,6 +19,17 @@ impl ColumnKey {
             },
         }
     }
+
+    /// Key of a GROUP BY entry; an expression is keyed by its text.
+    pub fn of_expr(expr: &ScalarExpr) -> Self {
+        match expr {
+            ScalarExpr::Column(col) => Self::of(col),
+            other => Self {
+                column: String::new(),
+                name: other.to_string(),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
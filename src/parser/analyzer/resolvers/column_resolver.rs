@@ -1,6 +1,6 @@
 use crate::parser::{
     analyzer::{AnalysisContext, AnalyzerError, ResolvedField},
-    ast::Column,
+    ast::{Column, PathSegment},
 };
 
 pub struct ColumnResolver;
@@ -12,13 +12,17 @@ impl ColumnResolver {
     ) -> Result<(Column, ResolvedField), AnalyzerError> {
         match col {
             Column::WithCollection { collection, name } => {
-                let coll_ref = ctx
-                    .collections
-                    .get(collection)
-                    .ok_or_else(|| AnalyzerError::UnknownCollection(collection.clone()))?;
-                let schema = ctx
-                    .schema_of_collection_ref(coll_ref)
-                    .ok_or_else(|| AnalyzerError::UnknownCollection(coll_ref.clone()))?;
+                let schema = match ctx.collections.get(collection) {
+                    Some(coll_ref) => ctx
+                        .schema_of_collection_ref(coll_ref)
+                        .ok_or_else(|| AnalyzerError::UnknownCollection(coll_ref.clone()))?,
+                    // correlated reference to a collection of an enclosing query
+                    None => ctx
+                        .outer
+                        .get(collection)
+                        .cloned()
+                        .ok_or_else(|| AnalyzerError::UnknownCollection(collection.clone()))?,
+                };
                 let field_info = schema
                     .get(name)
                     .ok_or_else(|| AnalyzerError::UnknownColumn {
@@ -54,6 +58,22 @@ impl ColumnResolver {
                         ));
                     }
                 }
+                // only when no local collection has it, try the enclosing queries
+                if matches.is_empty() {
+                    for (visible_coll, schema) in &ctx.outer {
+                        if let Some(field_info) = schema.get(name) {
+                            matches.push((
+                                visible_coll.clone(),
+                                ResolvedField {
+                                    collection: visible_coll.clone(),
+                                    name: name.clone(),
+                                    ty: field_info.ty,
+                                    nullable: field_info.nullable,
+                                },
+                            ));
+                        }
+                    }
+                }
                 match matches.len() {
                     0 => Err(AnalyzerError::UnknownColumn {
                         name: name.clone(),
@@ -80,6 +100,33 @@ impl ColumnResolver {
             }
         }
     }
+
+    /// Qualifies a column together with a JSON path read from it.
+    /// `a.b` and `a.b.c` name collection `a` when it is in scope; otherwise they
+    /// read key `b` (then `c`) from column `a`.
+    pub fn qualify_column_path(
+        col: &Column,
+        path: &[PathSegment],
+        ctx: &AnalysisContext,
+    ) -> Result<(Column, Vec<PathSegment>, ResolvedField), AnalyzerError> {
+        if let Column::WithCollection { collection, name } = col
+            && !ctx.collections.contains_key(collection)
+            && !ctx.outer.contains_key(collection)
+            && let Ok((qualified, rf)) = Self::qualify_column(
+                &Column::Name {
+                    name: collection.clone(),
+                },
+                ctx,
+            )
+        {
+            let mut nested = vec![PathSegment::Key(name.clone())];
+            nested.extend_from_slice(path);
+            return Ok((qualified, nested, rf));
+        }
+
+        let (qualified, rf) = Self::qualify_column(col, ctx)?;
+        Ok((qualified, path.to_vec(), rf))
+    }
 }
 
 #[cfg(test)]
@@ -170,6 +217,65 @@ mod tests {
         assert!(!rf.nullable);
     }
 
+    #[test]
+    fn qualify_column_path_reads_into_a_column_when_no_collection_matches() {
+        let sp = DummySchemas::new().with(
+            "people",
+            vec![
+                ("id", JsonPrimitive::Int, false),
+                ("profile", JsonPrimitive::Object, false),
+            ],
+        );
+        let ctx = ctx_with_alias(&sp, "people", "p");
+
+        // `profile.city`: no collection named `profile`, so it is key `city` of column `profile`
+        let (qualified, path, rf) = ColumnResolver::qualify_column_path(
+            &Column::WithCollection {
+                collection: "profile".into(),
+                name: "city".into(),
+            },
+            &[PathSegment::Index(0)],
+            &ctx,
+        )
+        .expect("qualify path");
+        assert_eq!(
+            qualified,
+            Column::WithCollection {
+                collection: "p".into(),
+                name: "profile".into(),
+            }
+        );
+        assert_eq!(
+            path,
+            vec![PathSegment::Key("city".into()), PathSegment::Index(0)]
+        );
+        assert_eq!(rf.ty, JsonPrimitive::Object);
+
+        // `p.profile` names the collection, so the path is unchanged
+        let (_, path, _) = ColumnResolver::qualify_column_path(
+            &Column::WithCollection {
+                collection: "p".into(),
+                name: "profile".into(),
+            },
+            &[],
+            &ctx,
+        )
+        .expect("qualify column");
+        assert!(path.is_empty());
+
+        // neither a collection nor a column keeps the collection error
+        let err = ColumnResolver::qualify_column_path(
+            &Column::WithCollection {
+                collection: "x".into(),
+                name: "city".into(),
+            },
+            &[],
+            &ctx,
+        )
+        .unwrap_err();
+        assert!(matches!(err, AnalyzerError::UnknownCollection(name) if name == "x"));
+    }
+
     #[test]
     fn qualify_unqualified_column_when_unique_among_visible_collections() {
         // two backings: users(id,name) and orders(id,total)
@@ -337,4 +443,56 @@ mod tests {
         assert_eq!(rf.name, "id");
         assert_eq!(rf.ty, JsonPrimitive::Int);
     }
+
+    #[test]
+    fn qualify_falls_back_to_enclosing_query_collections() {
+        let sp = DummySchemas::new()
+            .with("users", vec![("id", JsonPrimitive::Int, false)])
+            .with(
+                "orders",
+                vec![
+                    ("id", JsonPrimitive::Int, false),
+                    ("user_id", JsonPrimitive::Int, true),
+                ],
+            );
+        let mut outer = AnalysisContext::new(&sp);
+        outer.add_collection("u", "users");
+
+        let mut ctx = AnalysisContext::new(&sp);
+        ctx.outer = outer.nested_scope().outer;
+        ctx.add_collection("o", "orders");
+
+        // local columns win over enclosing ones
+        let (qualified, _) =
+            ColumnResolver::qualify_column(&Column::Name { name: "id".into() }, &ctx).unwrap();
+        assert_eq!(
+            qualified,
+            Column::WithCollection {
+                collection: "o".into(),
+                name: "id".into()
+            }
+        );
+
+        let (qualified, rf) = ColumnResolver::qualify_column(
+            &Column::WithCollection {
+                collection: "u".into(),
+                name: "id".into(),
+            },
+            &ctx,
+        )
+        .expect("correlated column");
+        assert_eq!(rf.collection, "u");
+        assert!(
+            matches!(qualified, Column::WithCollection { collection, .. } if collection == "u")
+        );
+
+        let err = ColumnResolver::qualify_column(
+            &Column::WithCollection {
+                collection: "x".into(),
+                name: "id".into(),
+            },
+            &ctx,
+        );
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "x"));
+    }
 }
@@ -0,0 +1,394 @@
+use serde_json::Value;
+
+use crate::{
+    SchemaDict,
+    database::SchemaProvider,
+    parser::{
+        aggregators_helper::AggregateRegistry,
+        analyzer::{
+            AggregateResolver, AnalysisContext, AnalyzedIdentifier, AnalyzedQuery,
+            AnalyzedSetOperation, AnalyzerError, QueryScope, SetOperationResolver,
+        },
+        ast::{Collection, Cte, Query, SetOperator},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedCte {
+    pub name: String,
+    pub columns: Vec<String>, // output names of the CTE, in projection order
+    /// the whole query, or the non-recursive anchor of a recursive CTE
+    pub query: AnalyzedQuery,
+    /// `UNION [ALL]` terms that read the CTE itself; re-run until they add no rows
+    pub recursive_terms: Vec<AnalyzedSetOperation>,
+}
+
+pub struct CteResolver;
+
+impl CteResolver {
+    /// Analyze WITH queries in order, adding each output schema to `scope`
+    /// so later CTEs and the main query can read it by name.
+    pub fn analyze_ctes<'a>(
+        ctes: &[Cte],
+        schema_provider: &'a dyn SchemaProvider,
+        aggregates: &'a AggregateRegistry,
+        parameters: Value,
+        current_param: &mut usize,
+        scope: &mut QueryScope,
+    ) -> Result<Vec<AnalyzedCte>, AnalyzerError> {
+        let mut out: Vec<AnalyzedCte> = Vec::with_capacity(ctes.len());
+        for cte in ctes {
+            if out.iter().any(|c| c.name == cte.name) {
+                return Err(AnalyzerError::Other(format!(
+                    "WITH query name {} specified more than once",
+                    cte.name
+                )));
+            }
+
+            let analyzed = if cte.recursive && Self::reads_itself(cte) {
+                Self::analyze_recursive(
+                    cte,
+                    schema_provider,
+                    aggregates,
+                    parameters.clone(),
+                    current_param,
+                    scope,
+                )?
+            } else {
+                let query = AnalysisContext::analyze_query_in_scope(
+                    &cte.query,
+                    schema_provider,
+                    aggregates,
+                    parameters.clone(),
+                    current_param,
+                    scope,
+                )?;
+                let columns = Self::output_columns(cte, &query.projection)?;
+                AnalyzedCte {
+                    name: cte.name.clone(),
+                    columns,
+                    query,
+                    recursive_terms: vec![],
+                }
+            };
+
+            scope.ctes.insert(
+                cte.name.clone(),
+                Self::schema_of(&analyzed.query.projection, &analyzed.columns),
+            );
+            out.push(analyzed);
+        }
+        Ok(out)
+    }
+
+    /// `anchor UNION [ALL] term ...`: the anchor is analyzed first, so its output
+    /// schema is in scope while the terms that read the CTE are analyzed.
+    fn analyze_recursive<'a>(
+        cte: &Cte,
+        schema_provider: &'a dyn SchemaProvider,
+        aggregates: &'a AggregateRegistry,
+        parameters: Value,
+        current_param: &mut usize,
+        scope: &mut QueryScope,
+    ) -> Result<AnalyzedCte, AnalyzerError> {
+        let query = &cte.query;
+        let Some(first) = query.set_operations.first() else {
+            return Err(AnalyzerError::Other(format!(
+                "WITH RECURSIVE query {} must have the form anchor UNION [ALL] recursive term",
+                cte.name
+            )));
+        };
+        if Self::block_reads(query, &cte.name) {
+            return Err(AnalyzerError::Other(format!(
+                "the first query of WITH RECURSIVE {} must not refer to itself",
+                cte.name
+            )));
+        }
+        if query
+            .set_operations
+            .iter()
+            .any(|s| s.op != SetOperator::Union || s.all != first.all)
+        {
+            return Err(AnalyzerError::Other(format!(
+                "WITH RECURSIVE query {} must combine its terms with a single UNION or UNION ALL",
+                cte.name
+            )));
+        }
+        if !query.order_by.is_empty() || query.limit.is_some() || query.offset.is_some() {
+            return Err(AnalyzerError::Other(format!(
+                "ORDER BY, LIMIT and OFFSET are not supported in WITH RECURSIVE query {}",
+                cte.name
+            )));
+        }
+
+        let anchor = Query {
+            set_operations: vec![],
+            ..query.clone()
+        };
+        let mut anchor = AnalysisContext::analyze_query_in_scope(
+            &anchor,
+            schema_provider,
+            aggregates,
+            parameters.clone(),
+            current_param,
+            scope,
+        )?;
+        let columns = Self::output_columns(cte, &anchor.projection)?;
+
+        let mut recursive_terms = Vec::with_capacity(query.set_operations.len());
+        for operation in &query.set_operations {
+            // a term sees only the rows of the previous round, so it cannot aggregate them all
+            let term_query = &operation.query;
+            if !term_query.group_by.is_empty()
+                || term_query
+                    .projection
+                    .iter()
+                    .any(|id| AggregateResolver::contains_aggregate(&id.expression))
+                || term_query
+                    .having
+                    .as_ref()
+                    .is_some_and(AggregateResolver::predicate_contains_aggregate)
+            {
+                return Err(AnalyzerError::Other(format!(
+                    "aggregates and GROUP BY are not allowed in the recursive term of WITH RECURSIVE query {}",
+                    cte.name
+                )));
+            }
+            scope.ctes.insert(
+                cte.name.clone(),
+                Self::schema_of(&anchor.projection, &columns),
+            );
+            let mut term = AnalysisContext::analyze_query_in_scope(
+                &operation.query,
+                schema_provider,
+                aggregates,
+                parameters.clone(),
+                current_param,
+                scope,
+            )?;
+            SetOperationResolver::unify_columns(
+                operation.op,
+                &mut anchor.projection,
+                &mut term.projection,
+            )?;
+            recursive_terms.push(AnalyzedSetOperation {
+                op: operation.op,
+                all: operation.all,
+                query: term,
+            });
+        }
+
+        Ok(AnalyzedCte {
+            name: cte.name.clone(),
+            columns,
+            query: anchor,
+            recursive_terms,
+        })
+    }
+
+    /// Output names of the CTE: the column list when given, else the projection names.
+    fn output_columns(
+        cte: &Cte,
+        projection: &[AnalyzedIdentifier],
+    ) -> Result<Vec<String>, AnalyzerError> {
+        if cte.columns.is_empty() {
+            return Ok(projection.iter().map(|id| id.output_name.clone()).collect());
+        }
+        if cte.columns.len() != projection.len() {
+            return Err(AnalyzerError::Other(format!(
+                "WITH query {} has {} columns available but {} columns specified",
+                cte.name,
+                projection.len(),
+                cte.columns.len()
+            )));
+        }
+        Ok(cte.columns.clone())
+    }
+
+    fn schema_of(projection: &[AnalyzedIdentifier], columns: &[String]) -> SchemaDict {
+        let renamed: Vec<AnalyzedIdentifier> = projection
+            .iter()
+            .zip(columns)
+            .map(|(id, column)| AnalyzedIdentifier {
+                output_name: column.clone(),
+                ..id.clone()
+            })
+            .collect();
+        AnalysisContext::schema_from_projection(&renamed)
+    }
+
+    fn reads_itself(cte: &Cte) -> bool {
+        Self::block_reads(&cte.query, &cte.name)
+            || cte
+                .query
+                .set_operations
+                .iter()
+                .any(|s| Self::block_reads(&s.query, &cte.name))
+    }
+
+    /// Does a SELECT block name `name` directly in FROM or JOIN?
+    fn block_reads(query: &Query, name: &str) -> bool {
+        query
+            .collections
+            .iter()
+            .chain(query.joins.iter().map(|j| &j.collection))
+            .any(|c| matches!(c, Collection::Table { name: n, .. } if n == name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            aggregators_helper::AggregateRegistry,
+            analyzer::{AnalysisContext, AnalyzedQuery, AnalyzedSource, AnalyzerError},
+            ast::Query,
+        },
+    };
+
+    use indexmap::IndexMap;
+    use serde_json::Value;
+
+    struct Schemas;
+    impl SchemaProvider for Schemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            if backing_collection != "orders" {
+                return None;
+            }
+            let mut fields = IndexMap::new();
+            for (name, ty) in [
+                ("id", JsonPrimitive::Int),
+                ("customer", JsonPrimitive::String),
+                ("total", JsonPrimitive::Float),
+            ] {
+                fields.insert(
+                    name.to_string(),
+                    FieldInfo {
+                        ty,
+                        nullable: false,
+                    },
+                );
+            }
+            Some(SchemaDict { fields })
+        }
+    }
+
+    fn analyze(text: &str) -> Result<AnalyzedQuery, AnalyzerError> {
+        let query = Query::try_from(text).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        AnalysisContext::analyze_query(&query, &Schemas, &registry, Value::Null)
+    }
+
+    #[test]
+    fn ctes_see_previous_ctes_and_rename_columns() {
+        let analyzed = analyze(
+            "WITH big AS (SELECT customer, total FROM orders WHERE total > 10), \
+             per_customer (who, spent) AS (SELECT customer, SUM(total) FROM big GROUP BY customer) \
+             SELECT p.who, p.spent FROM per_customer p JOIN big b ON b.customer = p.who",
+        )
+        .unwrap();
+
+        assert_eq!(analyzed.ctes.len(), 2);
+        assert_eq!(analyzed.ctes[1].columns, vec!["who", "spent"]);
+        assert!(matches!(
+            &analyzed.collections[0],
+            AnalyzedSource::Cte { visible, name, .. } if visible == "p" && name == "per_customer"
+        ));
+        assert!(
+            matches!(&analyzed.joins[0].source, AnalyzedSource::Cte { name, .. } if name == "big")
+        );
+        assert_eq!(analyzed.projection[1].ty, JsonPrimitive::Float);
+    }
+
+    #[test]
+    fn ctes_are_visible_in_nested_subqueries_and_set_operations() {
+        let analyzed = analyze(
+            "WITH ids AS (SELECT id FROM orders) \
+             SELECT s.id FROM (SELECT id FROM ids) s UNION SELECT id FROM ids",
+        )
+        .unwrap();
+
+        assert_eq!(analyzed.ctes.len(), 1);
+        assert_eq!(analyzed.set_operations.len(), 1);
+    }
+
+    #[test]
+    fn invalid_ctes_are_rejected() {
+        let err = analyze(
+            "WITH a AS (SELECT id FROM orders), a AS (SELECT id FROM orders) SELECT * FROM a",
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("more than once"))
+        );
+
+        let err = analyze("WITH a (x, y) AS (SELECT id FROM orders) SELECT * FROM a");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("columns specified"))
+        );
+
+        // a CTE cannot read itself without RECURSIVE
+        let err = analyze("WITH a AS (SELECT id FROM a) SELECT * FROM a");
+        assert!(matches!(err, Err(AnalyzerError::UnknownColumn { .. })));
+    }
+
+    #[test]
+    fn recursive_ctes_split_anchor_and_terms() {
+        let analyzed = analyze(
+            "WITH RECURSIVE chain (n, who) AS (SELECT id, customer FROM orders WHERE id = 1 \
+             UNION ALL SELECT o.id, o.customer FROM orders o JOIN chain c ON o.id = c.n) \
+             SELECT who FROM chain",
+        )
+        .unwrap();
+
+        let cte = &analyzed.ctes[0];
+        assert_eq!(cte.columns, vec!["n", "who"]);
+        assert!(cte.query.set_operations.is_empty());
+        assert_eq!(cte.recursive_terms.len(), 1);
+        assert!(cte.recursive_terms[0].all);
+
+        // RECURSIVE without a self reference is an ordinary CTE
+        let analyzed =
+            analyze("WITH RECURSIVE ids AS (SELECT id FROM orders) SELECT id FROM ids").unwrap();
+        assert!(analyzed.ctes[0].recursive_terms.is_empty());
+    }
+
+    #[test]
+    fn invalid_recursive_ctes_are_rejected() {
+        let err = analyze(
+            "WITH RECURSIVE r AS (SELECT id FROM orders o JOIN r ON r.id = o.id) SELECT id FROM r",
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("anchor UNION"))
+        );
+
+        let err = analyze(
+            "WITH RECURSIVE r AS (SELECT id FROM orders UNION ALL SELECT id FROM orders \
+             EXCEPT SELECT id FROM r) SELECT id FROM r",
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("single UNION"))
+        );
+
+        let err = analyze(
+            "WITH RECURSIVE r AS (SELECT id FROM r UNION SELECT id FROM orders) SELECT id FROM r",
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("must not refer"))
+        );
+
+        for text in [
+            "WITH RECURSIVE r (n) AS (SELECT id FROM orders UNION ALL SELECT COUNT(*) AS x FROM r) \
+             SELECT n FROM r",
+            "WITH RECURSIVE r AS (SELECT id FROM orders UNION SELECT id FROM r GROUP BY id) \
+             SELECT id FROM r",
+        ] {
+            let err = analyze(text);
+            assert!(
+                matches!(err, Err(AnalyzerError::Other(message)) if message.contains("not allowed in the recursive term")),
+                "{text} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::parser::{
+    analyzer::{
+        AnalysisContext, AnalyzedDelete, AnalyzerError, PredicateResolver, ReturningResolver,
+    },
+    ast::Delete,
+};
+
+pub struct DeleteResolver;
+
+impl DeleteResolver {
+    pub fn analyze_delete(
+        delete: &Delete,
+        ctx: &mut AnalysisContext,
+    ) -> Result<AnalyzedDelete, AnalyzerError> {
+        let schema = ctx
+            .schemas
+            .schema_of(&delete.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(delete.collection.clone()))?;
+        ctx.add_collection(delete.collection.clone(), delete.collection.clone());
+
+        let criteria = match &delete.criteria {
+            Some(predicate) => Some(PredicateResolver::resolve_write_criteria(predicate, ctx)?),
+            None => None,
+        };
+
+        let returning = ReturningResolver::analyze_returning(
+            &delete.collection,
+            &schema,
+            &[],
+            &delete.returning,
+            ctx,
+        )?;
+
+        Ok(AnalyzedDelete {
+            collection: delete.collection.clone(),
+            criteria,
+            returning,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            QueryParser,
+            aggregators_helper::AggregateRegistry,
+            analyzer::{AnalysisContext, AnalyzedDelete, AnalyzerError, DeleteResolver},
+            ast::{Column, Delete, Literal, Predicate, ScalarExpr},
+        },
+    };
+
+    use indexmap::IndexMap;
+    use serde_json::json;
+
+    struct DummySchemas {
+        by_name: std::collections::HashMap<String, SchemaDict>,
+    }
+    impl SchemaProvider for DummySchemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            self.by_name.get(backing_collection).cloned()
+        }
+    }
+
+    fn analyze(sql: &str, parameters: serde_json::Value) -> Result<AnalyzedDelete, AnalyzerError> {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "id".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Int,
+                nullable: false,
+            },
+        );
+        let schemas = DummySchemas {
+            by_name: [("people".to_string(), SchemaDict { fields })].into(),
+        };
+        let delete = Delete::parse(&mut QueryParser::new(sql)).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        let mut ctx = AnalysisContext::new_with_aggregates(&schemas, &registry);
+        ctx.parameters = parameters;
+        DeleteResolver::analyze_delete(&delete, &mut ctx)
+    }
+
+    #[test]
+    fn criteria_is_qualified_and_bound() {
+        let analyzed = analyze("DELETE FROM people WHERE id = ?", json!(3)).unwrap();
+
+        assert_eq!(
+            analyzed.criteria,
+            Some(Predicate::Compare {
+                left: ScalarExpr::Column(Column::WithCollection {
+                    collection: "people".into(),
+                    name: "id".into(),
+                }),
+                op: crate::parser::ast::ComparatorOp::Eq,
+                right: ScalarExpr::Literal(Literal::Int(3)),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_targets_and_aggregates_are_rejected() {
+        let err = analyze("DELETE FROM nope", json!(null));
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "nope"));
+
+        let err = analyze("DELETE FROM people WHERE age = 1", json!(null));
+        assert!(matches!(err, Err(AnalyzerError::UnknownColumn { .. })));
+
+        let err = analyze("DELETE FROM people WHERE MAX(id) > 1", json!(null));
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("Aggregates"))
+        );
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::HashSet;
+
+use crate::{
+    JsonPrimitive, SchemaDict,
+    database::FieldInfo,
+    parser::{
+        analyzer::{
+            AnalysisContext, AnalyzedConflictAction, AnalyzedInsert, AnalyzedInsertSource,
+            AnalyzedOnConflict, AnalyzerError, AssignmentResolver, QueryScope, ReturningResolver,
+            ScalarResolver, TypeInference, UpdateResolver,
+        },
+        ast::{ConflictAction, Insert, InsertSource, OnConflict},
+    },
+};
+
+/// Visible name of the incoming row inside `ON CONFLICT ... DO UPDATE SET`.
+pub const EXCLUDED: &str = "excluded";
+
+pub struct InsertResolver;
+
+impl InsertResolver {
+    pub fn analyze_insert<'a>(
+        insert: &Insert,
+        ctx: &mut AnalysisContext<'a>,
+    ) -> Result<AnalyzedInsert, AnalyzerError> {
+        let schema = ctx
+            .schemas
+            .schema_of(&insert.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(insert.collection.clone()))?;
+
+        let mut seen = HashSet::new();
+        for column in &insert.columns {
+            if !seen.insert(column.as_str()) {
+                return Err(AnalyzerError::Other(format!(
+                    "column {column} specified more than once in INSERT"
+                )));
+            }
+        }
+
+        let (columns, types, source) = match &insert.source {
+            InsertSource::Values(rows) => {
+                if insert.columns.is_empty() {
+                    return Err(AnalyzerError::Other(
+                        "INSERT ... VALUES requires a column list".into(),
+                    ));
+                }
+
+                let mut analyzed_rows = Vec::with_capacity(rows.len());
+                let mut types = vec![JsonPrimitive::Null; insert.columns.len()];
+                for row in rows {
+                    Self::check_arity(insert.columns.len(), row.len())?;
+
+                    let mut values = Vec::with_capacity(row.len());
+                    for ((column, expr), seen_ty) in
+                        insert.columns.iter().zip(row).zip(types.iter_mut())
+                    {
+                        // VALUES have no visible collections, so any column reference fails here
+                        let qexpr = ScalarResolver::qualify_scalar(expr, ctx, false)?;
+                        let fexpr = ScalarResolver::fold_scalar(&qexpr);
+                        let (ty, _) = TypeInference::infer_scalar(&fexpr, ctx)?;
+                        AssignmentResolver::check_assignable(
+                            &insert.collection,
+                            &schema,
+                            column,
+                            ty,
+                        )?;
+                        AssignmentResolver::check_constant(
+                            &insert.collection,
+                            &schema,
+                            column,
+                            &fexpr,
+                        )?;
+                        if *seen_ty == JsonPrimitive::Null {
+                            *seen_ty = ty;
+                        }
+                        values.push(fexpr);
+                    }
+                    analyzed_rows.push(values);
+                }
+
+                (
+                    insert.columns.clone(),
+                    types,
+                    AnalyzedInsertSource::Values(analyzed_rows),
+                )
+            }
+            InsertSource::Query(query) => {
+                let analyzed = ctx.analyze_nested_query(query, &QueryScope::default())?;
+
+                // without a column list the SELECT output names become the field names
+                let columns = if insert.columns.is_empty() {
+                    analyzed
+                        .projection
+                        .iter()
+                        .map(|id| id.output_name.clone())
+                        .collect::<Vec<_>>()
+                } else {
+                    Self::check_arity(insert.columns.len(), analyzed.projection.len())?;
+                    insert.columns.clone()
+                };
+
+                for (column, id) in columns.iter().zip(&analyzed.projection) {
+                    AssignmentResolver::check_assignable(
+                        &insert.collection,
+                        &schema,
+                        column,
+                        id.ty,
+                    )?;
+                }
+
+                let types = analyzed.projection.iter().map(|id| id.ty).collect();
+                (
+                    columns,
+                    types,
+                    AnalyzedInsertSource::Query(Box::new(analyzed)),
+                )
+            }
+        };
+
+        let mut written = columns.iter().cloned().zip(types).collect::<Vec<_>>();
+        let on_conflict = match &insert.on_conflict {
+            Some(on_conflict) => Some(Self::analyze_on_conflict(
+                &insert.collection,
+                &schema,
+                &mut written,
+                on_conflict,
+                ctx,
+            )?),
+            None => None,
+        };
+        let returning = ReturningResolver::analyze_returning(
+            &insert.collection,
+            &schema,
+            &written,
+            &insert.returning,
+            ctx,
+        )?;
+
+        Ok(AnalyzedInsert {
+            collection: insert.collection.clone(),
+            columns,
+            source,
+            on_conflict,
+            returning,
+        })
+    }
+
+    /// `DO UPDATE SET` sees the stored row under the collection name and the
+    /// incoming row as `excluded`, like Postgres.
+    fn analyze_on_conflict(
+        collection: &str,
+        schema: &SchemaDict,
+        written: &mut Vec<(String, JsonPrimitive)>,
+        on_conflict: &OnConflict,
+        ctx: &mut AnalysisContext,
+    ) -> Result<AnalyzedOnConflict, AnalyzerError> {
+        let action = match &on_conflict.action {
+            ConflictAction::DoNothing => AnalyzedConflictAction::DoNothing,
+            ConflictAction::DoUpdate(assignments) if assignments.is_empty() => {
+                AnalyzedConflictAction::DoUpdate(vec![])
+            }
+            ConflictAction::DoUpdate(assignments) => {
+                let mut excluded = SchemaDict::default();
+                for (column, ty) in written.iter() {
+                    excluded.fields.insert(
+                        column.clone(),
+                        FieldInfo {
+                            ty: *ty,
+                            nullable: true,
+                        },
+                    );
+                }
+                ctx.add_collection(collection, collection);
+                ctx.add_subquery(EXCLUDED, excluded);
+
+                let analyzed = UpdateResolver::analyze_assignments(
+                    collection,
+                    schema,
+                    assignments,
+                    written,
+                    ctx,
+                )?;
+
+                // `excluded` is only visible inside DO UPDATE SET
+                ctx.collections.shift_remove(EXCLUDED);
+                ctx.subquery_schemas.shift_remove(EXCLUDED);
+                AnalyzedConflictAction::DoUpdate(analyzed)
+            }
+        };
+
+        Ok(AnalyzedOnConflict {
+            target: on_conflict.target.clone(),
+            action,
+        })
+    }
+
+    fn check_arity(columns: usize, values: usize) -> Result<(), AnalyzerError> {
+        if columns != values {
+            return Err(AnalyzerError::Other(format!(
+                "INSERT has {columns} target columns but {values} values"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            aggregators_helper::AggregateRegistry,
+            analyzer::{
+                AnalysisContext, AnalyzedConflictAction, AnalyzedInsertSource, AnalyzerError,
+                InsertResolver,
+            },
+            ast::{Column, Insert, Literal, ScalarExpr},
+        },
+    };
+
+    use indexmap::IndexMap;
+    use serde_json::json;
+
+    struct DummySchemas {
+        by_name: std::collections::HashMap<String, SchemaDict>,
+    }
+    impl DummySchemas {
+        fn new() -> Self {
+            Self {
+                by_name: std::collections::HashMap::new(),
+            }
+        }
+        fn with(mut self, name: &str, fields: Vec<(&str, JsonPrimitive, bool)>) -> Self {
+            let mut m = IndexMap::new();
+            for (k, ty, nullable) in fields {
+                m.insert(k.to_string(), FieldInfo { ty, nullable });
+            }
+            self.by_name
+                .insert(name.to_string(), SchemaDict { fields: m });
+            self
+        }
+    }
+    impl SchemaProvider for DummySchemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            self.by_name.get(backing_collection).cloned()
+        }
+    }
+
+    fn people() -> DummySchemas {
+        DummySchemas::new().with(
+            "people",
+            vec![
+                ("id", JsonPrimitive::Int, false),
+                ("name", JsonPrimitive::String, false),
+                ("age", JsonPrimitive::Int, true),
+                ("score", JsonPrimitive::Float, true),
+            ],
+        )
+    }
+
+    fn analyze(
+        sql: &str,
+        schemas: &DummySchemas,
+        parameters: serde_json::Value,
+    ) -> Result<crate::parser::analyzer::AnalyzedInsert, AnalyzerError> {
+        let insert = Insert::parse(&mut crate::parser::QueryParser::new(sql)).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        let mut ctx = AnalysisContext::new_with_aggregates(schemas, &registry);
+        ctx.parameters = parameters;
+        InsertResolver::analyze_insert(&insert, &mut ctx)
+    }
+
+    #[test]
+    fn values_are_bound_and_folded() {
+        let schemas = people();
+        let analyzed = analyze(
+            "INSERT INTO people (name, age, score) VALUES (upper('ada'), ?, 1)",
+            &schemas,
+            json!([37]),
+        )
+        .unwrap();
+
+        assert_eq!(analyzed.columns, vec!["name", "age", "score"]);
+        match analyzed.source {
+            AnalyzedInsertSource::Values(rows) => {
+                assert_eq!(
+                    rows[0],
+                    vec![
+                        ScalarExpr::Literal(Literal::String("ADA".into())),
+                        ScalarExpr::Literal(Literal::Int(37)),
+                        ScalarExpr::Literal(Literal::Int(1)),
+                    ]
+                );
+            }
+            AnalyzedInsertSource::Query(_) => panic!(),
+        }
+    }
+
+    #[test]
+    fn unknown_collection_is_rejected() {
+        let schemas = people();
+        let err = analyze(
+            "INSERT INTO nope (name) VALUES ('x')",
+            &schemas,
+            json!(null),
+        );
+
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "nope"));
+    }
+
+    #[test]
+    fn values_require_column_list_and_matching_arity() {
+        let schemas = people();
+
+        let err = analyze("INSERT INTO people VALUES ('x')", &schemas, json!(null));
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("column list"))
+        );
+
+        let err = analyze(
+            "INSERT INTO people (name, age) VALUES ('x')",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("2 target columns but 1 values"))
+        );
+
+        let err = analyze(
+            "INSERT INTO people (name, name) VALUES ('x', 'y')",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("more than once"))
+        );
+    }
+
+    #[test]
+    fn values_are_checked_against_schema_types() {
+        let schemas = people();
+
+        let err = analyze(
+            "INSERT INTO people (age) VALUES ('old')",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("people.age"))
+        );
+
+        let err = analyze(
+            "INSERT INTO people (name) VALUES (NULL)",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("not nullable"))
+        );
+
+        // new fields are accepted as-is
+        assert!(
+            analyze(
+                "INSERT INTO people (nickname) VALUES ('A')",
+                &schemas,
+                json!(null)
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn values_cannot_reference_columns() {
+        let schemas = people();
+        let err = analyze(
+            "INSERT INTO people (name) VALUES (name)",
+            &schemas,
+            json!(null),
+        );
+
+        assert!(matches!(err, Err(AnalyzerError::UnknownColumn { .. })));
+    }
+
+    #[test]
+    fn select_source_uses_output_names_without_column_list() {
+        let schemas = people().with("archive", vec![("id", JsonPrimitive::Int, false)]);
+        let analyzed = analyze(
+            "INSERT INTO archive SELECT name, age AS years FROM people",
+            &schemas,
+            json!(null),
+        )
+        .unwrap();
+
+        assert_eq!(analyzed.columns, vec!["name", "years"]);
+        assert!(matches!(analyzed.source, AnalyzedInsertSource::Query(_)));
+
+        let err = analyze(
+            "INSERT INTO archive (name) SELECT name, age FROM people",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("1 target columns but 2 values"))
+        );
+    }
+
+    #[test]
+    fn on_conflict_set_sees_stored_and_excluded_rows_only() {
+        let schemas = people();
+        let analyzed = analyze(
+            "INSERT INTO people (id, name) VALUES (1, ?) \
+             ON CONFLICT (id) DO UPDATE SET name = excluded.name, age = people.age",
+            &schemas,
+            json!(["Ada"]),
+        )
+        .unwrap();
+
+        let on_conflict = analyzed.on_conflict.expect("on conflict");
+        assert_eq!(on_conflict.target, Some("id".into()));
+        match on_conflict.action {
+            AnalyzedConflictAction::DoUpdate(assignments) => {
+                assert_eq!(
+                    assignments[0].1,
+                    ScalarExpr::Column(Column::WithCollection {
+                        collection: "excluded".into(),
+                        name: "name".into(),
+                    })
+                );
+                assert_eq!(
+                    assignments[1].1,
+                    ScalarExpr::Column(Column::WithCollection {
+                        collection: "people".into(),
+                        name: "age".into(),
+                    })
+                );
+            }
+            AnalyzedConflictAction::DoNothing => panic!(),
+        }
+
+        let err = analyze(
+            "INSERT INTO people (id) VALUES (1) ON CONFLICT DO UPDATE SET name = excluded.id",
+            &schemas,
+            json!(null),
+        );
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("people.name"))
+        );
+
+        let err = analyze(
+            "INSERT INTO people (id) VALUES (1) ON CONFLICT DO UPDATE SET age = 1 RETURNING excluded.id",
+            &schemas,
+            json!(null),
+        );
+        assert!(err.is_err());
+    }
+}
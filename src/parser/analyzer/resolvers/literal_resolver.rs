@@ -1,4 +1,9 @@
-use crate::parser::ast::{ComparatorOp, Literal, Truth};
+use ordered_float::NotNan;
+
+use crate::parser::{
+    analyzer::TemporalResolver,
+    ast::{ArithmeticOp, ComparatorOp, Literal, Truth},
+};
 
 pub struct LiteralResolver;
 
@@ -79,6 +84,13 @@ impl LiteralResolver {
             }
         };
 
+        // ISO-8601 strings compare chronologically
+        if let (Literal::String(a), Literal::String(b)) = (l, r)
+            && let Some(truth) = TemporalResolver::compare3(a, op, b)
+        {
+            return truth;
+        }
+
         match (l, r) {
             (Literal::Bool(a), Literal::Bool(b)) => match op {
                 ComparatorOp::Eq => {
@@ -124,6 +136,81 @@ impl LiteralResolver {
         }
     }
 
+    /// `l op r`: NULL in, NULL out. Int with Int stays Int (integer division),
+    /// a Float operand makes the result Float. Division or modulo by zero,
+    /// integer overflow and non-numeric operands yield NULL. `||` concatenates
+    /// the text of any two non-NULL scalars.
+    pub fn eval_arithmetic(l: &Literal, op: ArithmeticOp, r: &Literal) -> Literal {
+        if matches!(l, Literal::Null) || matches!(r, Literal::Null) {
+            return Literal::Null;
+        }
+
+        if op == ArithmeticOp::Concat {
+            return match (Self::literal_text(l), Self::literal_text(r)) {
+                (Some(a), Some(b)) => Literal::String(a + &b),
+                _ => Literal::Null,
+            };
+        }
+
+        match (l, r) {
+            (Literal::Int(a), Literal::Int(b)) => {
+                let result = match op {
+                    ArithmeticOp::Add => a.checked_add(*b),
+                    ArithmeticOp::Sub => a.checked_sub(*b),
+                    ArithmeticOp::Mul => a.checked_mul(*b),
+                    ArithmeticOp::Div => a.checked_div(*b),
+                    ArithmeticOp::Mod => a.checked_rem(*b),
+                    ArithmeticOp::Concat => None,
+                };
+                result.map_or(Literal::Null, Literal::Int)
+            }
+            _ => match (Self::literal_f64(l), Self::literal_f64(r)) {
+                (Some(a), Some(b)) => {
+                    let result = match op {
+                        ArithmeticOp::Add => a + b,
+                        ArithmeticOp::Sub => a - b,
+                        ArithmeticOp::Mul => a * b,
+                        ArithmeticOp::Div | ArithmeticOp::Mod if b == 0.0 => {
+                            return Literal::Null;
+                        }
+                        ArithmeticOp::Div => a / b,
+                        ArithmeticOp::Mod => a % b,
+                        ArithmeticOp::Concat => return Literal::Null,
+                    };
+                    NotNan::new(result).map_or(Literal::Null, Literal::Float)
+                }
+                _ => Literal::Null,
+            },
+        }
+    }
+
+    /// Unary minus; NULL for NULL, non-numeric values and overflow.
+    pub fn eval_negate(value: &Literal) -> Literal {
+        match value {
+            Literal::Int(i) => i.checked_neg().map_or(Literal::Null, Literal::Int),
+            Literal::Float(f) => Literal::Float(-*f),
+            _ => Literal::Null,
+        }
+    }
+
+    fn literal_f64(value: &Literal) -> Option<f64> {
+        match value {
+            Literal::Int(i) => Some(*i as f64),
+            Literal::Float(f) => Some(f.into_inner()),
+            _ => None,
+        }
+    }
+
+    fn literal_text(value: &Literal) -> Option<String> {
+        match value {
+            Literal::String(s) => Some(s.clone()),
+            Literal::Int(i) => Some(i.to_string()),
+            Literal::Float(f) => Some(f.to_string()),
+            Literal::Bool(b) => Some(b.to_string()),
+            Literal::Null => None,
+        }
+    }
+
     pub fn eval_like(value: &str, pattern: &str) -> Truth {
         // very small LIKE: % -> .*  _ -> .  (no escapes)
         let mut re = String::from("(?i)^");
@@ -287,6 +374,36 @@ mod tests {
         assert_eq!(LiteralResolver::eval_compare3(&Null, NotEq, &Null), Unknown); // SQL 3VL: NULL <> NULL => Unknown
     }
 
+    #[test]
+    fn compare3_iso_strings_compare_chronologically() {
+        use ComparatorOp::*;
+        use Literal::*;
+        use Truth::*;
+
+        let s = |text: &str| String(text.into());
+        assert_eq!(
+            LiteralResolver::eval_compare3(
+                &s("2024-01-01T12:00:00+02:00"),
+                Lt,
+                &s("2024-01-01T11:00:00Z")
+            ),
+            True
+        );
+        assert_eq!(
+            LiteralResolver::eval_compare3(&s("2024-01-01"), Eq, &s("2024-01-01T00:00:00Z")),
+            True
+        );
+        // other strings keep equality only
+        assert_eq!(
+            LiteralResolver::eval_compare3(&s("a"), Lt, &s("b")),
+            Unknown
+        );
+        assert_eq!(
+            LiteralResolver::eval_compare3(&s("2024-01-01"), Lt, &s("later")),
+            Unknown
+        );
+    }
+
     // --- eval_compare3: boundary with tolerance on <= and >= -----------------
 
     #[test]
@@ -308,6 +425,83 @@ mod tests {
 
     // --- eval_like ------------------------------------------------------------
 
+    #[test]
+    fn arithmetic_promotes_and_yields_null_on_division_by_zero() {
+        let i = Literal::Int;
+
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&i(7), ArithmeticOp::Div, &i(2)),
+            i(3)
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&i(7), ArithmeticOp::Mod, &i(4)),
+            i(3)
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&i(3), ArithmeticOp::Mul, &lf(1.5)),
+            lf(4.5)
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&lf(1.0), ArithmeticOp::Sub, &i(3)),
+            lf(-2.0)
+        );
+        for op in [ArithmeticOp::Div, ArithmeticOp::Mod] {
+            assert_eq!(
+                LiteralResolver::eval_arithmetic(&i(1), op, &i(0)),
+                Literal::Null
+            );
+            assert_eq!(
+                LiteralResolver::eval_arithmetic(&lf(1.0), op, &lf(0.0)),
+                Literal::Null
+            );
+        }
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&i(i64::MAX), ArithmeticOp::Add, &i(1)),
+            Literal::Null
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(&Literal::Null, ArithmeticOp::Add, &i(1)),
+            Literal::Null
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(
+                &Literal::String("a".into()),
+                ArithmeticOp::Add,
+                &i(1)
+            ),
+            Literal::Null
+        );
+    }
+
+    #[test]
+    fn concat_and_negate() {
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(
+                &Literal::String("n".into()),
+                ArithmeticOp::Concat,
+                &Literal::Int(1)
+            ),
+            Literal::String("n1".into())
+        );
+        assert_eq!(
+            LiteralResolver::eval_arithmetic(
+                &Literal::String("n".into()),
+                ArithmeticOp::Concat,
+                &Literal::Null
+            ),
+            Literal::Null
+        );
+        assert_eq!(
+            LiteralResolver::eval_negate(&Literal::Int(2)),
+            Literal::Int(-2)
+        );
+        assert_eq!(LiteralResolver::eval_negate(&lf(-1.5)), lf(1.5));
+        assert_eq!(
+            LiteralResolver::eval_negate(&Literal::Bool(true)),
+            Literal::Null
+        );
+    }
+
     #[test]
     fn like_case_insensitive_and_simple_wildcards() {
         use Truth::*;
@@ -10,6 +10,15 @@ pub use wildcard_resolver::*;
 pub mod literal_resolver;
 pub use literal_resolver::*;
 
+pub mod temporal_resolver;
+pub use temporal_resolver::*;
+
+pub mod cast_resolver;
+pub use cast_resolver::*;
+
+pub mod window_resolver;
+pub use window_resolver::*;
+
 pub mod scalar_resolver;
 pub use scalar_resolver::*;
 
@@ -24,3 +33,30 @@ pub use column_key::*;
 
 pub mod order_by_resolver;
 pub use order_by_resolver::*;
+
+pub mod insert_resolver;
+pub use insert_resolver::*;
+
+pub mod assignment_resolver;
+pub use assignment_resolver::*;
+
+pub mod update_resolver;
+pub use update_resolver::*;
+
+pub mod delete_resolver;
+pub use delete_resolver::*;
+
+pub mod returning_resolver;
+pub use returning_resolver::*;
+
+pub mod collection_ddl_resolver;
+pub use collection_ddl_resolver::*;
+
+pub mod set_operation_resolver;
+pub use set_operation_resolver::*;
+
+pub mod cte_resolver;
+pub use cte_resolver::*;
+
+pub mod subquery_resolver;
+pub use subquery_resolver::*;
@@ -92,7 +92,11 @@ impl OrderByResolver {
             }
 
             // Already-qualified column: keep it.
-            ScalarExpr::Column(Column::WithCollection { .. }) => Ok(e.clone()),
+            ScalarExpr::Column(Column::WithCollection { collection, .. })
+                if ctx.collections.contains_key(collection) =>
+            {
+                Ok(e.clone())
+            }
 
             // Recurse into functions so aliases inside functions are handled.
             ScalarExpr::Function(Function {
@@ -114,8 +118,26 @@ impl OrderByResolver {
             // Literals/parameters pass through; they were already expanded/folded.
             ScalarExpr::Literal(_) | ScalarExpr::Args(_) => Ok(e.clone()),
 
+            // `x.key` may read into a column; JSON paths are qualified like columns
+            ScalarExpr::Column(_)
+            | ScalarExpr::JsonPath { .. }
+            | ScalarExpr::Subquery(_)
+            | ScalarExpr::Case(_)
+            | ScalarExpr::Window(_) => ScalarResolver::qualify_scalar(e, ctx, false),
+
+            ScalarExpr::Binary { left, op, right } => Ok(ScalarExpr::Binary {
+                left: Box::new(Self::rewrite_ob_expr_non_agg(left, alias_to_expr, ctx)?),
+                op: *op,
+                right: Box::new(Self::rewrite_ob_expr_non_agg(right, alias_to_expr, ctx)?),
+            }),
+
+            ScalarExpr::Negate(expr) => Ok(ScalarExpr::Negate(Box::new(
+                Self::rewrite_ob_expr_non_agg(expr, alias_to_expr, ctx)?,
+            ))),
+
             // Wildcards/Parameter should not appear here in ORDER BY after analysis; keep safe.
             ScalarExpr::Parameter
+            | ScalarExpr::NamedParameter(_)
             | ScalarExpr::WildCard
             | ScalarExpr::WildCardWithCollection(_) => {
                 Err(AnalyzerError::Other("unexpected token in ORDER BY".into()))
@@ -1,5 +1,8 @@
 use crate::parser::{
-    analyzer::{AnalysisContext, AnalyzerError, LiteralResolver, ScalarResolver},
+    analyzer::{
+        AggregateResolver, AnalysisContext, AnalyzerError, LiteralResolver, ScalarResolver,
+        SubqueryResolver,
+    },
     ast::{Literal, Predicate, ScalarExpr, Truth},
 };
 
@@ -48,6 +51,10 @@ impl PredicateResolver {
                     Predicate::Or(out)
                 }
             }
+            Predicate::Not(p) => match Self::fold_predicate(p) {
+                Predicate::Const3(t) => Predicate::Const3(t.not()),
+                other => Predicate::Not(Box::new(other)),
+            },
 
             Predicate::Compare { left, op, right } => {
                 let l = ScalarResolver::fold_scalar(left);
@@ -117,7 +124,7 @@ impl PredicateResolver {
 
                 if let (Some(elit), Some(set)) = (el, lits) {
                     // SQL IN/NOT IN with NULLs:
-                    // If any element is NULL and no match found => Unknown
+                    // If the value or any element is NULL and no match found => Unknown
                     let mut has_null = false;
                     let mut found = false;
                     for v in &set {
@@ -130,7 +137,7 @@ impl PredicateResolver {
                     }
                     let t = if found {
                         Truth::True
-                    } else if has_null {
+                    } else if has_null || (matches!(elit, Literal::Null) && !set.is_empty()) {
                         Truth::Unknown
                     } else {
                         Truth::False
@@ -173,6 +180,16 @@ impl PredicateResolver {
                 }
             }
 
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Predicate::InSubquery {
+                expr: ScalarResolver::fold_scalar(expr),
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            Predicate::Exists { .. } => pred.clone(),
             Predicate::Const3(t) => Predicate::Const3(*t),
         }
     }
@@ -192,6 +209,7 @@ impl PredicateResolver {
                     .map(|x| Self::qualify_predicate(x, ctx))
                     .collect::<Result<Vec<_>, _>>()?,
             ),
+            Predicate::Not(p) => Predicate::Not(Box::new(Self::qualify_predicate(p, ctx)?)),
             Predicate::Compare { left, op, right } => Predicate::Compare {
                 left: ScalarResolver::qualify_scalar(left, ctx, false)?,
                 op: *op,
@@ -222,9 +240,36 @@ impl PredicateResolver {
                 pattern: ScalarResolver::qualify_scalar(pattern, ctx, false)?,
                 negated: *negated,
             },
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Predicate::InSubquery {
+                expr: ScalarResolver::qualify_scalar(expr, ctx, false)?,
+                subquery: SubqueryResolver::analyze_single_column(subquery, ctx)?,
+                negated: *negated,
+            },
+            Predicate::Exists { subquery, negated } => Predicate::Exists {
+                subquery: SubqueryResolver::analyze_subquery(subquery, ctx)?,
+                negated: *negated,
+            },
             Predicate::Const3(value) => Predicate::Const3(*value),
         })
     }
+
+    /// Qualify and fold the WHERE predicate of an UPDATE or DELETE.
+    pub fn resolve_write_criteria(
+        predicate: &Predicate,
+        ctx: &mut AnalysisContext,
+    ) -> Result<Predicate, AnalyzerError> {
+        let qualified = Self::qualify_predicate(predicate, ctx)?;
+        if AggregateResolver::predicate_contains_aggregate(&qualified) {
+            return Err(AnalyzerError::Other(
+                "Aggregates are not allowed in WHERE".into(),
+            ));
+        }
+        Ok(Self::fold_predicate(&qualified))
+    }
 }
 
 #[cfg(test)]
@@ -973,6 +1018,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fold_not_negates_constants_and_keeps_unknown() {
+        let not = |p: Predicate| Predicate::Not(Box::new(p));
+        assert_eq!(
+            PredicateResolver::fold_predicate(&not(Predicate::Const3(Truth::False))),
+            Predicate::Const3(Truth::True)
+        );
+        assert_eq!(
+            PredicateResolver::fold_predicate(&not(Predicate::Const3(Truth::Unknown))),
+            Predicate::Const3(Truth::Unknown)
+        );
+
+        let compare = Predicate::Compare {
+            left: ScalarExpr::Column(Column::Name { name: "x".into() }),
+            op: ComparatorOp::Eq,
+            right: lit_i(1),
+        };
+        assert_eq!(
+            PredicateResolver::fold_predicate(&not(compare.clone())),
+            not(compare)
+        );
+    }
+
     #[test]
     fn fold_in_list_without_match_or_null_is_false_and_not_in_is_true() {
         let predicate = Predicate::InList {
@@ -0,0 +1,141 @@
+use crate::{
+    JsonPrimitive, SchemaDict,
+    database::FieldInfo,
+    parser::{
+        analyzer::{AggregateResolver, AnalysisContext, AnalyzedIdentifier, AnalyzerError},
+        ast::Identifier,
+    },
+};
+
+pub struct ReturningResolver;
+
+impl ReturningResolver {
+    /// Resolve a RETURNING list against the written collection.
+    /// `written` lists the columns the statement stores, so fields the collection
+    /// schema has not seen yet can still be returned.
+    pub fn analyze_returning(
+        collection: &str,
+        schema: &SchemaDict,
+        written: &[(String, JsonPrimitive)],
+        returning: &[Identifier],
+        ctx: &mut AnalysisContext,
+    ) -> Result<Vec<AnalyzedIdentifier>, AnalyzerError> {
+        if returning.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut schema = schema.clone();
+        for (column, ty) in written {
+            schema.fields.entry(column.clone()).or_insert(FieldInfo {
+                ty: *ty,
+                nullable: true,
+            });
+        }
+        ctx.add_subquery(collection, schema);
+
+        let analyzed = ctx.analyze_projection(returning)?;
+        if analyzed
+            .iter()
+            .any(|id| AggregateResolver::contains_aggregate(&id.expression))
+        {
+            return Err(AnalyzerError::Other(
+                "Aggregates are not allowed in RETURNING".into(),
+            ));
+        }
+
+        Ok(analyzed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            QueryParser,
+            aggregators_helper::AggregateRegistry,
+            analyzer::{AnalysisContext, AnalyzerError, ReturningResolver},
+            ast::{Column, ReturningParser, ScalarExpr},
+        },
+    };
+
+    use indexmap::IndexMap;
+
+    // the written collection is registered by the resolver itself
+    struct NoSchemas;
+    impl SchemaProvider for NoSchemas {
+        fn schema_of(&self, _backing_collection: &str) -> Option<SchemaDict> {
+            None
+        }
+    }
+
+    fn people() -> SchemaDict {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "id".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::Int,
+                nullable: false,
+            },
+        );
+        fields.insert(
+            "name".to_string(),
+            FieldInfo {
+                ty: JsonPrimitive::String,
+                nullable: false,
+            },
+        );
+        SchemaDict { fields }
+    }
+
+    fn analyze(
+        returning: &str,
+        written: &[(String, JsonPrimitive)],
+    ) -> Result<Vec<crate::parser::analyzer::AnalyzedIdentifier>, AnalyzerError> {
+        let schemas = NoSchemas;
+        let registry = AggregateRegistry::default_aggregate_registry();
+        let mut ctx = AnalysisContext::new_with_aggregates(&schemas, &registry);
+        let returning = ReturningParser::parse(&mut QueryParser::new(returning)).unwrap();
+        ReturningResolver::analyze_returning("people", &people(), written, &returning, &mut ctx)
+    }
+
+    #[test]
+    fn wildcard_expands_to_schema_and_written_columns() {
+        let analyzed = analyze(
+            "RETURNING *",
+            &[
+                ("name".to_string(), JsonPrimitive::String),
+                ("nick".to_string(), JsonPrimitive::String),
+            ],
+        )
+        .unwrap();
+
+        let names = analyzed
+            .iter()
+            .map(|id| id.output_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "name", "nick"]);
+        assert_eq!(
+            analyzed[0].expression,
+            ScalarExpr::Column(Column::WithCollection {
+                collection: "people".into(),
+                name: "id".into(),
+            })
+        );
+        assert!(analyzed[2].nullable);
+    }
+
+    #[test]
+    fn aliases_name_outputs_and_aggregates_are_rejected() {
+        let analyzed = analyze("RETURNING upper(name) AS shout", &[]).unwrap();
+        assert_eq!(analyzed[0].output_name, "shout");
+        assert_eq!(analyzed[0].ty, JsonPrimitive::String);
+
+        let err = analyze("RETURNING missing", &[]);
+        assert!(matches!(err, Err(AnalyzerError::UnknownColumn { .. })));
+
+        let err = analyze("RETURNING MAX(id)", &[]);
+        assert!(matches!(err, Err(AnalyzerError::Other(message)) if message.contains("RETURNING")));
+    }
+}
@@ -2,8 +2,11 @@ use ordered_float::NotNan;
 use serde_json::Value;
 
 use crate::parser::{
-    analyzer::{AnalysisContext, AnalyzerError, ColumnResolver},
-    ast::{Function, Literal, ScalarExpr},
+    analyzer::{
+        AggregateResolver, AnalysisContext, AnalyzerError, ColumnResolver, LiteralResolver,
+        PredicateResolver, SubqueryResolver, WindowResolver,
+    },
+    ast::{Case, Column, Function, Literal, PathSegment, Predicate, ScalarExpr, Truth},
 };
 
 pub struct ScalarResolver;
@@ -74,19 +77,68 @@ impl ScalarResolver {
                     })
                 })
             }
+            ScalarExpr::Case(case) => Self::fold_case(case),
+            ScalarExpr::Window(window) => ScalarExpr::Window(WindowResolver::fold_window(window)),
+            ScalarExpr::Binary { left, op, right } => {
+                let left = Self::fold_scalar(left);
+                let right = Self::fold_scalar(right);
+                match (&left, &right) {
+                    (ScalarExpr::Literal(l), ScalarExpr::Literal(r)) => {
+                        ScalarExpr::Literal(LiteralResolver::eval_arithmetic(l, *op, r))
+                    }
+                    _ => ScalarExpr::Binary {
+                        left: Box::new(left),
+                        op: *op,
+                        right: Box::new(right),
+                    },
+                }
+            }
+            ScalarExpr::Negate(expr) => match Self::fold_scalar(expr) {
+                ScalarExpr::Literal(value) => {
+                    ScalarExpr::Literal(LiteralResolver::eval_negate(&value))
+                }
+                expr => ScalarExpr::Negate(Box::new(expr)),
+            },
             _ => expr.clone(),
         }
     }
 
+    /// Drop branches whose condition folds to FALSE or NULL, and resolve the CASE
+    /// when the first remaining condition is constant TRUE.
+    fn fold_case(case: &Case) -> ScalarExpr {
+        let mut branches = Vec::with_capacity(case.branches.len());
+        for (condition, result) in &case.branches {
+            let result = Self::fold_scalar(result);
+            match PredicateResolver::fold_predicate(condition) {
+                Predicate::Const3(Truth::True) if branches.is_empty() => return result,
+                Predicate::Const3(Truth::False | Truth::Unknown) => {}
+                condition => branches.push((condition, result)),
+            }
+        }
+
+        let otherwise = case
+            .otherwise
+            .as_deref()
+            .map(Self::fold_scalar)
+            .unwrap_or(ScalarExpr::Literal(Literal::Null));
+        if branches.is_empty() {
+            return otherwise;
+        }
+        ScalarExpr::Case(Case {
+            branches,
+            otherwise: case.otherwise.as_ref().map(|_| Box::new(otherwise)),
+        })
+    }
+
     pub fn qualify_scalar(
         expr: &ScalarExpr,
         ctx: &mut AnalysisContext,
         allow_args: bool,
     ) -> Result<ScalarExpr, AnalyzerError> {
         match expr {
-            ScalarExpr::Column(c) => Ok(ScalarExpr::Column(
-                ColumnResolver::qualify_column(c, ctx)?.0,
-            )),
+            ScalarExpr::Column(c) => Self::qualify_column_path(c, &[], ctx),
+
+            ScalarExpr::JsonPath { column, path } => Self::qualify_column_path(column, path, ctx),
 
             ScalarExpr::Function(Function {
                 name,
@@ -105,18 +157,61 @@ impl ScalarResolver {
                 }
 
                 // Otherwise, qualify all args normally (wildcards are illegal outside COUNT)
-                let mut new_args = Vec::with_capacity(args.len());
-                for arg in args {
-                    new_args.push(Self::qualify_scalar(arg, ctx, true)?);
-                }
+                // an aggregate runs before any window, so its args cannot hold one
+                let windows_allowed = ctx.windows_allowed;
+                ctx.windows_allowed &= !AggregateResolver::is_aggregate_name(&lname);
+                let new_args = args
+                    .iter()
+                    .map(|arg| Self::qualify_scalar(arg, ctx, true))
+                    .collect::<Result<Vec<_>, _>>();
+                ctx.windows_allowed = windows_allowed;
                 Ok(ScalarExpr::Function(Function {
                     name: name.clone(),
-                    args: new_args,
+                    args: new_args?,
                     distinct: *distinct,
                 }))
             }
 
             ScalarExpr::Parameter => Self::qualify_parameter(ctx, allow_args),
+            ScalarExpr::NamedParameter(name) => {
+                Self::qualify_named_parameter(name, ctx, allow_args)
+            }
+
+            ScalarExpr::Subquery(subquery) => Ok(ScalarExpr::Subquery(
+                SubqueryResolver::analyze_single_column(subquery, ctx)?,
+            )),
+
+            ScalarExpr::Case(case) => {
+                let mut branches = Vec::with_capacity(case.branches.len());
+                for (condition, result) in &case.branches {
+                    branches.push((
+                        PredicateResolver::qualify_predicate(condition, ctx)?,
+                        Self::qualify_scalar(result, ctx, false)?,
+                    ));
+                }
+                let otherwise = match &case.otherwise {
+                    Some(otherwise) => Some(Box::new(Self::qualify_scalar(otherwise, ctx, false)?)),
+                    None => None,
+                };
+                Ok(ScalarExpr::Case(Case {
+                    branches,
+                    otherwise,
+                }))
+            }
+
+            ScalarExpr::Window(window) => Ok(ScalarExpr::Window(WindowResolver::qualify_window(
+                window, ctx,
+            )?)),
+
+            ScalarExpr::Binary { left, op, right } => Ok(ScalarExpr::Binary {
+                left: Box::new(Self::qualify_scalar(left, ctx, false)?),
+                op: *op,
+                right: Box::new(Self::qualify_scalar(right, ctx, false)?),
+            }),
+
+            ScalarExpr::Negate(expr) => Ok(ScalarExpr::Negate(Box::new(Self::qualify_scalar(
+                expr, ctx, false,
+            )?))),
 
             // Wildcards should already have been expanded — except COUNT(*), handled above.
             ScalarExpr::WildCard | ScalarExpr::WildCardWithCollection(_) => Err(
@@ -127,6 +222,18 @@ impl ScalarResolver {
         }
     }
 
+    fn qualify_column_path(
+        column: &Column,
+        path: &[PathSegment],
+        ctx: &AnalysisContext,
+    ) -> Result<ScalarExpr, AnalyzerError> {
+        let (column, path, _) = ColumnResolver::qualify_column_path(column, path, ctx)?;
+        Ok(match path.is_empty() {
+            true => ScalarExpr::Column(column),
+            false => ScalarExpr::JsonPath { column, path },
+        })
+    }
+
     fn qualify_parameter(
         ctx: &mut AnalysisContext,
         allow_args: bool,
@@ -157,6 +264,74 @@ impl ScalarResolver {
         }
     }
 
+    /// `:name` reads a key of the JSON object, `$1` the first element of the
+    /// JSON array (or the single value); unlike `?`, a name can repeat.
+    fn qualify_named_parameter(
+        name: &str,
+        ctx: &mut AnalysisContext,
+        allow_args: bool,
+    ) -> Result<ScalarExpr, AnalyzerError> {
+        let value = Self::named_parameter_value(name, &ctx.parameters).ok_or_else(|| {
+            AnalyzerError::ParameterMismatch {
+                missing: vec![name.to_string()],
+                unused: vec![],
+            }
+        })?;
+        Self::expand_parameter_value(value, allow_args).ok_or(AnalyzerError::InvalidParameterValue)
+    }
+
+    fn named_parameter_value<'v>(name: &str, parameters: &'v Value) -> Option<&'v Value> {
+        match name.split_at(1) {
+            (":", key) => parameters.as_object()?.get(key),
+            (_, index) => {
+                let index = index.parse::<usize>().ok()?.checked_sub(1)?;
+                match parameters {
+                    Value::Array(values) => values.get(index),
+                    Value::Null | Value::Object(_) => None,
+                    single => (index == 0).then_some(single),
+                }
+            }
+        }
+    }
+
+    /// Checks the named and numbered parameters a statement uses against the
+    /// bound values, listing every missing name and every value nothing uses.
+    /// Statements with only `?` placeholders are left to positional binding.
+    pub fn check_named_parameters(
+        names: &[String],
+        parameters: &Value,
+    ) -> Result<(), AnalyzerError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let missing: Vec<String> = names
+            .iter()
+            .filter(|name| Self::named_parameter_value(name, parameters).is_none())
+            .cloned()
+            .collect();
+        let bound: Vec<String> = match parameters {
+            Value::Object(map) => map.keys().map(|key| format!(":{}", key)).collect(),
+            Value::Array(values) => (1..=values.len()).map(|i| format!("${}", i)).collect(),
+            Value::Null => vec![],
+            _ => vec!["$1".to_string()],
+        };
+        let unused: Vec<String> = bound
+            .into_iter()
+            .filter(|name| !names.contains(name))
+            .collect();
+
+        match missing.is_empty() && unused.is_empty() {
+            true => Ok(()),
+            false => Err(AnalyzerError::ParameterMismatch { missing, unused }),
+        }
+    }
+
+    /// Literal for a scalar JSON value; arrays and objects have none.
+    pub fn value_to_scalar(json_value: &Value) -> Option<ScalarExpr> {
+        Self::expand_parameter_value(json_value, false)
+    }
+
     fn expand_parameter_value(json_value: &Value, allow_args: bool) -> Option<ScalarExpr> {
         match json_value {
             Value::Null => Some(ScalarExpr::Literal(Literal::Null)),
@@ -308,6 +483,64 @@ mod tests {
         assert_eq!(folded, expr);
     }
 
+    #[test]
+    fn fold_scalar_folds_case_with_constant_conditions() {
+        let parse = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            ScalarExpr::parse(&mut parser, false).unwrap()
+        };
+
+        // first branch is false, second is always true
+        let folded =
+            ScalarResolver::fold_scalar(&parse("CASE WHEN 1 = 2 THEN 'a' WHEN 2 = 2 THEN 'b' END"));
+        assert_eq!(folded, ScalarExpr::Literal(Literal::String("b".into())));
+
+        // no branch can match and ELSE is missing
+        let folded = ScalarResolver::fold_scalar(&parse("CASE WHEN NULL = 1 THEN 'a' END"));
+        assert_eq!(folded, ScalarExpr::Literal(Literal::Null));
+
+        // a column condition keeps the branch, dropping only the dead ones
+        let folded = ScalarResolver::fold_scalar(&parse(
+            "CASE WHEN 1 = 2 THEN 'a' WHEN x > 1 THEN 'b' ELSE 'c' END",
+        ));
+        match folded {
+            ScalarExpr::Case(case) => {
+                assert_eq!(case.branches.len(), 1);
+                assert_eq!(
+                    case.otherwise.as_deref(),
+                    Some(&ScalarExpr::Literal(Literal::String("c".into())))
+                );
+            }
+            other => panic!("expected a case, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fold_scalar_folds_constant_arithmetic() {
+        let parse = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            ScalarExpr::parse(&mut parser, false).unwrap()
+        };
+
+        assert_eq!(
+            ScalarResolver::fold_scalar(&parse("-(1 + 2) * 4")),
+            ScalarExpr::Literal(Literal::Int(-12))
+        );
+        assert_eq!(
+            ScalarResolver::fold_scalar(&parse("1 / 0")),
+            ScalarExpr::Literal(Literal::Null)
+        );
+        assert_eq!(
+            ScalarResolver::fold_scalar(&parse("'v' || 1 + 1")),
+            ScalarExpr::Literal(Literal::String("v2".into()))
+        );
+        // only the constant operand folds
+        assert_eq!(
+            ScalarResolver::fold_scalar(&parse("x + 2 * 3")).to_string(),
+            "(col: x + lit: i: 6)"
+        );
+    }
+
     #[test]
     fn qualify_scalar_allows_count_star_and_keeps_wildcard() {
         let sp = DummySchemas::new().with("t", vec![("id", JsonPrimitive::Int, false)]);
@@ -384,6 +617,85 @@ mod tests {
         }
     }
 
+    #[test]
+    fn qualify_scalar_named_and_numbered_parameters() {
+        let sp = DummySchemas::new().with("t", vec![("id", JsonPrimitive::Int, false)]);
+        let mut ctx = ctx_for_single_table(&sp, "t", None);
+        let named = |name: &str| ScalarExpr::NamedParameter(name.to_string());
+
+        ctx.parameters = json!({ "id": 7, "ids": [1, 2] });
+        for _ in 0..2 {
+            assert_eq!(
+                ScalarResolver::qualify_scalar(&named(":id"), &mut ctx, false).unwrap(),
+                ScalarExpr::Literal(Literal::Int(7))
+            );
+        }
+        assert!(matches!(
+            ScalarResolver::qualify_scalar(&named(":ids"), &mut ctx, true).unwrap(),
+            ScalarExpr::Args(args) if args.len() == 2
+        ));
+        assert_eq!(
+            ScalarResolver::qualify_scalar(&named(":name"), &mut ctx, false),
+            Err(AnalyzerError::ParameterMismatch {
+                missing: vec![":name".into()],
+                unused: vec![]
+            })
+        );
+
+        ctx.parameters = json!(["a", "b"]);
+        assert_eq!(
+            ScalarResolver::qualify_scalar(&named("$2"), &mut ctx, false).unwrap(),
+            ScalarExpr::Literal(Literal::String("b".into()))
+        );
+        ctx.parameters = json!("only");
+        assert_eq!(
+            ScalarResolver::qualify_scalar(&named("$1"), &mut ctx, false).unwrap(),
+            ScalarExpr::Literal(Literal::String("only".into()))
+        );
+    }
+
+    #[test]
+    fn check_named_parameters_lists_missing_and_unused_names() {
+        let names = |list: &[&str]| list.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            ScalarResolver::check_named_parameters(
+                &names(&[":a", ":b"]),
+                &json!({ "a": 1, "b": 2 })
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            ScalarResolver::check_named_parameters(
+                &names(&[":a", ":b", ":c"]),
+                &json!({ "a": 1, "d": 2, "e": 3 })
+            ),
+            Err(AnalyzerError::ParameterMismatch {
+                missing: names(&[":b", ":c"]),
+                unused: names(&[":d", ":e"]),
+            })
+        );
+        assert_eq!(
+            ScalarResolver::check_named_parameters(&names(&["$1", "$3"]), &json!([1, 2, 3, 4])),
+            Err(AnalyzerError::ParameterMismatch {
+                missing: vec![],
+                unused: names(&["$2", "$4"]),
+            })
+        );
+        assert_eq!(
+            ScalarResolver::check_named_parameters(&names(&[":a"]), &json!([1])),
+            Err(AnalyzerError::ParameterMismatch {
+                missing: names(&[":a"]),
+                unused: names(&["$1"]),
+            })
+        );
+        // positional placeholders are not checked here
+        assert_eq!(
+            ScalarResolver::check_named_parameters(&[], &json!([1, 2])),
+            Ok(())
+        );
+    }
+
     #[test]
     fn qualify_scalar_parameter_one() {
         let sp = DummySchemas::new().with("t", vec![("id", JsonPrimitive::Int, false)]);
@@ -0,0 +1,264 @@
+use serde_json::Value;
+
+use crate::{
+    JsonPrimitive,
+    database::SchemaProvider,
+    parser::{
+        aggregators_helper::AggregateRegistry,
+        analyzer::{AnalysisContext, AnalyzedIdentifier, AnalyzedQuery, AnalyzerError, QueryScope},
+        ast::{Column, Literal, OrderBy, Query, ScalarExpr, SetOperator},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct AnalyzedSetOperation {
+    pub op: SetOperator,
+    pub all: bool,
+    pub query: AnalyzedQuery, // output names aligned with the first block
+}
+
+pub struct SetOperationResolver;
+
+impl SetOperationResolver {
+    /// Analyze `block (op block)* [ORDER BY] [LIMIT]`. The first block carries the
+    /// compound output columns; ORDER BY/LIMIT apply to the combined rows. `?` placeholders
+    /// are numbered across the blocks, in the order they are written.
+    pub fn analyze_compound<'a>(
+        query: &Query,
+        schema_provider: &'a dyn SchemaProvider,
+        aggregates: &'a AggregateRegistry,
+        parameters: Value,
+        current_param: &mut usize,
+        scope: &QueryScope,
+    ) -> Result<AnalyzedQuery, AnalyzerError> {
+        let first = Query {
+            set_operations: vec![],
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            ..query.clone()
+        };
+        let mut analyzed = AnalysisContext::analyze_query_in_scope(
+            &first,
+            schema_provider,
+            aggregates,
+            parameters.clone(),
+            current_param,
+            scope,
+        )?;
+
+        let mut set_operations = Vec::with_capacity(query.set_operations.len());
+        for operation in &query.set_operations {
+            let mut right = AnalysisContext::analyze_query_in_scope(
+                &operation.query,
+                schema_provider,
+                aggregates,
+                parameters.clone(),
+                current_param,
+                scope,
+            )?;
+            Self::unify_columns(
+                operation.op,
+                &mut analyzed.projection,
+                &mut right.projection,
+            )?;
+            set_operations.push(AnalyzedSetOperation {
+                op: operation.op,
+                all: operation.all,
+                query: right,
+            });
+        }
+
+        analyzed.order_by = Self::resolve_order_by(&query.order_by, &analyzed.projection)?;
+        analyzed.limit = query.limit;
+        analyzed.offset = query.offset;
+        analyzed.set_operations = set_operations;
+        Ok(analyzed)
+    }
+
+    /// Check column count and types, widen the compound output types and rename the
+    /// right block's outputs so rows from both sides share the same keys.
+    pub(crate) fn unify_columns(
+        op: SetOperator,
+        left: &mut [AnalyzedIdentifier],
+        right: &mut [AnalyzedIdentifier],
+    ) -> Result<(), AnalyzerError> {
+        if left.len() != right.len() {
+            return Err(AnalyzerError::Other(format!(
+                "each {} query must have the same number of columns ({} vs {})",
+                op,
+                left.len(),
+                right.len()
+            )));
+        }
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            if !Self::compatible(l.ty, r.ty) {
+                return Err(AnalyzerError::Other(format!(
+                    "{} types {:?} and {:?} cannot be matched for column {}",
+                    op, l.ty, r.ty, l.output_name
+                )));
+            }
+            l.ty = JsonPrimitive::promote(l.ty, r.ty);
+            l.nullable |= r.nullable;
+            r.output_name = l.output_name.clone();
+        }
+        Ok(())
+    }
+
+    /// Can values of types `a` and `b` share one output column? Numbers widen to
+    /// Float, and ISO strings stand for dates.
+    pub(crate) fn compatible(a: JsonPrimitive, b: JsonPrimitive) -> bool {
+        use JsonPrimitive::*;
+        a == b
+            || matches!((a, b), (Null | Any, _) | (_, Null | Any))
+            || matches!((a, b), (Int, Float) | (Float, Int))
+            || matches!(
+                (a, b),
+                (Date | DateTime | String, Date | DateTime) | (Date | DateTime, String)
+            )
+    }
+
+    /// ORDER BY over a compound result can only name output columns (by name or position).
+    fn resolve_order_by(
+        order_by: &[OrderBy],
+        projection: &[AnalyzedIdentifier],
+    ) -> Result<Vec<OrderBy>, AnalyzerError> {
+        let mut out = Vec::with_capacity(order_by.len());
+        for ob in order_by {
+            let output = match &ob.expr {
+                ScalarExpr::Literal(Literal::Int(pos)) => {
+                    let pos = *pos as usize;
+                    if pos == 0 || pos > projection.len() {
+                        return Err(AnalyzerError::Other(format!(
+                            "ORDER BY position {} out of range [1..{}]",
+                            pos,
+                            projection.len()
+                        )));
+                    }
+                    Some(&projection[pos - 1])
+                }
+                ScalarExpr::Column(Column::Name { name }) => projection
+                    .iter()
+                    .find(|id| id.output_name.eq_ignore_ascii_case(name)),
+                _ => None,
+            };
+            let Some(output) = output else {
+                return Err(AnalyzerError::Other(
+                    "ORDER BY on a UNION, INTERSECT or EXCEPT result must use output column names or positions"
+                        .into(),
+                ));
+            };
+            out.push(OrderBy {
+                expr: ScalarExpr::Column(Column::Name {
+                    name: output.output_name.clone(),
+                }),
+                ascending: ob.ascending,
+            });
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            aggregators_helper::AggregateRegistry,
+            analyzer::{AnalysisContext, AnalyzedQuery, AnalyzerError},
+            ast::{Column, Query, ScalarExpr, SetOperator},
+        },
+    };
+
+    use indexmap::IndexMap;
+    use serde_json::Value;
+
+    struct Schemas;
+    impl SchemaProvider for Schemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            let fields: &[(&str, JsonPrimitive, bool)] = match backing_collection {
+                "orders" => &[
+                    ("id", JsonPrimitive::Int, false),
+                    ("total", JsonPrimitive::Int, false),
+                    ("note", JsonPrimitive::String, true),
+                ],
+                "payments" => &[
+                    ("order_id", JsonPrimitive::Int, false),
+                    ("amount", JsonPrimitive::Float, true),
+                ],
+                _ => return None,
+            };
+            let mut map = IndexMap::new();
+            for (name, ty, nullable) in fields {
+                map.insert(
+                    name.to_string(),
+                    FieldInfo {
+                        ty: *ty,
+                        nullable: *nullable,
+                    },
+                );
+            }
+            Some(SchemaDict { fields: map })
+        }
+    }
+
+    fn analyze(text: &str) -> Result<AnalyzedQuery, AnalyzerError> {
+        let query = Query::try_from(text).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        AnalysisContext::analyze_query(&query, &Schemas, &registry, Value::Null)
+    }
+
+    #[test]
+    fn compound_widens_types_and_aligns_output_names() {
+        let analyzed = analyze(
+            "SELECT id, total FROM orders UNION ALL SELECT order_id, amount FROM payments ORDER BY total DESC, 1",
+        )
+        .unwrap();
+
+        assert_eq!(analyzed.projection[1].ty, JsonPrimitive::Float);
+        assert!(analyzed.projection[1].nullable);
+        assert_eq!(analyzed.set_operations.len(), 1);
+        assert_eq!(analyzed.set_operations[0].op, SetOperator::Union);
+        assert!(analyzed.set_operations[0].all);
+        let right_names = analyzed.set_operations[0]
+            .query
+            .projection
+            .iter()
+            .map(|id| id.output_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(right_names, vec!["id", "total"]);
+        assert_eq!(
+            analyzed.order_by[1].expr,
+            ScalarExpr::Column(Column::Name { name: "id".into() })
+        );
+    }
+
+    #[test]
+    fn column_count_and_types_must_match() {
+        let err = analyze("SELECT id FROM orders EXCEPT SELECT order_id, amount FROM payments");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("same number of columns"))
+        );
+
+        let err = analyze("SELECT note FROM orders INTERSECT SELECT amount FROM payments");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("cannot be matched"))
+        );
+    }
+
+    #[test]
+    fn order_by_must_use_output_columns() {
+        let err =
+            analyze("SELECT id FROM orders UNION SELECT order_id FROM payments ORDER BY total");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("output column"))
+        );
+
+        let err = analyze("SELECT id FROM orders UNION SELECT order_id FROM payments ORDER BY 2");
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("out of range"))
+        );
+    }
+}
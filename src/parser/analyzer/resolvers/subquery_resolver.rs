@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::parser::{
+    analyzer::{AnalysisContext, AnalyzerError},
+    ast::Subquery,
+};
+
+pub struct SubqueryResolver;
+
+impl SubqueryResolver {
+    /// Analyze a subquery nested in an expression. Columns it cannot resolve on its
+    /// own are looked up in the collections visible to `ctx` (correlated references).
+    /// Its `?` placeholders take the arguments following those already bound in `ctx`.
+    pub fn analyze_subquery(
+        subquery: &Subquery,
+        ctx: &mut AnalysisContext,
+    ) -> Result<Subquery, AnalyzerError> {
+        let scope = ctx.nested_scope();
+        let analyzed = ctx.analyze_nested_query(&subquery.query, &scope)?;
+        Ok(Subquery {
+            query: subquery.query.clone(),
+            analyzed: Some(Arc::new(analyzed)),
+        })
+    }
+
+    /// Analyze a subquery used as a value or as an `IN` list, which must return one column.
+    pub fn analyze_single_column(
+        subquery: &Subquery,
+        ctx: &mut AnalysisContext,
+    ) -> Result<Subquery, AnalyzerError> {
+        let analyzed = Self::analyze_subquery(subquery, ctx)?;
+        let columns = analyzed.analyzed.as_ref().map_or(0, |a| a.projection.len());
+        if columns != 1 {
+            return Err(AnalyzerError::Other(format!(
+                "subquery must return only one column, but returns {}",
+                columns
+            )));
+        }
+        Ok(analyzed)
+    }
+}
@@ -0,0 +1,418 @@
+use std::{cmp::Ordering, fmt::Write};
+
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+};
+
+use crate::parser::ast::{ComparatorOp, Truth};
+
+/// A date or a UTC date and time read from an ISO-8601 string.
+///
+/// Temporal values are stored as JSON strings; this is their parsed form while
+/// a comparison or a date function works on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temporal {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+/// Units understood by `date_trunc`, `extract`, `date_add` and `date_diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateUnit {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    // fields that only `extract` reads
+    DayOfWeek,
+    DayOfYear,
+    Epoch,
+}
+
+impl DateUnit {
+    /// Case-insensitive; plurals are accepted (`days`).
+    pub fn parse(text: &str) -> Option<DateUnit> {
+        let lower = text.trim().to_ascii_lowercase();
+        let unit = match lower.as_str() {
+            "dow" => return Some(DateUnit::DayOfWeek),
+            "doy" => return Some(DateUnit::DayOfYear),
+            "epoch" => return Some(DateUnit::Epoch),
+            other => other.strip_suffix('s').unwrap_or(other),
+        };
+        match unit {
+            "year" => Some(DateUnit::Year),
+            "quarter" => Some(DateUnit::Quarter),
+            "month" => Some(DateUnit::Month),
+            "week" => Some(DateUnit::Week),
+            "day" => Some(DateUnit::Day),
+            "hour" => Some(DateUnit::Hour),
+            "minute" => Some(DateUnit::Minute),
+            "second" => Some(DateUnit::Second),
+            _ => None,
+        }
+    }
+
+    /// Whether the unit can be truncated to, added or counted.
+    pub fn is_interval(self) -> bool {
+        !matches!(
+            self,
+            DateUnit::DayOfWeek | DateUnit::DayOfYear | DateUnit::Epoch
+        )
+    }
+
+    /// Calendar units keep a date a date.
+    pub fn is_calendar(self) -> bool {
+        matches!(
+            self,
+            DateUnit::Year | DateUnit::Quarter | DateUnit::Month | DateUnit::Week | DateUnit::Day
+        )
+    }
+}
+
+impl Temporal {
+    /// `2024-05-01`, `2024-05-01T10:30:00[.123]`, the same with a space instead
+    /// of `T`, or RFC 3339 with `Z` or an offset, which is converted to UTC.
+    pub fn parse(text: &str) -> Option<Temporal> {
+        let text = text.trim();
+        // cheap rejection for the common case of a non-temporal string
+        let bytes = text.as_bytes();
+        if bytes.len() < 10 || !bytes[..4].iter().all(u8::is_ascii_digit) || bytes[4] != b'-' {
+            return None;
+        }
+
+        if bytes.len() == 10 {
+            return NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(Temporal::Date);
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+            return Some(Temporal::DateTime(datetime.naive_utc()));
+        }
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .map(Temporal::DateTime)
+    }
+
+    pub fn now() -> Temporal {
+        Temporal::DateTime(Utc::now().naive_utc())
+    }
+
+    /// Dates are midnight when compared with date-times.
+    pub fn datetime(self) -> NaiveDateTime {
+        match self {
+            Temporal::Date(date) => date.and_time(NaiveTime::MIN),
+            Temporal::DateTime(datetime) => datetime,
+        }
+    }
+
+    /// `2024-05-01` for dates, `2024-05-01T10:30:00Z` (with fractional seconds
+    /// when present) for date-times.
+    pub fn format(self) -> String {
+        match self {
+            Temporal::Date(date) => date.format("%Y-%m-%d").to_string(),
+            Temporal::DateTime(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+        }
+    }
+
+    /// strftime-style format; `None` when the format is invalid.
+    pub fn format_with(self, format: &str) -> Option<String> {
+        let mut out = String::new();
+        write!(out, "{}", self.datetime().format(format)).ok()?;
+        Some(out)
+    }
+
+    /// Start of the unit the value falls in; dates stay dates.
+    pub fn truncate(self, unit: DateUnit) -> Option<Temporal> {
+        let datetime = self.datetime();
+        let date = datetime.date();
+        let start = match unit {
+            DateUnit::Year => date.with_day(1)?.with_month(1)?,
+            DateUnit::Quarter => date.with_day(1)?.with_month((date.month0() / 3) * 3 + 1)?,
+            DateUnit::Month => date.with_day(1)?,
+            DateUnit::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            DateUnit::Day => date,
+            DateUnit::Hour | DateUnit::Minute | DateUnit::Second => {
+                let time = datetime.time();
+                let time = match unit {
+                    DateUnit::Hour => NaiveTime::from_hms_opt(time.hour(), 0, 0)?,
+                    DateUnit::Minute => NaiveTime::from_hms_opt(time.hour(), time.minute(), 0)?,
+                    _ => time.with_nanosecond(0)?,
+                };
+                return Some(match self {
+                    Temporal::Date(_) => self,
+                    Temporal::DateTime(_) => Temporal::DateTime(date.and_time(time)),
+                });
+            }
+            _ => return None,
+        };
+        Some(match self {
+            Temporal::Date(_) => Temporal::Date(start),
+            Temporal::DateTime(_) => Temporal::DateTime(start.and_time(NaiveTime::MIN)),
+        })
+    }
+
+    /// A single field: `year`, `month`, `dow` (0 = Sunday), `epoch` seconds, ...
+    pub fn extract(self, unit: DateUnit) -> i64 {
+        let datetime = self.datetime();
+        match unit {
+            DateUnit::Year => datetime.year() as i64,
+            DateUnit::Quarter => (datetime.month0() / 3 + 1) as i64,
+            DateUnit::Month => datetime.month() as i64,
+            DateUnit::Week => datetime.iso_week().week() as i64,
+            DateUnit::Day => datetime.day() as i64,
+            DateUnit::Hour => datetime.hour() as i64,
+            DateUnit::Minute => datetime.minute() as i64,
+            DateUnit::Second => datetime.second() as i64,
+            DateUnit::DayOfWeek => datetime.weekday().num_days_from_sunday() as i64,
+            DateUnit::DayOfYear => datetime.ordinal() as i64,
+            DateUnit::Epoch => datetime.and_utc().timestamp(),
+        }
+    }
+
+    /// Moves the value by `amount` units. Months clamp to the last day of the
+    /// month; a date moved by hours or less becomes a date-time.
+    pub fn add(self, amount: i64, unit: DateUnit) -> Option<Temporal> {
+        let months = match unit {
+            DateUnit::Year => amount.checked_mul(12),
+            DateUnit::Quarter => amount.checked_mul(3),
+            DateUnit::Month => Some(amount),
+            _ => None,
+        };
+        let datetime = self.datetime();
+        let moved = match months {
+            Some(months) => {
+                let step = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+                match months {
+                    0.. => datetime.checked_add_months(step)?,
+                    _ => datetime.checked_sub_months(step)?,
+                }
+            }
+            None => {
+                let duration = match unit {
+                    DateUnit::Week => Duration::try_weeks(amount)?,
+                    DateUnit::Day => Duration::try_days(amount)?,
+                    DateUnit::Hour => Duration::try_hours(amount)?,
+                    DateUnit::Minute => Duration::try_minutes(amount)?,
+                    DateUnit::Second => Duration::try_seconds(amount)?,
+                    _ => return None,
+                };
+                datetime.checked_add_signed(duration)?
+            }
+        };
+        Some(match self {
+            Temporal::Date(_) if unit.is_calendar() => Temporal::Date(moved.date()),
+            _ => Temporal::DateTime(moved),
+        })
+    }
+
+    /// Whole units from `start` to `end`, negative when `end` comes first.
+    pub fn diff(unit: DateUnit, start: Temporal, end: Temporal) -> Option<i64> {
+        let (start, end) = (start.datetime(), end.datetime());
+        let months = |step: i64| {
+            let mut months = (end.year() as i64 * 12 + end.month0() as i64)
+                - (start.year() as i64 * 12 + start.month0() as i64);
+            // only count a month once the same day and time are reached
+            let rest = |value: NaiveDateTime| (value.day(), value.time());
+            if months > 0 && rest(end) < rest(start) {
+                months -= 1;
+            } else if months < 0 && rest(end) > rest(start) {
+                months += 1;
+            }
+            months / step
+        };
+        let elapsed = end - start;
+        match unit {
+            DateUnit::Year => Some(months(12)),
+            DateUnit::Quarter => Some(months(3)),
+            DateUnit::Month => Some(months(1)),
+            DateUnit::Week => Some(elapsed.num_weeks()),
+            DateUnit::Day => Some(elapsed.num_days()),
+            DateUnit::Hour => Some(elapsed.num_hours()),
+            DateUnit::Minute => Some(elapsed.num_minutes()),
+            DateUnit::Second => Some(elapsed.num_seconds()),
+            _ => None,
+        }
+    }
+}
+
+pub struct TemporalResolver;
+
+impl TemporalResolver {
+    /// Chronological order of two strings when both are ISO-8601 values.
+    pub fn compare(a: &str, b: &str) -> Option<Ordering> {
+        let a = Temporal::parse(a)?;
+        let b = Temporal::parse(b)?;
+        Some(a.datetime().cmp(&b.datetime()))
+    }
+
+    /// Total order of two strings for sorting: ISO-8601 values chronologically
+    /// and before any other string, other strings by text. Comparing by date
+    /// only when both sides parse is not transitive.
+    pub fn order(a: &str, b: &str) -> Ordering {
+        match (Temporal::parse(a), Temporal::parse(b)) {
+            (Some(x), Some(y)) => x.datetime().cmp(&y.datetime()),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.cmp(b),
+        }
+    }
+
+    /// `a op b` for two temporal strings; `None` when either is not one.
+    pub fn compare3(a: &str, op: ComparatorOp, b: &str) -> Option<Truth> {
+        let ordering = Self::compare(a, b)?;
+        let holds = match op {
+            ComparatorOp::Eq => ordering == Ordering::Equal,
+            ComparatorOp::NotEq => ordering != Ordering::Equal,
+            ComparatorOp::Lt => ordering == Ordering::Less,
+            ComparatorOp::LtEq => ordering != Ordering::Greater,
+            ComparatorOp::Gt => ordering == Ordering::Greater,
+            ComparatorOp::GtEq => ordering != Ordering::Less,
+        };
+        Some(if holds { Truth::True } else { Truth::False })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use crate::parser::{
+        analyzer::{DateUnit, Temporal, TemporalResolver},
+        ast::{ComparatorOp, Truth},
+    };
+
+    fn parse(text: &str) -> Temporal {
+        Temporal::parse(text).unwrap_or_else(|| panic!("{text} should parse"))
+    }
+
+    #[test]
+    fn parses_dates_and_date_times_and_formats_them_back() {
+        assert_eq!(parse("2024-02-29").format(), "2024-02-29");
+        assert_eq!(
+            parse("2024-02-29T10:30:00").format(),
+            "2024-02-29T10:30:00Z"
+        );
+        assert_eq!(
+            parse("2024-02-29 10:30:00.5").format(),
+            "2024-02-29T10:30:00.500Z"
+        );
+        // offsets are converted to UTC
+        assert_eq!(
+            parse("2024-02-29T01:00:00+02:00").format(),
+            "2024-02-28T23:00:00Z"
+        );
+        for text in [
+            "2024-02-30",
+            "2024/02/01",
+            "hello",
+            "2024-01-01T25:00:00",
+            "",
+        ] {
+            assert_eq!(Temporal::parse(text), None, "{text} should not parse");
+        }
+    }
+
+    #[test]
+    fn compares_chronologically_across_forms() {
+        assert_eq!(
+            TemporalResolver::compare("2024-01-01", "2024-01-01T00:00:00Z"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            TemporalResolver::compare("2024-01-02T00:00:00+03:00", "2024-01-01T22:00:00Z"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(TemporalResolver::compare("2024-01-01", "soon"), None);
+        assert_eq!(
+            TemporalResolver::order("2024-01-01", "2024-01-01T00:00:00Z"),
+            Ordering::Equal
+        );
+        // "10" < "2024-01-01" < "9" by text, but dates always rank first
+        assert_eq!(
+            TemporalResolver::order("10", "2024-01-01"),
+            Ordering::Greater
+        );
+        assert_eq!(TemporalResolver::order("2024-01-01", "9"), Ordering::Less);
+        assert_eq!(TemporalResolver::order("10", "9"), Ordering::Less);
+        assert_eq!(
+            TemporalResolver::compare3("2024-01-09", ComparatorOp::Lt, "2024-01-10"),
+            Some(Truth::True)
+        );
+    }
+
+    #[test]
+    fn truncates_extracts_adds_and_diffs() {
+        let value = parse("2024-05-15T13:45:30Z");
+        assert_eq!(
+            value.truncate(DateUnit::Quarter).unwrap().format(),
+            "2024-04-01T00:00:00Z"
+        );
+        assert_eq!(
+            value.truncate(DateUnit::Hour).unwrap().format(),
+            "2024-05-15T13:00:00Z"
+        );
+        // 2024-05-15 is a Wednesday
+        assert_eq!(
+            parse("2024-05-15")
+                .truncate(DateUnit::Week)
+                .unwrap()
+                .format(),
+            "2024-05-13"
+        );
+        assert_eq!(value.extract(DateUnit::Quarter), 2);
+        assert_eq!(value.extract(DateUnit::DayOfWeek), 3);
+        assert_eq!(value.extract(DateUnit::DayOfYear), 136);
+        assert_eq!(parse("1970-01-02").extract(DateUnit::Epoch), 86_400);
+
+        assert_eq!(
+            parse("2024-01-31")
+                .add(1, DateUnit::Month)
+                .unwrap()
+                .format(),
+            "2024-02-29"
+        );
+        assert_eq!(
+            parse("2024-01-31").add(-2, DateUnit::Day).unwrap().format(),
+            "2024-01-29"
+        );
+        assert_eq!(
+            parse("2024-01-31")
+                .add(90, DateUnit::Minute)
+                .unwrap()
+                .format(),
+            "2024-01-31T01:30:00Z"
+        );
+
+        let diff = |unit, a, b| Temporal::diff(unit, parse(a), parse(b)).unwrap();
+        assert_eq!(diff(DateUnit::Month, "2024-01-31", "2024-02-29"), 0);
+        assert_eq!(diff(DateUnit::Month, "2024-01-15", "2024-03-15"), 2);
+        assert_eq!(diff(DateUnit::Year, "2024-06-01", "2023-06-02"), 0);
+        assert_eq!(diff(DateUnit::Day, "2024-03-01", "2024-02-01"), -29);
+        assert_eq!(
+            diff(DateUnit::Hour, "2024-03-01", "2024-03-01T05:59:00Z"),
+            5
+        );
+    }
+
+    #[test]
+    fn units_and_formats() {
+        assert_eq!(DateUnit::parse("Days"), Some(DateUnit::Day));
+        assert_eq!(DateUnit::parse("DOW"), Some(DateUnit::DayOfWeek));
+        assert_eq!(DateUnit::parse("fortnight"), None);
+        assert!(!DateUnit::DayOfWeek.is_interval());
+
+        let value = parse("2024-05-01T08:05:00Z");
+        assert_eq!(
+            value.format_with("%d/%m/%Y %H:%M").unwrap(),
+            "01/05/2024 08:05"
+        );
+        assert_eq!(value.format_with("%Q"), None);
+        // naive values have no offset to print
+        assert_eq!(value.format_with("%z"), None);
+    }
+}
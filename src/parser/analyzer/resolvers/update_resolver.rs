@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use crate::{
+    JsonPrimitive, SchemaDict,
+    parser::{
+        analyzer::{
+            AggregateResolver, AnalysisContext, AnalyzedUpdate, AnalyzerError, AssignmentResolver,
+            PredicateResolver, ReturningResolver, ScalarResolver, TypeInference,
+        },
+        ast::{Assignment, ScalarExpr, Update},
+    },
+};
+
+pub struct UpdateResolver;
+
+impl UpdateResolver {
+    pub fn analyze_update(
+        update: &Update,
+        ctx: &mut AnalysisContext,
+    ) -> Result<AnalyzedUpdate, AnalyzerError> {
+        let schema = ctx
+            .schemas
+            .schema_of(&update.collection)
+            .ok_or_else(|| AnalyzerError::UnknownCollection(update.collection.clone()))?;
+        ctx.add_collection(update.collection.clone(), update.collection.clone());
+
+        let mut written = Vec::with_capacity(update.assignments.len());
+        let assignments = Self::analyze_assignments(
+            &update.collection,
+            &schema,
+            &update.assignments,
+            &mut written,
+            ctx,
+        )?;
+
+        let criteria = match &update.criteria {
+            Some(predicate) => Some(PredicateResolver::resolve_write_criteria(predicate, ctx)?),
+            None => None,
+        };
+
+        let returning = ReturningResolver::analyze_returning(
+            &update.collection,
+            &schema,
+            &written,
+            &update.returning,
+            ctx,
+        )?;
+
+        Ok(AnalyzedUpdate {
+            collection: update.collection.clone(),
+            assignments,
+            criteria,
+            returning,
+        })
+    }
+
+    /// Qualify, fold and type-check `SET` assignments against the target schema.
+    /// The type of every assigned column is pushed to `written`.
+    pub fn analyze_assignments(
+        collection: &str,
+        schema: &SchemaDict,
+        assignments: &[Assignment],
+        written: &mut Vec<(String, JsonPrimitive)>,
+        ctx: &mut AnalysisContext,
+    ) -> Result<Vec<(String, ScalarExpr)>, AnalyzerError> {
+        let mut seen = HashSet::new();
+        let mut analyzed = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            if !seen.insert(assignment.column.as_str()) {
+                return Err(AnalyzerError::Other(format!(
+                    "column {} assigned more than once in UPDATE",
+                    assignment.column
+                )));
+            }
+
+            let qexpr = ScalarResolver::qualify_scalar(&assignment.value, ctx, false)?;
+            if AggregateResolver::contains_aggregate(&qexpr) {
+                return Err(AnalyzerError::Other(
+                    "Aggregates are not allowed in UPDATE".into(),
+                ));
+            }
+            let fexpr = ScalarResolver::fold_scalar(&qexpr);
+            let (ty, _) = TypeInference::infer_scalar(&fexpr, ctx)?;
+            AssignmentResolver::check_assignable(collection, schema, &assignment.column, ty)?;
+            AssignmentResolver::check_constant(collection, schema, &assignment.column, &fexpr)?;
+            analyzed.push((assignment.column.clone(), fexpr));
+            written.push((assignment.column.clone(), ty));
+        }
+
+        Ok(analyzed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsonPrimitive, SchemaDict,
+        database::{FieldInfo, SchemaProvider},
+        parser::{
+            QueryParser,
+            aggregators_helper::AggregateRegistry,
+            analyzer::{AnalysisContext, AnalyzedUpdate, AnalyzerError, UpdateResolver},
+            ast::{Column, Function, Literal, Predicate, ScalarExpr, Update},
+        },
+    };
+
+    use indexmap::IndexMap;
+    use serde_json::json;
+
+    struct DummySchemas {
+        by_name: std::collections::HashMap<String, SchemaDict>,
+    }
+    impl DummySchemas {
+        fn new() -> Self {
+            Self {
+                by_name: std::collections::HashMap::new(),
+            }
+        }
+        fn with(mut self, name: &str, fields: Vec<(&str, JsonPrimitive, bool)>) -> Self {
+            let mut m = IndexMap::new();
+            for (k, ty, nullable) in fields {
+                m.insert(k.to_string(), FieldInfo { ty, nullable });
+            }
+            self.by_name
+                .insert(name.to_string(), SchemaDict { fields: m });
+            self
+        }
+    }
+    impl SchemaProvider for DummySchemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            self.by_name.get(backing_collection).cloned()
+        }
+    }
+
+    fn analyze(sql: &str, parameters: serde_json::Value) -> Result<AnalyzedUpdate, AnalyzerError> {
+        let schemas = DummySchemas::new().with(
+            "people",
+            vec![
+                ("id", JsonPrimitive::Int, false),
+                ("name", JsonPrimitive::String, false),
+                ("age", JsonPrimitive::Int, true),
+            ],
+        );
+        let update = Update::parse(&mut QueryParser::new(sql)).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        let mut ctx = AnalysisContext::new_with_aggregates(&schemas, &registry);
+        ctx.parameters = parameters;
+        UpdateResolver::analyze_update(&update, &mut ctx)
+    }
+
+    fn col(name: &str) -> ScalarExpr {
+        ScalarExpr::Column(Column::WithCollection {
+            collection: "people".into(),
+            name: name.into(),
+        })
+    }
+
+    #[test]
+    fn assignments_and_criteria_are_qualified_in_parameter_order() {
+        let analyzed = analyze(
+            "UPDATE people SET name = upper(name), age = ? WHERE id = ?",
+            json!([40, 1]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            analyzed.assignments,
+            vec![
+                (
+                    "name".to_string(),
+                    ScalarExpr::Function(Function {
+                        name: "upper".into(),
+                        args: vec![col("name")],
+                        distinct: false,
+                    })
+                ),
+                ("age".to_string(), ScalarExpr::Literal(Literal::Int(40))),
+            ]
+        );
+        match analyzed.criteria {
+            Some(Predicate::Compare { left, right, .. }) => {
+                assert_eq!(left, col("id"));
+                assert_eq!(right, ScalarExpr::Literal(Literal::Int(1)));
+            }
+            other => panic!("unexpected criteria {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unknown_collection_and_columns_are_rejected() {
+        let schemas = DummySchemas::new();
+        let update = Update::parse(&mut QueryParser::new("UPDATE nope SET a = 1")).unwrap();
+        let registry = AggregateRegistry::default_aggregate_registry();
+        let mut ctx = AnalysisContext::new_with_aggregates(&schemas, &registry);
+        let err = UpdateResolver::analyze_update(&update, &mut ctx);
+        assert!(matches!(err, Err(AnalyzerError::UnknownCollection(name)) if name == "nope"));
+
+        let err = analyze("UPDATE people SET age = 1 WHERE missing = 1", json!(null));
+        assert!(matches!(err, Err(AnalyzerError::UnknownColumn { .. })));
+    }
+
+    #[test]
+    fn invalid_assignments_are_rejected() {
+        let err = analyze("UPDATE people SET age = 'old'", json!(null));
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("people.age"))
+        );
+
+        let err = analyze("UPDATE people SET age = 1, age = 2", json!(null));
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("more than once"))
+        );
+
+        let err = analyze("UPDATE people SET age = MAX(age)", json!(null));
+        assert!(
+            matches!(err, Err(AnalyzerError::Other(message)) if message.contains("Aggregates"))
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    JsonPrimitive,
+    parser::{
+        analyzer::{AnalysisContext, AnalyzerError, ScalarResolver, TypeInference},
+        ast::{Literal, OrderBy, ScalarExpr, Window},
+    },
+};
+
+/// Checks and types `function(...) OVER (...)`: ranking functions, offset
+/// functions and any registered aggregate computed over a window.
+pub struct WindowResolver;
+
+impl WindowResolver {
+    pub fn is_ranking_name(name: &str) -> bool {
+        matches!(
+            name.to_ascii_lowercase().as_str(),
+            "row_number" | "rank" | "dense_rank" | "ntile"
+        )
+    }
+
+    pub fn is_offset_name(name: &str) -> bool {
+        matches!(
+            name.to_ascii_lowercase().as_str(),
+            "lag" | "lead" | "first_value" | "last_value"
+        )
+    }
+
+    /// Qualifies the function arguments, PARTITION BY and ORDER BY; windows
+    /// cannot nest, so none are allowed inside them.
+    pub fn qualify_window(
+        window: &Window,
+        ctx: &mut AnalysisContext,
+    ) -> Result<Window, AnalyzerError> {
+        if !ctx.windows_allowed {
+            return Err(AnalyzerError::Other(
+                "Window functions are only allowed in the SELECT list and ORDER BY".into(),
+            ));
+        }
+        let function = &window.function;
+        if !Self::is_ranking_name(&function.name)
+            && !Self::is_offset_name(&function.name)
+            && ctx.aggregates.get(&function.name).is_none()
+        {
+            return Err(AnalyzerError::FunctionNotFound(function.name.clone()));
+        }
+        if function.distinct {
+            return Err(AnalyzerError::Other(
+                "DISTINCT is not supported in window functions".into(),
+            ));
+        }
+
+        ctx.windows_allowed = false;
+        let qualified = Self::qualify_parts(window, ctx);
+        ctx.windows_allowed = true;
+        qualified
+    }
+
+    fn qualify_parts(window: &Window, ctx: &mut AnalysisContext) -> Result<Window, AnalyzerError> {
+        let function = match ScalarResolver::qualify_scalar(
+            &ScalarExpr::Function(window.function.clone()),
+            ctx,
+            false,
+        )? {
+            ScalarExpr::Function(function) => function,
+            _ => unreachable!("a function qualifies to a function"),
+        };
+        let mut partition_by = Vec::with_capacity(window.partition_by.len());
+        for expr in &window.partition_by {
+            partition_by.push(ScalarResolver::qualify_scalar(expr, ctx, false)?);
+        }
+        let mut order_by = Vec::with_capacity(window.order_by.len());
+        for ob in &window.order_by {
+            order_by.push(OrderBy {
+                expr: ScalarResolver::qualify_scalar(&ob.expr, ctx, false)?,
+                ascending: ob.ascending,
+            });
+        }
+        Ok(Window {
+            function,
+            partition_by,
+            order_by,
+            frame: window.frame,
+        })
+    }
+
+    pub fn fold_window(window: &Window) -> Window {
+        let mut function = window.function.clone();
+        function.args = function
+            .args
+            .iter()
+            .map(ScalarResolver::fold_scalar)
+            .collect();
+        Window {
+            function,
+            partition_by: window
+                .partition_by
+                .iter()
+                .map(ScalarResolver::fold_scalar)
+                .collect(),
+            order_by: window
+                .order_by
+                .iter()
+                .map(|ob| OrderBy {
+                    expr: ScalarResolver::fold_scalar(&ob.expr),
+                    ascending: ob.ascending,
+                })
+                .collect(),
+            frame: window.frame,
+        }
+    }
+
+    /// Ranking functions are Int; offset functions keep the type of their
+    /// value and are NULL past the partition edges; aggregates type as usual.
+    pub fn infer_type(
+        window: &Window,
+        ctx: &AnalysisContext,
+    ) -> Result<(JsonPrimitive, bool), AnalyzerError> {
+        for expr in window
+            .partition_by
+            .iter()
+            .chain(window.order_by.iter().map(|ob| &ob.expr))
+        {
+            TypeInference::infer_scalar(expr, ctx)?;
+        }
+
+        let function = &window.function;
+        let lname = function.name.to_ascii_lowercase();
+        if ctx.aggregates.get(&lname).is_some() {
+            return ctx.aggregates.infer_type(function, ctx);
+        }
+
+        let mut arg_types = Vec::with_capacity(function.args.len());
+        for arg in &function.args {
+            arg_types.push(TypeInference::infer_scalar(arg, ctx)?);
+        }
+        let is_int = |ty: JsonPrimitive| {
+            matches!(
+                ty,
+                JsonPrimitive::Int | JsonPrimitive::Null | JsonPrimitive::Any
+            )
+        };
+
+        match (lname.as_str(), arg_types.as_slice()) {
+            ("row_number" | "rank" | "dense_rank", []) => Ok((JsonPrimitive::Int, false)),
+            ("ntile", [(buckets, _)]) if is_int(*buckets) => {
+                if let [ScalarExpr::Literal(Literal::Int(buckets))] = function.args.as_slice()
+                    && *buckets <= 0
+                {
+                    return Err(AnalyzerError::Other(format!(
+                        "NTILE bucket count must be positive, got {}",
+                        buckets
+                    )));
+                }
+                Ok((JsonPrimitive::Int, false))
+            }
+            ("lag" | "lead", [(ty, _)]) => Ok((*ty, true)),
+            ("lag" | "lead", [(ty, _), (offset, _)]) if is_int(*offset) => Ok((*ty, true)),
+            ("lag" | "lead", [(ty, _), (offset, _), (default, _)]) if is_int(*offset) => {
+                Ok((JsonPrimitive::promote(*ty, *default), true))
+            }
+            ("first_value" | "last_value", [(ty, _)]) => Ok((*ty, true)),
+            (name, got) => Err(AnalyzerError::FunctionArgMismatch {
+                name: function.name.clone(),
+                expected: match name {
+                    "ntile" => "NTILE(buckets)",
+                    "lag" => "LAG(value[, offset[, default]])",
+                    "lead" => "LEAD(value[, offset[, default]])",
+                    "first_value" => "FIRST_VALUE(value)",
+                    "last_value" => "LAST_VALUE(value)",
+                    _ => "no arguments",
+                }
+                .into(),
+                got: got.iter().map(|(ty, _)| *ty).collect(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use crate::{
+        JsonPrimitive,
+        database::{FieldInfo, SchemaDict, SchemaProvider},
+        parser::{
+            QueryParser,
+            analyzer::{AnalysisContext, AnalyzerError, ScalarResolver, WindowResolver},
+            ast::{Column, ScalarExpr},
+        },
+    };
+
+    struct Schemas;
+    impl SchemaProvider for Schemas {
+        fn schema_of(&self, backing_collection: &str) -> Option<SchemaDict> {
+            let mut fields = IndexMap::new();
+            for (name, ty) in [("cat", JsonPrimitive::String), ("amt", JsonPrimitive::Int)] {
+                fields.insert(
+                    name.to_string(),
+                    FieldInfo {
+                        ty,
+                        nullable: false,
+                    },
+                );
+            }
+            (backing_collection == "t").then_some(SchemaDict { fields })
+        }
+    }
+
+    fn analyze(text: &str, ctx: &mut AnalysisContext) -> Result<ScalarExpr, AnalyzerError> {
+        let expr = ScalarExpr::parse(&mut QueryParser::new(text), true).expect("should parse");
+        ScalarResolver::qualify_scalar(&expr, ctx, false)
+    }
+
+    #[test]
+    fn qualify_window_qualifies_every_part() {
+        let sp = Schemas;
+        let mut ctx = AnalysisContext::new(&sp);
+        ctx.add_collection("t", "t");
+        ctx.windows_allowed = true;
+
+        let expr = analyze("sum(amt) OVER (PARTITION BY cat ORDER BY amt)", &mut ctx).unwrap();
+        let ScalarExpr::Window(window) = &expr else {
+            panic!("expected a window, got {expr:?}");
+        };
+        let qualified = |name: &str| {
+            ScalarExpr::Column(Column::WithCollection {
+                collection: "t".into(),
+                name: name.into(),
+            })
+        };
+        assert_eq!(window.function.args, vec![qualified("amt")]);
+        assert_eq!(window.partition_by, vec![qualified("cat")]);
+        assert_eq!(window.order_by[0].expr, qualified("amt"));
+        assert!(ctx.windows_allowed);
+    }
+
+    #[test]
+    fn qualify_window_rejects_misplaced_and_unknown_windows() {
+        let sp = Schemas;
+        let mut ctx = AnalysisContext::new(&sp);
+        ctx.add_collection("t", "t");
+
+        assert!(matches!(
+            analyze("row_number() OVER ()", &mut ctx),
+            Err(AnalyzerError::Other(_))
+        ));
+
+        ctx.windows_allowed = true;
+        assert!(matches!(
+            analyze("median(amt) OVER ()", &mut ctx),
+            Err(AnalyzerError::FunctionNotFound(_))
+        ));
+        assert!(analyze("count(distinct amt) OVER ()", &mut ctx).is_err());
+        assert!(analyze("sum(rank() OVER (ORDER BY amt)) OVER ()", &mut ctx).is_err());
+        assert!(analyze("lag(amt) OVER (ORDER BY rank() OVER ())", &mut ctx).is_err());
+    }
+
+    #[test]
+    fn infer_type_of_window_functions() {
+        let sp = Schemas;
+        let mut ctx = AnalysisContext::new(&sp);
+        ctx.add_collection("t", "t");
+        ctx.windows_allowed = true;
+
+        let infer = |text: &str, ctx: &mut AnalysisContext| {
+            let expr = analyze(text, ctx)?;
+            let ScalarExpr::Window(window) = expr else {
+                panic!("expected a window");
+            };
+            WindowResolver::infer_type(&window, ctx)
+        };
+
+        assert_eq!(
+            infer("rank() OVER (ORDER BY amt)", &mut ctx),
+            Ok((JsonPrimitive::Int, false))
+        );
+        assert_eq!(
+            infer("ntile(4) OVER (ORDER BY amt)", &mut ctx),
+            Ok((JsonPrimitive::Int, false))
+        );
+        assert_eq!(
+            infer("lag(cat, 1, 'none') OVER (ORDER BY amt)", &mut ctx),
+            Ok((JsonPrimitive::String, true))
+        );
+        assert_eq!(
+            infer("count(*) OVER (PARTITION BY cat)", &mut ctx),
+            Ok((JsonPrimitive::Int, false))
+        );
+        assert!(matches!(
+            infer("row_number(amt) OVER ()", &mut ctx),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("lead(amt, 'x') OVER ()", &mut ctx),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("ntile(0) OVER (ORDER BY amt)", &mut ctx),
+            Err(AnalyzerError::Other(msg)) if msg == "NTILE bucket count must be positive, got 0"
+        ));
+    }
+}
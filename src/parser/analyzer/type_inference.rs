@@ -1,8 +1,11 @@
 use crate::{
     JsonPrimitive,
     parser::{
-        analyzer::{AnalysisContext, AnalyzerError, ColumnResolver},
-        ast::{Function, Literal, ScalarExpr},
+        analyzer::{
+            AnalysisContext, AnalyzerError, CastResolver, ColumnResolver, DateUnit,
+            SetOperationResolver, WindowResolver,
+        },
+        ast::{ArithmeticOp, Function, Literal, ScalarExpr},
     },
 };
 
@@ -29,6 +32,24 @@ impl TypeInference {
                 let (_qc, rf) = ColumnResolver::qualify_column(column, ctx)?;
                 Ok((rf.ty, rf.nullable))
             }
+            // nested values have no schema; a missing key or index reads as NULL
+            ScalarExpr::JsonPath { column, path } => {
+                let (_qc, rf) = ColumnResolver::qualify_column(column, ctx)?;
+                match rf.ty {
+                    JsonPrimitive::Object
+                    | JsonPrimitive::Array
+                    | JsonPrimitive::Null
+                    | JsonPrimitive::Any => Ok((JsonPrimitive::Any, true)),
+                    other => Err(AnalyzerError::Other(format!(
+                        "cannot read path {} from {}.{} of type {:?}",
+                        path.iter().map(|s| s.to_string()).collect::<String>(),
+                        rf.collection,
+                        rf.name,
+                        other
+                    ))),
+                }
+            }
+            ScalarExpr::Window(window) => WindowResolver::infer_type(window, ctx),
             ScalarExpr::Function(function) => {
                 // delegate to registry
                 let ret = Self::infer_function_type(function, ctx)?;
@@ -37,12 +58,75 @@ impl TypeInference {
             ScalarExpr::WildCard | ScalarExpr::WildCardWithCollection(_) => Err(
                 AnalyzerError::Other("wildcards should be expanded before type inference".into()),
             ),
-            ScalarExpr::Parameter => Err(AnalyzerError::Other(
+            ScalarExpr::Parameter | ScalarExpr::NamedParameter(_) => Err(AnalyzerError::Other(
                 "Parameter should be expanded before type inference".into(),
             )),
             ScalarExpr::Args(_) => Err(AnalyzerError::Other(
                 "Args should be expanded before type inference".into(),
             )),
+            // a subquery yields NULL when it returns no rows
+            ScalarExpr::Subquery(subquery) => match &subquery.analyzed {
+                Some(analyzed) => Ok((analyzed.projection[0].ty, true)),
+                None => Err(AnalyzerError::Other(
+                    "subquery should be analyzed before type inference".into(),
+                )),
+            },
+            // branch types must match like UNION columns and are promoted;
+            // NULL when nothing matches and there is no ELSE
+            ScalarExpr::Case(case) => {
+                let mut ty = JsonPrimitive::Null;
+                let mut nullable = case.otherwise.is_none();
+                for result in case
+                    .branches
+                    .iter()
+                    .map(|(_, result)| result)
+                    .chain(case.otherwise.as_deref())
+                {
+                    let (result_ty, result_nullable) = Self::infer_scalar(result, ctx)?;
+                    if !SetOperationResolver::compatible(ty, result_ty) {
+                        return Err(AnalyzerError::Other(format!(
+                            "CASE types {:?} and {:?} cannot be matched",
+                            ty, result_ty
+                        )));
+                    }
+                    ty = JsonPrimitive::promote(ty, result_ty);
+                    nullable |= result_nullable;
+                }
+                Ok((ty, nullable))
+            }
+            // Int with Float promotes to Float; `/` and `%` yield NULL on a zero divisor
+            ScalarExpr::Binary { left, op, right } => {
+                let (left_ty, left_nullable) = Self::infer_scalar(left, ctx)?;
+                let (right_ty, right_nullable) = Self::infer_scalar(right, ctx)?;
+                let nullable = left_nullable || right_nullable;
+                if *op == ArithmeticOp::Concat {
+                    return Ok((JsonPrimitive::String, nullable));
+                }
+                Self::expect_numeric(*op, left_ty)?;
+                Self::expect_numeric(*op, right_ty)?;
+                Ok((
+                    JsonPrimitive::promote(left_ty, right_ty),
+                    nullable || matches!(op, ArithmeticOp::Div | ArithmeticOp::Mod),
+                ))
+            }
+            ScalarExpr::Negate(expr) => {
+                let (ty, nullable) = Self::infer_scalar(expr, ctx)?;
+                Self::expect_numeric(ArithmeticOp::Sub, ty)?;
+                Ok((ty, nullable))
+            }
+        }
+    }
+
+    fn expect_numeric(op: ArithmeticOp, ty: JsonPrimitive) -> Result<(), AnalyzerError> {
+        match ty {
+            JsonPrimitive::Int
+            | JsonPrimitive::Float
+            | JsonPrimitive::Null
+            | JsonPrimitive::Any => Ok(()),
+            other => Err(AnalyzerError::Other(format!(
+                "operator {} expects numeric operands, got {:?}",
+                op, other
+            ))),
         }
     }
 
@@ -65,14 +149,27 @@ impl TypeInference {
 
         match (lname.as_str(), arg_types.as_slice()) {
             // UPPER(s), LOWER(s), TRIM(s)
+            // a dynamic argument that is not a string yields NULL at runtime
             ("upper", [(JsonPrimitive::String, nullable)])
             | ("lower", [(JsonPrimitive::String, nullable)])
             | ("trim", [(JsonPrimitive::String, nullable)]) => {
                 Ok((JsonPrimitive::String, *nullable))
             }
+            ("upper" | "lower" | "trim", [(JsonPrimitive::Any, _)]) => {
+                Ok((JsonPrimitive::String, true))
+            }
 
             // LENGTH(s) -> Int
             ("length", [(JsonPrimitive::String, nullable)]) => Ok((JsonPrimitive::Int, *nullable)),
+            ("length", [(JsonPrimitive::Any, _)]) => Ok((JsonPrimitive::Int, true)),
+
+            // GROUPING(key, ...) -> Int with a bit set for each key the grouping set leaves out
+            ("grouping", keys) if !keys.is_empty() => Ok((JsonPrimitive::Int, false)),
+            ("grouping", []) => Err(AnalyzerError::FunctionArgMismatch {
+                name: function.name.clone(),
+                expected: "GROUPING(expr, ...)".into(),
+                got: vec![],
+            }),
 
             // COALESCE(a,b,...) -> promoted type, nullable if all inputs nullable
             ("coalesce", many) if !many.is_empty() => {
@@ -85,9 +182,414 @@ impl TypeInference {
                 Ok((ty, all_nullable)) // not all nullable -> result non-null; if all nullable, nullable
             }
 
+            // CAST(x AS type) -> type; TRY_CAST yields NULL where CAST would be rejected
+            ("cast" | "try_cast", [(from, nullable), (JsonPrimitive::String, _)]) => {
+                Self::infer_cast(function, *from, *nullable)
+            }
+            ("cast" | "try_cast", got) => Err(AnalyzerError::FunctionArgMismatch {
+                name: function.name.clone(),
+                expected: format!("{}(value AS type)", lname.to_ascii_uppercase()),
+                got: got.iter().map(|(ty, _)| *ty).collect(),
+            }),
+
+            // ABS, SIGN, FLOOR, CEIL and ROUND keep Int or Float, like SUM
+            ("abs" | "sign" | "floor" | "ceil" | "ceiling", [(ty, nullable)])
+                if Self::is_numeric(*ty) =>
+            {
+                Ok((*ty, *nullable || *ty == JsonPrimitive::Any))
+            }
+            ("round", [(ty, nullable)]) if Self::is_numeric(*ty) => {
+                Ok((*ty, *nullable || *ty == JsonPrimitive::Any))
+            }
+            ("round", [(ty, nullable), (digits, digits_nullable)])
+                if Self::is_numeric(*ty)
+                    && matches!(
+                        digits,
+                        JsonPrimitive::Int | JsonPrimitive::Any | JsonPrimitive::Null
+                    ) =>
+            {
+                Ok((
+                    *ty,
+                    *nullable || *digits_nullable || *ty == JsonPrimitive::Any,
+                ))
+            }
+            // MOD follows `%`: NULL on a zero divisor
+            ("mod", [(a, _), (b, _)]) if Self::is_numeric(*a) && Self::is_numeric(*b) => {
+                Ok((JsonPrimitive::promote(*a, *b), true))
+            }
+            // SQRT and POWER always yield Float, like AVG; NULL when undefined
+            ("sqrt", [(ty, _)]) if Self::is_numeric(*ty) => Ok((JsonPrimitive::Float, true)),
+            ("power" | "pow", [(a, _), (b, _)]) if Self::is_numeric(*a) && Self::is_numeric(*b) => {
+                Ok((JsonPrimitive::Float, true))
+            }
+            ("random", []) => Ok((JsonPrimitive::Float, false)),
+            // GREATEST/LEAST skip NULLs, so they are nullable only if every input is
+            ("greatest" | "least", many) if !many.is_empty() => {
+                let mut ty = JsonPrimitive::Null;
+                for (arg, _) in many {
+                    let comparable = ty == *arg
+                        || (Self::is_numeric(ty) && Self::is_numeric(*arg))
+                        || matches!(ty, JsonPrimitive::Null | JsonPrimitive::Any)
+                        || matches!(arg, JsonPrimitive::Null | JsonPrimitive::Any);
+                    if !comparable || matches!(arg, JsonPrimitive::Object | JsonPrimitive::Array) {
+                        return Err(AnalyzerError::FunctionArgMismatch {
+                            name: function.name.clone(),
+                            expected: format!(
+                                "{}(value, ...) of one comparable type",
+                                lname.to_ascii_uppercase()
+                            ),
+                            got: many.iter().map(|(ty, _)| *ty).collect(),
+                        });
+                    }
+                    ty = JsonPrimitive::promote(ty, *arg);
+                }
+                Ok((ty, many.iter().all(|(_, nullable)| *nullable)))
+            }
+            (name, got) if Self::math_signature(name).is_some() => {
+                Err(AnalyzerError::FunctionArgMismatch {
+                    name: function.name.clone(),
+                    expected: Self::math_signature(name).unwrap_or_default().into(),
+                    got: got.iter().map(|(ty, _)| *ty).collect(),
+                })
+            }
+
+            // Date functions read ISO-8601 strings; anything else yields NULL at runtime
+            ("now", []) => Ok((JsonPrimitive::DateTime, false)),
+            ("date_trunc", [(unit, _), (ty, _)])
+                if Self::is_text(*unit) && Self::is_temporal(*ty) =>
+            {
+                Self::literal_unit(function, 0, true)?;
+                Ok((Self::temporal_result(*ty), true))
+            }
+            ("extract" | "date_part", [(unit, _), (ty, _)])
+                if Self::is_text(*unit) && Self::is_temporal(*ty) =>
+            {
+                Self::literal_unit(function, 0, false)?;
+                Ok((JsonPrimitive::Int, true))
+            }
+            ("date_add", [(ty, _), (amount, _), unit @ ..])
+                if Self::is_temporal(*ty)
+                    && matches!(
+                        amount,
+                        JsonPrimitive::Int | JsonPrimitive::Any | JsonPrimitive::Null
+                    )
+                    && unit.len() <= 1
+                    && unit.iter().all(|(unit, _)| Self::is_text(*unit)) =>
+            {
+                let unit = match unit.is_empty() {
+                    true => Some(DateUnit::Day),
+                    false => Self::literal_unit(function, 2, true)?,
+                };
+                // a date moved by whole days or more stays a date
+                let ty = match (ty, unit) {
+                    (JsonPrimitive::Date, Some(unit)) if unit.is_calendar() => JsonPrimitive::Date,
+                    (JsonPrimitive::Date, Some(_)) => JsonPrimitive::DateTime,
+                    (JsonPrimitive::DateTime, _) => JsonPrimitive::DateTime,
+                    _ => JsonPrimitive::String,
+                };
+                Ok((ty, true))
+            }
+            ("date_diff", [(unit, _), (start, _), (end, _)])
+                if Self::is_text(*unit) && Self::is_temporal(*start) && Self::is_temporal(*end) =>
+            {
+                Self::literal_unit(function, 0, true)?;
+                Ok((JsonPrimitive::Int, true))
+            }
+            ("to_char", [(ty, _), (format, _)]) | ("strftime", [(format, _), (ty, _)])
+                if Self::is_temporal(*ty) && Self::is_text(*format) =>
+            {
+                Ok((JsonPrimitive::String, true))
+            }
+            (name, got) if Self::temporal_signature(name).is_some() => {
+                Err(AnalyzerError::FunctionArgMismatch {
+                    name: function.name.clone(),
+                    expected: Self::temporal_signature(name).unwrap_or_default().into(),
+                    got: got.iter().map(|(ty, _)| *ty).collect(),
+                })
+            }
+
+            // CONCAT(a, b, ...) -> String, NULL when any input is NULL, like `||`
+            ("concat", many)
+                if !many.is_empty()
+                    && many.iter().all(|(ty, _)| {
+                        !matches!(ty, JsonPrimitive::Object | JsonPrimitive::Array)
+                    }) =>
+            {
+                Ok((
+                    JsonPrimitive::String,
+                    many.iter().any(|(_, nullable)| *nullable),
+                ))
+            }
+            ("concat", got) => Err(AnalyzerError::FunctionArgMismatch {
+                name: function.name.clone(),
+                expected: "CONCAT(value, ...)".into(),
+                got: got.iter().map(|(ty, _)| *ty).collect(),
+            }),
+
+            // SUBSTR, REPLACE, LPAD, ...: fixed signatures, NULL in gives NULL out
+            (name, got) if Self::string_signature(name).is_some() => {
+                Self::infer_string_function(function, got)
+            }
+
+            // JSON functions: a document of the wrong shape yields NULL at runtime
+            ("json_extract", [(doc, _), (JsonPrimitive::String, _)]) if Self::is_json_doc(*doc) => {
+                Ok((JsonPrimitive::Any, true))
+            }
+            ("json_array_length", [(doc, _)]) if Self::is_json_doc(*doc) => {
+                Ok((JsonPrimitive::Int, true))
+            }
+            ("json_keys", [(doc, _)]) if Self::is_json_doc(*doc) => {
+                Ok((JsonPrimitive::Array, true))
+            }
+            ("json_type", [_]) => Ok((JsonPrimitive::String, false)),
+            ("json_contains", [(doc, _), _]) if Self::is_json_doc(*doc) => {
+                Ok((JsonPrimitive::Bool, true))
+            }
+            ("json_object", pairs)
+                if pairs.len() % 2 == 0
+                    && pairs.iter().step_by(2).all(|(key, _)| {
+                        matches!(key, JsonPrimitive::String | JsonPrimitive::Any)
+                    }) =>
+            {
+                Ok((JsonPrimitive::Object, false))
+            }
+            ("json_array", _) => Ok((JsonPrimitive::Array, false)),
+            (name, got) if Self::json_signature(name).is_some() => {
+                Err(AnalyzerError::FunctionArgMismatch {
+                    name: function.name.clone(),
+                    expected: Self::json_signature(name).unwrap_or_default().into(),
+                    got: got.iter().map(|(ty, _)| *ty).collect(),
+                })
+            }
+
             _ => Err(AnalyzerError::FunctionNotFound(function.name.clone())),
         }
     }
+
+    /// The type a cast names. CAST also rejects a literal that does not convert
+    /// and a type that never does; row values that do not convert fail at runtime.
+    fn infer_cast(
+        function: &Function,
+        from: JsonPrimitive,
+        nullable: bool,
+    ) -> Result<(JsonPrimitive, bool), AnalyzerError> {
+        let name = function.name.to_ascii_uppercase();
+        let Some(ScalarExpr::Literal(Literal::String(ty))) = function.args.get(1) else {
+            return Err(AnalyzerError::Other(format!(
+                "{} needs a literal type",
+                name
+            )));
+        };
+        let to = CastResolver::target(ty)
+            .ok_or_else(|| AnalyzerError::Other(format!("unknown type '{}' in {}", ty, name)))?;
+        if name == "TRY_CAST" {
+            return Ok((to, true));
+        }
+
+        match function.args.first() {
+            Some(ScalarExpr::Literal(literal)) => {
+                let value = CastResolver::literal_value(literal);
+                if CastResolver::cast(&value, to).is_none() {
+                    return Err(AnalyzerError::Other(format!(
+                        "cannot cast {} to {:?}",
+                        value, to
+                    )));
+                }
+            }
+            _ if !CastResolver::castable(from, to) => {
+                return Err(AnalyzerError::Other(format!(
+                    "cannot cast {:?} to {:?}",
+                    from, to
+                )));
+            }
+            _ => {}
+        }
+        Ok((to, nullable || !CastResolver::infallible(from, to)))
+    }
+
+    fn is_text(ty: JsonPrimitive) -> bool {
+        matches!(
+            ty,
+            JsonPrimitive::String | JsonPrimitive::Any | JsonPrimitive::Null
+        )
+    }
+
+    /// Types a date function accepts: temporal columns or ISO-8601 strings.
+    fn is_temporal(ty: JsonPrimitive) -> bool {
+        Self::is_text(ty) || matches!(ty, JsonPrimitive::Date | JsonPrimitive::DateTime)
+    }
+
+    /// Dates stay dates; a plain string gives a string.
+    fn temporal_result(ty: JsonPrimitive) -> JsonPrimitive {
+        match ty {
+            JsonPrimitive::Date | JsonPrimitive::DateTime => ty,
+            _ => JsonPrimitive::String,
+        }
+    }
+
+    /// Checks a unit given as a literal; `None` when it is only known at runtime.
+    fn literal_unit(
+        function: &Function,
+        index: usize,
+        interval: bool,
+    ) -> Result<Option<DateUnit>, AnalyzerError> {
+        let Some(ScalarExpr::Literal(Literal::String(text))) = function.args.get(index) else {
+            return Ok(None);
+        };
+        match DateUnit::parse(text) {
+            Some(unit) if unit.is_interval() || !interval => Ok(Some(unit)),
+            _ => Err(AnalyzerError::Other(format!(
+                "unknown date unit '{}' in {}",
+                text,
+                function.name.to_ascii_uppercase()
+            ))),
+        }
+    }
+
+    fn temporal_signature(name: &str) -> Option<&'static str> {
+        match name {
+            "now" => Some("NOW()"),
+            "date_trunc" => Some("DATE_TRUNC(unit, date)"),
+            "extract" => Some("EXTRACT(unit FROM date)"),
+            "date_part" => Some("DATE_PART(unit, date)"),
+            "date_add" => Some("DATE_ADD(date, amount[, unit])"),
+            "date_diff" => Some("DATE_DIFF(unit, start, end)"),
+            "to_char" => Some("TO_CHAR(date, format)"),
+            "strftime" => Some("STRFTIME(format, date)"),
+            _ => None,
+        }
+    }
+
+    /// Types a math function accepts; a dynamic value that is not a number
+    /// yields NULL at runtime.
+    fn is_numeric(ty: JsonPrimitive) -> bool {
+        matches!(
+            ty,
+            JsonPrimitive::Int | JsonPrimitive::Float | JsonPrimitive::Any | JsonPrimitive::Null
+        )
+    }
+
+    fn math_signature(name: &str) -> Option<&'static str> {
+        match name {
+            "abs" => Some("ABS(number)"),
+            "sign" => Some("SIGN(number)"),
+            "floor" => Some("FLOOR(number)"),
+            "ceil" | "ceiling" => Some("CEIL(number)"),
+            "round" => Some("ROUND(number[, digits])"),
+            "mod" => Some("MOD(number, divisor)"),
+            "sqrt" => Some("SQRT(number)"),
+            "power" | "pow" => Some("POWER(base, exponent)"),
+            "random" => Some("RANDOM()"),
+            "greatest" => Some("GREATEST(value, ...)"),
+            "least" => Some("LEAST(value, ...)"),
+            _ => None,
+        }
+    }
+
+    /// Checks the arguments of a string function against its signature. A
+    /// dynamic argument of the wrong type yields NULL at runtime.
+    fn infer_string_function(
+        function: &Function,
+        got: &[(JsonPrimitive, bool)],
+    ) -> Result<(JsonPrimitive, bool), AnalyzerError> {
+        let lname = function.name.to_ascii_lowercase();
+        let Some((params, required, ty, signature)) = Self::string_signature(&lname) else {
+            return Err(AnalyzerError::FunctionNotFound(function.name.clone()));
+        };
+
+        let fits = got.len() >= required
+            && got.len() <= params.len()
+            && got.iter().zip(params).all(|((arg, _), param)| match param {
+                JsonPrimitive::String => Self::is_temporal(*arg),
+                _ => arg == param || matches!(arg, JsonPrimitive::Any | JsonPrimitive::Null),
+            });
+        if !fits {
+            return Err(AnalyzerError::FunctionArgMismatch {
+                name: function.name.clone(),
+                expected: format!("{}({})", lname.to_ascii_uppercase(), signature),
+                got: got.iter().map(|(ty, _)| *ty).collect(),
+            });
+        }
+
+        if lname == "regexp_replace" {
+            Self::check_regexp(function)?;
+        }
+
+        // a dynamic pattern or flag that is invalid also yields NULL
+        let nullable = lname == "regexp_replace"
+            || got.iter().any(|(arg, nullable)| {
+                *nullable || matches!(arg, JsonPrimitive::Any | JsonPrimitive::Null)
+            });
+        Ok((ty, nullable))
+    }
+
+    /// REGEXP_REPLACE rejects a literal pattern that does not compile and
+    /// literal flags other than `g` and `i`.
+    fn check_regexp(function: &Function) -> Result<(), AnalyzerError> {
+        if let Some(ScalarExpr::Literal(Literal::String(pattern))) = function.args.get(1)
+            && regex::Regex::new(pattern).is_err()
+        {
+            return Err(AnalyzerError::Other(format!(
+                "invalid regular expression '{}' in REGEXP_REPLACE",
+                pattern
+            )));
+        }
+        if let Some(ScalarExpr::Literal(Literal::String(flags))) = function.args.get(3)
+            && let Some(flag) = flags.chars().find(|flag| !matches!(flag, 'g' | 'i'))
+        {
+            return Err(AnalyzerError::Other(format!(
+                "unknown flag '{}' in REGEXP_REPLACE",
+                flag
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parameter types, how many of them are required, result type and the
+    /// parameter list shown in errors.
+    fn string_signature(
+        name: &str,
+    ) -> Option<(&'static [JsonPrimitive], usize, JsonPrimitive, &'static str)> {
+        use JsonPrimitive::{Bool, Int, String};
+        Some(match name {
+            "substr" | "substring" => (&[String, Int, Int], 2, String, "string, start[, length]"),
+            "replace" => (&[String, String, String], 3, String, "string, from, to"),
+            "position" => (&[String, String], 2, Int, "substring, string"),
+            "strpos" => (&[String, String], 2, Int, "string, substring"),
+            "ltrim" | "rtrim" => (&[String, String], 1, String, "string[, characters]"),
+            "lpad" | "rpad" => (&[String, Int, String], 2, String, "string, length[, fill]"),
+            "left" | "right" => (&[String, Int], 2, String, "string, count"),
+            "split_part" => (&[String, String, Int], 3, String, "string, delimiter, n"),
+            "starts_with" | "ends_with" => (&[String, String], 2, Bool, "string, affix"),
+            "regexp_replace" => (
+                &[String, String, String, String],
+                3,
+                String,
+                "string, pattern, replacement[, flags]",
+            ),
+            _ => return None,
+        })
+    }
+
+    /// Types a JSON function can read into.
+    fn is_json_doc(ty: JsonPrimitive) -> bool {
+        matches!(
+            ty,
+            JsonPrimitive::Object | JsonPrimitive::Array | JsonPrimitive::Any | JsonPrimitive::Null
+        )
+    }
+
+    fn json_signature(name: &str) -> Option<&'static str> {
+        match name {
+            "json_extract" => Some("JSON_EXTRACT(json, path)"),
+            "json_array_length" => Some("JSON_ARRAY_LENGTH(json)"),
+            "json_keys" => Some("JSON_KEYS(json)"),
+            "json_type" => Some("JSON_TYPE(value)"),
+            "json_contains" => Some("JSON_CONTAINS(json, value)"),
+            "json_object" => Some("JSON_OBJECT(key, value, ...)"),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -291,6 +793,421 @@ mod tests {
         assert!(!nullable);
     }
 
+    #[test]
+    fn infer_case_promotes_branch_types_and_is_nullable_without_else() {
+        let sp = DummySchemas::new().with("t", vec![("a", JsonPrimitive::Int, false)]);
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let case = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            ScalarExpr::parse(&mut parser, false).unwrap()
+        };
+
+        let (ty, nullable) =
+            TypeInference::infer_scalar(&case("CASE WHEN a > 1 THEN a ELSE 2.5 END"), &ctx)
+                .unwrap();
+        assert_eq!(ty, JsonPrimitive::Float);
+        assert!(!nullable);
+
+        let (ty, nullable) =
+            TypeInference::infer_scalar(&case("CASE a WHEN 1 THEN 'one' END"), &ctx).unwrap();
+        assert_eq!(ty, JsonPrimitive::String);
+        assert!(nullable);
+
+        let (ty, _) = TypeInference::infer_scalar(
+            &case("CASE WHEN a > 1 THEN NULL WHEN a > 0 THEN 1 ELSE 2.5 END"),
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(ty, JsonPrimitive::Float);
+
+        assert!(matches!(
+            TypeInference::infer_scalar(&case("CASE WHEN a > 30 THEN 'a' ELSE 1 END"), &ctx),
+            Err(AnalyzerError::Other(msg)) if msg == "CASE types String and Int cannot be matched"
+        ));
+    }
+
+    #[test]
+    fn infer_arithmetic_promotes_numbers_and_rejects_strings() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("a", JsonPrimitive::Int, false),
+                ("s", JsonPrimitive::String, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let expr = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            ScalarExpr::parse(&mut parser, false).unwrap()
+        };
+
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("a * 2 - -a"), &ctx).unwrap(),
+            (JsonPrimitive::Int, false)
+        );
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("a + 0.5"), &ctx).unwrap(),
+            (JsonPrimitive::Float, false)
+        );
+        // a zero divisor yields NULL
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("a / 2"), &ctx).unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("s || a"), &ctx).unwrap(),
+            (JsonPrimitive::String, false)
+        );
+        assert!(TypeInference::infer_scalar(&expr("s + 1"), &ctx).is_err());
+        assert!(TypeInference::infer_scalar(&expr("-s"), &ctx).is_err());
+    }
+
+    #[test]
+    fn infer_json_path_is_dynamic_and_nullable() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("doc", JsonPrimitive::Object, false),
+                ("tags", JsonPrimitive::Array, false),
+                ("s", JsonPrimitive::String, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let expr = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            ScalarExpr::parse(&mut parser, false).unwrap()
+        };
+
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("t.doc.city"), &ctx).unwrap(),
+            (JsonPrimitive::Any, true)
+        );
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("t.tags[0]"), &ctx).unwrap(),
+            (JsonPrimitive::Any, true)
+        );
+        // a dynamic operand is checked at runtime
+        assert_eq!(
+            TypeInference::infer_scalar(&expr("t.doc.n * 2"), &ctx).unwrap(),
+            (JsonPrimitive::Any, true)
+        );
+        assert!(TypeInference::infer_scalar(&expr("t.s.city"), &ctx).is_err());
+    }
+
+    #[test]
+    fn infer_date_functions() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("d", JsonPrimitive::Date, false),
+                ("ts", JsonPrimitive::DateTime, true),
+                ("s", JsonPrimitive::String, false),
+                ("i", JsonPrimitive::Int, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let infer = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            TypeInference::infer_scalar(&ScalarExpr::parse(&mut parser, false).unwrap(), &ctx)
+        };
+
+        assert_eq!(infer("now()").unwrap(), (JsonPrimitive::DateTime, false));
+        assert_eq!(
+            infer("date_trunc('month', d)").unwrap(),
+            (JsonPrimitive::Date, true)
+        );
+        assert_eq!(
+            infer("date_trunc('day', ts)").unwrap(),
+            (JsonPrimitive::DateTime, true)
+        );
+        assert_eq!(
+            infer("date_trunc('day', s)").unwrap(),
+            (JsonPrimitive::String, true)
+        );
+        assert_eq!(
+            infer("extract(dow FROM d)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(
+            infer("date_add(d, 7)").unwrap(),
+            (JsonPrimitive::Date, true)
+        );
+        assert_eq!(
+            infer("date_add(d, 2, 'hours')").unwrap(),
+            (JsonPrimitive::DateTime, true)
+        );
+        assert_eq!(
+            infer("date_diff('month', d, ts)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(
+            infer("strftime('%Y', ts)").unwrap(),
+            (JsonPrimitive::String, true)
+        );
+        // temporal values can be used where a string is expected
+        assert_eq!(
+            infer("substr(d, 1, 4)").unwrap(),
+            (JsonPrimitive::String, false)
+        );
+
+        assert!(matches!(
+            infer("date_trunc('fortnight', d)"),
+            Err(AnalyzerError::Other(msg)) if msg == "unknown date unit 'fortnight' in DATE_TRUNC"
+        ));
+        assert!(matches!(
+            infer("date_diff('epoch', d, ts)"),
+            Err(AnalyzerError::Other(_))
+        ));
+        assert!(matches!(
+            infer("extract(year FROM i)"),
+            Err(AnalyzerError::FunctionArgMismatch { expected, .. }) if expected == "EXTRACT(unit FROM date)"
+        ));
+        assert!(matches!(
+            infer("date_add(d, 'x')"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn infer_math_functions_keep_int_and_float() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("i", JsonPrimitive::Int, false),
+                ("f", JsonPrimitive::Float, true),
+                ("s", JsonPrimitive::String, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let infer = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            TypeInference::infer_scalar(&ScalarExpr::parse(&mut parser, false).unwrap(), &ctx)
+        };
+
+        assert_eq!(infer("abs(i)").unwrap(), (JsonPrimitive::Int, false));
+        assert_eq!(infer("floor(f)").unwrap(), (JsonPrimitive::Float, true));
+        assert_eq!(infer("round(i, -1)").unwrap(), (JsonPrimitive::Int, false));
+        assert_eq!(infer("round(f, 2)").unwrap(), (JsonPrimitive::Float, true));
+        assert_eq!(infer("mod(i, 2)").unwrap(), (JsonPrimitive::Int, true));
+        assert_eq!(infer("mod(i, 2.5)").unwrap(), (JsonPrimitive::Float, true));
+        assert_eq!(infer("power(i, 2)").unwrap(), (JsonPrimitive::Float, true));
+        assert_eq!(infer("random()").unwrap(), (JsonPrimitive::Float, false));
+        assert_eq!(
+            infer("greatest(i, f)").unwrap(),
+            (JsonPrimitive::Float, false)
+        );
+        assert_eq!(
+            infer("least(f, NULL)").unwrap(),
+            (JsonPrimitive::Float, true)
+        );
+
+        assert!(matches!(
+            infer("abs(s)"),
+            Err(AnalyzerError::FunctionArgMismatch { expected, .. }) if expected == "ABS(number)"
+        ));
+        assert!(matches!(
+            infer("round(f, 1.5)"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("random(1)"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("greatest(i, s)"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn infer_string_functions() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("s", JsonPrimitive::String, false),
+                ("n", JsonPrimitive::Int, true),
+                ("doc", JsonPrimitive::Object, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let infer = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            TypeInference::infer_scalar(&ScalarExpr::parse(&mut parser, false).unwrap(), &ctx)
+        };
+
+        assert_eq!(
+            infer("substr(s, 1, 2)").unwrap(),
+            (JsonPrimitive::String, false)
+        );
+        // nullable arguments and dynamic values make the result nullable
+        assert_eq!(infer("left(s, n)").unwrap(), (JsonPrimitive::String, true));
+        assert_eq!(
+            infer("lpad(t.doc.code, 3, '0')").unwrap(),
+            (JsonPrimitive::String, true)
+        );
+        assert_eq!(
+            infer("strpos(s, 'a')").unwrap(),
+            (JsonPrimitive::Int, false)
+        );
+        assert_eq!(
+            infer("starts_with(s, 'a')").unwrap(),
+            (JsonPrimitive::Bool, false)
+        );
+        assert_eq!(
+            infer("concat(s, 1, 2.5)").unwrap(),
+            (JsonPrimitive::String, false)
+        );
+        assert_eq!(
+            infer("regexp_replace(s, 'a', 'b')").unwrap(),
+            (JsonPrimitive::String, true)
+        );
+
+        assert!(matches!(
+            infer("substr(s)"),
+            Err(AnalyzerError::FunctionArgMismatch { expected, .. })
+                if expected == "SUBSTR(string, start[, length])"
+        ));
+        assert!(matches!(
+            infer("replace(s, 1, 'b')"),
+            Err(AnalyzerError::FunctionArgMismatch { got, .. })
+                if got == vec![JsonPrimitive::String, JsonPrimitive::Int, JsonPrimitive::String]
+        ));
+        assert!(matches!(
+            infer("concat(s, doc)"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("regexp_replace(s, '[a', 'b')"),
+            Err(AnalyzerError::Other(msg)) if msg == "invalid regular expression '[a' in REGEXP_REPLACE"
+        ));
+        assert!(matches!(
+            infer("regexp_replace(s, 'a', 'b', 'gx')"),
+            Err(AnalyzerError::Other(msg)) if msg == "unknown flag 'x' in REGEXP_REPLACE"
+        ));
+    }
+
+    #[test]
+    fn infer_json_functions() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("doc", JsonPrimitive::Object, false),
+                ("s", JsonPrimitive::String, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+
+        let infer = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            TypeInference::infer_scalar(&ScalarExpr::parse(&mut parser, false).unwrap(), &ctx)
+        };
+
+        assert_eq!(
+            infer("json_extract(doc, '$.a')").unwrap(),
+            (JsonPrimitive::Any, true)
+        );
+        assert_eq!(
+            infer("json_array_length(t.doc.items)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(
+            infer("json_keys(doc)").unwrap(),
+            (JsonPrimitive::Array, true)
+        );
+        assert_eq!(
+            infer("json_type(s)").unwrap(),
+            (JsonPrimitive::String, false)
+        );
+        assert_eq!(
+            infer("json_contains(t.doc.tags, 'x')").unwrap(),
+            (JsonPrimitive::Bool, true)
+        );
+        assert_eq!(
+            infer("json_object('s', s, 'n', 1)").unwrap(),
+            (JsonPrimitive::Object, false)
+        );
+        assert_eq!(
+            infer("json_array()").unwrap(),
+            (JsonPrimitive::Array, false)
+        );
+
+        assert!(matches!(
+            infer("json_keys(s)"),
+            Err(AnalyzerError::FunctionArgMismatch { got, .. }) if got == vec![JsonPrimitive::String]
+        ));
+        assert!(matches!(
+            infer("json_object('a')"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+        assert!(matches!(
+            infer("json_object(1, 2)"),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn infer_casts_to_the_named_type() {
+        let sp = DummySchemas::new().with(
+            "t",
+            vec![
+                ("code", JsonPrimitive::String, false),
+                ("n", JsonPrimitive::Int, false),
+                ("doc", JsonPrimitive::Object, true),
+                ("day", JsonPrimitive::Date, false),
+            ],
+        );
+        let ctx = ctx_with_table(&sp, "t", None);
+        let infer = |text: &str| {
+            let mut parser = crate::parser::QueryParser::new(text);
+            let expr = ScalarExpr::parse(&mut parser, false).unwrap();
+            TypeInference::infer_scalar(&expr, &ctx)
+        };
+
+        // conversions that may fail at runtime are nullable
+        assert_eq!(
+            infer("CAST(t.code AS Int)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(infer("t.n::float").unwrap(), (JsonPrimitive::Float, false));
+        assert_eq!(infer("t.n::text").unwrap(), (JsonPrimitive::String, false));
+        assert_eq!(
+            infer("t.day::timestamp").unwrap(),
+            (JsonPrimitive::DateTime, false)
+        );
+        assert_eq!(infer("'42'::int + 1").unwrap(), (JsonPrimitive::Int, true));
+        assert_eq!(
+            infer("TRY_CAST(t.doc AS Int)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+        assert_eq!(
+            infer("try_cast('abc' AS Int)").unwrap(),
+            (JsonPrimitive::Int, true)
+        );
+
+        assert!(matches!(
+            infer("CAST(t.doc AS Int)"),
+            Err(AnalyzerError::Other(msg)) if msg == "cannot cast Object to Int"
+        ));
+        assert!(matches!(
+            infer("CAST('abc' AS Int)"),
+            Err(AnalyzerError::Other(msg)) if msg == "cannot cast \"abc\" to Int"
+        ));
+        assert!(matches!(
+            infer("t.n::money"),
+            Err(AnalyzerError::Other(msg)) if msg == "unknown type 'money' in CAST"
+        ));
+        assert!(matches!(
+            TypeInference::infer_scalar(&fun("cast", vec![col("t", "n"), lit_i(1)]), &ctx),
+            Err(AnalyzerError::FunctionArgMismatch { .. })
+        ));
+    }
+
     #[test]
     fn infer_coalesce_all_inputs_nullable_yields_nullable() {
         let sp = DummySchemas::new();
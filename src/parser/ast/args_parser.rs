@@ -1,4 +1,7 @@
-use crate::parser::{ParseError, QueryParser, ast::ScalarExpr};
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{Literal, ScalarExpr},
+};
 
 #[derive(Debug, Default)]
 pub struct ArgsExpr {
@@ -64,12 +67,153 @@ impl ArgsExpr {
 
         Ok(expr)
     }
+
+    /// `(unit FROM expr)` becomes the arguments `('unit', expr)`; any other
+    /// argument list is parsed as usual, so `extract('year', expr)` also works.
+    pub fn parse_extract(parser: &mut QueryParser) -> Result<ArgsExpr, ParseError> {
+        let pivot = parser.position;
+        if !ArgsExpr::is_args_start(parser) {
+            return Err(ParseError::new("Invalid args value", pivot, parser));
+        }
+        parser.next();
+        parser.next_non_whitespace();
+        if !parser.current().is_ascii_alphabetic() {
+            parser.position = pivot;
+            return ArgsExpr::parse(parser, false);
+        }
+
+        let start = parser.position;
+        while parser.current().is_ascii_alphanumeric() || parser.current() == '_' {
+            parser.next();
+        }
+        let unit = parser.text_from_pivot(start).to_ascii_lowercase();
+        parser.next_non_whitespace();
+        if !parser.comparers.from.compare(parser) {
+            return Err(ParseError::new("Expected FROM in extract", pivot, parser));
+        }
+        parser.jump(parser.comparers.from.length);
+        let expr = ScalarExpr::parse(parser, false)?;
+
+        parser.next_non_whitespace();
+        if !ArgsExpr::is_args_end(parser) {
+            return Err(ParseError::new("Expected ) in extract", pivot, parser));
+        }
+        parser.next();
+
+        Ok(ArgsExpr {
+            args: vec![ScalarExpr::Literal(Literal::String(unit)), expr],
+            distinct: false,
+        })
+    }
+
+    /// `(expr AS type)` becomes the arguments `(expr, 'type')`.
+    pub fn parse_cast(parser: &mut QueryParser) -> Result<ArgsExpr, ParseError> {
+        let pivot = parser.position;
+        if !ArgsExpr::is_args_start(parser) {
+            return Err(ParseError::new("Invalid args value", pivot, parser));
+        }
+        parser.next();
+        let expr = ScalarExpr::parse(parser, false)?;
+
+        parser.next_non_whitespace();
+        if !parser.comparers.alias.compare(parser) {
+            return Err(ParseError::new("Expected AS in cast", pivot, parser));
+        }
+        parser.jump(parser.comparers.alias.length);
+        let ty = ArgsExpr::parse_type_name(parser)?;
+
+        parser.next_non_whitespace();
+        if !ArgsExpr::is_args_end(parser) {
+            return Err(ParseError::new("Expected ) in cast", pivot, parser));
+        }
+        parser.next();
+
+        Ok(ArgsExpr {
+            args: vec![expr, ScalarExpr::Literal(Literal::String(ty))],
+            distinct: false,
+        })
+    }
+
+    /// Type name of a cast, lowercased; whether it is a known type is up to the analyzer.
+    pub fn parse_type_name(parser: &mut QueryParser) -> Result<String, ParseError> {
+        parser.next_non_whitespace();
+        let start = parser.position;
+        while parser.current().is_ascii_alphanumeric() || parser.current() == '_' {
+            parser.next();
+        }
+        if parser.position == start {
+            return Err(ParseError::new("Expected type name", start, parser));
+        }
+        Ok(parser.text_from_pivot(start).to_ascii_lowercase())
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use crate::parser::{QueryParser, ast::ArgsExpr};
 
+    #[test]
+    pub fn test_args_extract() {
+        use crate::parser::ast::{Column, Literal, ScalarExpr};
+
+        let mut parser = QueryParser::new("(YEAR FROM e.created_at) + 1");
+        let result = ArgsExpr::parse_extract(&mut parser).expect("extract args should parse");
+        assert_eq!(
+            result.args,
+            vec![
+                ScalarExpr::Literal(Literal::String("year".into())),
+                ScalarExpr::Column(Column::WithCollection {
+                    collection: "e".into(),
+                    name: "created_at".into()
+                }),
+            ]
+        );
+        assert_eq!(parser.current(), ' ');
+
+        let mut parser = QueryParser::new("('month', created_at)");
+        let result = ArgsExpr::parse_extract(&mut parser).expect("plain args should parse");
+        assert_eq!(result.args.len(), 2);
+
+        for text in [
+            "(year created_at)",
+            "(year FROM created_at",
+            "(year, created_at)",
+        ] {
+            let mut parser = QueryParser::new(text);
+            assert!(
+                ArgsExpr::parse_extract(&mut parser).is_err(),
+                "{text} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_args_cast() {
+        use crate::parser::ast::{Column, Literal, ScalarExpr};
+
+        let mut parser = QueryParser::new("(o.user_id  as  Integer) = 1");
+        let result = ArgsExpr::parse_cast(&mut parser).expect("cast args should parse");
+        assert_eq!(
+            result.args,
+            vec![
+                ScalarExpr::Column(Column::WithCollection {
+                    collection: "o".into(),
+                    name: "user_id".into()
+                }),
+                ScalarExpr::Literal(Literal::String("integer".into())),
+            ]
+        );
+        assert_eq!(parser.current(), ' ');
+
+        for text in ["(x int)", "(x AS )", "(x AS int", "(x, 'int')"] {
+            let mut parser = QueryParser::new(text);
+            assert!(
+                ArgsExpr::parse_cast(&mut parser).is_err(),
+                "{text} should not parse"
+            );
+        }
+    }
+
     #[test]
     pub fn test_args_empty() {
         let text = "()";
@@ -0,0 +1,164 @@
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{ComparatorOp, Predicate, ScalarExpr},
+};
+
+/// `CASE WHEN condition THEN result ... [ELSE result] END`.
+/// A simple `CASE operand WHEN value ...` is stored in this searched form,
+/// each branch comparing the operand with its WHEN value.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Case {
+    pub branches: Vec<(Predicate, ScalarExpr)>,
+    pub otherwise: Option<Box<ScalarExpr>>, // NULL when no branch matches and ELSE is missing
+}
+
+impl Case {
+    pub fn is_case_start(parser: &QueryParser) -> bool {
+        parser.comparers.case.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Case, ParseError> {
+        let pivot = parser.position;
+        if !Case::is_case_start(parser) {
+            return ParseError::new("Invalid case", pivot, parser).err();
+        }
+        parser.jump(parser.comparers.case.length);
+        parser.next_non_whitespace();
+
+        let operand = if parser.comparers.when.compare(parser) {
+            None
+        } else {
+            Some(ScalarExpr::parse(parser, false)?)
+        };
+
+        let mut branches = vec![];
+        loop {
+            parser.next_non_whitespace();
+            if !parser.comparers.when.compare(parser) {
+                break;
+            }
+            parser.jump(parser.comparers.when.length);
+
+            let condition = match &operand {
+                Some(operand) => Predicate::Compare {
+                    left: operand.clone(),
+                    op: ComparatorOp::Eq,
+                    right: ScalarExpr::parse(parser, false)?,
+                },
+                None => Predicate::parse(parser, false)?,
+            };
+
+            parser.next_non_whitespace();
+            if !parser.comparers.then.compare(parser) {
+                return ParseError::new("Expected THEN in case", parser.position, parser).err();
+            }
+            parser.jump(parser.comparers.then.length);
+
+            branches.push((condition, ScalarExpr::parse(parser, false)?));
+        }
+
+        if branches.is_empty() {
+            return ParseError::new("Expected WHEN in case", pivot, parser).err();
+        }
+
+        let mut otherwise = None;
+        if parser.comparers.r#else.compare(parser) {
+            parser.jump(parser.comparers.r#else.length);
+            otherwise = Some(Box::new(ScalarExpr::parse(parser, false)?));
+            parser.next_non_whitespace();
+        }
+
+        if !parser.comparers.end.compare(parser) {
+            return ParseError::new("Expected END in case", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.end.length);
+
+        Ok(Case {
+            branches,
+            otherwise,
+        })
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CASE")?;
+        for (condition, result) in &self.branches {
+            write!(f, " WHEN {} THEN {}", condition, result)?;
+        }
+        if let Some(otherwise) = &self.otherwise {
+            write!(f, " ELSE {}", otherwise)?;
+        }
+        write!(f, " END")
+    }
+}
+
+impl fmt::Debug for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Case({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{Case, ComparatorOp, Literal, Predicate, ScalarExpr},
+    };
+
+    #[test]
+    pub fn test_searched_case() {
+        let mut parser = QueryParser::new(
+            "CASE WHEN o.total > 20 AND o.paid = true THEN 'big' WHEN o.total IS NULL THEN 'none' ELSE 'small' END AS size",
+        );
+
+        let result = Case::parse(&mut parser).expect("Failed to parse case");
+
+        assert_eq!(result.branches.len(), 2);
+        assert!(matches!(result.branches[0].0, Predicate::And(_)));
+        assert!(matches!(result.branches[1].0, Predicate::IsNull { .. }));
+        assert_eq!(
+            result.otherwise.as_deref(),
+            Some(&ScalarExpr::Literal(Literal::String("small".into())))
+        );
+        assert_eq!(parser.current(), ' ');
+    }
+
+    #[test]
+    pub fn test_simple_case_without_else() {
+        let mut parser =
+            QueryParser::new("case status when 1 then 'open' when 2 then 'closed' end");
+
+        let result = Case::parse(&mut parser).expect("Failed to parse case");
+
+        assert_eq!(result.branches.len(), 2);
+        assert!(matches!(
+            &result.branches[1].0,
+            Predicate::Compare {
+                op: ComparatorOp::Eq,
+                right: ScalarExpr::Literal(Literal::Int(2)),
+                ..
+            }
+        ));
+        assert!(result.otherwise.is_none());
+        assert!(parser.eof());
+        assert_eq!(
+            result.to_string(),
+            "CASE WHEN col: status = lit: i: 1 THEN lit: s: \"open\" WHEN col: status = lit: i: 2 THEN lit: s: \"closed\" END"
+        );
+    }
+
+    #[test]
+    pub fn test_case_errors() {
+        for text in [
+            "CASE ELSE 1 END",
+            "CASE WHEN a = 1 'x' END",
+            "CASE WHEN a = 1 THEN 'x'",
+        ] {
+            let mut parser = QueryParser::new(text);
+            assert!(Case::parse(&mut parser).is_err(), "{text} should not parse");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::parser::{
     ParseError, QueryParser,
-    ast::{Query, TextCollector},
+    ast::{Query, ScalarExpr, TextCollector},
 };
 
 #[derive(Clone, PartialEq)]
@@ -13,15 +13,35 @@ pub enum Collection {
         query: Box<Query>,
         alias: Option<String>,
     },
+    /// `UNNEST(expr) [AS] alias[(column)]`: one row per element of the array `expr`,
+    /// which can read the collections before it. `column` defaults to the alias.
+    Unnest {
+        expr: ScalarExpr,
+        alias: String,
+        column: String,
+    },
 }
 
 impl Collection {
+    /// How many `?` placeholders this source holds.
+    pub fn parameter_count(&self) -> usize {
+        match self {
+            Collection::Table { .. } => 0,
+            Collection::Query { query, .. } => query.parameter_count(),
+            Collection::Unnest { expr, .. } => expr.parameter_count(),
+        }
+    }
+
     pub fn parse(parser: &mut QueryParser) -> Result<Collection, ParseError> {
         parser.next_non_whitespace();
         if parser.current() == '(' {
             return Self::parse_query(parser);
         }
 
+        if parser.comparers.unnest.compare(parser) {
+            return Self::parse_unnest(parser);
+        }
+
         let name = TextCollector::collect_with_stopper(parser, |current| current == '.')?;
 
         parser.next_non_whitespace();
@@ -51,7 +71,7 @@ impl Collection {
     fn parse_query(parser: &mut QueryParser) -> Result<Collection, ParseError> {
         let pivot = parser.position;
         let query_text = Self::collect_parenthesized_query(parser)?;
-        let query = Query::try_from(query_text.trim())
+        let query = Query::parse_nested(parser, query_text.trim())
             .map_err(|_| ParseError::new("Invalid subquery", pivot, parser))?;
 
         parser.next_non_whitespace();
@@ -83,7 +103,63 @@ impl Collection {
         ParseError::new("Invalid collection", pivot, parser).err()
     }
 
-    fn collect_parenthesized_query(parser: &mut QueryParser) -> Result<String, ParseError> {
+    fn parse_unnest(parser: &mut QueryParser) -> Result<Collection, ParseError> {
+        let pivot = parser.position;
+        parser.jump(parser.comparers.unnest.length);
+        parser.next_non_whitespace();
+        if parser.current() != '(' {
+            return ParseError::new("Expected ( after UNNEST", pivot, parser).err();
+        }
+        parser.next();
+        let expr = ScalarExpr::parse(parser, false)?;
+        parser.next_non_whitespace();
+        if parser.current() != ')' {
+            return ParseError::new("Expected ) in unnest", pivot, parser).err();
+        }
+        parser.next();
+
+        parser.next_non_whitespace();
+        if parser.comparers.alias.compare(parser) {
+            parser.jump(parser.comparers.alias.length);
+            parser.next_non_whitespace();
+        }
+
+        if parser.eof()
+            || parser.current() == ','
+            || parser.comparers.on.compare(parser)
+            || parser.check_next_phase()
+        {
+            return ParseError::new("Unnest requires an alias", parser.position, parser).err();
+        }
+
+        let alias = TextCollector::collect(parser)?;
+        let mut column = alias.clone();
+        if parser.current() == '(' {
+            let column_pivot = parser.position;
+            parser.next();
+            column = TextCollector::collect(parser)?;
+            parser.next_non_whitespace();
+            if column.is_empty() || parser.current() != ')' {
+                return ParseError::new("Invalid unnest column", column_pivot, parser).err();
+            }
+            parser.next();
+        }
+        parser.next_non_whitespace();
+
+        let next_phase = parser.check_next_phase();
+        let pivot = parser.position;
+        if parser.current() == ',' || next_phase || parser.comparers.on.compare(parser) {
+            return Ok(Collection::Unnest {
+                expr,
+                alias,
+                column,
+            });
+        }
+
+        ParseError::new("Invalid collection", pivot, parser).err()
+    }
+
+    pub fn collect_parenthesized_query(parser: &mut QueryParser) -> Result<String, ParseError> {
         let pivot = parser.position;
         parser.next();
         let start = parser.position;
@@ -134,6 +210,11 @@ impl fmt::Display for Collection {
                     write!(f, "Query")
                 }
             }
+            Collection::Unnest {
+                expr,
+                alias,
+                column,
+            } => write!(f, "Unnest({}) as {}({})", expr, alias, column),
         }
     }
 }
@@ -161,7 +242,35 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias, None);
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    pub fn test_collection_unnest() {
+        let mut parser = QueryParser::new("UNNEST(p.tags) AS t(tag) WHERE t.tag = 'a'");
+
+        let result = Collection::parse(&mut parser).expect("Failed to parse unnest");
+
+        assert_eq!(
+            result.to_string(),
+            "Unnest(col: p.tags) as t(tag)".to_string()
+        );
+
+        let mut parser = QueryParser::new("unnest (tags) tag, other");
+        match Collection::parse(&mut parser).expect("Failed to parse unnest") {
+            Collection::Unnest { alias, column, .. } => {
+                assert_eq!((alias.as_str(), column.as_str()), ("tag", "tag"))
+            }
+            other => panic!("expected unnest, got {other:?}"),
+        }
+
+        for text in ["UNNEST(tags)", "UNNEST(tags t", "UNNEST(tags) t()"] {
+            let mut parser = QueryParser::new(text);
+            assert!(
+                Collection::parse(&mut parser).is_err(),
+                "{text} should not parse"
+            );
         }
     }
 
@@ -178,7 +287,7 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias.unwrap(), "a");
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -195,7 +304,7 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias.as_deref(), Some("a"));
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -212,7 +321,7 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias.unwrap(), "a");
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -229,7 +338,7 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias.unwrap(), "a");
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -246,7 +355,7 @@ mod tests {
                 assert_eq!(name, "table");
                 assert_eq!(alias.unwrap(), "a");
             }
-            Collection::Query { .. } => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -1,6 +1,6 @@
 use crate::parser::{
     ParseError, QueryParser, WordComparer,
-    ast::{ArgsExpr, Function, ScalarExpr, TextCollector},
+    ast::{ArgsExpr, ArithmeticOp, Function, JsonPath, ScalarExpr, TextCollector, Window},
 };
 use std::fmt;
 
@@ -30,6 +30,7 @@ impl Column {
         let mut args_expr: Option<ArgsExpr> = None;
         let mut name = "".to_string();
         let mut is_wildcard = false;
+        let mut path = vec![];
 
         let mut text = String::new();
 
@@ -38,6 +39,15 @@ impl Column {
         }
 
         while !parser.eof() && !WordComparer::is_any_delimiter(parser.current()) {
+            // an operator or a `::type` cast ends the scalar, except `*` where a name is
+            // expected (`*`, `coll.*`)
+            if (ArithmeticOp::is_operator_char(parser.current())
+                && !(parser.current() == '*' && parser.position == pivot))
+                || parser.current() == ':'
+            {
+                break;
+            }
+
             if args_expr.is_some() {
                 return Err(ParseError::new("Invalid function", pivot, parser));
             }
@@ -46,20 +56,28 @@ impl Column {
                 return Err(ParseError::new("Invalid wildcard", pivot, parser));
             }
 
-            text = TextCollector::collect_with_stopper(parser, |current| current == '*')?;
+            text = TextCollector::collect_with_stopper(parser, JsonPath::is_name_stopper)?;
 
             let current = parser.current();
-            if current == '.' {
-                if collection.is_some() {
+            // `coll.col.key` or `col[0]`: everything after the column is a path into its value
+            if (current == '.' && collection.is_some()) || current == '[' {
+                if text.is_empty() {
                     return Err(ParseError::new("Invalid column", pivot, parser));
                 }
+                path = JsonPath::parse_segments(parser)?;
+                break;
+            } else if current == '.' {
                 collection = Some(text.clone());
                 pivot = parser.position + 1;
                 parser.next();
             } else if parser.current() == '(' {
                 name = text.clone();
-                args_expr = Some(ArgsExpr::parse(parser, allow_wildcard)?);
-            } else if current == '*' {
+                args_expr = Some(match name.to_ascii_lowercase().as_str() {
+                    "extract" => ArgsExpr::parse_extract(parser)?,
+                    "cast" | "try_cast" => ArgsExpr::parse_cast(parser)?,
+                    _ => ArgsExpr::parse(parser, allow_wildcard)?,
+                });
+            } else if current == '*' && text.is_empty() {
                 is_wildcard = true;
                 parser.next();
             }
@@ -79,21 +97,36 @@ impl Column {
                 None => ScalarExpr::WildCard,
             },
             false => match args_expr {
-                Some(args_expr) => ScalarExpr::Function(Function {
-                    name: format!(
-                        "{}{}",
-                        collection.map_or("".to_string(), |coll| format!("{}.", coll)),
-                        name
-                    ),
-                    args: args_expr.args,
-                    distinct: args_expr.distinct,
-                }),
-                None => match collection {
-                    Some(collection) => {
-                        ScalarExpr::Column(Column::WithCollection { collection, name })
+                Some(args_expr) => {
+                    let function = Function {
+                        name: format!(
+                            "{}{}",
+                            collection.map_or("".to_string(), |coll| format!("{}.", coll)),
+                            name
+                        ),
+                        args: args_expr.args,
+                        distinct: args_expr.distinct,
+                    };
+                    let pivot = parser.position;
+                    parser.next_non_whitespace();
+                    match Window::is_over_start(parser) {
+                        true => ScalarExpr::Window(Window::parse(parser, function)?),
+                        false => {
+                            parser.position = pivot;
+                            ScalarExpr::Function(function)
+                        }
                     }
-                    None => ScalarExpr::Column(Column::Name { name }),
-                },
+                }
+                None => {
+                    let column = match collection {
+                        Some(collection) => Column::WithCollection { collection, name },
+                        None => Column::Name { name },
+                    };
+                    match path.is_empty() {
+                        true => ScalarExpr::Column(column),
+                        false => ScalarExpr::JsonPath { column, path },
+                    }
+                }
             },
         };
 
@@ -125,7 +158,7 @@ impl fmt::Debug for Column {
 mod tests {
     use crate::parser::{
         QueryParser,
-        ast::{Column, Function, ScalarExpr},
+        ast::{Column, Function, PathSegment, ScalarExpr},
     };
 
     fn parse_scalar(text: &str, allow_wildcard: bool) -> ScalarExpr {
@@ -231,11 +264,38 @@ mod tests {
     }
 
     #[test]
-    pub fn test_column_name_error_dot() {
-        let text = "col.column.err";
+    pub fn test_column_json_path() {
+        assert_eq!(
+            parse_scalar("p.profile.city = 'Paris'", false),
+            ScalarExpr::JsonPath {
+                column: Column::WithCollection {
+                    collection: "p".to_string(),
+                    name: "profile".to_string()
+                },
+                path: vec![PathSegment::Key("city".to_string())],
+            }
+        );
+        assert_eq!(
+            parse_scalar("items[0].sku,", false),
+            ScalarExpr::JsonPath {
+                column: Column::Name {
+                    name: "items".to_string()
+                },
+                path: vec![PathSegment::Index(0), PathSegment::Key("sku".to_string())],
+            }
+        );
+        assert_eq!(parse_scalar("tags[1]", false).to_string(), "col: tags[1]");
+    }
 
-        let (start, end, text) = parse_error(text, false);
-        assert_eq!((start, end, text), (4, 10, "column.".to_string()));
+    #[test]
+    pub fn test_column_json_path_errors() {
+        for text in ["col.column.", "col.column.*", "tags[x]", "tags[0", "[0]"] {
+            let mut parser = QueryParser::new(text);
+            assert!(
+                Column::parse_column_or_function(&mut parser).is_err(),
+                "{text} should not parse"
+            );
+        }
     }
 
     #[test]
@@ -0,0 +1,188 @@
+use crate::parser::{ParseError, QueryParser, ast::TextCollector};
+
+#[derive(Clone, PartialEq)]
+pub struct ColumnDef {
+    pub name: String,
+    /// Compact schema type spec, e.g. `Id`, `Uuid`, `None:String`, `String!` or `Int`.
+    pub type_spec: String,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct CreateCollection {
+    pub name: String,
+    pub if_not_exists: bool,
+    pub columns: Vec<ColumnDef>,
+}
+
+impl CreateCollection {
+    pub fn is_create_collection_start(parser: &QueryParser) -> bool {
+        parser.comparers.create_collection.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<CreateCollection, ParseError> {
+        if !CreateCollection::is_create_collection_start(parser) {
+            return ParseError::new("Invalid create collection", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.create_collection.length);
+        parser.next_non_whitespace();
+
+        let if_not_exists = parser.comparers.if_not_exists.compare(parser);
+        if if_not_exists {
+            parser.jump(parser.comparers.if_not_exists.length);
+            parser.next_non_whitespace();
+        }
+
+        let pivot = parser.position;
+        let name = TextCollector::collect(parser)?;
+        if name.is_empty() {
+            return ParseError::new("Invalid collection name", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        if parser.current() != '(' {
+            return ParseError::new("Expected column definitions", parser.position, parser).err();
+        }
+        let columns = Self::parse_columns(parser)?;
+
+        parser.next_non_whitespace();
+        if !parser.eof() {
+            return ParseError::new("Invalid create collection", parser.position, parser).err();
+        }
+
+        Ok(CreateCollection {
+            name,
+            if_not_exists,
+            columns,
+        })
+    }
+
+    fn parse_columns(parser: &mut QueryParser) -> Result<Vec<ColumnDef>, ParseError> {
+        parser.next();
+
+        let mut columns = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let pivot = parser.position;
+            let name = TextCollector::collect(parser)?;
+            parser.next_non_whitespace();
+            let type_spec = Self::collect_type_spec(parser);
+            if name.is_empty() || type_spec.is_empty() {
+                return ParseError::new("Invalid column definition", pivot, parser).err();
+            }
+            columns.push(ColumnDef { name, type_spec });
+
+            parser.next_non_whitespace();
+            match parser.current() {
+                ',' => parser.next(),
+                ')' => {
+                    parser.next();
+                    return Ok(columns);
+                }
+                _ => return ParseError::new("Invalid column definition", pivot, parser).err(),
+            }
+        }
+    }
+
+    fn collect_type_spec(parser: &mut QueryParser) -> String {
+        let pivot = parser.position;
+        while !parser.eof()
+            && (parser.current().is_ascii_alphanumeric() || matches!(parser.current(), ':' | '!'))
+        {
+            parser.next();
+        }
+        parser.text_from_pivot(pivot)
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for ColumnDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.type_spec)
+    }
+}
+
+impl fmt::Debug for ColumnDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ColumnDef({})", self)
+    }
+}
+
+impl fmt::Display for CreateCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| format!("{}", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "CreateCollection(name={}, if_not_exists={}, columns=[{}])",
+            self.name, self.if_not_exists, columns
+        )
+    }
+}
+
+impl fmt::Debug for CreateCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{QueryParser, ast::CreateCollection};
+
+    #[test]
+    pub fn test_create_collection() {
+        let text = "CREATE COLLECTION users (user_id Id, name String!, age Int, tag None:String)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CreateCollection::parse(&mut parser).expect("Failed to parse create");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "CreateCollection(name=users, if_not_exists=false, columns=[user_id Id, name String!, age Int, tag None:String])"
+        );
+    }
+
+    #[test]
+    pub fn test_create_collection_if_not_exists() {
+        let text = "create collection if not exists users(\n  user_id Uuid\n)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CreateCollection::parse(&mut parser).expect("Failed to parse create");
+
+        assert!(result.if_not_exists);
+        assert_eq!(result.name, "users");
+        assert_eq!(result.columns.len(), 1);
+        assert_eq!(result.columns[0].type_spec, "Uuid");
+    }
+
+    #[test]
+    pub fn test_create_collection_without_columns() {
+        let text = "CREATE COLLECTION users";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CreateCollection::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Expected column definitions");
+    }
+
+    #[test]
+    pub fn test_create_collection_column_without_type() {
+        let text = "CREATE COLLECTION users (user_id Id, name)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CreateCollection::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid column definition");
+    }
+}
@@ -0,0 +1,197 @@
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{Collection, Query, TextCollector},
+};
+
+/// `name [(column, ...)] AS (query)` from a WITH clause.
+#[derive(Clone, PartialEq)]
+pub struct Cte {
+    /// declared in a `WITH RECURSIVE` clause, so the query may read its own name
+    pub recursive: bool,
+    pub name: String,
+    pub columns: Vec<String>, // renames the query outputs when present
+    pub query: Query,
+}
+
+pub struct CteParser;
+
+impl CteParser {
+    pub fn is_with_start(parser: &QueryParser) -> bool {
+        parser.comparers.with.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Vec<Cte>, ParseError> {
+        if !CteParser::is_with_start(parser) {
+            return ParseError::new("Invalid with", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.with.length);
+        parser.next_non_whitespace();
+
+        let recursive = parser.comparers.recursive.compare(parser);
+        if recursive {
+            parser.jump(parser.comparers.recursive.length);
+        }
+
+        let mut ctes = vec![];
+        loop {
+            parser.next_non_whitespace();
+            ctes.push(Self::parse_single(parser, recursive)?);
+
+            parser.next_non_whitespace();
+            if parser.current() != ',' {
+                return Ok(ctes);
+            }
+            parser.next();
+        }
+    }
+
+    fn parse_single(parser: &mut QueryParser, recursive: bool) -> Result<Cte, ParseError> {
+        let pivot = parser.position;
+        let name = TextCollector::collect(parser)?;
+        if name.is_empty() {
+            return ParseError::new("Invalid CTE name", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        let mut columns = vec![];
+        if parser.current() == '(' {
+            columns = Self::parse_columns(parser)?;
+            parser.next_non_whitespace();
+        }
+
+        if !parser.comparers.alias.compare(parser) {
+            return ParseError::new("Expected AS in CTE", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.alias.length);
+        parser.next_non_whitespace();
+
+        let pivot = parser.position;
+        if parser.current() != '(' {
+            return ParseError::new("Expected CTE query", pivot, parser).err();
+        }
+        let text = Collection::collect_parenthesized_query(parser)?;
+        let query = Query::parse_nested(parser, text.trim())
+            .map_err(|_| ParseError::new("Invalid CTE query", pivot, parser))?;
+
+        Ok(Cte {
+            recursive,
+            name,
+            columns,
+            query,
+        })
+    }
+
+    fn parse_columns(parser: &mut QueryParser) -> Result<Vec<String>, ParseError> {
+        let pivot = parser.position;
+        parser.next();
+
+        let mut columns = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let column = TextCollector::collect(parser)?;
+            if column.is_empty() {
+                return ParseError::new("Invalid CTE columns", pivot, parser).err();
+            }
+            columns.push(column);
+
+            parser.next_non_whitespace();
+            match parser.current() {
+                ',' => parser.next(),
+                ')' => {
+                    parser.next();
+                    return Ok(columns);
+                }
+                _ => return ParseError::new("Invalid CTE columns", pivot, parser).err(),
+            }
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Cte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        if self.columns.is_empty() {
+            write!(f, "{} AS {}", self.name, self.query)
+        } else {
+            write!(
+                f,
+                "{}({}) AS {}",
+                self.name,
+                self.columns.join(", "),
+                self.query
+            )
+        }
+    }
+}
+
+impl fmt::Debug for Cte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cte({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{QueryParser, ast::CteParser};
+
+    #[test]
+    pub fn test_with() {
+        let text = "WITH big AS (SELECT id FROM orders WHERE total > 10), \
+                    totals (customer, total) AS (SELECT customer, SUM(total) FROM big GROUP BY customer) \
+                    SELECT * FROM totals";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CteParser::parse(&mut parser).expect("Failed to parse with");
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].name, "big");
+        assert!(result[0].columns.is_empty());
+        assert!(result[0].query.criteria.is_some());
+        assert_eq!(result[1].columns, vec!["customer", "total"]);
+        assert_eq!(result[1].query.group_by.len(), 1);
+        assert!(parser.comparers.select.compare(&parser));
+    }
+
+    #[test]
+    pub fn test_with_without_as() {
+        let mut parser = QueryParser::new("WITH big (SELECT 1 FROM t) SELECT * FROM big");
+
+        let result = CteParser::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid CTE columns");
+    }
+
+    #[test]
+    pub fn test_with_invalid_query() {
+        let mut parser = QueryParser::new("WITH big AS (SELEC id FROM t) SELECT * FROM big");
+
+        let result = CteParser::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid CTE query");
+    }
+
+    #[test]
+    pub fn test_with_recursive() {
+        let text = "WITH RECURSIVE tree (id, root) AS \
+                    (SELECT id, id FROM regions WHERE parent_id IS NULL \
+                     UNION ALL SELECT r.id, t.root FROM regions r JOIN tree t ON r.parent_id = t.id) \
+                    SELECT * FROM tree";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = CteParser::parse(&mut parser).expect("Failed to parse with recursive");
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].recursive);
+        assert_eq!(result[0].name, "tree");
+        assert_eq!(result[0].query.set_operations.len(), 1);
+        assert!(format!("{:?}", result[0]).starts_with("Cte(RECURSIVE tree(id, root) AS "));
+    }
+}
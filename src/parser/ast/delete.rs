@@ -0,0 +1,133 @@
+use crate::parser::{
+    ParseError, Phase, QueryParser,
+    ast::{Identifier, Predicate, ReturningParser, TextCollector, WhereParser},
+};
+
+#[derive(Clone, PartialEq)]
+pub struct Delete {
+    pub collection: String,
+    pub criteria: Option<Predicate>,
+    pub returning: Vec<Identifier>,
+}
+
+impl Delete {
+    pub fn is_delete_start(parser: &QueryParser) -> bool {
+        parser.comparers.delete_from.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Delete, ParseError> {
+        if !Delete::is_delete_start(parser) {
+            return ParseError::new("Invalid delete", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.delete_from.length);
+        parser.next_non_whitespace();
+
+        let pivot = parser.position;
+        let collection = TextCollector::collect(parser)?;
+        if collection.is_empty() {
+            return ParseError::new("Invalid delete collection", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        let mut criteria = None;
+        if parser.comparers.r#where.compare(parser) {
+            parser.phase = Phase::Criteria;
+            criteria = Some(WhereParser::parse(parser)?);
+        }
+
+        let returning = ReturningParser::parse_optional(parser)?;
+        if !parser.eof() {
+            return ParseError::new("Invalid delete", parser.position, parser).err();
+        }
+
+        Ok(Delete {
+            collection,
+            criteria,
+            returning,
+        })
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Delete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let crit = match &self.criteria {
+            Some(c) => format!("{:?}", c),
+            None => "None".to_string(),
+        };
+        write!(
+            f,
+            "Delete(collection={}, criteria={}{})",
+            self.collection,
+            crit,
+            ReturningParser::display_suffix(&self.returning)
+        )
+    }
+}
+
+impl fmt::Debug for Delete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{Delete, Predicate},
+    };
+
+    #[test]
+    pub fn test_delete() {
+        let text = "DELETE FROM people WHERE age > 30 AND name LIKE 'A%'";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Delete::parse(&mut parser).expect("Failed to parse delete");
+
+        assert_eq!(result.collection, "people");
+        assert!(matches!(result.criteria, Some(Predicate::And(ref list)) if list.len() == 2));
+    }
+
+    #[test]
+    pub fn test_delete_without_where() {
+        let text = "delete from people";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Delete::parse(&mut parser).expect("Failed to parse delete");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "Delete(collection=people, criteria=None)"
+        );
+    }
+
+    #[test]
+    pub fn test_delete_trailing_clause() {
+        let text = "DELETE FROM people LIMIT 1";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Delete::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid delete");
+    }
+
+    #[test]
+    pub fn test_delete_returning() {
+        let text = "DELETE FROM people WHERE age > 30 RETURNING id";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Delete::parse(&mut parser).expect("Failed to parse delete");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "Delete(collection=people, criteria=Predicate(col: age > lit: i: 30), returning=[col: id])"
+        );
+    }
+}
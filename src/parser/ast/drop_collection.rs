@@ -0,0 +1,95 @@
+use crate::parser::{ParseError, QueryParser, ast::TextCollector};
+
+#[derive(Clone, PartialEq)]
+pub struct DropCollection {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+impl DropCollection {
+    pub fn is_drop_collection_start(parser: &QueryParser) -> bool {
+        parser.comparers.drop_collection.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<DropCollection, ParseError> {
+        if !DropCollection::is_drop_collection_start(parser) {
+            return ParseError::new("Invalid drop collection", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.drop_collection.length);
+        parser.next_non_whitespace();
+
+        let if_exists = parser.comparers.if_exists.compare(parser);
+        if if_exists {
+            parser.jump(parser.comparers.if_exists.length);
+            parser.next_non_whitespace();
+        }
+
+        let pivot = parser.position;
+        let name = TextCollector::collect(parser)?;
+        if name.is_empty() {
+            return ParseError::new("Invalid collection name", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        if !parser.eof() {
+            return ParseError::new("Invalid drop collection", parser.position, parser).err();
+        }
+
+        Ok(DropCollection { name, if_exists })
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for DropCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DropCollection(name={}, if_exists={})",
+            self.name, self.if_exists
+        )
+    }
+}
+
+impl fmt::Debug for DropCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{QueryParser, ast::DropCollection};
+
+    #[test]
+    pub fn test_drop_collection() {
+        let mut parser = QueryParser::new("DROP COLLECTION users");
+
+        let result = DropCollection::parse(&mut parser).expect("Failed to parse drop");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "DropCollection(name=users, if_exists=false)"
+        );
+    }
+
+    #[test]
+    pub fn test_drop_collection_if_exists() {
+        let mut parser = QueryParser::new("drop collection if exists users");
+
+        let result = DropCollection::parse(&mut parser).expect("Failed to parse drop");
+
+        assert!(result.if_exists);
+        assert_eq!(result.name, "users");
+    }
+
+    #[test]
+    pub fn test_drop_collection_trailing_text() {
+        let mut parser = QueryParser::new("DROP COLLECTION users cascade");
+
+        let result = DropCollection::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid drop collection");
+    }
+}
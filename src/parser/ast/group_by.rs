@@ -1,18 +1,24 @@
-use crate::parser::{
-    ParseError, QueryParser,
-    ast::{Column, ScalarExpr},
-};
+use crate::parser::{ParseError, QueryParser, ast::ScalarExpr};
 
 pub struct GroupBy;
 
 impl GroupBy {
-    pub fn parse(parser: &mut QueryParser) -> Result<Vec<Column>, ParseError> {
+    /// Parse `GROUP BY item, ...` where an item is an expression (a column or something such as
+    /// `CASE`, but not a position), `ROLLUP(...)`, `CUBE(...)` or `GROUPING SETS (...)`.
+    ///
+    /// Returns the distinct grouping expressions and the sets to group by, as indexes into them;
+    /// the sets are empty for a plain `GROUP BY`, which groups by every expression at once.
+    pub fn parse(
+        parser: &mut QueryParser,
+    ) -> Result<(Vec<ScalarExpr>, Vec<Vec<usize>>), ParseError> {
         if !parser.comparers.group_by.compare(parser) {
             return ParseError::new("Invalid group by", parser.position, parser).err();
         }
         parser.jump(parser.comparers.group_by.length);
 
-        let mut groups: Vec<Column> = vec![];
+        let mut groups: Vec<ScalarExpr> = vec![];
+        let mut items: Vec<Vec<Vec<usize>>> = vec![];
+        let mut has_sets = false;
         let mut can_consume = true;
         while !parser.check_next_phase() {
             if parser.current() == ',' {
@@ -24,11 +30,9 @@ impl GroupBy {
                 parser.next_non_whitespace();
             }
             if can_consume {
-                let expr = ScalarExpr::parse(parser, false)?;
-                match expr {
-                    ScalarExpr::Column(column) => groups.push(column),
-                    _ => return ParseError::new("Invalid group by", parser.position, parser).err(),
-                };
+                let (sets, is_sets) = Self::parse_item(parser, &mut groups)?;
+                items.push(sets);
+                has_sets |= is_sets;
                 parser.next_non_whitespace();
                 can_consume = false;
             } else {
@@ -36,7 +40,145 @@ impl GroupBy {
             }
         }
 
-        Ok(groups)
+        if !has_sets {
+            return Ok((groups, vec![]));
+        }
+
+        // several items group by every combination of their sets
+        let mut sets: Vec<Vec<usize>> = vec![vec![]];
+        for item in items {
+            sets = sets
+                .iter()
+                .flat_map(|set| {
+                    item.iter().map(move |other| {
+                        let mut merged: Vec<usize> = set.iter().chain(other).copied().collect();
+                        merged.sort_unstable();
+                        merged.dedup();
+                        merged
+                    })
+                })
+                .collect();
+        }
+        Ok((groups, sets))
+    }
+
+    /// One `GROUP BY` item as its grouping sets, and whether it was written as sets.
+    fn parse_item(
+        parser: &mut QueryParser,
+        groups: &mut Vec<ScalarExpr>,
+    ) -> Result<(Vec<Vec<usize>>, bool), ParseError> {
+        if parser.comparers.rollup.compare(parser) {
+            parser.jump(parser.comparers.rollup.length);
+            // ROLLUP(a, b) groups by (a, b), (a) and ()
+            let keys = Self::parse_key_list(parser, groups, false)?;
+            let sets = (0..=keys.len()).rev().map(|n| keys[..n].to_vec());
+            return Ok((sets.collect(), true));
+        }
+
+        if parser.comparers.cube.compare(parser) {
+            let pivot = parser.position;
+            parser.jump(parser.comparers.cube.length);
+            // CUBE(a, b) groups by (a, b), (a), (b) and ()
+            let keys = Self::parse_key_list(parser, groups, false)?;
+            if keys.len() > 12 {
+                return ParseError::new("CUBE is limited to 12 expressions", pivot, parser).err();
+            }
+            let n = keys.len();
+            let sets = (0..1usize << n).rev().map(|mask| {
+                keys.iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask >> (n - 1 - i) & 1 == 1)
+                    .map(|(_, key)| *key)
+                    .collect()
+            });
+            return Ok((sets.collect(), true));
+        }
+
+        if parser.comparers.grouping_sets.compare(parser) {
+            parser.jump(parser.comparers.grouping_sets.length);
+            parser.next_non_whitespace();
+            if parser.current() != '(' {
+                return ParseError::new("Invalid group by", parser.position, parser).err();
+            }
+            parser.next();
+
+            let mut sets = vec![];
+            loop {
+                parser.next_non_whitespace();
+                match parser.current() {
+                    '(' => sets.push(Self::parse_key_list(parser, groups, true)?),
+                    _ => sets.push(vec![Self::parse_key(parser, groups)?]),
+                }
+                parser.next_non_whitespace();
+                match parser.current() {
+                    ',' => parser.next(),
+                    ')' => {
+                        parser.next();
+                        return Ok((sets, true));
+                    }
+                    _ => return ParseError::new("Invalid group by", parser.position, parser).err(),
+                }
+            }
+        }
+
+        Ok((vec![vec![Self::parse_key(parser, groups)?]], false))
+    }
+
+    /// `(expr, ...)` as indexes into `groups`; `()` only when `allow_empty`.
+    fn parse_key_list(
+        parser: &mut QueryParser,
+        groups: &mut Vec<ScalarExpr>,
+        allow_empty: bool,
+    ) -> Result<Vec<usize>, ParseError> {
+        parser.next_non_whitespace();
+        if parser.current() != '(' {
+            return ParseError::new("Invalid group by", parser.position, parser).err();
+        }
+        parser.next();
+        parser.next_non_whitespace();
+
+        let mut keys = vec![];
+        if parser.current() == ')' {
+            if !allow_empty {
+                return ParseError::new("Invalid group by", parser.position, parser).err();
+            }
+            parser.next();
+            return Ok(keys);
+        }
+        loop {
+            keys.push(Self::parse_key(parser, groups)?);
+            parser.next_non_whitespace();
+            match parser.current() {
+                ',' => {
+                    parser.next();
+                    parser.next_non_whitespace();
+                }
+                ')' => {
+                    parser.next();
+                    return Ok(keys);
+                }
+                _ => return ParseError::new("Invalid group by", parser.position, parser).err(),
+            }
+        }
+    }
+
+    /// A grouping expression, as its index in `groups`; repeated expressions share one.
+    fn parse_key(
+        parser: &mut QueryParser,
+        groups: &mut Vec<ScalarExpr>,
+    ) -> Result<usize, ParseError> {
+        match ScalarExpr::parse(parser, false)? {
+            ScalarExpr::Literal(_) | ScalarExpr::Parameter | ScalarExpr::NamedParameter(_) => {
+                ParseError::new("Invalid group by", parser.position, parser).err()
+            }
+            expr => match groups.iter().position(|group| *group == expr) {
+                Some(index) => Ok(index),
+                None => {
+                    groups.push(expr);
+                    Ok(groups.len() - 1)
+                }
+            },
+        }
     }
 }
 
@@ -44,14 +186,14 @@ impl GroupBy {
 mod tests {
     use crate::parser::{
         QueryParser,
-        ast::{Column, GroupBy},
+        ast::{Column, GroupBy, ScalarExpr},
     };
 
-    fn parse_group_by(text: &str) -> Vec<Column> {
+    fn parse_group_by(text: &str) -> Vec<ScalarExpr> {
         let mut parser = QueryParser::new(text);
         parser.check_next_phase();
         match GroupBy::parse(&mut parser) {
-            Ok(groups) => groups,
+            Ok((groups, _)) => groups,
             Err(err) => panic!("expected GROUP BY to parse from {text:?}, got {err:?}"),
         }
     }
@@ -65,11 +207,11 @@ mod tests {
         }
     }
 
-    fn names(groups: &[Column]) -> Vec<&str> {
+    fn names(groups: &[ScalarExpr]) -> Vec<&str> {
         groups
             .iter()
             .map(|column| match column {
-                Column::Name { name } => name.as_str(),
+                ScalarExpr::Column(Column::Name { name }) => name.as_str(),
                 other => panic!("expected unqualified column, got {other:?}"),
             })
             .collect()
@@ -115,6 +257,15 @@ mod tests {
         assert_eq!((start, end, text), (10, 10, String::new()));
     }
 
+    #[test]
+    fn group_by_accepts_case_expression() {
+        let result =
+            parse_group_by("GROUP BY CASE WHEN total > 10 THEN 'big' ELSE 'small' END, city");
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0], ScalarExpr::Case(_)));
+        assert_eq!(names(&result[1..]), ["city"]);
+    }
+
     #[test]
     fn group_by_rejects_missing_keyword() {
         let mut parser = QueryParser::new("ORDER BY columnA");
@@ -124,4 +275,55 @@ mod tests {
             Err(err) => assert_eq!((err.start, err.end, err.text), (0, 0, "O".to_string())),
         }
     }
+
+    fn parse_group_by_sets(text: &str) -> (Vec<ScalarExpr>, Vec<Vec<usize>>) {
+        let mut parser = QueryParser::new(text);
+        parser.check_next_phase();
+        match GroupBy::parse(&mut parser) {
+            Ok(parsed) => parsed,
+            Err(err) => panic!("expected GROUP BY to parse from {text:?}, got {err:?}"),
+        }
+    }
+
+    #[test]
+    fn group_by_rollup_cube_and_grouping_sets() {
+        let (groups, sets) = parse_group_by_sets("GROUP BY ROLLUP(region, city)");
+        assert_eq!(names(&groups), ["region", "city"]);
+        assert_eq!(sets, vec![vec![0, 1], vec![0], vec![]]);
+
+        let (groups, sets) = parse_group_by_sets("GROUP BY cube (a, b)");
+        assert_eq!(names(&groups), ["a", "b"]);
+        assert_eq!(sets, vec![vec![0, 1], vec![0], vec![1], vec![]]);
+
+        let (groups, sets) = parse_group_by_sets("GROUP BY GROUPING SETS ((a, b), c, ())");
+        assert_eq!(names(&groups), ["a", "b", "c"]);
+        assert_eq!(sets, vec![vec![0, 1], vec![2], vec![]]);
+
+        // a plain expression is kept in every set; repeated expressions share an index
+        let (groups, sets) = parse_group_by_sets("GROUP BY a, ROLLUP(b, a) ORDER BY a");
+        assert_eq!(names(&groups), ["a", "b"]);
+        assert_eq!(sets, vec![vec![0, 1], vec![0, 1], vec![0]]);
+
+        let (_, sets) = parse_group_by_sets("GROUP BY a, b");
+        assert!(sets.is_empty());
+    }
+
+    #[test]
+    fn group_by_rejects_invalid_grouping_sets() {
+        for text in [
+            "GROUP BY ROLLUP()",
+            "GROUP BY ROLLUP(a, 1)",
+            "GROUP BY CUBE(a b)",
+            "GROUP BY GROUPING SETS (a, (b)",
+            "GROUP BY GROUPING SETS a",
+            "GROUP BY CUBE(a, b, c, d, e, f, g, h, i, j, k, l, m)",
+        ] {
+            let mut parser = QueryParser::new(text);
+            parser.check_next_phase();
+            assert!(
+                GroupBy::parse(&mut parser).is_err(),
+                "{text} should not parse"
+            );
+        }
+    }
 }
@@ -0,0 +1,465 @@
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{
+        ArgsExpr, Assignment, Identifier, Query, ReturningParser, ScalarExpr, TextCollector, Update,
+    },
+};
+
+#[derive(Clone, PartialEq)]
+pub enum InsertSource {
+    Values(Vec<Vec<ScalarExpr>>),
+    Query(Box<Query>),
+}
+
+#[derive(Clone, PartialEq)]
+pub enum ConflictAction {
+    DoNothing,
+    /// `DO UPDATE` merges the whole incoming row; with `SET` only the assigned columns.
+    DoUpdate(Vec<Assignment>),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct OnConflict {
+    pub target: Option<String>,
+    pub action: ConflictAction,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Insert {
+    pub collection: String,
+    pub columns: Vec<String>,
+    pub source: InsertSource,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Vec<Identifier>,
+}
+
+impl Insert {
+    pub fn is_insert_start(parser: &QueryParser) -> bool {
+        parser.comparers.insert_into.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Insert, ParseError> {
+        if !Insert::is_insert_start(parser) {
+            return ParseError::new("Invalid insert", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.insert_into.length);
+        parser.next_non_whitespace();
+
+        let pivot = parser.position;
+        let collection = TextCollector::collect(parser)?;
+        if collection.is_empty() {
+            return ParseError::new("Invalid insert collection", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        let columns = if parser.current() == '(' {
+            Self::parse_columns(parser)?
+        } else {
+            vec![]
+        };
+
+        parser.next_non_whitespace();
+        let source = if parser.comparers.values.compare(parser) {
+            parser.jump(parser.comparers.values.length);
+            InsertSource::Values(Self::parse_values(parser)?)
+        } else if parser.comparers.select.compare(parser) {
+            InsertSource::Query(Box::new(Query::parse(parser)?))
+        } else {
+            return ParseError::new("Expected VALUES or SELECT", parser.position, parser).err();
+        };
+
+        parser.next_non_whitespace();
+        let on_conflict = if parser.comparers.on_conflict.compare(parser) {
+            Some(Self::parse_on_conflict(parser)?)
+        } else {
+            None
+        };
+
+        let returning = ReturningParser::parse_optional(parser)?;
+        if !parser.eof() {
+            return ParseError::new("Invalid insert", parser.position, parser).err();
+        }
+
+        Ok(Insert {
+            collection,
+            columns,
+            source,
+            on_conflict,
+            returning,
+        })
+    }
+
+    fn parse_columns(parser: &mut QueryParser) -> Result<Vec<String>, ParseError> {
+        let pivot = parser.position;
+        parser.next();
+
+        let mut columns = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let column = TextCollector::collect(parser)?;
+            if column.is_empty() {
+                return ParseError::new("Invalid insert columns", pivot, parser).err();
+            }
+            columns.push(column);
+
+            parser.next_non_whitespace();
+            match parser.current() {
+                ',' => parser.next(),
+                ')' => {
+                    parser.next();
+                    return Ok(columns);
+                }
+                _ => return ParseError::new("Invalid insert columns", pivot, parser).err(),
+            }
+        }
+    }
+
+    fn parse_on_conflict(parser: &mut QueryParser) -> Result<OnConflict, ParseError> {
+        let pivot = parser.position;
+        parser.jump(parser.comparers.on_conflict.length);
+        parser.next_non_whitespace();
+
+        let mut target = None;
+        if parser.current() == '(' {
+            parser.next();
+            parser.next_non_whitespace();
+            let column = TextCollector::collect(parser)?;
+            parser.next_non_whitespace();
+            if column.is_empty() || parser.current() != ')' {
+                return ParseError::new("Invalid conflict target", pivot, parser).err();
+            }
+            parser.next();
+            parser.next_non_whitespace();
+            target = Some(column);
+        }
+
+        let action = if parser.comparers.do_nothing.compare(parser) {
+            parser.jump(parser.comparers.do_nothing.length);
+            ConflictAction::DoNothing
+        } else if parser.comparers.do_update.compare(parser) {
+            parser.jump(parser.comparers.do_update.length);
+            parser.next_non_whitespace();
+            if parser.comparers.set.compare(parser) {
+                parser.jump(parser.comparers.set.length);
+                ConflictAction::DoUpdate(Update::parse_assignments(parser)?)
+            } else {
+                ConflictAction::DoUpdate(vec![])
+            }
+        } else {
+            return ParseError::new("Expected DO NOTHING or DO UPDATE", parser.position, parser)
+                .err();
+        };
+
+        Ok(OnConflict { target, action })
+    }
+
+    fn parse_values(parser: &mut QueryParser) -> Result<Vec<Vec<ScalarExpr>>, ParseError> {
+        let mut rows = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let pivot = parser.position;
+            let row = ArgsExpr::parse(parser, false)?;
+            if row.args.is_empty() {
+                return ParseError::new("Invalid values", pivot, parser).err();
+            }
+            rows.push(row.args);
+
+            parser.next_non_whitespace();
+            if parser.eof()
+                || parser.comparers.on_conflict.compare(parser)
+                || ReturningParser::is_returning_start(parser)
+            {
+                return Ok(rows);
+            }
+            if parser.current() != ',' {
+                return ParseError::new("Invalid values", parser.position, parser).err();
+            }
+            parser.next();
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for InsertSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertSource::Values(rows) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        let values = row
+                            .iter()
+                            .map(|v| format!("{}", v))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("({})", values)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "Values[{}]", rows)
+            }
+            InsertSource::Query(query) => write!(f, "{}", query),
+        }
+    }
+}
+
+impl fmt::Debug for InsertSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for OnConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = self.target.as_deref().unwrap_or("");
+        match &self.action {
+            ConflictAction::DoNothing => write!(f, "OnConflict({}, DoNothing)", target),
+            ConflictAction::DoUpdate(assignments) => {
+                let assignments = assignments
+                    .iter()
+                    .map(|a| format!("{}", a))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "OnConflict({}, DoUpdate[{}])", target, assignments)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for OnConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for Insert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let on_conflict = match &self.on_conflict {
+            Some(c) => format!(", {}", c),
+            None => String::new(),
+        };
+        write!(
+            f,
+            "Insert(collection={}, columns=[{}], source={}{}{})",
+            self.collection,
+            self.columns.join(", "),
+            self.source,
+            on_conflict,
+            ReturningParser::display_suffix(&self.returning)
+        )
+    }
+}
+
+impl fmt::Debug for Insert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{ConflictAction, Insert, InsertSource, Literal, ScalarExpr},
+    };
+
+    #[test]
+    pub fn test_insert_values() {
+        let text = "INSERT INTO people (name, age) VALUES ('Ada', 37), (?, ?)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert_eq!(result.collection, "people");
+        assert_eq!(result.columns, vec!["name", "age"]);
+        match result.source {
+            InsertSource::Values(rows) => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(
+                    rows[0][0],
+                    ScalarExpr::Literal(Literal::String("Ada".into()))
+                );
+                assert_eq!(rows[0][1], ScalarExpr::Literal(Literal::Int(37)));
+                assert_eq!(rows[1], vec![ScalarExpr::Parameter, ScalarExpr::Parameter]);
+            }
+            InsertSource::Query(_) => panic!(),
+        }
+    }
+
+    #[test]
+    pub fn test_insert_select() {
+        let text = "INSERT INTO archive (name) SELECT p.name FROM people p WHERE p.age > 30";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert_eq!(result.collection, "archive");
+        assert_eq!(result.columns, vec!["name"]);
+        match result.source {
+            InsertSource::Query(query) => {
+                assert_eq!(query.projection.len(), 1);
+                assert_eq!(query.collections.len(), 1);
+                assert!(query.criteria.is_some());
+            }
+            InsertSource::Values(_) => panic!(),
+        }
+    }
+
+    #[test]
+    pub fn test_insert_select_without_columns() {
+        let text = "insert into archive select * from people";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert!(result.columns.is_empty());
+        assert!(matches!(result.source, InsertSource::Query(_)));
+    }
+
+    #[test]
+    pub fn test_insert_display() {
+        let text = "INSERT INTO people (name) VALUES ('Ada')";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "Insert(collection=people, columns=[name], source=Values[(lit: s: \"Ada\")])"
+        );
+    }
+
+    #[test]
+    pub fn test_insert_without_source() {
+        let text = "INSERT INTO people (name)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser);
+
+        assert!(result.is_err());
+        let err = result.err().unwrap();
+        assert_eq!(err.message, "Expected VALUES or SELECT");
+    }
+
+    #[test]
+    pub fn test_insert_invalid_columns() {
+        let text = "INSERT INTO people (name age) VALUES ('Ada', 37)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid insert columns");
+    }
+
+    #[test]
+    pub fn test_insert_values_trailing_text() {
+        let text = "INSERT INTO people (name) VALUES ('Ada') extra";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid values");
+    }
+
+    #[test]
+    pub fn test_insert_values_returning() {
+        let text = "INSERT INTO people (name) VALUES ('Ada'), ('Grace') RETURNING id, name AS n";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert!(matches!(result.source, InsertSource::Values(ref rows) if rows.len() == 2));
+        assert_eq!(
+            format!("{:?}", result),
+            "Insert(collection=people, columns=[name], source=Values[(lit: s: \"Ada\"), (lit: s: \"Grace\")], returning=[col: id, col: name as n])"
+        );
+    }
+
+    #[test]
+    pub fn test_insert_select_returning() {
+        let text =
+            "INSERT INTO archive (name) SELECT p.name FROM people p WHERE p.age > 30 RETURNING *";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        match &result.source {
+            InsertSource::Query(query) => assert!(query.criteria.is_some()),
+            InsertSource::Values(_) => panic!(),
+        }
+        assert_eq!(result.returning.len(), 1);
+        assert_eq!(result.returning[0].expression, ScalarExpr::WildCard);
+    }
+
+    #[test]
+    pub fn test_insert_on_conflict_do_nothing() {
+        let text = "INSERT INTO people (id, name) VALUES (1, 'Ada') ON CONFLICT (id) DO NOTHING";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert_eq!(
+            format!("{:?}", result),
+            "Insert(collection=people, columns=[id, name], source=Values[(lit: i: 1, lit: s: \"Ada\")], OnConflict(id, DoNothing))"
+        );
+    }
+
+    #[test]
+    pub fn test_insert_on_conflict_do_update() {
+        let text = "INSERT INTO people (id, name) SELECT id, name FROM staff ON CONFLICT(id) DO UPDATE SET name = excluded.name RETURNING id";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        assert!(matches!(result.source, InsertSource::Query(_)));
+        let on_conflict = result.on_conflict.expect("on conflict");
+        assert_eq!(on_conflict.target, Some("id".into()));
+        match on_conflict.action {
+            ConflictAction::DoUpdate(assignments) => {
+                assert_eq!(assignments.len(), 1);
+                assert_eq!(assignments[0].column, "name");
+            }
+            ConflictAction::DoNothing => panic!(),
+        }
+        assert_eq!(result.returning.len(), 1);
+
+        let text = "INSERT INTO people (id) VALUES (1) ON CONFLICT DO UPDATE";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser).expect("Failed to parse insert");
+
+        let on_conflict = result.on_conflict.expect("on conflict");
+        assert_eq!(on_conflict.target, None);
+        assert!(matches!(on_conflict.action, ConflictAction::DoUpdate(ref a) if a.is_empty()));
+    }
+
+    #[test]
+    pub fn test_insert_on_conflict_without_action() {
+        let text = "INSERT INTO people (id) VALUES (1) ON CONFLICT (id) DO";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Insert::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().message,
+            "Expected DO NOTHING or DO UPDATE"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::parser::{
     ParseError, Phase, QueryParser,
-    ast::{Collection, Predicate},
+    ast::{Collection, Predicate, Truth},
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -53,6 +53,17 @@ impl Join {
     pub fn parse(parser: &mut QueryParser) -> Result<Vec<Join>, ParseError> {
         let mut joins: Vec<Join> = vec![];
         while parser.phase == Phase::Joins {
+            // CROSS JOIN is an inner join without ON
+            if parser.comparers.cross_join.compare(parser) {
+                parser.jump(parser.comparers.cross_join.length);
+                joins.push(Join {
+                    join_type: JoinType::Inner,
+                    collection: Collection::parse(parser)?,
+                    predicate: Predicate::Const3(Truth::True),
+                });
+                continue;
+            }
+
             let join_type = JoinType::parse(parser)?;
             let collection = Collection::parse(parser)?;
 
@@ -79,7 +90,7 @@ impl Join {
 mod tests {
     use crate::parser::{
         QueryParser,
-        ast::{Collection, Join, JoinType, Predicate},
+        ast::{Collection, Join, JoinType, Predicate, Truth},
     };
 
     fn parse_joins(text: &str) -> Vec<Join> {
@@ -94,7 +105,7 @@ mod tests {
     fn table_name_and_alias(collection: &Collection) -> (&str, Option<&str>) {
         match collection {
             Collection::Table { name, alias } => (name.as_str(), alias.as_deref()),
-            Collection::Query { .. } => panic!("expected table collection, got {collection:?}"),
+            _ => panic!("expected table collection, got {collection:?}"),
         }
     }
 
@@ -165,6 +176,23 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_cross_join_has_no_on() {
+        let text = "CROSS JOIN tableA a CROSS JOIN UNNEST(a.tags) AS t(tag) WHERE t.tag = 'x'";
+        let result = parse_joins(text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].join_type, JoinType::Inner);
+        assert_eq!(
+            table_name_and_alias(&result[0].collection),
+            ("tableA", Some("a"))
+        );
+        assert_eq!(result[0].predicate, Predicate::Const3(Truth::True));
+        assert!(matches!(
+            &result[1].collection,
+            Collection::Unnest { alias, column, .. } if alias == "t" && column == "tag"
+        ));
+    }
+
     #[test]
     pub fn test_all_joins() {
         let text = r#"
@@ -0,0 +1,122 @@
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{ArithmeticOp, TextCollector},
+};
+use std::fmt;
+
+/// One step into a nested document: `.key` or `[index]`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+pub struct JsonPath;
+
+impl JsonPath {
+    /// Does a path segment start at the current position?
+    pub fn is_path_start(parser: &QueryParser) -> bool {
+        parser.current() == '.' || parser.current() == '['
+    }
+
+    /// Stops a name before an operator, an array index or a `::type` cast.
+    pub fn is_name_stopper(ch: char) -> bool {
+        ArithmeticOp::is_operator_char(ch) || ch == '[' || ch == ':'
+    }
+
+    /// Parses `.key` and `[index]` segments until neither follows.
+    pub fn parse_segments(parser: &mut QueryParser) -> Result<Vec<PathSegment>, ParseError> {
+        let mut segments = vec![];
+        while Self::is_path_start(parser) {
+            let pivot = parser.position;
+            if parser.current() == '.' {
+                parser.next();
+                let key = TextCollector::collect_with_stopper(parser, Self::is_name_stopper)?;
+                if key.is_empty() {
+                    return ParseError::new("Invalid json path", pivot, parser).err();
+                }
+                segments.push(PathSegment::Key(key));
+            } else {
+                parser.next();
+                let start = parser.position;
+                while parser.current().is_ascii_digit() {
+                    parser.next();
+                }
+                let index = parser.text_from_pivot(start);
+                if index.is_empty() || parser.current() != ']' {
+                    return ParseError::new("Invalid json path index", pivot, parser).err();
+                }
+                parser.next();
+                segments.push(PathSegment::Index(index.parse().map_err(|_| {
+                    ParseError::new("Invalid json path index", pivot, parser)
+                })?));
+            }
+        }
+        Ok(segments)
+    }
+
+    /// The name a projected path gets without an alias: its last key.
+    pub fn default_name(column_name: &str, path: &[PathSegment]) -> String {
+        path.iter()
+            .rev()
+            .find_map(|segment| match segment {
+                PathSegment::Key(key) => Some(key.clone()),
+                PathSegment::Index(_) => None,
+            })
+            .unwrap_or_else(|| column_name.to_string())
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, ".{}", key),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+impl fmt::Debug for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{JsonPath, PathSegment},
+    };
+
+    #[test]
+    pub fn test_json_path_segments() {
+        let mut parser = QueryParser::new(".profile.tags[12].name = 1");
+
+        let segments = JsonPath::parse_segments(&mut parser).expect("Failed to parse path");
+
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::Key("profile".into()),
+                PathSegment::Key("tags".into()),
+                PathSegment::Index(12),
+                PathSegment::Key("name".into()),
+            ]
+        );
+        assert_eq!(parser.current(), ' ');
+        assert_eq!(JsonPath::default_name("p", &segments), "name");
+        assert_eq!(JsonPath::default_name("tags", &segments[2..3]), "tags");
+    }
+
+    #[test]
+    pub fn test_json_path_errors() {
+        for text in [".", "[]", "[a]", "[1", ".*"] {
+            let mut parser = QueryParser::new(text);
+            assert!(
+                JsonPath::parse_segments(&mut parser).is_err(),
+                "{text} should not parse"
+            );
+        }
+    }
+}
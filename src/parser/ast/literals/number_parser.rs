@@ -1,4 +1,7 @@
-use crate::parser::{ParseError, QueryParser, WordComparer, ast::Literal};
+use crate::parser::{
+    ParseError, QueryParser, WordComparer,
+    ast::{ArithmeticOp, Literal},
+};
 
 pub struct NumberParser;
 
@@ -16,14 +19,23 @@ impl NumberParser {
             return Err(ParseError::new("Invalid number value", pivot, parser));
         }
 
-        while !parser.eof() && (NumberParser::is_number(parser) || parser.current() == '.') {
+        // the sign is only allowed first; a later `+`/`-` is an operator
+        parser.next();
+        while !parser.eof() && (parser.current().is_ascii_digit() || parser.current() == '.') {
             if parser.current() == '.' {
                 is_float = true;
             }
             parser.next();
         }
 
-        if !parser.eof() && !WordComparer::is_any_delimiter(parser.current()) {
+        // a `::type` cast may follow the number
+        let is_cast =
+            parser.current() == ':' && parser.position + 1 < parser.length && parser.peek(1) == ':';
+        if !parser.eof()
+            && !WordComparer::is_any_delimiter(parser.current())
+            && !ArithmeticOp::is_operator_char(parser.current())
+            && !is_cast
+        {
             return Err(ParseError::new("Invalid number value", pivot, parser));
         }
 
@@ -123,6 +135,14 @@ pub mod tests {
         assert_eq!(parse_number("32\r"), Literal::Int(32));
     }
 
+    #[test]
+    pub fn test_number_parser_stops_at_operator() {
+        let mut parser = QueryParser::new("-3-2");
+
+        assert_eq!(NumberParser::parse(&mut parser).unwrap(), Literal::Int(-3));
+        assert_eq!(parser.current(), '-');
+    }
+
     #[test]
     pub fn test_number_parser_wrong_value() {
         let text = "32a";
@@ -3,8 +3,9 @@ use crate::parser::{ParseError, QueryParser, ast::ScalarExpr};
 pub struct ParamParser;
 
 impl ParamParser {
+    /// `?`, `:name` or `$1`.
     pub fn is_param(parser: &QueryParser) -> bool {
-        parser.comparers.param.compare(parser)
+        parser.comparers.param.compare(parser) || ParamParser::named_sigil(parser).is_some()
     }
 
     pub fn parse(parser: &mut QueryParser) -> Result<ScalarExpr, ParseError> {
@@ -13,12 +14,41 @@ impl ParamParser {
             return Ok(ScalarExpr::Parameter);
         }
 
+        if let Some(sigil) = ParamParser::named_sigil(parser) {
+            let pivot = parser.position;
+            parser.next();
+            while match sigil {
+                ':' => parser.current().is_ascii_alphanumeric() || parser.current() == '_',
+                _ => parser.current().is_ascii_digit(),
+            } {
+                parser.next();
+            }
+            let name = parser.text_from_pivot(pivot);
+            if sigil == '$' && name == "$0" {
+                return Err(ParseError::new("Parameters start at $1", pivot, parser));
+            }
+            if !parser.parameters.contains(&name) {
+                parser.parameters.push(name.clone());
+            }
+            return Ok(ScalarExpr::NamedParameter(name));
+        }
+
         Err(ParseError::new(
             "Invalid Parameter",
             parser.position,
             parser,
         ))
     }
+
+    /// `:` before a name or `$` before a number; `::` is a cast, not a parameter.
+    fn named_sigil(parser: &QueryParser) -> Option<char> {
+        let next = parser.text_v.get(parser.position + 1)?;
+        match parser.current() {
+            ':' if next.is_ascii_alphabetic() || *next == '_' => Some(':'),
+            '$' if next.is_ascii_digit() => Some('$'),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -61,6 +91,34 @@ pub mod tests {
         parse_param("?\r\n");
     }
 
+    #[test]
+    pub fn test_param_parser_named_and_numbered() {
+        let mut parser = QueryParser::new(":user_id, $2, :user_id::int, $10)");
+        let mut names = vec![];
+        for _ in 0..4 {
+            parser.next_non_whitespace();
+            match ParamParser::parse(&mut parser) {
+                Ok(ScalarExpr::NamedParameter(name)) => names.push(name),
+                other => panic!("expected named parameter, got {other:?}"),
+            }
+            while parser.current() != ',' && parser.current() != ')' {
+                parser.next();
+            }
+            parser.next();
+        }
+
+        assert_eq!(names, vec![":user_id", "$2", ":user_id", "$10"]);
+        // each name is recorded once, in order of appearance
+        assert_eq!(parser.parameters, vec![":user_id", "$2", "$10"]);
+
+        for text in ["::int", ": a", "$a", "$"] {
+            let parser = QueryParser::new(text);
+            assert!(!ParamParser::is_param(&parser), "{text} is not a parameter");
+        }
+        let mut parser = QueryParser::new("$0");
+        assert!(ParamParser::parse(&mut parser).is_err());
+    }
+
     #[test]
     pub fn test_param_parser_param_wrong() {
         let text = "?e";
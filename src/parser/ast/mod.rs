@@ -1,6 +1,9 @@
 pub mod column;
 pub use column::*;
 
+pub mod json_path;
+pub use json_path::*;
+
 pub mod function;
 pub use function::*;
 
@@ -52,5 +55,44 @@ pub use having_parser::*;
 pub mod limit_offset_parser;
 pub use limit_offset_parser::*;
 
+pub mod cte;
+pub use cte::*;
+
+pub mod subquery;
+pub use subquery::*;
+
+pub mod case_expr;
+pub use case_expr::*;
+
+pub mod window;
+pub use window::*;
+
+pub mod set_operation;
+pub use set_operation::*;
+
 pub mod query;
 pub use query::*;
+
+pub mod returning_parser;
+pub use returning_parser::*;
+
+pub mod insert;
+pub use insert::*;
+
+pub mod update;
+pub use update::*;
+
+pub mod delete;
+pub use delete::*;
+
+pub mod create_collection;
+pub use create_collection::*;
+
+pub mod drop_collection;
+pub use drop_collection::*;
+
+pub mod statement;
+pub use statement::*;
+
+pub mod script;
+pub use script::*;
@@ -1,6 +1,6 @@
 use crate::parser::QueryParser;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ComparatorOp {
     Eq,
     NotEq,
@@ -69,12 +69,65 @@ impl ComparatorOp {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ArithmeticOp {
     Add,
     Sub,
     Mul,
     Div,
+    Mod,
+    Concat,
+}
+
+impl fmt::Display for ArithmeticOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticOp::Add => write!(f, "+"),
+            ArithmeticOp::Sub => write!(f, "-"),
+            ArithmeticOp::Mul => write!(f, "*"),
+            ArithmeticOp::Div => write!(f, "/"),
+            ArithmeticOp::Mod => write!(f, "%"),
+            ArithmeticOp::Concat => write!(f, "||"),
+        }
+    }
+}
+
+impl ArithmeticOp {
+    /// Binding strength: `* / %` before `+ -`, both before `||`.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            ArithmeticOp::Mul | ArithmeticOp::Div | ArithmeticOp::Mod => 3,
+            ArithmeticOp::Add | ArithmeticOp::Sub => 2,
+            ArithmeticOp::Concat => 1,
+        }
+    }
+
+    /// Operator at the current position, without consuming it.
+    pub fn peek(parser: &QueryParser) -> Option<ArithmeticOp> {
+        match parser.current() {
+            '+' => Some(ArithmeticOp::Add),
+            '-' => Some(ArithmeticOp::Sub),
+            '*' => Some(ArithmeticOp::Mul),
+            '/' => Some(ArithmeticOp::Div),
+            '%' => Some(ArithmeticOp::Mod),
+            '|' if parser.text_v.get(parser.position + 1) == Some(&'|') => {
+                Some(ArithmeticOp::Concat)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        match self {
+            ArithmeticOp::Concat => 2,
+            _ => 1,
+        }
+    }
+
+    /// Characters that end an identifier or number because they start an operator.
+    pub fn is_operator_char(ch: char) -> bool {
+        matches!(ch, '+' | '-' | '*' | '/' | '%' | '|')
+    }
 }
 
 #[cfg(test)]
@@ -131,4 +184,27 @@ mod tests {
         assert_eq!(format!("{:?}", ArithmeticOp::Mul), "Mul");
         assert_eq!(format!("{:?}", ArithmeticOp::Div), "Div");
     }
+
+    #[test]
+    fn arithmetic_peek_recognizes_operators_and_precedence() {
+        let cases = [
+            ("+1", ArithmeticOp::Add, 2),
+            ("- x", ArithmeticOp::Sub, 2),
+            ("*b", ArithmeticOp::Mul, 3),
+            ("/ 2", ArithmeticOp::Div, 3),
+            ("% 2", ArithmeticOp::Mod, 3),
+            ("|| 'x'", ArithmeticOp::Concat, 1),
+        ];
+
+        for (input, expected, precedence) in cases {
+            let parser = QueryParser::new(input);
+            let op = ArithmeticOp::peek(&parser).expect("operator expected");
+            assert_eq!(op, expected);
+            assert_eq!(op.precedence(), precedence);
+            assert!(input.starts_with(&op.to_string()));
+        }
+
+        assert_eq!(ArithmeticOp::peek(&QueryParser::new("| x")), None);
+        assert_eq!(ArithmeticOp::peek(&QueryParser::new("= 1")), None);
+    }
 }
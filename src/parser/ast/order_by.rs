@@ -1,6 +1,6 @@
 use crate::parser::{ParseError, Phase, QueryParser, ast::ScalarExpr};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct OrderBy {
     pub expr: ScalarExpr,
     pub ascending: bool,
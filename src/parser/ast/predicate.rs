@@ -1,13 +1,13 @@
 use crate::parser::{
     ParseError, QueryParser,
-    ast::{ArgsExpr, ComparatorOp, ScalarExpr, Truth},
+    ast::{ArgsExpr, ComparatorOp, ScalarExpr, Subquery, Truth},
 };
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Predicate {
     And(Vec<Predicate>),
     Or(Vec<Predicate>),
-    //Not(Box<Predicate>),
+    Not(Box<Predicate>),
 
     // Predicates that *embed* scalars:
     Compare {
@@ -29,11 +29,63 @@ pub enum Predicate {
         pattern: ScalarExpr,
         negated: bool,
     },
+    /// `expr [NOT] IN (SELECT ...)` over the single column the subquery returns.
+    InSubquery {
+        expr: ScalarExpr,
+        subquery: Subquery,
+        negated: bool,
+    },
+    /// `[NOT] EXISTS (SELECT ...)`; the subquery may read columns of the enclosing query.
+    Exists {
+        subquery: Subquery,
+        negated: bool,
+    },
 
     Const3(Truth),
 }
 
 impl Predicate {
+    /// Does evaluating this predicate need to run a subquery?
+    pub fn has_subquery(&self) -> bool {
+        match self {
+            Predicate::And(list) | Predicate::Or(list) => list.iter().any(Self::has_subquery),
+            Predicate::Not(predicate) => predicate.has_subquery(),
+            Predicate::Exists { .. } | Predicate::InSubquery { .. } => true,
+            Predicate::Compare { left, right, .. } => left.has_subquery() || right.has_subquery(),
+            Predicate::IsNull { expr, .. } => expr.has_subquery(),
+            Predicate::InList { expr, list, .. } => {
+                expr.has_subquery() || list.iter().any(ScalarExpr::has_subquery)
+            }
+            Predicate::Like { expr, pattern, .. } => expr.has_subquery() || pattern.has_subquery(),
+            Predicate::Const3(_) => false,
+        }
+    }
+
+    /// How many `?` placeholders this predicate holds, including those of its subqueries.
+    pub fn parameter_count(&self) -> usize {
+        match self {
+            Predicate::And(list) | Predicate::Or(list) => {
+                list.iter().map(Self::parameter_count).sum()
+            }
+            Predicate::Not(predicate) => predicate.parameter_count(),
+            Predicate::Exists { subquery, .. } => subquery.query.parameter_count(),
+            Predicate::InSubquery { expr, subquery, .. } => {
+                expr.parameter_count() + subquery.query.parameter_count()
+            }
+            Predicate::Compare { left, right, .. } => {
+                left.parameter_count() + right.parameter_count()
+            }
+            Predicate::IsNull { expr, .. } => expr.parameter_count(),
+            Predicate::InList { expr, list, .. } => {
+                expr.parameter_count() + list.iter().map(ScalarExpr::parameter_count).sum::<usize>()
+            }
+            Predicate::Like { expr, pattern, .. } => {
+                expr.parameter_count() + pattern.parameter_count()
+            }
+            Predicate::Const3(_) => 0,
+        }
+    }
+
     pub fn is_start(parser: &QueryParser) -> bool {
         parser.comparers.on.compare(parser)
     }
@@ -42,6 +94,22 @@ impl Predicate {
         parser: &mut QueryParser,
         allow_wildcard: bool,
     ) -> Result<Self, ParseError> {
+        if parser.comparers.exists.compare(parser) {
+            parser.jump(parser.comparers.exists.length);
+            return Ok(Self::Exists {
+                subquery: Subquery::parse(parser)?,
+                negated: false,
+            });
+        }
+
+        if parser.comparers.not_exists.compare(parser) {
+            parser.jump(parser.comparers.not_exists.length);
+            return Ok(Self::Exists {
+                subquery: Subquery::parse(parser)?,
+                negated: true,
+            });
+        }
+
         let left = ScalarExpr::parse(parser, allow_wildcard)?;
 
         parser.next_non_whitespace();
@@ -75,6 +143,13 @@ impl Predicate {
         if parser.comparers.r#in.compare(parser) {
             parser.jump(parser.comparers.r#in.length);
             parser.next_non_whitespace();
+            if Subquery::is_subquery_start(parser) {
+                return Ok(Self::InSubquery {
+                    expr: left,
+                    subquery: Subquery::parse(parser)?,
+                    negated: false,
+                });
+            }
             let args_expr = ArgsExpr::parse(parser, allow_wildcard)?;
             return Ok(Self::InList {
                 expr: left,
@@ -86,6 +161,13 @@ impl Predicate {
         if parser.comparers.not_in.compare(parser) {
             parser.jump(parser.comparers.not_in.length);
             parser.next_non_whitespace();
+            if Subquery::is_subquery_start(parser) {
+                return Ok(Self::InSubquery {
+                    expr: left,
+                    subquery: Subquery::parse(parser)?,
+                    negated: true,
+                });
+            }
             let args_expr = ArgsExpr::parse(parser, allow_wildcard)?;
             return Ok(Self::InList {
                 expr: left,
@@ -110,78 +192,131 @@ impl Predicate {
             parser.jump(parser.comparers.not_like.length);
             parser.next_non_whitespace();
 
-            let pattern = ScalarExpr::parse(parser, true)?;
+            let pattern = ScalarExpr::parse(parser, false)?;
             return Ok(Self::Like {
                 expr: left,
                 pattern,
-                negated: false,
+                negated: true,
             });
         }
 
-        ParseError::new("Invalid predicate", pivot, parser).err()
-    }
-
-    pub fn parse_all(
-        parser: &mut QueryParser,
-        allow_wildcard: bool,
-        depth: i8,
-    ) -> Result<Self, ParseError> {
-        let mut pivot = parser.position;
-        let mut predicates: Vec<Predicate> = vec![];
-        let mut and = false;
-        let mut or = false;
-        while (!parser.check_next_phase()) && (depth == 0 || parser.current() != ')') {
-            predicates.push(Self::parse_single(parser, allow_wildcard)?);
-            parser.next_non_whitespace();
-
-            if parser.comparers.and.compare(parser) {
-                parser.jump(parser.comparers.and.length);
-                parser.next_non_whitespace();
-                and = true;
+        let between = parser.comparers.between.compare(parser);
+        let not_between = parser.comparers.not_between.compare(parser);
+        if between || not_between {
+            match between {
+                true => parser.jump(parser.comparers.between.length),
+                false => parser.jump(parser.comparers.not_between.length),
             }
+            let low = ScalarExpr::parse(parser, false)?;
 
-            if parser.comparers.or.compare(parser) {
-                parser.jump(parser.comparers.or.length);
-                parser.next_non_whitespace();
-                or = true;
+            // this AND belongs to BETWEEN, not to the connective
+            parser.next_non_whitespace();
+            if !parser.comparers.and.compare(parser) {
+                return ParseError::new("Expected AND in between", parser.position, parser).err();
             }
+            parser.jump(parser.comparers.and.length);
+            let high = ScalarExpr::parse(parser, false)?;
+
+            let range = Self::And(vec![
+                Self::Compare {
+                    left: left.clone(),
+                    op: ComparatorOp::GtEq,
+                    right: low,
+                },
+                Self::Compare {
+                    left,
+                    op: ComparatorOp::LtEq,
+                    right: high,
+                },
+            ]);
+            return Ok(match not_between {
+                true => Self::Not(Box::new(range)),
+                false => range,
+            });
+        }
 
-            if parser.current() == '(' {
-                parser.next();
-                predicates.push(Self::parse_all(parser, allow_wildcard, depth + 1)?);
-                parser.next();
-            }
+        ParseError::new("Invalid predicate", pivot, parser).err()
+    }
 
-            pivot = parser.position;
-        }
+    /// `OR` binds loosest, then `AND`, then `NOT`; parentheses group.
+    pub fn parse(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
+        let predicate = Self::parse_or(parser, allow_wildcard)?;
 
-        if depth > 0 && parser.current() != ')' {
-            return ParseError::new("Invalid predicate", pivot, parser).err();
+        // THEN closes the condition of a CASE branch
+        if !parser.check_next_phase() && !parser.comparers.then.compare(parser) {
+            return ParseError::new("Invalid predicate", parser.position, parser).err();
         }
+        Ok(predicate)
+    }
 
-        if and {
-            if predicates.len() == 1 {
-                return ParseError::new("Invalid predicate", pivot, parser).err();
+    fn parse_or(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
+        let mut predicates = vec![Self::parse_and(parser, allow_wildcard)?];
+        loop {
+            parser.next_non_whitespace();
+            if !parser.comparers.or.compare(parser) {
+                break;
             }
-            return Ok(Self::And(predicates));
+            parser.jump(parser.comparers.or.length);
+            predicates.push(Self::parse_and(parser, allow_wildcard)?);
         }
 
-        if or {
-            if predicates.len() == 1 {
-                return ParseError::new("Invalid predicate", pivot, parser).err();
+        Ok(match predicates.len() {
+            1 => predicates.pop().unwrap(),
+            _ => Self::Or(predicates),
+        })
+    }
+
+    fn parse_and(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
+        let mut predicates = vec![Self::parse_not(parser, allow_wildcard)?];
+        loop {
+            parser.next_non_whitespace();
+            if !parser.comparers.and.compare(parser) {
+                break;
             }
-            return Ok(Self::Or(predicates));
+            parser.jump(parser.comparers.and.length);
+            predicates.push(Self::parse_not(parser, allow_wildcard)?);
         }
 
-        if predicates.len() == 1 {
-            return Ok(predicates.pop().unwrap());
+        Ok(match predicates.len() {
+            1 => predicates.pop().unwrap(),
+            _ => Self::And(predicates),
+        })
+    }
+
+    fn parse_not(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
+        parser.next_non_whitespace();
+        if parser.comparers.not.compare(parser) && !parser.comparers.not_exists.compare(parser) {
+            parser.jump(parser.comparers.not.length);
+            return Ok(Self::Not(Box::new(Self::parse_not(
+                parser,
+                allow_wildcard,
+            )?)));
         }
 
-        ParseError::new("Invalid predicate", pivot, parser).err()
+        Self::parse_group(parser, allow_wildcard)
     }
 
-    pub fn parse(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
-        Self::parse_all(parser, allow_wildcard, 0)
+    /// `(` opens either a group of predicates or a scalar, as in `(a + b) > 1`.
+    fn parse_group(parser: &mut QueryParser, allow_wildcard: bool) -> Result<Self, ParseError> {
+        if parser.current() != '(' || Subquery::is_subquery_start(parser) {
+            return Self::parse_single(parser, allow_wildcard);
+        }
+
+        let pivot = parser.position;
+        parser.next();
+        let group = Self::parse_or(parser, allow_wildcard).and_then(|predicate| {
+            parser.next_non_whitespace();
+            if parser.current() != ')' {
+                return ParseError::new("Invalid predicate", parser.position, parser).err();
+            }
+            parser.next();
+            Ok(predicate)
+        });
+
+        group.or_else(|err| {
+            parser.position = pivot;
+            Self::parse_single(parser, allow_wildcard).map_err(|_| err)
+        })
     }
 }
 
@@ -206,6 +341,7 @@ impl fmt::Display for Predicate {
                     .join(" OR ");
                 write!(f, "({})", s)
             }
+            Predicate::Not(predicate) => write!(f, "NOT {}", predicate),
             Predicate::Compare { left, op, right } => write!(f, "{} {} {}", left, op, right),
             Predicate::IsNull { expr, negated } => {
                 if *negated {
@@ -241,6 +377,24 @@ impl fmt::Display for Predicate {
                     write!(f, "{} LIKE {}", expr, pattern)
                 }
             }
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                if *negated {
+                    write!(f, "{} NOT IN {}", expr, subquery)
+                } else {
+                    write!(f, "{} IN {}", expr, subquery)
+                }
+            }
+            Predicate::Exists { subquery, negated } => {
+                if *negated {
+                    write!(f, "NOT EXISTS {}", subquery)
+                } else {
+                    write!(f, "EXISTS {}", subquery)
+                }
+            }
             Predicate::Const3(t) => write!(f, "{}", t),
         }
     }
@@ -499,7 +653,7 @@ mod tests {
                 expr: _,
                 pattern: _,
                 negated,
-            } => assert!(!negated),
+            } => assert!(negated),
             _ => panic!(),
         };
     }
@@ -576,12 +730,13 @@ mod tests {
 
         let result = Predicate::parse(&mut parser, false);
 
+        // the group ends where its closing parenthesis is missing
         match result {
             Ok(_) => panic!(),
             Err(err) => {
-                assert_eq!(err.text, "");
-                assert_eq!(err.start, 66);
-                assert_eq!(err.end, 66);
+                assert_eq!(err.text, "F");
+                assert_eq!(err.start, 62);
+                assert_eq!(err.end, 62);
             }
         };
     }
@@ -712,4 +867,123 @@ mod tests {
         assert_eq!(truth.to_string(), "Unknown");
         assert_eq!(format!("{:?}", truth), "Predicate(Unknown)");
     }
+
+    #[test]
+    pub fn test_predicate_exists_and_not_exists() {
+        let text = "EXISTS (SELECT o.id FROM orders o WHERE o.customer_id = c.id) \
+                    AND NOT EXISTS(SELECT p.id FROM payments p)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        match result {
+            Predicate::And(list) => {
+                assert!(
+                    matches!(&list[0], Predicate::Exists { negated: false, subquery } if subquery.query.criteria.is_some())
+                );
+                assert!(matches!(&list[1], Predicate::Exists { negated: true, .. }));
+                assert!(
+                    list[1]
+                        .to_string()
+                        .starts_with("NOT EXISTS (Query(projection=")
+                );
+            }
+            other => panic!("expected And, got {other:?}"),
+        }
+    }
+
+    #[test]
+    pub fn test_predicate_in_subquery_and_scalar_subquery() {
+        let text = "o.customer_id NOT IN (SELECT c.id FROM customers c) \
+                    AND (o.total > (SELECT AVG(x.total) FROM orders x) OR o.id IN (1, 2))";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        match result {
+            Predicate::And(list) => {
+                assert!(matches!(
+                    &list[0],
+                    Predicate::InSubquery { negated: true, subquery, .. }
+                        if subquery.query.projection.len() == 1
+                ));
+                match &list[1] {
+                    Predicate::Or(inner) => {
+                        assert!(matches!(
+                            &inner[0],
+                            Predicate::Compare {
+                                right: ScalarExpr::Subquery(_),
+                                ..
+                            }
+                        ));
+                        assert!(matches!(
+                            &inner[1],
+                            Predicate::InList { negated: false, .. }
+                        ));
+                    }
+                    other => panic!("expected Or, got {other:?}"),
+                }
+            }
+            other => panic!("expected And, got {other:?}"),
+        }
+    }
+
+    #[test]
+    pub fn test_predicate_and_binds_tighter_than_or() {
+        let mut parser = QueryParser::new("a = 1 OR b = 2 AND c = 3 OR d = 4");
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        assert_eq!(
+            result.to_string(),
+            "(col: a = lit: i: 1 OR (col: b = lit: i: 2 AND col: c = lit: i: 3) OR col: d = lit: i: 4)"
+        );
+    }
+
+    #[test]
+    pub fn test_predicate_not_and_leading_group() {
+        let mut parser =
+            QueryParser::new("NOT (a = 1 OR b = 2) AND NOT c IS NULL AND NOT(d = 3) GROUP BY d");
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        assert_eq!(
+            result.to_string(),
+            "(NOT (col: a = lit: i: 1 OR col: b = lit: i: 2) AND NOT col: c IS NULL AND NOT col: d = lit: i: 3)"
+        );
+        assert!(parser.comparers.group_by.compare(&parser));
+    }
+
+    #[test]
+    pub fn test_predicate_between_does_not_split_on_its_and() {
+        let mut parser =
+            QueryParser::new("a BETWEEN 1 AND b + 1 AND c NOT BETWEEN 'x' AND 'y' OR d = 0");
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        assert_eq!(
+            result.to_string(),
+            concat!(
+                "(((col: a >= lit: i: 1 AND col: a <= (col: b + lit: i: 1)) AND ",
+                "NOT (col: c >= lit: s: \"x\" AND col: c <= lit: s: \"y\")) OR col: d = lit: i: 0)"
+            )
+        );
+
+        let mut parser = QueryParser::new("a BETWEEN 1 OR 2");
+        assert!(Predicate::parse(&mut parser, false).is_err());
+    }
+
+    #[test]
+    pub fn test_predicate_parenthesized_scalar_is_not_a_group() {
+        let mut parser = QueryParser::new("(a + 1) * 2 > 4 AND ((b)) IS NOT NULL");
+
+        let result = Predicate::parse(&mut parser, false).expect("Failed to parse predicate");
+
+        assert_eq!(
+            result.to_string(),
+            "(((col: a + lit: i: 1) * lit: i: 2) > lit: i: 4 AND col: b IS NOT NULL)"
+        );
+    }
 }
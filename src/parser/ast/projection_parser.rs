@@ -8,11 +8,24 @@ impl ProjectionParser {
     }
 
     pub fn parse(parser: &mut QueryParser) -> Result<Vec<Identifier>, ParseError> {
+        Self::parse_with_distinct(parser).map(|(_, projection)| projection)
+    }
+
+    /// Parse `SELECT [DISTINCT] ...`, returning whether DISTINCT was given.
+    pub fn parse_with_distinct(
+        parser: &mut QueryParser,
+    ) -> Result<(bool, Vec<Identifier>), ParseError> {
         if !ProjectionParser::is_projection_start(parser) {
             return ParseError::new("Invalid projection", parser.position, parser).err();
         }
         parser.jump(parser.comparers.select.length);
 
+        parser.next_non_whitespace();
+        let distinct = parser.comparers.distinct.compare(parser);
+        if distinct {
+            parser.jump(parser.comparers.distinct.length);
+        }
+
         let mut pivot = parser.position;
         let mut result: Vec<Identifier> = vec![];
         let mut can_consume = true;
@@ -52,7 +65,7 @@ impl ProjectionParser {
             return ParseError::new("Invalid projection", pivot, parser).err();
         }
 
-        Ok(result)
+        Ok((distinct, result))
     }
 }
 
@@ -71,6 +84,32 @@ mod tests {
         assert_eq!(result.len(), 1);
     }
 
+    #[test]
+    pub fn test_projection_distinct() {
+        let text = "SELECT DISTINCT a, b FROM table";
+
+        let mut parser = QueryParser::new(text);
+
+        let (distinct, result) =
+            ProjectionParser::parse_with_distinct(&mut parser).expect("Failed to parse Projection");
+
+        assert!(distinct);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    pub fn test_projection_column_named_like_distinct() {
+        let text = "SELECT distinct_id FROM table";
+
+        let mut parser = QueryParser::new(text);
+
+        let (distinct, result) =
+            ProjectionParser::parse_with_distinct(&mut parser).expect("Failed to parse Projection");
+
+        assert!(!distinct);
+        assert_eq!(result.len(), 1);
+    }
+
     #[test]
     pub fn test_projection_three_columns() {
         let text = "SELECT column, other_column, column as alias FROM table";
@@ -10,22 +10,29 @@
 use crate::parser::{
     ParseError, Phase, QueryParser,
     ast::{
-        Collection, CollectionsParser, Column, GroupBy, HavingParser, Identifier, Join,
-        LimitAndOffsetParser, OrderBy, Predicate, ProjectionParser, WhereParser,
+        Collection, CollectionsParser, Cte, CteParser, GroupBy, HavingParser, Identifier, Join,
+        LimitAndOffsetParser, OrderBy, Predicate, ProjectionParser, ScalarExpr, SetOperation,
+        WhereParser,
     },
 };
 
 #[derive(Default, Clone, PartialEq)]
 pub struct Query {
+    pub ctes: Vec<Cte>,
+    pub distinct: bool,
     pub projection: Vec<Identifier>,
     pub collections: Vec<Collection>,
     pub joins: Vec<Join>,
     pub criteria: Option<Predicate>,
-    pub group_by: Vec<Column>,
+    pub group_by: Vec<ScalarExpr>,
+    /// `ROLLUP` / `CUBE` / `GROUPING SETS` as indexes into `group_by`; empty groups by all of it.
+    pub grouping_sets: Vec<Vec<usize>>,
     pub having: Option<Predicate>,
     pub order_by: Vec<OrderBy>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// `UNION` / `INTERSECT` / `EXCEPT` blocks; ORDER BY and LIMIT then apply to the combined result.
+    pub set_operations: Vec<SetOperation>,
 }
 
 impl Query {
@@ -33,27 +40,108 @@ impl Query {
         parser.next_non_whitespace();
 
         let mut query = Query::default();
+        if CteParser::is_with_start(parser) {
+            query.ctes = CteParser::parse(parser)?;
+            parser.next_non_whitespace();
+        }
 
-        while parser.phase != Phase::EOF {
+        // ON CONFLICT and RETURNING belong to the enclosing INSERT, so the query stops in front of them
+        while parser.phase < Phase::OnConflict {
             match parser.phase {
-                Phase::Projection => query.projection = ProjectionParser::parse(parser)?,
-                Phase::Collections => query.collections = CollectionsParser::parse(parser)?,
-                Phase::Joins => query.joins = Join::parse(parser)?,
-                Phase::Criteria => query.criteria = Some(WhereParser::parse(parser)?),
-                Phase::Aggregates => query.group_by = GroupBy::parse(parser)?,
-                Phase::Having => query.having = Some(HavingParser::parse(parser)?),
+                Phase::SetOperation => query.set_operations.push(SetOperation::parse(parser)?),
                 Phase::OrderBy => query.order_by = OrderBy::parse(parser)?,
                 Phase::LimitAndOffset => {
                     let (limit, offset) = LimitAndOffsetParser::parse(parser)?;
                     query.limit = limit;
                     query.offset = offset;
                 }
-                Phase::EOF => todo!(),
+                Phase::OnConflict | Phase::Returning | Phase::EOF => todo!(),
+                _ => Self::parse_block_phase(&mut query, parser)?,
             }
         }
 
         Ok(query)
     }
+
+    /// Parse the text of a query nested in the one `parser` reads, such as a subquery
+    /// or a WITH query, keeping the named parameters it uses in `parser`.
+    pub fn parse_nested(parser: &mut QueryParser, text: &str) -> Result<Self, ParseError> {
+        let mut nested = QueryParser::new(text);
+        let query = Query::parse(&mut nested)?;
+        for name in nested.parameters {
+            if !parser.parameters.contains(&name) {
+                parser.parameters.push(name);
+            }
+        }
+        Ok(query)
+    }
+
+    /// How many `?` placeholders the query holds, in all of its clauses and nested queries.
+    pub fn parameter_count(&self) -> usize {
+        self.ctes
+            .iter()
+            .map(|cte| cte.query.parameter_count())
+            .sum::<usize>()
+            + self
+                .projection
+                .iter()
+                .map(|id| id.expression.parameter_count())
+                .sum::<usize>()
+            + self
+                .collections
+                .iter()
+                .map(Collection::parameter_count)
+                .sum::<usize>()
+            + self
+                .joins
+                .iter()
+                .map(|join| join.collection.parameter_count() + join.predicate.parameter_count())
+                .sum::<usize>()
+            + self.criteria.as_ref().map_or(0, Predicate::parameter_count)
+            + self
+                .group_by
+                .iter()
+                .map(ScalarExpr::parameter_count)
+                .sum::<usize>()
+            + self.having.as_ref().map_or(0, Predicate::parameter_count)
+            + self
+                .order_by
+                .iter()
+                .map(|o| o.expr.parameter_count())
+                .sum::<usize>()
+            + self
+                .set_operations
+                .iter()
+                .map(|op| op.query.parameter_count())
+                .sum::<usize>()
+    }
+
+    /// Parse a single SELECT block, stopping at a set operator, ORDER BY or LIMIT.
+    pub fn parse_block(parser: &mut QueryParser) -> Result<Self, ParseError> {
+        parser.next_non_whitespace();
+
+        let mut query = Query::default();
+        while parser.phase < Phase::SetOperation {
+            Self::parse_block_phase(&mut query, parser)?;
+        }
+
+        Ok(query)
+    }
+
+    fn parse_block_phase(query: &mut Query, parser: &mut QueryParser) -> Result<(), ParseError> {
+        match parser.phase {
+            Phase::Projection => {
+                (query.distinct, query.projection) = ProjectionParser::parse_with_distinct(parser)?
+            }
+            Phase::Collections => query.collections = CollectionsParser::parse(parser)?,
+            Phase::Joins => query.joins = Join::parse(parser)?,
+            Phase::Criteria => query.criteria = Some(WhereParser::parse(parser)?),
+            Phase::Aggregates => (query.group_by, query.grouping_sets) = GroupBy::parse(parser)?,
+            Phase::Having => query.having = Some(HavingParser::parse(parser)?),
+            _ => unreachable!("{:?} is not part of a SELECT block", parser.phase),
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<&str> for Query {
@@ -97,6 +185,11 @@ impl fmt::Display for Query {
             .map(|g| format!("{:?}", g))
             .collect::<Vec<_>>()
             .join(", ");
+        let grouping_sets = if self.grouping_sets.is_empty() {
+            String::new()
+        } else {
+            format!(", grouping_sets={:?}", self.grouping_sets)
+        };
         let having = match &self.having {
             Some(h) => format!("{:?}", h),
             None => "None".to_string(),
@@ -108,10 +201,40 @@ impl fmt::Display for Query {
             .collect::<Vec<_>>()
             .join(", ");
 
+        let with = if self.ctes.is_empty() {
+            String::new()
+        } else {
+            let ctes = self
+                .ctes
+                .iter()
+                .map(|c| format!("{}", c))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("with=[{}], ", ctes)
+        };
+        let distinct = if self.distinct { "DISTINCT " } else { "" };
+        let set_operations = self
+            .set_operations
+            .iter()
+            .map(|s| format!(", {}", s))
+            .collect::<String>();
+
         write!(
             f,
-            "Query(projection=[{}], collections=[{}], joins=[{}], criteria={}, group_by=[{}], having={}, order_by=[{}], limit={:?}, offset={:?})",
-            proj, cols, joins, crit, group, having, order, self.limit, self.offset
+            "Query({}{}projection=[{}], collections=[{}], joins=[{}], criteria={}, group_by=[{}]{}, having={}{}, order_by=[{}], limit={:?}, offset={:?})",
+            with,
+            distinct,
+            proj,
+            cols,
+            joins,
+            crit,
+            group,
+            grouping_sets,
+            having,
+            set_operations,
+            order,
+            self.limit,
+            self.offset
         )
     }
 }
@@ -124,7 +247,7 @@ impl fmt::Debug for Query {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::ast::Query;
+    use crate::parser::{QueryParser, ast::Query};
 
     #[test]
     pub fn test_query() {
@@ -167,6 +290,19 @@ LIMIT 20
         assert!(query.order_by.is_empty());
         assert_eq!(query.limit, None);
         assert_eq!(query.offset, None);
+        assert!(!query.distinct);
+    }
+
+    #[test]
+    fn try_from_parses_select_distinct() {
+        let query = Query::try_from("SELECT DISTINCT city, age FROM people ORDER BY city LIMIT 2")
+            .expect("query should parse");
+
+        assert!(query.distinct);
+        assert_eq!(query.projection.len(), 2);
+        assert_eq!(query.order_by.len(), 1);
+        assert_eq!(query.limit, Some(2));
+        assert!(query.to_string().starts_with("Query(DISTINCT projection=["));
     }
 
     #[test]
@@ -186,6 +322,18 @@ LIMIT 20
         }
     }
 
+    #[test]
+    fn nested_queries_report_their_named_parameters() {
+        let mut parser = QueryParser::new(
+            "WITH c AS (SELECT id FROM t WHERE id > :a) \
+             SELECT s.id FROM (SELECT id FROM c WHERE id < $2) s \
+             WHERE s.id IN (SELECT id FROM t WHERE id <> :a AND id <> :b)",
+        );
+        Query::parse(&mut parser).expect("query should parse");
+
+        assert_eq!(parser.parameters, vec![":a", "$2", ":b"]);
+    }
+
     #[test]
     fn try_from_rejects_from_subquery_without_alias() {
         let err = Query::try_from("SELECT name FROM (SELECT name FROM people)");
@@ -0,0 +1,116 @@
+use crate::parser::{ParseError, Phase, QueryParser, ast::Identifier};
+
+pub struct ReturningParser;
+
+impl ReturningParser {
+    pub fn is_returning_start(parser: &QueryParser) -> bool {
+        parser.comparers.returning.compare(parser)
+    }
+
+    /// Parse an optional trailing `RETURNING` list; an empty list means no RETURNING clause.
+    pub fn parse_optional(parser: &mut QueryParser) -> Result<Vec<Identifier>, ParseError> {
+        parser.next_non_whitespace();
+        if !ReturningParser::is_returning_start(parser) {
+            return Ok(vec![]);
+        }
+        ReturningParser::parse(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Vec<Identifier>, ParseError> {
+        if !ReturningParser::is_returning_start(parser) {
+            return ParseError::new("Invalid returning", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.returning.length);
+        parser.phase = Phase::Returning;
+
+        let mut result = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let pivot = parser.position;
+            if parser.eof() || parser.current() == ',' {
+                return ParseError::new("Invalid returning", pivot, parser).err();
+            }
+            result.push(Identifier::parse(parser)?);
+
+            parser.next_non_whitespace();
+            if parser.eof() {
+                parser.phase = Phase::EOF;
+                return Ok(result);
+            }
+            if parser.current() != ',' {
+                return ParseError::new("Invalid returning", parser.position, parser).err();
+            }
+            parser.next();
+        }
+    }
+
+    /// `", returning=[...]"` for statement Display impls, empty without RETURNING.
+    pub fn display_suffix(returning: &[Identifier]) -> String {
+        if returning.is_empty() {
+            return String::new();
+        }
+        let items = returning
+            .iter()
+            .map(|r| format!("{}", r))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(", returning=[{}]", items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{Column, ReturningParser, ScalarExpr},
+    };
+
+    #[test]
+    pub fn test_returning() {
+        let text = "RETURNING id, upper(name) AS upper_name";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = ReturningParser::parse(&mut parser).expect("Failed to parse returning");
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].expression,
+            ScalarExpr::Column(Column::Name { name: "id".into() })
+        );
+        assert_eq!(result[1].alias, Some("upper_name".into()));
+    }
+
+    #[test]
+    pub fn test_returning_wildcard() {
+        let text = "returning *";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = ReturningParser::parse(&mut parser).expect("Failed to parse returning");
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].expression, ScalarExpr::WildCard);
+    }
+
+    #[test]
+    pub fn test_returning_optional_absent() {
+        let mut parser = QueryParser::new("  ");
+
+        let result = ReturningParser::parse_optional(&mut parser).expect("Failed to parse");
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    pub fn test_returning_empty_list() {
+        let text = "RETURNING id,";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = ReturningParser::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid returning");
+    }
+}
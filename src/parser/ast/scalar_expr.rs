@@ -1,7 +1,8 @@
 use crate::parser::{
     ParseError, QueryParser,
     ast::{
-        BoolParser, Column, Function, Literal, NullParser, NumberParser, ParamParser, StringParser,
+        ArgsExpr, ArithmeticOp, BoolParser, Case, Column, Function, Literal, NullParser,
+        NumberParser, ParamParser, PathSegment, StringParser, Subquery, Window,
     },
 };
 use std::fmt;
@@ -10,15 +11,186 @@ use std::fmt;
 pub enum ScalarExpr {
     Literal(Literal),
     Column(Column),
+    /// A value nested inside a column: `p.profile.city`, `tags[0]`.
+    /// Missing keys and out of range indexes read as NULL.
+    JsonPath {
+        column: Column,
+        path: Vec<PathSegment>,
+    },
     Function(Function),
     WildCard,
     WildCardWithCollection(String),
     Parameter,
+    /// `:name` or `$1`, kept with its sigil; every occurrence binds the same value.
+    NamedParameter(String),
     Args(Vec<ScalarExpr>),
+    /// `(SELECT ...)` returning a single column; NULL when it returns no rows.
+    Subquery(Subquery),
+    Case(Case),
+    /// `function(...) OVER (...)`, computed over the rows of its partition.
+    Window(Window),
+    /// `left op right` for arithmetic and `||` concatenation.
+    Binary {
+        left: Box<ScalarExpr>,
+        op: ArithmeticOp,
+        right: Box<ScalarExpr>,
+    },
+    /// Unary minus.
+    Negate(Box<ScalarExpr>),
 }
 
 impl ScalarExpr {
+    /// Does evaluating this expression need to run a subquery?
+    pub fn has_subquery(&self) -> bool {
+        match self {
+            ScalarExpr::Subquery(_) => true,
+            ScalarExpr::Function(function) => function.args.iter().any(Self::has_subquery),
+            ScalarExpr::Args(args) => args.iter().any(Self::has_subquery),
+            ScalarExpr::Case(case) => {
+                case.branches
+                    .iter()
+                    .any(|(condition, result)| condition.has_subquery() || result.has_subquery())
+                    || case.otherwise.as_deref().is_some_and(Self::has_subquery)
+            }
+            ScalarExpr::Window(window) => {
+                window.function.args.iter().any(Self::has_subquery)
+                    || window.partition_by.iter().any(Self::has_subquery)
+                    || window.order_by.iter().any(|o| o.expr.has_subquery())
+            }
+            ScalarExpr::Binary { left, right, .. } => left.has_subquery() || right.has_subquery(),
+            ScalarExpr::Negate(expr) => expr.has_subquery(),
+            _ => false,
+        }
+    }
+
+    /// How many `?` placeholders this expression holds, including those of its subqueries.
+    pub fn parameter_count(&self) -> usize {
+        match self {
+            ScalarExpr::Parameter => 1,
+            ScalarExpr::Subquery(subquery) => subquery.query.parameter_count(),
+            ScalarExpr::Function(function) => function.args.iter().map(Self::parameter_count).sum(),
+            ScalarExpr::Args(args) => args.iter().map(Self::parameter_count).sum(),
+            ScalarExpr::Case(case) => {
+                case.branches
+                    .iter()
+                    .map(|(condition, result)| {
+                        condition.parameter_count() + result.parameter_count()
+                    })
+                    .sum::<usize>()
+                    + case.otherwise.as_deref().map_or(0, Self::parameter_count)
+            }
+            ScalarExpr::Window(window) => {
+                window
+                    .function
+                    .args
+                    .iter()
+                    .map(Self::parameter_count)
+                    .sum::<usize>()
+                    + window
+                        .partition_by
+                        .iter()
+                        .map(Self::parameter_count)
+                        .sum::<usize>()
+                    + window
+                        .order_by
+                        .iter()
+                        .map(|o| o.expr.parameter_count())
+                        .sum::<usize>()
+            }
+            ScalarExpr::Binary { left, right, .. } => {
+                left.parameter_count() + right.parameter_count()
+            }
+            ScalarExpr::Negate(expr) => expr.parameter_count(),
+            _ => 0,
+        }
+    }
+
     pub fn parse(parser: &mut QueryParser, allow_wildcard: bool) -> Result<ScalarExpr, ParseError> {
+        Self::parse_binary(parser, allow_wildcard, 0)
+    }
+
+    /// Precedence climbing: folds operators binding at least as tight as `min_precedence`.
+    fn parse_binary(
+        parser: &mut QueryParser,
+        allow_wildcard: bool,
+        min_precedence: u8,
+    ) -> Result<ScalarExpr, ParseError> {
+        let mut left = Self::parse_unary(parser, allow_wildcard)?;
+
+        loop {
+            let pivot = parser.position;
+            parser.next_non_whitespace();
+            match ArithmeticOp::peek(parser) {
+                Some(op) if op.precedence() >= min_precedence => {
+                    parser.jump(op.length());
+                    let right = Self::parse_binary(parser, allow_wildcard, op.precedence() + 1)?;
+                    left = ScalarExpr::Binary {
+                        left: Box::new(left),
+                        op,
+                        right: Box::new(right),
+                    };
+                }
+                _ => {
+                    // leave the parser right after the expression, as a single term would
+                    parser.position = pivot;
+                    return Ok(left);
+                }
+            }
+        }
+    }
+
+    fn parse_unary(
+        parser: &mut QueryParser,
+        allow_wildcard: bool,
+    ) -> Result<ScalarExpr, ParseError> {
+        parser.next_non_whitespace();
+
+        // a sign followed by a digit is part of a number literal
+        let current = parser.current();
+        let signed_number = parser
+            .text_v
+            .get(parser.position + 1)
+            .is_some_and(char::is_ascii_digit);
+        if (current == '-' || current == '+') && !signed_number {
+            parser.next();
+            let operand = Self::parse_unary(parser, allow_wildcard)?;
+            return Ok(match current {
+                '-' => ScalarExpr::Negate(Box::new(operand)),
+                _ => operand,
+            });
+        }
+
+        let term = Self::parse_term(parser, allow_wildcard)?;
+        Self::parse_casts(parser, term)
+    }
+
+    /// Postfix `expr::type`, which binds tighter than any operator; `x::a::b`
+    /// casts twice.
+    fn parse_casts(
+        parser: &mut QueryParser,
+        mut expr: ScalarExpr,
+    ) -> Result<ScalarExpr, ParseError> {
+        loop {
+            let pivot = parser.position;
+            parser.next_non_whitespace();
+            if parser.current() != ':' || parser.text_v.get(parser.position + 1) != Some(&':') {
+                parser.position = pivot;
+                return Ok(expr);
+            }
+            parser.jump(2);
+            let ty = ArgsExpr::parse_type_name(parser)?;
+            expr = ScalarExpr::Function(Function {
+                name: "cast".to_string(),
+                args: vec![expr, ScalarExpr::Literal(Literal::String(ty))],
+                distinct: false,
+            });
+        }
+    }
+
+    fn parse_term(
+        parser: &mut QueryParser,
+        allow_wildcard: bool,
+    ) -> Result<ScalarExpr, ParseError> {
         parser.next_non_whitespace();
 
         if parser.eof() {
@@ -45,6 +217,26 @@ impl ScalarExpr {
             return ParamParser::parse(parser);
         }
 
+        if Case::is_case_start(parser) {
+            return Case::parse(parser).map(ScalarExpr::Case);
+        }
+
+        if Subquery::is_subquery_start(parser) {
+            return Subquery::parse(parser).map(ScalarExpr::Subquery);
+        }
+
+        if parser.current() == '(' {
+            let pivot = parser.position;
+            parser.next();
+            let expr = Self::parse(parser, false)?;
+            parser.next_non_whitespace();
+            if parser.current() != ')' {
+                return ParseError::new("Expected ) in expression", pivot, parser).err();
+            }
+            parser.next();
+            return Ok(expr);
+        }
+
         Column::parse_general_scalar(parser, allow_wildcard)
     }
 }
@@ -54,10 +246,15 @@ impl fmt::Display for ScalarExpr {
         match self {
             ScalarExpr::Literal(l) => write!(f, "lit: {}", l),
             ScalarExpr::Column(c) => write!(f, "{}", c),
+            ScalarExpr::JsonPath { column, path } => {
+                write!(f, "{}", column)?;
+                path.iter().try_for_each(|segment| write!(f, "{}", segment))
+            }
             ScalarExpr::Function(fun) => write!(f, "{}", fun),
             ScalarExpr::WildCard => write!(f, "*"),
             ScalarExpr::WildCardWithCollection(coll) => write!(f, "{}.*", coll),
             ScalarExpr::Parameter => write!(f, "?"),
+            ScalarExpr::NamedParameter(name) => write!(f, "{}", name),
             ScalarExpr::Args(args) => write!(
                 f,
                 "({})",
@@ -66,6 +263,11 @@ impl fmt::Display for ScalarExpr {
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
+            ScalarExpr::Subquery(subquery) => write!(f, "{}", subquery),
+            ScalarExpr::Case(case) => write!(f, "{}", case),
+            ScalarExpr::Window(window) => write!(f, "{}", window),
+            ScalarExpr::Binary { left, op, right } => write!(f, "({} {} {})", left, op, right),
+            ScalarExpr::Negate(expr) => write!(f, "-{}", expr),
         }
     }
 }
@@ -75,13 +277,20 @@ impl fmt::Debug for ScalarExpr {
         match self {
             ScalarExpr::Literal(_) => write!(f, "Literal({})", self),
             ScalarExpr::Column(_) => write!(f, "Column({})", self),
+            ScalarExpr::JsonPath { .. } => write!(f, "JsonPath({})", self),
             ScalarExpr::Function(_) => write!(f, "Function({})", self),
             ScalarExpr::WildCard => write!(f, "WildCard(*)"),
             ScalarExpr::WildCardWithCollection(coll) => {
                 write!(f, "WildCardWithCollection({}.*)", coll)
             }
             ScalarExpr::Parameter => write!(f, "Parameter(?)"),
+            ScalarExpr::NamedParameter(name) => write!(f, "Parameter({})", name),
             ScalarExpr::Args(_) => write!(f, "Parameter({})", self),
+            ScalarExpr::Subquery(_) => write!(f, "Subquery{}", self),
+            ScalarExpr::Case(_) => write!(f, "Case({})", self),
+            ScalarExpr::Window(_) => write!(f, "Window({})", self),
+            ScalarExpr::Binary { .. } => write!(f, "Binary{}", self),
+            ScalarExpr::Negate(_) => write!(f, "Negate({})", self),
         }
     }
 }
@@ -203,6 +412,90 @@ mod tests {
         assert_eq!((start, end, text), (11, 12, "*".to_string()));
     }
 
+    #[test]
+    pub fn test_scalar_arithmetic_precedence() {
+        assert_eq!(
+            parse_scalar("a + b * 2 - c", false).to_string(),
+            "((col: a + (col: b * lit: i: 2)) - col: c)"
+        );
+        assert_eq!(
+            parse_scalar("(a + b) * 2", false).to_string(),
+            "((col: a + col: b) * lit: i: 2)"
+        );
+        assert_eq!(
+            parse_scalar("'#' || id % 3 + 1", false).to_string(),
+            "(lit: s: \"#\" || ((col: id % lit: i: 3) + lit: i: 1))"
+        );
+        assert_eq!(
+            parse_scalar("-price*i.quantity/-2", false).to_string(),
+            "((-col: price * col: i.quantity) / lit: i: -2)"
+        );
+        assert_eq!(
+            parse_scalar("count(*)*2", true).to_string(),
+            "(count(*) * lit: i: 2)"
+        );
+    }
+
+    #[test]
+    pub fn test_scalar_arithmetic_stops_before_next_token() {
+        let mut parser = QueryParser::new("total - 1 AS net, id");
+        let expr = ScalarExpr::parse(&mut parser, false).expect("expression should parse");
+
+        assert!(matches!(expr, ScalarExpr::Binary { .. }));
+        assert_eq!(parser.current(), ' ');
+
+        let (start, _, _) = parse_error("(a + 1", false);
+        assert_eq!(start, 0);
+        let (start, _, _) = parse_error("a * ", false);
+        assert_eq!(start, 4);
+    }
+
+    #[test]
+    pub fn test_scalar_postfix_cast() {
+        assert_eq!(
+            parse_scalar("o.user_id::int + 1", false).to_string(),
+            "(cast(col: o.user_id, lit: s: \"int\") + lit: i: 1)"
+        );
+        assert_eq!(
+            parse_scalar("-price :: Float::text", false).to_string(),
+            "-cast(cast(col: price, lit: s: \"float\"), lit: s: \"text\")"
+        );
+        assert_eq!(
+            parse_scalar("CAST(a + 1 AS String)", false).to_string(),
+            "CAST((col: a + lit: i: 1), lit: s: \"string\")"
+        );
+
+        assert_eq!(
+            parse_scalar("1::String", false).to_string(),
+            "cast(lit: i: 1, lit: s: \"string\")"
+        );
+        assert_eq!(
+            parse_scalar("2.5::Int * -3::float", false).to_string(),
+            "(cast(lit: f: 2.5, lit: s: \"int\") * cast(lit: i: -3, lit: s: \"float\"))"
+        );
+
+        let mut parser = QueryParser::new("id::int AS n");
+        ScalarExpr::parse(&mut parser, false).expect("cast should parse");
+        assert_eq!(parser.current(), ' ');
+
+        let (start, _, _) = parse_error("id:: + 1", false);
+        assert_eq!(start, 5);
+        let (start, _, _) = parse_error("1:int", false);
+        assert_eq!(start, 0);
+    }
+
+    #[test]
+    pub fn test_scalar_subquery() {
+        let mut parser = QueryParser::new("(SELECT MAX(o.total) FROM orders o) AS top");
+        let expr = ScalarExpr::parse(&mut parser, false).expect("subquery should parse");
+
+        match expr {
+            ScalarExpr::Subquery(subquery) => assert_eq!(subquery.query.projection.len(), 1),
+            other => panic!("expected scalar subquery, got {other:?}"),
+        }
+        assert_eq!(parser.current(), ' ');
+    }
+
     #[test]
     fn display_and_debug_cover_all_scalar_expr_variants() {
         let literal = ScalarExpr::Literal(Literal::Int(1));
@@ -234,6 +527,9 @@ mod tests {
         );
         assert_eq!(parameter.to_string(), "?");
         assert_eq!(format!("{:?}", parameter), "Parameter(?)");
+        let named = ScalarExpr::NamedParameter(":user_id".to_string());
+        assert_eq!(named.to_string(), ":user_id");
+        assert_eq!(format!("{:?}", named), "Parameter(:user_id)");
         assert_eq!(args.to_string(), "(lit: i: 1, ?)");
         assert_eq!(format!("{:?}", args), "Parameter((lit: i: 1, ?))");
     }
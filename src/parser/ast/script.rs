@@ -0,0 +1,114 @@
+use crate::parser::{ParseError, ast::Statement};
+
+/// A `;`-separated list of statements, e.g. the contents of a `.sql` file.
+pub struct Script;
+
+impl Script {
+    /// Parse every statement up front so a syntax error anywhere rejects the whole script.
+    pub fn parse(text: &str) -> Result<Vec<Statement>, ParseError> {
+        Script::split(text)?
+            .iter()
+            .map(|statement| Statement::try_from(statement.as_str()))
+            .collect()
+    }
+
+    /// Split on `;` outside string literals, dropping `--` line comments and empty statements.
+    pub fn split(text: &str) -> Result<Vec<String>, ParseError> {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut statements = vec![];
+        let mut current = String::new();
+        let mut quote: Option<(char, usize)> = None;
+
+        let mut position = 0;
+        while position < chars.len() {
+            let c = chars[position];
+            match quote {
+                Some((delimiter, _)) => {
+                    if c == delimiter {
+                        quote = None;
+                    }
+                    current.push(c);
+                }
+                None if c == '\'' || c == '"' => {
+                    quote = Some((c, position));
+                    current.push(c);
+                }
+                None if c == '-' && chars.get(position + 1) == Some(&'-') => {
+                    while position < chars.len() && chars[position] != '\n' {
+                        position += 1;
+                    }
+                    continue;
+                }
+                None if c == ';' => Script::push_statement(&mut statements, &mut current),
+                None => current.push(c),
+            }
+            position += 1;
+        }
+
+        if let Some((_, pivot)) = quote {
+            return Err(ParseError {
+                message: "Invalid string".to_string(),
+                text: chars[pivot..].iter().collect(),
+                start: pivot,
+                end: chars.len(),
+            });
+        }
+        Script::push_statement(&mut statements, &mut current);
+
+        Ok(statements)
+    }
+
+    fn push_statement(statements: &mut Vec<String>, current: &mut String) {
+        let statement = current.trim();
+        if !statement.is_empty() {
+            statements.push(statement.to_string());
+        }
+        current.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::{Script, Statement};
+
+    #[test]
+    pub fn test_script_split() {
+        let text = "
+            -- bootstrap
+            CREATE COLLECTION users (user_id Id, name String!);
+            INSERT INTO users (name) VALUES ('a;b'), (\"--c\"); -- trailing
+            ;
+            SELECT * FROM users
+        ";
+
+        let result = Script::split(text).expect("Failed to split script");
+
+        assert_eq!(
+            result,
+            vec![
+                "CREATE COLLECTION users (user_id Id, name String!)",
+                "INSERT INTO users (name) VALUES ('a;b'), (\"--c\")",
+                "SELECT * FROM users",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_script_parse() {
+        let text = "DROP COLLECTION IF EXISTS users; CREATE COLLECTION users (user_id Id);";
+
+        let result = Script::parse(text).expect("Failed to parse script");
+
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0], Statement::DropCollection(_)));
+        assert!(matches!(result[1], Statement::CreateCollection(_)));
+    }
+
+    #[test]
+    pub fn test_script_unclosed_string() {
+        let result = Script::split("SELECT 'a; SELECT b");
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid string");
+    }
+}
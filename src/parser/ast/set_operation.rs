@@ -0,0 +1,136 @@
+use crate::parser::{ParseError, Phase, QueryParser, ast::Query};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct SetOperation {
+    pub op: SetOperator,
+    pub all: bool,
+    pub query: Query, // single SELECT block, without ORDER BY/LIMIT
+}
+
+impl SetOperation {
+    pub fn is_set_operation_start(parser: &QueryParser) -> bool {
+        parser.comparers.union.compare(parser)
+            || parser.comparers.intersect.compare(parser)
+            || parser.comparers.except.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<SetOperation, ParseError> {
+        let op = if parser.comparers.union.compare(parser) {
+            parser.jump(parser.comparers.union.length);
+            SetOperator::Union
+        } else if parser.comparers.intersect.compare(parser) {
+            parser.jump(parser.comparers.intersect.length);
+            SetOperator::Intersect
+        } else if parser.comparers.except.compare(parser) {
+            parser.jump(parser.comparers.except.length);
+            SetOperator::Except
+        } else {
+            return ParseError::new("Invalid set operation", parser.position, parser).err();
+        };
+
+        parser.next_non_whitespace();
+        let all = parser.comparers.all.compare(parser);
+        if all {
+            parser.jump(parser.comparers.all.length);
+            parser.next_non_whitespace();
+        }
+
+        if !parser.comparers.select.compare(parser) {
+            return ParseError::new(
+                "Expected SELECT after set operator",
+                parser.position,
+                parser,
+            )
+            .err();
+        }
+        parser.phase = Phase::Projection;
+        let query = Query::parse_block(parser)?;
+
+        Ok(SetOperation { op, all, query })
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl fmt::Display for SetOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = if self.all { " ALL" } else { "" };
+        write!(f, "{}{} {}", self.op, all, self.query)
+    }
+}
+
+impl fmt::Debug for SetOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        Phase, QueryParser,
+        ast::{Query, SetOperation, SetOperator},
+    };
+
+    #[test]
+    pub fn test_set_operation() {
+        let mut parser = QueryParser::new("UNION ALL SELECT id FROM payments ORDER BY id");
+
+        let result = SetOperation::parse(&mut parser).expect("Failed to parse set operation");
+
+        assert_eq!(result.op, SetOperator::Union);
+        assert!(result.all);
+        assert_eq!(result.query.collections.len(), 1);
+        // ORDER BY is left for the compound query
+        assert!(result.query.order_by.is_empty());
+        assert_eq!(parser.phase, Phase::OrderBy);
+    }
+
+    #[test]
+    pub fn test_compound_query() {
+        let text = "SELECT id FROM orders WHERE total > 10 \
+                    EXCEPT SELECT order_id FROM payments \
+                    INTERSECT SELECT id FROM refunds \
+                    ORDER BY id DESC LIMIT 5";
+
+        let query = Query::try_from(text).expect("Failed to parse compound query");
+
+        assert!(query.criteria.is_some());
+        assert_eq!(query.set_operations.len(), 2);
+        assert_eq!(query.set_operations[0].op, SetOperator::Except);
+        assert!(!query.set_operations[0].all);
+        assert_eq!(query.set_operations[1].op, SetOperator::Intersect);
+        assert_eq!(query.order_by.len(), 1);
+        assert_eq!(query.limit, Some(5));
+    }
+
+    #[test]
+    pub fn test_set_operation_without_select() {
+        let mut parser = QueryParser::new("UNION orders");
+
+        let result = SetOperation::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().message,
+            "Expected SELECT after set operator"
+        );
+    }
+}
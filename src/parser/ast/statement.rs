@@ -0,0 +1,151 @@
+use crate::parser::{
+    ParseError, Phase, QueryParser,
+    ast::{CreateCollection, Delete, DropCollection, Insert, Query, Update},
+};
+
+#[derive(Clone, PartialEq)]
+pub enum Statement {
+    Select(Box<Query>),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    CreateCollection(CreateCollection),
+    DropCollection(DropCollection),
+}
+
+impl Statement {
+    pub fn parse(parser: &mut QueryParser) -> Result<Self, ParseError> {
+        parser.next_non_whitespace();
+
+        if Insert::is_insert_start(parser) {
+            return Ok(Statement::Insert(Insert::parse(parser)?));
+        }
+
+        if Update::is_update_start(parser) {
+            return Ok(Statement::Update(Update::parse(parser)?));
+        }
+
+        if Delete::is_delete_start(parser) {
+            return Ok(Statement::Delete(Delete::parse(parser)?));
+        }
+
+        if CreateCollection::is_create_collection_start(parser) {
+            return Ok(Statement::CreateCollection(CreateCollection::parse(
+                parser,
+            )?));
+        }
+
+        if DropCollection::is_drop_collection_start(parser) {
+            return Ok(Statement::DropCollection(DropCollection::parse(parser)?));
+        }
+
+        let query = Query::parse(parser)?;
+        if parser.phase == Phase::OnConflict {
+            return ParseError::new(
+                "ON CONFLICT is only allowed in INSERT",
+                parser.position,
+                parser,
+            )
+            .err();
+        }
+        if parser.phase == Phase::Returning {
+            return ParseError::new(
+                "RETURNING is only allowed in INSERT, UPDATE or DELETE",
+                parser.position,
+                parser,
+            )
+            .err();
+        }
+
+        Ok(Statement::Select(Box::new(query)))
+    }
+}
+
+impl TryFrom<&str> for Statement {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parser = QueryParser::new(value);
+        Statement::parse(&mut parser)
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Select(query) => write!(f, "{}", query),
+            Statement::Insert(insert) => write!(f, "{}", insert),
+            Statement::Update(update) => write!(f, "{}", update),
+            Statement::Delete(delete) => write!(f, "{}", delete),
+            Statement::CreateCollection(create) => write!(f, "{}", create),
+            Statement::DropCollection(drop) => write!(f, "{}", drop),
+        }
+    }
+}
+
+impl fmt::Debug for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Statement({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::Statement;
+
+    #[test]
+    pub fn test_statement_select() {
+        let result = Statement::try_from("SELECT a FROM t").expect("Failed to parse statement");
+
+        assert!(matches!(result, Statement::Select(_)));
+    }
+
+    #[test]
+    pub fn test_statement_insert() {
+        let result = Statement::try_from("  INSERT INTO t (a) VALUES (1)")
+            .expect("Failed to parse statement");
+
+        match result {
+            Statement::Insert(insert) => assert_eq!(insert.collection, "t"),
+            other => panic!("expected insert, got {other:?}"),
+        }
+    }
+
+    #[test]
+    pub fn test_statement_update() {
+        let result = Statement::try_from("UPDATE t SET a = 1").expect("Failed to parse statement");
+
+        assert!(matches!(result, Statement::Update(_)));
+    }
+
+    #[test]
+    pub fn test_statement_delete() {
+        let result =
+            Statement::try_from("DELETE FROM t WHERE a = 1").expect("Failed to parse statement");
+
+        assert!(matches!(result, Statement::Delete(_)));
+    }
+
+    #[test]
+    pub fn test_statement_create_and_drop_collection() {
+        let result = Statement::try_from("CREATE COLLECTION t (id Id, a Int)")
+            .expect("Failed to parse statement");
+        assert!(matches!(result, Statement::CreateCollection(_)));
+
+        let result = Statement::try_from("DROP COLLECTION t").expect("Failed to parse statement");
+        assert!(matches!(result, Statement::DropCollection(_)));
+    }
+
+    #[test]
+    pub fn test_statement_select_rejects_returning() {
+        let result = Statement::try_from("SELECT a FROM t RETURNING a");
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().message,
+            "RETURNING is only allowed in INSERT, UPDATE or DELETE"
+        );
+    }
+}
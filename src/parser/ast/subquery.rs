@@ -0,0 +1,121 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::parser::{
+    ParseError, QueryParser,
+    analyzer::AnalyzedQuery,
+    ast::{Collection, Query},
+};
+
+/// `(SELECT ...)` nested inside a predicate or expression.
+/// The analyzer fills `analyzed`; the executor plans and runs it per outer row.
+#[derive(Clone)]
+pub struct Subquery {
+    pub query: Box<Query>,
+    pub analyzed: Option<Arc<AnalyzedQuery>>,
+}
+
+impl Subquery {
+    pub fn new(query: Query) -> Self {
+        Self {
+            query: Box::new(query),
+            analyzed: None,
+        }
+    }
+
+    /// Is the parser at `(` opening a `SELECT` or `WITH` query rather than a plain group?
+    pub fn is_subquery_start(parser: &mut QueryParser) -> bool {
+        if parser.current() != '(' {
+            return false;
+        }
+
+        let position = parser.position;
+        parser.next();
+        parser.next_non_whitespace();
+        let is_query =
+            parser.comparers.select.compare(parser) || parser.comparers.with.compare(parser);
+        parser.position = position;
+        is_query
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Subquery, ParseError> {
+        parser.next_non_whitespace();
+        let pivot = parser.position;
+        if parser.current() != '(' {
+            return ParseError::new("Expected subquery", pivot, parser).err();
+        }
+
+        let text = Collection::collect_parenthesized_query(parser)?;
+        let query = Query::parse_nested(parser, text.trim())
+            .map_err(|_| ParseError::new("Invalid subquery", pivot, parser))?;
+        Ok(Subquery::new(query))
+    }
+}
+
+impl PartialEq for Subquery {
+    fn eq(&self, other: &Self) -> bool {
+        self.query == other.query
+    }
+}
+
+impl Eq for Subquery {}
+
+impl Hash for Subquery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.query.to_string().hash(state);
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Subquery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", self.query)
+    }
+}
+
+impl fmt::Debug for Subquery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Subquery{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{QueryParser, ast::Subquery};
+
+    #[test]
+    pub fn test_subquery() {
+        let mut parser =
+            QueryParser::new("(SELECT o.id FROM orders o WHERE o.total > 10) AND x = 1");
+
+        let result = Subquery::parse(&mut parser).expect("Failed to parse subquery");
+
+        assert_eq!(result.query.projection.len(), 1);
+        assert!(result.query.criteria.is_some());
+        assert!(result.analyzed.is_none());
+        assert_eq!(parser.current(), ' ');
+    }
+
+    #[test]
+    pub fn test_is_subquery_start() {
+        let mut parser = QueryParser::new("( select id FROM orders)");
+        assert!(Subquery::is_subquery_start(&mut parser));
+        assert_eq!(parser.position, 0);
+
+        let mut parser = QueryParser::new("(a = 1 OR b = 2)");
+        assert!(!Subquery::is_subquery_start(&mut parser));
+    }
+
+    #[test]
+    pub fn test_subquery_invalid() {
+        let mut parser = QueryParser::new("(SELEC id FROM orders)");
+
+        let result = Subquery::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid subquery");
+    }
+}
@@ -0,0 +1,241 @@
+use crate::parser::{
+    ParseError, Phase, QueryParser,
+    ast::{Identifier, Predicate, ReturningParser, ScalarExpr, TextCollector, WhereParser},
+};
+
+#[derive(Clone, PartialEq)]
+pub struct Assignment {
+    pub column: String,
+    pub value: ScalarExpr,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Update {
+    pub collection: String,
+    pub assignments: Vec<Assignment>,
+    pub criteria: Option<Predicate>,
+    pub returning: Vec<Identifier>,
+}
+
+impl Update {
+    pub fn is_update_start(parser: &QueryParser) -> bool {
+        parser.comparers.update.compare(parser)
+    }
+
+    pub fn parse(parser: &mut QueryParser) -> Result<Update, ParseError> {
+        if !Update::is_update_start(parser) {
+            return ParseError::new("Invalid update", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.update.length);
+        parser.next_non_whitespace();
+
+        let pivot = parser.position;
+        let collection = TextCollector::collect(parser)?;
+        if collection.is_empty() {
+            return ParseError::new("Invalid update collection", pivot, parser).err();
+        }
+
+        parser.next_non_whitespace();
+        if !parser.comparers.set.compare(parser) {
+            return ParseError::new("Expected SET", parser.position, parser).err();
+        }
+        parser.jump(parser.comparers.set.length);
+
+        let assignments = Self::parse_assignments(parser)?;
+
+        parser.next_non_whitespace();
+        let mut criteria = None;
+        if parser.comparers.r#where.compare(parser) {
+            parser.phase = Phase::Criteria;
+            criteria = Some(WhereParser::parse(parser)?);
+        }
+
+        let returning = ReturningParser::parse_optional(parser)?;
+        if !parser.eof() {
+            return ParseError::new("Invalid update", parser.position, parser).err();
+        }
+
+        Ok(Update {
+            collection,
+            assignments,
+            criteria,
+            returning,
+        })
+    }
+
+    pub(crate) fn parse_assignments(
+        parser: &mut QueryParser,
+    ) -> Result<Vec<Assignment>, ParseError> {
+        let mut assignments = vec![];
+        loop {
+            parser.next_non_whitespace();
+            let pivot = parser.position;
+            let column = TextCollector::collect(parser)?;
+            if column.is_empty() {
+                return ParseError::new("Invalid assignment", pivot, parser).err();
+            }
+
+            parser.next_non_whitespace();
+            if !parser.comparers.equal.compare(parser) {
+                return ParseError::new("Invalid assignment", pivot, parser).err();
+            }
+            parser.jump(parser.comparers.equal.length);
+            parser.next_non_whitespace();
+
+            let value = ScalarExpr::parse(parser, false)?;
+            assignments.push(Assignment { column, value });
+
+            parser.next_non_whitespace();
+            if parser.current() != ',' {
+                return Ok(assignments);
+            }
+            parser.next();
+        }
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.column, self.value)
+    }
+}
+
+impl fmt::Debug for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Assignment({})", self)
+    }
+}
+
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let assignments = self
+            .assignments
+            .iter()
+            .map(|a| format!("{}", a))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let crit = match &self.criteria {
+            Some(c) => format!("{:?}", c),
+            None => "None".to_string(),
+        };
+        write!(
+            f,
+            "Update(collection={}, set=[{}], criteria={}{})",
+            self.collection,
+            assignments,
+            crit,
+            ReturningParser::display_suffix(&self.returning)
+        )
+    }
+}
+
+impl fmt::Debug for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{Column, Literal, Predicate, ScalarExpr, Update},
+    };
+
+    #[test]
+    pub fn test_update() {
+        let text = "UPDATE people SET name = 'Ada', age = ? WHERE id = 1";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser).expect("Failed to parse update");
+
+        assert_eq!(result.collection, "people");
+        assert_eq!(result.assignments.len(), 2);
+        assert_eq!(result.assignments[0].column, "name");
+        assert_eq!(
+            result.assignments[0].value,
+            ScalarExpr::Literal(Literal::String("Ada".into()))
+        );
+        assert_eq!(result.assignments[1].value, ScalarExpr::Parameter);
+        assert!(matches!(result.criteria, Some(Predicate::Compare { .. })));
+    }
+
+    #[test]
+    pub fn test_update_without_where() {
+        let text = "update t set cat = upper(cat)";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser).expect("Failed to parse update");
+
+        assert!(result.criteria.is_none());
+        assert_eq!(
+            format!("{:?}", result),
+            "Update(collection=t, set=[cat = upper(col: cat)], criteria=None)"
+        );
+        assert!(matches!(
+            &result.assignments[0].value,
+            ScalarExpr::Function(f) if f.args == vec![ScalarExpr::Column(Column::Name { name: "cat".into() })]
+        ));
+    }
+
+    #[test]
+    pub fn test_update_without_set() {
+        let text = "UPDATE people name = 'Ada'";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Expected SET");
+    }
+
+    #[test]
+    pub fn test_update_invalid_assignment() {
+        let text = "UPDATE people SET name 'Ada'";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid assignment");
+    }
+
+    #[test]
+    pub fn test_update_trailing_clause() {
+        let text = "UPDATE people SET name = 'Ada' WHERE id = 1 ORDER BY id";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser);
+
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap().message, "Invalid update");
+    }
+
+    #[test]
+    pub fn test_update_returning() {
+        let text = "UPDATE people SET age = 1 RETURNING *";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser).expect("Failed to parse update");
+
+        assert!(result.criteria.is_none());
+        assert_eq!(result.returning.len(), 1);
+
+        let text = "UPDATE people SET age = 1 WHERE id = 2 RETURNING id, age";
+
+        let mut parser = QueryParser::new(text);
+
+        let result = Update::parse(&mut parser).expect("Failed to parse update");
+
+        assert!(result.criteria.is_some());
+        assert_eq!(result.returning.len(), 2);
+    }
+}
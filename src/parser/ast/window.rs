@@ -0,0 +1,353 @@
+use crate::parser::{
+    ParseError, QueryParser,
+    ast::{Function, OrderBy, ScalarExpr},
+};
+
+/// `function(...) OVER ([PARTITION BY ...] [ORDER BY ...] [ROWS ...])`.
+///
+/// Without a frame, the function sees the partition up to the last row that
+/// sorts equal to the current one, or the whole partition without ORDER BY.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Window {
+    pub function: Function,
+    pub partition_by: Vec<ScalarExpr>,
+    pub order_by: Vec<OrderBy>,
+    pub frame: Option<WindowFrame>,
+}
+
+/// `ROWS BETWEEN start AND end`, or `ROWS start` which ends at the current row.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WindowFrame {
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(usize),
+    CurrentRow,
+    Following(usize),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    /// Row index the bound points at for the row at `position`; may fall outside the partition.
+    /// Offsets too large for an `isize` saturate, which is still outside the partition.
+    pub fn offset(self, position: usize, len: usize) -> isize {
+        let position = position as isize;
+        let rows = |n: usize| isize::try_from(n).unwrap_or(isize::MAX);
+        match self {
+            FrameBound::UnboundedPreceding => 0,
+            FrameBound::Preceding(n) => position.saturating_sub(rows(n)),
+            FrameBound::CurrentRow => position,
+            FrameBound::Following(n) => position.saturating_add(rows(n)),
+            FrameBound::UnboundedFollowing => len as isize - 1,
+        }
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            FrameBound::UnboundedPreceding => 0,
+            FrameBound::Preceding(_) => 1,
+            FrameBound::CurrentRow => 2,
+            FrameBound::Following(_) => 3,
+            FrameBound::UnboundedFollowing => 4,
+        }
+    }
+
+    fn parse(parser: &mut QueryParser) -> Result<FrameBound, ParseError> {
+        parser.next_non_whitespace();
+        let pivot = parser.position;
+        if parser.comparers.unbounded_preceding.compare(parser) {
+            parser.jump(parser.comparers.unbounded_preceding.length);
+            return Ok(FrameBound::UnboundedPreceding);
+        }
+        if parser.comparers.unbounded_following.compare(parser) {
+            parser.jump(parser.comparers.unbounded_following.length);
+            return Ok(FrameBound::UnboundedFollowing);
+        }
+        if parser.comparers.current_row.compare(parser) {
+            parser.jump(parser.comparers.current_row.length);
+            return Ok(FrameBound::CurrentRow);
+        }
+
+        while parser.current().is_ascii_digit() {
+            parser.next();
+        }
+        let Ok(offset) = parser.text_from_pivot(pivot).parse::<usize>() else {
+            return ParseError::new("Invalid window frame bound", pivot, parser).err();
+        };
+        parser.next_non_whitespace();
+        if parser.comparers.preceding.compare(parser) {
+            parser.jump(parser.comparers.preceding.length);
+            return Ok(FrameBound::Preceding(offset));
+        }
+        if parser.comparers.following.compare(parser) {
+            parser.jump(parser.comparers.following.length);
+            return Ok(FrameBound::Following(offset));
+        }
+        ParseError::new("Expected PRECEDING or FOLLOWING", pivot, parser).err()
+    }
+}
+
+impl Window {
+    pub fn is_over_start(parser: &QueryParser) -> bool {
+        parser.comparers.over.compare(parser)
+    }
+
+    /// Parses the `OVER (...)` clause that follows `function`.
+    pub fn parse(parser: &mut QueryParser, function: Function) -> Result<Window, ParseError> {
+        let pivot = parser.position;
+        if !Window::is_over_start(parser) {
+            return ParseError::new("Expected OVER", pivot, parser).err();
+        }
+        parser.jump(parser.comparers.over.length);
+        parser.next_non_whitespace();
+        if parser.current() != '(' {
+            return ParseError::new("Expected ( after OVER", pivot, parser).err();
+        }
+        parser.next();
+        parser.next_non_whitespace();
+
+        let mut partition_by = vec![];
+        if parser.comparers.partition_by.compare(parser) {
+            parser.jump(parser.comparers.partition_by.length);
+            loop {
+                partition_by.push(ScalarExpr::parse(parser, false)?);
+                parser.next_non_whitespace();
+                if parser.current() != ',' {
+                    break;
+                }
+                parser.next();
+            }
+        }
+
+        let mut order_by = vec![];
+        if parser.comparers.order_by.compare(parser) {
+            parser.jump(parser.comparers.order_by.length);
+            loop {
+                let expr = ScalarExpr::parse(parser, false)?;
+                parser.next_non_whitespace();
+                let mut ascending = true;
+                if parser.comparers.asc.compare(parser) {
+                    parser.jump(parser.comparers.asc.length);
+                } else if parser.comparers.desc.compare(parser) {
+                    parser.jump(parser.comparers.desc.length);
+                    ascending = false;
+                }
+                order_by.push(OrderBy { expr, ascending });
+                parser.next_non_whitespace();
+                if parser.current() != ',' {
+                    break;
+                }
+                parser.next();
+            }
+        }
+
+        let mut frame = None;
+        if parser.comparers.rows.compare(parser) {
+            parser.jump(parser.comparers.rows.length);
+            parser.next_non_whitespace();
+            let window_frame = match parser.comparers.between.compare(parser) {
+                true => {
+                    parser.jump(parser.comparers.between.length);
+                    let start = FrameBound::parse(parser)?;
+                    parser.next_non_whitespace();
+                    if !parser.comparers.and.compare(parser) {
+                        return ParseError::new("Expected AND in window frame", pivot, parser)
+                            .err();
+                    }
+                    parser.jump(parser.comparers.and.length);
+                    WindowFrame {
+                        start,
+                        end: FrameBound::parse(parser)?,
+                    }
+                }
+                false => WindowFrame {
+                    start: FrameBound::parse(parser)?,
+                    end: FrameBound::CurrentRow,
+                },
+            };
+            if window_frame.start == FrameBound::UnboundedFollowing
+                || window_frame.end == FrameBound::UnboundedPreceding
+                || window_frame.start.rank() > window_frame.end.rank()
+            {
+                return ParseError::new("Invalid window frame", pivot, parser).err();
+            }
+            frame = Some(window_frame);
+            parser.next_non_whitespace();
+        }
+
+        if parser.current() != ')' {
+            return ParseError::new("Expected ) in window", pivot, parser).err();
+        }
+        parser.next();
+
+        Ok(Window {
+            function,
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+}
+
+use std::fmt;
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} OVER (", self.function)?;
+        let mut clauses = vec![];
+        if !self.partition_by.is_empty() {
+            let exprs: Vec<String> = self.partition_by.iter().map(|e| e.to_string()).collect();
+            clauses.push(format!("PARTITION BY {}", exprs.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            let keys: Vec<String> = self.order_by.iter().map(|o| o.to_string()).collect();
+            clauses.push(format!("ORDER BY {}", keys.join(", ")));
+        }
+        if let Some(frame) = &self.frame {
+            clauses.push(format!("ROWS BETWEEN {} AND {}", frame.start, frame.end));
+        }
+        write!(f, "{})", clauses.join(" "))
+    }
+}
+
+impl fmt::Debug for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Window({})", self)
+    }
+}
+
+impl fmt::Display for FrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(n) => write!(f, "{} PRECEDING", n),
+            FrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            FrameBound::Following(n) => write!(f, "{} FOLLOWING", n),
+            FrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        QueryParser,
+        ast::{Column, FrameBound, ScalarExpr, Window, WindowFrame},
+    };
+
+    fn parse_window(text: &str) -> Window {
+        match ScalarExpr::parse(&mut QueryParser::new(text), true) {
+            Ok(ScalarExpr::Window(window)) => window,
+            other => panic!("{text} should parse to a window, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_window_partition_order_and_frame() {
+        let window = parse_window(
+            "sum(amt) over (partition by cat, region order by day desc, id rows between 2 preceding and current row)",
+        );
+        assert_eq!(window.function.name, "sum");
+        assert_eq!(window.partition_by.len(), 2);
+        assert_eq!(window.order_by.len(), 2);
+        assert!(!window.order_by[0].ascending);
+        assert!(window.order_by[1].ascending);
+        assert_eq!(
+            window.frame,
+            Some(WindowFrame {
+                start: FrameBound::Preceding(2),
+                end: FrameBound::CurrentRow,
+            })
+        );
+    }
+
+    #[test]
+    fn test_window_empty_and_shorthand_frame() {
+        let window = parse_window("row_number() OVER ()");
+        assert!(window.partition_by.is_empty() && window.order_by.is_empty());
+        assert_eq!(window.frame, None);
+
+        let window = parse_window("count(*) OVER (ORDER BY t.id ASC ROWS UNBOUNDED PRECEDING)");
+        assert_eq!(
+            window.order_by[0].expr,
+            ScalarExpr::Column(Column::WithCollection {
+                collection: "t".into(),
+                name: "id".into()
+            })
+        );
+        assert_eq!(
+            window.frame,
+            Some(WindowFrame {
+                start: FrameBound::UnboundedPreceding,
+                end: FrameBound::CurrentRow,
+            })
+        );
+
+        let window = parse_window(
+            "last_value(x) OVER (ORDER BY x ROWS BETWEEN 1 FOLLOWING AND UNBOUNDED FOLLOWING)",
+        );
+        assert_eq!(
+            window.frame,
+            Some(WindowFrame {
+                start: FrameBound::Following(1),
+                end: FrameBound::UnboundedFollowing,
+            })
+        );
+    }
+
+    #[test]
+    fn test_frame_bound_offsets_saturate() {
+        assert_eq!(FrameBound::Preceding(2).offset(5, 10), 3);
+        assert_eq!(FrameBound::Following(2).offset(5, 10), 7);
+        assert_eq!(FrameBound::Following(usize::MAX).offset(5, 10), isize::MAX);
+        assert_eq!(
+            FrameBound::Following(isize::MAX as usize).offset(5, 10),
+            isize::MAX
+        );
+        assert_eq!(
+            FrameBound::Preceding(usize::MAX).offset(5, 10),
+            5 - isize::MAX
+        );
+    }
+
+    #[test]
+    fn test_window_in_expression() {
+        let mut parser = QueryParser::new("rank() OVER (ORDER BY x DESC) + 1 AS r");
+        let expr = ScalarExpr::parse(&mut parser, false).expect("should parse");
+        match expr {
+            ScalarExpr::Binary { left, .. } => assert!(matches!(*left, ScalarExpr::Window(_))),
+            other => panic!("expected a binary expression, got {other:?}"),
+        }
+        assert_eq!(parser.current(), ' ');
+
+        // a function without OVER stays a function
+        let mut parser = QueryParser::new("sum(x) over_total");
+        assert!(matches!(
+            ScalarExpr::parse(&mut parser, false),
+            Ok(ScalarExpr::Function(_))
+        ));
+    }
+
+    #[test]
+    fn test_window_invalid() {
+        for text in [
+            "rank() OVER",
+            "rank() OVER (ORDER BY x",
+            "rank() OVER (PARTITION x)",
+            "sum(x) OVER (ROWS BETWEEN CURRENT ROW AND 1 PRECEDING)",
+            "sum(x) OVER (ROWS UNBOUNDED FOLLOWING)",
+            "sum(x) OVER (ROWS BETWEEN 1 PRECEDING 1 FOLLOWING)",
+            "sum(x) OVER (ROWS BETWEEN -1 PRECEDING AND CURRENT ROW)",
+            "sum(x) OVER (ROWS 2 AFTER)",
+        ] {
+            assert!(
+                ScalarExpr::parse(&mut QueryParser::new(text), false).is_err(),
+                "{text} should not parse"
+            );
+        }
+    }
+}
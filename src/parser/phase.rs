@@ -7,7 +7,10 @@ pub enum Phase {
     Criteria = 3,
     Aggregates = 4,
     Having = 5,
-    OrderBy = 6,
-    LimitAndOffset = 7,
-    EOF = 8,
+    SetOperation = 6,
+    OrderBy = 7,
+    LimitAndOffset = 8,
+    OnConflict = 9,
+    Returning = 10,
+    EOF = 11,
 }
@@ -11,6 +11,8 @@ pub struct QueryComparers {
     pub left_join: WordComparer,
     pub right_join: WordComparer,
     pub full_join: WordComparer,
+    pub cross_join: WordComparer,
+    pub unnest: WordComparer,
     pub on: WordComparer,
     pub r#where: WordComparer,
     pub group_by: WordComparer,
@@ -35,10 +37,50 @@ pub struct QueryComparers {
     pub is_not_null: WordComparer,
     pub r#in: WordComparer,
     pub not_in: WordComparer,
+    pub exists: WordComparer,
+    pub not_exists: WordComparer,
+    pub not: WordComparer,
+    pub between: WordComparer,
+    pub not_between: WordComparer,
     pub b_true: WordComparer,
     pub b_false: WordComparer,
     pub null: WordComparer,
     pub param: WordComparer,
+    pub insert_into: WordComparer,
+    pub values: WordComparer,
+    pub update: WordComparer,
+    pub set: WordComparer,
+    pub delete_from: WordComparer,
+    pub returning: WordComparer,
+    pub on_conflict: WordComparer,
+    pub do_nothing: WordComparer,
+    pub do_update: WordComparer,
+    pub create_collection: WordComparer,
+    pub drop_collection: WordComparer,
+    pub if_not_exists: WordComparer,
+    pub if_exists: WordComparer,
+    pub union: WordComparer,
+    pub intersect: WordComparer,
+    pub except: WordComparer,
+    pub all: WordComparer,
+    pub with: WordComparer,
+    pub recursive: WordComparer,
+    pub case: WordComparer,
+    pub when: WordComparer,
+    pub then: WordComparer,
+    pub r#else: WordComparer,
+    pub end: WordComparer,
+    pub over: WordComparer,
+    pub partition_by: WordComparer,
+    pub rows: WordComparer,
+    pub unbounded_preceding: WordComparer,
+    pub unbounded_following: WordComparer,
+    pub current_row: WordComparer,
+    pub preceding: WordComparer,
+    pub following: WordComparer,
+    pub rollup: WordComparer,
+    pub cube: WordComparer,
+    pub grouping_sets: WordComparer,
 }
 
 impl Default for QueryComparers {
@@ -59,17 +101,23 @@ impl QueryComparers {
             left_join: WordComparer::new("LEFT JOIN").with_whitespace_postfix(),
             right_join: WordComparer::new("RIGHT JOIN").with_whitespace_postfix(),
             full_join: WordComparer::new("FULL JOIN").with_whitespace_postfix(),
+            cross_join: WordComparer::new("CROSS JOIN").with_whitespace_postfix(),
+            unnest: WordComparer::new("UNNEST")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
             on: WordComparer::new("ON").with_whitespace_postfix(),
             r#where: WordComparer::new("WHERE").with_whitespace_postfix(),
             group_by: WordComparer::new("GROUP BY").with_whitespace_postfix(),
             asc: WordComparer::new("ASC")
                 .with_whitespace_postfix()
                 .with_eof()
-                .with_optional_postfix(','),
+                .with_optional_postfix(',')
+                .with_optional_postfix(')'),
             desc: WordComparer::new("DESC")
                 .with_whitespace_postfix()
                 .with_eof()
-                .with_optional_postfix(','),
+                .with_optional_postfix(',')
+                .with_optional_postfix(')'),
             having: WordComparer::new("HAVING").with_whitespace_postfix(),
             order_by: WordComparer::new("ORDER BY").with_whitespace_postfix(),
             limit: WordComparer::new("LIMIT").with_whitespace_postfix(),
@@ -97,6 +145,17 @@ impl QueryComparers {
             not_in: WordComparer::new("NOT IN")
                 .with_whitespace_postfix()
                 .with_delimiter('('),
+            exists: WordComparer::new("EXISTS")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            not_exists: WordComparer::new("NOT EXISTS")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            not: WordComparer::new("NOT")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            between: WordComparer::new("BETWEEN").with_whitespace_postfix(),
+            not_between: WordComparer::new("NOT BETWEEN").with_whitespace_postfix(),
             b_true: WordComparer::new("TRUE")
                 .with_any_delimiter_postfix()
                 .with_eof(),
@@ -109,6 +168,70 @@ impl QueryComparers {
             param: WordComparer::new("?")
                 .with_any_delimiter_postfix()
                 .with_eof(),
+            insert_into: WordComparer::new("INSERT INTO").with_whitespace_postfix(),
+            values: WordComparer::new("VALUES")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            update: WordComparer::new("UPDATE").with_whitespace_postfix(),
+            set: WordComparer::new("SET").with_whitespace_postfix(),
+            delete_from: WordComparer::new("DELETE FROM").with_whitespace_postfix(),
+            returning: WordComparer::new("RETURNING").with_whitespace_postfix(),
+            on_conflict: WordComparer::new("ON CONFLICT")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            do_nothing: WordComparer::new("DO NOTHING")
+                .with_whitespace_postfix()
+                .with_eof(),
+            do_update: WordComparer::new("DO UPDATE")
+                .with_whitespace_postfix()
+                .with_eof(),
+            create_collection: WordComparer::new("CREATE COLLECTION").with_whitespace_postfix(),
+            drop_collection: WordComparer::new("DROP COLLECTION").with_whitespace_postfix(),
+            if_not_exists: WordComparer::new("IF NOT EXISTS").with_whitespace_postfix(),
+            if_exists: WordComparer::new("IF EXISTS").with_whitespace_postfix(),
+            union: WordComparer::new("UNION").with_whitespace_postfix(),
+            intersect: WordComparer::new("INTERSECT").with_whitespace_postfix(),
+            except: WordComparer::new("EXCEPT").with_whitespace_postfix(),
+            all: WordComparer::new("ALL").with_whitespace_postfix(),
+            with: WordComparer::new("WITH").with_whitespace_postfix(),
+            recursive: WordComparer::new("RECURSIVE").with_whitespace_postfix(),
+            case: WordComparer::new("CASE").with_whitespace_postfix(),
+            when: WordComparer::new("WHEN").with_whitespace_postfix(),
+            then: WordComparer::new("THEN").with_whitespace_postfix(),
+            r#else: WordComparer::new("ELSE").with_whitespace_postfix(),
+            end: WordComparer::new("END")
+                .with_any_delimiter_postfix()
+                .with_eof(),
+            over: WordComparer::new("OVER")
+                .with_whitespace_postfix()
+                .with_eof()
+                .with_delimiter('('),
+            partition_by: WordComparer::new("PARTITION BY").with_whitespace_postfix(),
+            rows: WordComparer::new("ROWS").with_whitespace_postfix(),
+            unbounded_preceding: WordComparer::new("UNBOUNDED PRECEDING")
+                .with_whitespace_postfix()
+                .with_delimiter(')'),
+            unbounded_following: WordComparer::new("UNBOUNDED FOLLOWING")
+                .with_whitespace_postfix()
+                .with_delimiter(')'),
+            current_row: WordComparer::new("CURRENT ROW")
+                .with_whitespace_postfix()
+                .with_delimiter(')'),
+            preceding: WordComparer::new("PRECEDING")
+                .with_whitespace_postfix()
+                .with_delimiter(')'),
+            following: WordComparer::new("FOLLOWING")
+                .with_whitespace_postfix()
+                .with_delimiter(')'),
+            rollup: WordComparer::new("ROLLUP")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            cube: WordComparer::new("CUBE")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
+            grouping_sets: WordComparer::new("GROUPING SETS")
+                .with_whitespace_postfix()
+                .with_delimiter('('),
         }
     }
 }
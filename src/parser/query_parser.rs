@@ -17,6 +17,8 @@ pub struct QueryParser {
     pub parentheses_depth: usize,
 
     pub comparers: QueryComparers,
+    /// named (`:name`) and numbered (`$1`) parameters seen so far, each once
+    pub parameters: Vec<String>,
 
     dbg: String,
 }
@@ -100,6 +102,16 @@ impl QueryParser {
             return true;
         }
 
+        if self.phase < Phase::Returning && self.comparers.returning.compare(self) {
+            self.phase = Phase::Returning;
+            return true;
+        }
+
+        if self.phase < Phase::OnConflict && self.comparers.on_conflict.compare(self) {
+            self.phase = Phase::OnConflict;
+            return true;
+        }
+
         if self.phase < Phase::LimitAndOffset
             && (self.comparers.limit.compare(self) || self.comparers.offset.compare(self))
         {
@@ -107,6 +119,15 @@ impl QueryParser {
             return true;
         }
 
+        if self.phase < Phase::SetOperation
+            && (self.comparers.union.compare(self)
+                || self.comparers.intersect.compare(self)
+                || self.comparers.except.compare(self))
+        {
+            self.phase = Phase::SetOperation;
+            return true;
+        }
+
         if self.phase < Phase::OrderBy && self.comparers.order_by.compare(self) {
             self.phase = Phase::OrderBy;
             return true;
@@ -132,7 +153,8 @@ impl QueryParser {
                 || self.comparers.inner_join.compare(self)
                 || self.comparers.left_join.compare(self)
                 || self.comparers.right_join.compare(self)
-                || self.comparers.full_join.compare(self))
+                || self.comparers.full_join.compare(self)
+                || self.comparers.cross_join.compare(self))
         {
             self.phase = Phase::Joins;
             return true;
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::parser::{
     analyzer::AggregateResolver,
-    ast::{Column, Function, Predicate, ScalarExpr},
+    ast::{Case, Column, Function, OrderBy, Predicate, ScalarExpr, Window},
 };
 
 /// A normalized aggregate call extracted from expressions.
@@ -28,6 +28,42 @@ impl AggregateCall {
         expr: &ScalarExpr,
         map: &HashMap<AggregateCall, String>,
     ) -> ScalarExpr {
+        Self::rewrite_scalar(expr, map, &HashMap::new())
+    }
+
+    pub fn rewrite_predicate_using_call_names(
+        predicate: &Predicate,
+        map: &HashMap<AggregateCall, String>,
+    ) -> Predicate {
+        Self::rewrite_predicate(predicate, map, &HashMap::new())
+    }
+
+    /// Rewrite an expression evaluated over `Aggregate` output rows: aggregate calls
+    /// and GROUP BY expressions (`groups`) become references to the names they are emitted under.
+    pub fn rewrite_scalar_for_aggregate_output(
+        expr: &ScalarExpr,
+        map: &HashMap<AggregateCall, String>,
+        groups: &HashMap<ScalarExpr, String>,
+    ) -> ScalarExpr {
+        Self::rewrite_scalar(expr, map, groups)
+    }
+
+    pub fn rewrite_predicate_for_aggregate_output(
+        predicate: &Predicate,
+        map: &HashMap<AggregateCall, String>,
+        groups: &HashMap<ScalarExpr, String>,
+    ) -> Predicate {
+        Self::rewrite_predicate(predicate, map, groups)
+    }
+    fn rewrite_scalar(
+        expr: &ScalarExpr,
+        map: &HashMap<AggregateCall, String>,
+        groups: &HashMap<ScalarExpr, String>,
+    ) -> ScalarExpr {
+        if let Some(name) = groups.get(expr) {
+            return ScalarExpr::Column(Column::Name { name: name.clone() });
+        }
+
         match expr {
             ScalarExpr::Function(f) if AggregateResolver::is_aggregate_name(&f.name) => {
                 let key: AggregateCall = f.into();
@@ -38,7 +74,7 @@ impl AggregateCall {
                 let new_args = f
                     .args
                     .iter()
-                    .map(|a| Self::rewrite_scalar_using_call_names(a, map))
+                    .map(|a| Self::rewrite_scalar(a, map, groups))
                     .collect();
                 ScalarExpr::Function(Function {
                     name: f.name.clone(),
@@ -46,32 +82,78 @@ impl AggregateCall {
                     distinct: f.distinct,
                 })
             }
+            ScalarExpr::Case(case) => ScalarExpr::Case(Case {
+                branches: case
+                    .branches
+                    .iter()
+                    .map(|(condition, result)| {
+                        (
+                            Self::rewrite_predicate(condition, map, groups),
+                            Self::rewrite_scalar(result, map, groups),
+                        )
+                    })
+                    .collect(),
+                otherwise: case
+                    .otherwise
+                    .as_deref()
+                    .map(|otherwise| Box::new(Self::rewrite_scalar(otherwise, map, groups))),
+            }),
+            ScalarExpr::Binary { left, op, right } => ScalarExpr::Binary {
+                left: Box::new(Self::rewrite_scalar(left, map, groups)),
+                op: *op,
+                right: Box::new(Self::rewrite_scalar(right, map, groups)),
+            },
+            ScalarExpr::Negate(expr) => {
+                ScalarExpr::Negate(Box::new(Self::rewrite_scalar(expr, map, groups)))
+            }
+            // the window function itself is not a grouping aggregate, its parts may hold some
+            ScalarExpr::Window(window) => {
+                let rewrite = |e: &ScalarExpr| Self::rewrite_scalar(e, map, groups);
+                ScalarExpr::Window(Window {
+                    function: Function {
+                        args: window.function.args.iter().map(rewrite).collect(),
+                        ..window.function.clone()
+                    },
+                    partition_by: window.partition_by.iter().map(rewrite).collect(),
+                    order_by: window
+                        .order_by
+                        .iter()
+                        .map(|ob| OrderBy {
+                            expr: rewrite(&ob.expr),
+                            ascending: ob.ascending,
+                        })
+                        .collect(),
+                    frame: window.frame,
+                })
+            }
             _ => expr.clone(),
         }
     }
 
-    pub fn rewrite_predicate_using_call_names(
+    fn rewrite_predicate(
         predicate: &Predicate,
         map: &HashMap<AggregateCall, String>,
+        groups: &HashMap<ScalarExpr, String>,
     ) -> Predicate {
         match predicate {
             Predicate::And(v) => Predicate::And(
                 v.iter()
-                    .map(|x| Self::rewrite_predicate_using_call_names(x, map))
+                    .map(|x| Self::rewrite_predicate(x, map, groups))
                     .collect(),
             ),
             Predicate::Or(v) => Predicate::Or(
                 v.iter()
-                    .map(|x| Self::rewrite_predicate_using_call_names(x, map))
+                    .map(|x| Self::rewrite_predicate(x, map, groups))
                     .collect(),
             ),
+            Predicate::Not(p) => Predicate::Not(Box::new(Self::rewrite_predicate(p, map, groups))),
             Predicate::Compare { left, op, right } => Predicate::Compare {
-                left: Self::rewrite_scalar_using_call_names(left, map),
+                left: Self::rewrite_scalar(left, map, groups),
                 op: *op,
-                right: Self::rewrite_scalar_using_call_names(right, map),
+                right: Self::rewrite_scalar(right, map, groups),
             },
             Predicate::IsNull { expr, negated } => Predicate::IsNull {
-                expr: Self::rewrite_scalar_using_call_names(expr, map),
+                expr: Self::rewrite_scalar(expr, map, groups),
                 negated: *negated,
             },
             Predicate::InList {
@@ -79,10 +161,10 @@ impl AggregateCall {
                 list,
                 negated,
             } => Predicate::InList {
-                expr: Self::rewrite_scalar_using_call_names(expr, map),
+                expr: Self::rewrite_scalar(expr, map, groups),
                 list: list
                     .iter()
-                    .map(|e| Self::rewrite_scalar_using_call_names(e, map))
+                    .map(|e| Self::rewrite_scalar(e, map, groups))
                     .collect(),
                 negated: *negated,
             },
@@ -91,10 +173,20 @@ impl AggregateCall {
                 pattern,
                 negated,
             } => Predicate::Like {
-                expr: Self::rewrite_scalar_using_call_names(expr, map),
-                pattern: Self::rewrite_scalar_using_call_names(pattern, map),
+                expr: Self::rewrite_scalar(expr, map, groups),
+                pattern: Self::rewrite_scalar(pattern, map, groups),
+                negated: *negated,
+            },
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Predicate::InSubquery {
+                expr: Self::rewrite_scalar(expr, map, groups),
+                subquery: subquery.clone(),
                 negated: *negated,
             },
+            Predicate::Exists { .. } => predicate.clone(),
             Predicate::Const3(t) => Predicate::Const3(*t),
         }
     }
@@ -1,6 +1,6 @@
 use crate::{
-    parser::ast::{Column, JoinType, OrderBy, Predicate},
-    planner::aggregate_call::AggregateCall,
+    parser::ast::{Column, JoinType, OrderBy, Predicate, ScalarExpr, SetOperator},
+    planner::{aggregate_call::AggregateCall, window_call::WindowCall},
 };
 
 #[derive(Debug, Clone)]
@@ -16,6 +16,28 @@ pub enum LogicalPlan {
         visible: String,
     },
 
+    /// Evaluate each WITH query once, in order, then run `input` over them.
+    With {
+        ctes: Vec<(String, LogicalPlan)>,
+        input: Box<LogicalPlan>,
+    },
+
+    /// WITH RECURSIVE fixpoint: run `anchor`, then re-run `step` over the rows
+    /// added by the previous round (visible as `name`) until it adds none.
+    RecursiveCte {
+        name: String,
+        anchor: Box<LogicalPlan>,
+        step: Box<LogicalPlan>,
+        all: bool, // UNION ALL keeps duplicates, UNION stops on rows already produced
+    },
+
+    /// Read the rows of a WITH query evaluated by an enclosing `With`.
+    CteScan {
+        name: String,
+        visible: String,
+        columns: Vec<String>, // output names, used for null-extension in joins
+    },
+
     Join {
         left: Box<LogicalPlan>,
         right: Box<LogicalPlan>,
@@ -23,17 +45,35 @@ pub enum LogicalPlan {
         on: Predicate, // already qualified + folded
     },
 
+    /// One row per element of the array `expr` evaluated on each input row, with
+    /// the element under `visible.column`; rows without elements are dropped.
+    Unnest {
+        input: Box<LogicalPlan>,
+        expr: ScalarExpr, // qualified + folded
+        visible: String,
+        column: String,
+    },
+
     /// Row-level filter (WHERE or HAVING depending on position in the tree).
     Filter {
         input: Box<LogicalPlan>,
         predicate: crate::parser::ast::Predicate,
     },
 
-    /// Group-by aggregation.
+    /// Group-by aggregation, once per grouping set when there are any; keys a set
+    /// leaves out are NULL in its rows.
     Aggregate {
         input: Box<LogicalPlan>,
-        group_keys: Vec<Column>,  // qualified
-        aggs: Vec<AggregateCall>, // aggregate calls we’ll compute
+        group_keys: Vec<ScalarExpr>,    // qualified
+        grouping_sets: Vec<Vec<usize>>, // indexes into group_keys; empty groups by all of them
+        aggs: Vec<AggregateCall>,       // aggregate calls we’ll compute
+    },
+
+    /// Window functions: each input row gains one column per window, under its
+    /// name; the rows keep their order.
+    Window {
+        input: Box<LogicalPlan>,
+        windows: Vec<WindowCall>,
     },
 
     /// Projection in SELECT order (qualified & folded).
@@ -42,6 +82,17 @@ pub enum LogicalPlan {
         exprs: Vec<crate::parser::analyzer::AnalyzedIdentifier>,
     },
 
+    /// SELECT DISTINCT: keep the first of each set of equal projected rows.
+    Distinct { input: Box<LogicalPlan> },
+
+    /// UNION / INTERSECT / EXCEPT over projected rows with the same output names.
+    SetOperation {
+        left: Box<LogicalPlan>,
+        right: Box<LogicalPlan>,
+        op: SetOperator,
+        all: bool,
+    },
+
     /// Stable sort with NULLS LAST policy (enforced in executor).
     Sort {
         input: Box<LogicalPlan>,
@@ -55,3 +106,17 @@ pub enum LogicalPlan {
         offset: Option<i64>,
     },
 }
+
+impl LogicalPlan {
+    /// Key under which `Aggregate` emits a group value: `collection.name` for a
+    /// column, the expression text for anything else.
+    pub fn group_key_name(expr: &ScalarExpr) -> String {
+        match expr {
+            ScalarExpr::Column(Column::WithCollection { collection, name }) => {
+                format!("{}.{}", collection, name)
+            }
+            ScalarExpr::Column(Column::Name { name }) => name.clone(),
+            other => other.to_string(),
+        }
+    }
+}
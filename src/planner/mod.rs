@@ -1,3 +1,4 @@
 pub mod aggregate_call;
 pub mod logical_plan;
 pub mod plan_builder;
+pub mod window_call;
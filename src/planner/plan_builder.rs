@@ -5,15 +5,23 @@ use crate::{
         analyzer::{
             AggregateResolver, AnalyzedIdentifier, AnalyzedQuery, AnalyzedSource, AnalyzerError,
         },
-        ast::{Column, JoinType, OrderBy, Predicate, ScalarExpr, Truth},
+        ast::{Column, JoinType, OrderBy, Predicate, ScalarExpr, SetOperator, Truth},
     },
-    planner::{aggregate_call::AggregateCall, logical_plan::LogicalPlan},
+    planner::{aggregate_call::AggregateCall, logical_plan::LogicalPlan, window_call::WindowCall},
 };
 
 pub struct PlanBuilder;
 
 impl PlanBuilder {
     pub fn from_analyzed(aq: &AnalyzedQuery) -> Result<LogicalPlan, AnalyzerError> {
+        if !aq.ctes.is_empty() {
+            return Self::plan_with(aq);
+        }
+
+        if !aq.set_operations.is_empty() {
+            return Self::plan_compound(aq);
+        }
+
         // Source: single collection only (joins later) ----
         if aq.collections.is_empty() {
             return Err(AnalyzerError::Other(
@@ -25,25 +33,19 @@ impl PlanBuilder {
         // --- support implicit CROSS JOINs for multiple FROM items (A, B, C, ...) ---
         if aq.collections.len() > 1 {
             for source in aq.collections.iter().skip(1) {
-                let right = Self::plan_source(source)?;
-                from = LogicalPlan::Join {
-                    left: Box::new(from),
-                    right: Box::new(right),
-                    join_type: JoinType::Inner, // CROSS JOIN semantics
-                    on: Predicate::Const3(Truth::True),
-                };
+                from = Self::join_source(
+                    from,
+                    source,
+                    JoinType::Inner, // CROSS JOIN semantics
+                    Predicate::Const3(Truth::True),
+                )?;
             }
         }
 
         // Apply explicit JOINs in order they appear ----
         for j in &aq.joins {
-            let right = Self::plan_source(&j.source)?;
-            from = LogicalPlan::Join {
-                left: Box::new(from),
-                right: Box::new(right),
-                join_type: j.join_type.clone(),
-                on: j.predicate.clone(), // already qualified + folded
-            };
+            // predicate is already qualified + folded
+            from = Self::join_source(from, &j.source, j.join_type.clone(), j.predicate.clone())?;
         }
 
         let mut plan = from;
@@ -98,13 +100,12 @@ impl PlanBuilder {
             let mut name_map: HashMap<AggregateCall, String> = HashMap::new();
 
             // reserve group-by key names (the aggregate node emits them with these keys)
-            for c in &aq.group_by {
-                let key = match c {
-                    Column::WithCollection { collection, name } => {
-                        format!("{}.{}", collection, name)
-                    }
-                    Column::Name { name } => name.clone(),
-                };
+            let mut group_names: HashMap<ScalarExpr, String> = HashMap::new();
+            for expr in &aq.group_by {
+                let key = LogicalPlan::group_key_name(expr);
+                if !matches!(expr, ScalarExpr::Column(_)) {
+                    group_names.insert(expr.clone(), key.clone());
+                }
                 used_names.insert(key);
             }
 
@@ -122,12 +123,15 @@ impl PlanBuilder {
             }
 
             // ---- rewrite SELECT and HAVING to reference aggregate internal names ----
-            let rewritten_projection: Vec<AnalyzedIdentifier> = aq
+            let mut rewritten_projection: Vec<AnalyzedIdentifier> = aq
                 .projection
                 .iter()
                 .map(|id| {
-                    let new_expr =
-                        AggregateCall::rewrite_scalar_using_call_names(&id.expression, &name_map);
+                    let new_expr = AggregateCall::rewrite_scalar_for_aggregate_output(
+                        &id.expression,
+                        &name_map,
+                        &group_names,
+                    );
                     AnalyzedIdentifier {
                         expression: new_expr,
                         alias: id.alias.clone(),
@@ -138,21 +142,22 @@ impl PlanBuilder {
                 })
                 .collect();
 
-            let rewritten_having: Option<Predicate> = aq
-                .having
-                .as_ref()
-                .map(|p| AggregateCall::rewrite_predicate_using_call_names(p, &name_map));
+            let rewritten_having: Option<Predicate> = aq.having.as_ref().map(|p| {
+                AggregateCall::rewrite_predicate_for_aggregate_output(p, &name_map, &group_names)
+            });
 
-            let rewritten_order_by = Self::rewrite_order_by_for_aggregate_input(
+            let mut rewritten_order_by = Self::rewrite_order_by_for_aggregate_input(
                 &aq.order_by,
                 &rewritten_projection,
                 &name_map,
+                &group_names,
             );
 
             // ---- build Aggregate node ----
             plan = LogicalPlan::Aggregate {
                 input: Box::new(plan),
                 group_keys: aq.group_by.clone(),
+                grouping_sets: aq.grouping_sets.clone(),
                 aggs: calls,
             };
 
@@ -164,6 +169,14 @@ impl PlanBuilder {
                 };
             }
 
+            // window functions run over the grouped rows
+            plan = Self::plan_windows(
+                plan,
+                &mut rewritten_projection,
+                &mut rewritten_order_by,
+                &used_names,
+            );
+
             // ORDER BY before Project so hidden aggregate sort keys remain available.
             if !rewritten_order_by.is_empty() {
                 plan = LogicalPlan::Sort {
@@ -178,18 +191,29 @@ impl PlanBuilder {
                 exprs: rewritten_projection,
             };
         } else {
+            let mut projection = aq.projection.clone();
+            let mut order_by = aq.order_by.clone();
+            plan = Self::plan_windows(plan, &mut projection, &mut order_by, &HashSet::new());
+
             // ORDER BY before Project so valid non-projected sort keys are still available.
-            if !aq.order_by.is_empty() {
+            if !order_by.is_empty() {
                 plan = LogicalPlan::Sort {
                     input: Box::new(plan),
-                    keys: aq.order_by.clone(),
+                    keys: order_by,
                 };
             }
 
             // Project (no aggregate)
             plan = LogicalPlan::Project {
                 input: Box::new(plan),
-                exprs: aq.projection.clone(),
+                exprs: projection,
+            };
+        }
+
+        // DISTINCT after Project (dedup output rows) and before LIMIT ----
+        if aq.distinct {
+            plan = LogicalPlan::Distinct {
+                input: Box::new(plan),
             };
         }
 
@@ -205,6 +229,183 @@ impl PlanBuilder {
         Ok(plan)
     }
 
+    /// Plan the WITH queries ahead of the query body that reads them.
+    fn plan_with(aq: &AnalyzedQuery) -> Result<LogicalPlan, AnalyzerError> {
+        let mut ctes = Vec::with_capacity(aq.ctes.len());
+        for cte in &aq.ctes {
+            let anchor = Self::from_analyzed(&cte.query)?;
+            let anchor = Self::rename_outputs(anchor, &cte.query.projection, &cte.columns);
+            if cte.recursive_terms.is_empty() {
+                ctes.push((cte.name.clone(), anchor));
+                continue;
+            }
+
+            let mut terms = cte.recursive_terms.iter();
+            let first = terms.next().expect("recursive CTE has a term");
+            let mut step = Self::from_analyzed(&first.query)?;
+            for term in terms {
+                step = LogicalPlan::SetOperation {
+                    left: Box::new(step),
+                    right: Box::new(Self::from_analyzed(&term.query)?),
+                    op: SetOperator::Union,
+                    all: true,
+                };
+            }
+            let step = Self::rename_outputs(step, &cte.query.projection, &cte.columns);
+
+            ctes.push((
+                cte.name.clone(),
+                LogicalPlan::RecursiveCte {
+                    name: cte.name.clone(),
+                    anchor: Box::new(anchor),
+                    step: Box::new(step),
+                    all: first.all,
+                },
+            ));
+        }
+
+        let body = AnalyzedQuery {
+            ctes: vec![],
+            ..aq.clone()
+        };
+        Ok(LogicalPlan::With {
+            ctes,
+            input: Box::new(Self::from_analyzed(&body)?),
+        })
+    }
+
+    /// WITH name (a, b) AS (...) renames the projected columns.
+    fn rename_outputs(
+        plan: LogicalPlan,
+        projection: &[AnalyzedIdentifier],
+        columns: &[String],
+    ) -> LogicalPlan {
+        let renamed = projection
+            .iter()
+            .zip(columns)
+            .any(|(id, column)| &id.output_name != column);
+        if !renamed {
+            return plan;
+        }
+
+        let exprs = projection
+            .iter()
+            .zip(columns)
+            .map(|(id, column)| AnalyzedIdentifier {
+                expression: ScalarExpr::Column(Column::Name {
+                    name: id.output_name.clone(),
+                }),
+                alias: Some(column.clone()),
+                output_name: column.clone(),
+                ..id.clone()
+            })
+            .collect();
+        LogicalPlan::Project {
+            input: Box::new(plan),
+            exprs,
+        }
+    }
+
+    /// Plan `block (op block)*`, then ORDER BY/LIMIT over the combined rows.
+    fn plan_compound(aq: &AnalyzedQuery) -> Result<LogicalPlan, AnalyzerError> {
+        let first = AnalyzedQuery {
+            set_operations: vec![],
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            ..aq.clone()
+        };
+
+        // INTERSECT binds tighter than UNION and EXCEPT
+        let mut terms = vec![Self::from_analyzed(&first)?];
+        let mut ops = Vec::new();
+        for operation in &aq.set_operations {
+            let right = Self::from_analyzed(&operation.query)?;
+            if operation.op == SetOperator::Intersect {
+                let left = terms.pop().expect("compound has a first block");
+                terms.push(LogicalPlan::SetOperation {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    op: operation.op,
+                    all: operation.all,
+                });
+            } else {
+                ops.push((operation.op, operation.all));
+                terms.push(right);
+            }
+        }
+
+        let mut terms = terms.into_iter();
+        let mut plan = terms.next().expect("compound has a first block");
+        for ((op, all), right) in ops.into_iter().zip(terms) {
+            plan = LogicalPlan::SetOperation {
+                left: Box::new(plan),
+                right: Box::new(right),
+                op,
+                all,
+            };
+        }
+
+        // keys name output columns, so Sort runs over the combined projected rows
+        if !aq.order_by.is_empty() {
+            plan = LogicalPlan::Sort {
+                input: Box::new(plan),
+                keys: aq.order_by.clone(),
+            };
+        }
+
+        if aq.limit.is_some() || aq.offset.is_some() {
+            plan = LogicalPlan::Limit {
+                input: Box::new(plan),
+                limit: aq.limit,
+                offset: aq.offset,
+            };
+        }
+
+        Ok(plan)
+    }
+
+    /// Join `source` to the rows planned so far; UNNEST expands them instead.
+    fn join_source(
+        from: LogicalPlan,
+        source: &AnalyzedSource,
+        join_type: JoinType,
+        on: Predicate,
+    ) -> Result<LogicalPlan, AnalyzerError> {
+        if let AnalyzedSource::Unnest {
+            visible,
+            expr,
+            column,
+        } = source
+        {
+            if join_type != JoinType::Inner {
+                return Err(AnalyzerError::Other(
+                    "Planner: UNNEST only supports CROSS and inner joins".into(),
+                ));
+            }
+            let plan = LogicalPlan::Unnest {
+                input: Box::new(from),
+                expr: expr.clone(),
+                visible: visible.clone(),
+                column: column.clone(),
+            };
+            return Ok(match on {
+                Predicate::Const3(Truth::True) => plan,
+                predicate => LogicalPlan::Filter {
+                    input: Box::new(plan),
+                    predicate,
+                },
+            });
+        }
+
+        Ok(LogicalPlan::Join {
+            left: Box::new(from),
+            right: Box::new(Self::plan_source(source)?),
+            join_type,
+            on,
+        })
+    }
+
     fn plan_source(source: &AnalyzedSource) -> Result<LogicalPlan, AnalyzerError> {
         match source {
             AnalyzedSource::Table { visible, backing } => Ok(LogicalPlan::Scan {
@@ -218,6 +419,66 @@ impl PlanBuilder {
                     visible: visible.clone(),
                 })
             }
+            AnalyzedSource::Cte {
+                visible,
+                name,
+                columns,
+            } => Ok(LogicalPlan::CteScan {
+                name: name.clone(),
+                visible: visible.clone(),
+                columns: columns.clone(),
+            }),
+            AnalyzedSource::Unnest { .. } => Err(AnalyzerError::Other(
+                "Planner: UNNEST must follow the collection it reads from".into(),
+            )),
+        }
+    }
+
+    /// Compute the windows of the SELECT list and ORDER BY in a `Window` node over
+    /// `plan`, and point both at the columns it adds; names avoid `used_names`.
+    fn plan_windows(
+        plan: LogicalPlan,
+        projection: &mut [AnalyzedIdentifier],
+        order_by: &mut [OrderBy],
+        used_names: &HashSet<String>,
+    ) -> LogicalPlan {
+        let mut windows = Vec::new();
+        for id in projection.iter() {
+            WindowCall::collect_in_scalar(&id.expression, &mut windows);
+        }
+        for ob in order_by.iter() {
+            WindowCall::collect_in_scalar(&ob.expr, &mut windows);
+        }
+        if windows.is_empty() {
+            return plan;
+        }
+
+        // base = "<func>_over"; suffix _1, _2… if repeated
+        let mut used = used_names.clone();
+        let mut names = HashMap::new();
+        let mut calls = Vec::with_capacity(windows.len());
+        for window in windows {
+            let base = format!("{}_over", window.function.name.to_ascii_lowercase());
+            let mut name = base.clone();
+            let mut k = 1usize;
+            while used.contains(&name) {
+                name = format!("{}_{}", base, k);
+                k += 1;
+            }
+            used.insert(name.clone());
+            names.insert(window.clone(), name.clone());
+            calls.push(WindowCall { name, window });
+        }
+
+        for id in projection.iter_mut() {
+            id.expression = WindowCall::rewrite_scalar(&id.expression, &names);
+        }
+        for ob in order_by.iter_mut() {
+            ob.expr = WindowCall::rewrite_scalar(&ob.expr, &names);
+        }
+        LogicalPlan::Window {
+            input: Box::new(plan),
+            windows: calls,
         }
     }
 
@@ -226,18 +487,45 @@ impl PlanBuilder {
         table: &mut HashMap<AggregateCall, usize>,
         calls: &mut Vec<AggregateCall>,
     ) {
-        if let ScalarExpr::Function(f) = e {
-            if AggregateResolver::is_aggregate_name(&f.name) {
+        match e {
+            ScalarExpr::Function(f) if AggregateResolver::is_aggregate_name(&f.name) => {
                 let key: AggregateCall = f.into();
                 if !table.contains_key(&key) {
                     table.insert(key.clone(), calls.len());
                     calls.push(key);
                 }
-            } else {
+            }
+            ScalarExpr::Function(f) => {
                 for a in &f.args {
                     Self::collect_aggregates_in_scalar(a, table, calls);
                 }
             }
+            ScalarExpr::Case(case) => {
+                for (condition, result) in &case.branches {
+                    Self::collect_aggregates_in_predicate(condition, table, calls);
+                    Self::collect_aggregates_in_scalar(result, table, calls);
+                }
+                if let Some(otherwise) = &case.otherwise {
+                    Self::collect_aggregates_in_scalar(otherwise, table, calls);
+                }
+            }
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::collect_aggregates_in_scalar(left, table, calls);
+                Self::collect_aggregates_in_scalar(right, table, calls);
+            }
+            ScalarExpr::Negate(expr) => Self::collect_aggregates_in_scalar(expr, table, calls),
+            ScalarExpr::Window(window) => {
+                for e in window
+                    .function
+                    .args
+                    .iter()
+                    .chain(&window.partition_by)
+                    .chain(window.order_by.iter().map(|ob| &ob.expr))
+                {
+                    Self::collect_aggregates_in_scalar(e, table, calls);
+                }
+            }
+            _ => {}
         }
     }
 
@@ -253,11 +541,12 @@ impl PlanBuilder {
                     Self::collect_aggregates_in_predicate(x, table, calls);
                 }
             }
+            Predicate::Not(p) => Self::collect_aggregates_in_predicate(p, table, calls),
             Predicate::Compare { left, right, .. } => {
                 Self::collect_aggregates_in_scalar(left, table, calls);
                 Self::collect_aggregates_in_scalar(right, table, calls);
             }
-            Predicate::IsNull { expr, .. } => {
+            Predicate::IsNull { expr, .. } | Predicate::InSubquery { expr, .. } => {
                 Self::collect_aggregates_in_scalar(expr, table, calls);
             }
             Predicate::InList { expr, list, .. } => {
@@ -270,7 +559,7 @@ impl PlanBuilder {
                 Self::collect_aggregates_in_scalar(expr, table, calls);
                 Self::collect_aggregates_in_scalar(pattern, table, calls);
             }
-            Predicate::Const3(_) => {}
+            Predicate::Exists { .. } | Predicate::Const3(_) => {}
         }
     }
 
@@ -278,6 +567,7 @@ impl PlanBuilder {
         order_bys: &[OrderBy],
         projection: &[AnalyzedIdentifier],
         name_map: &HashMap<AggregateCall, String>,
+        group_names: &HashMap<ScalarExpr, String>,
     ) -> Vec<OrderBy> {
         use crate::parser::ast::{Column, Literal, ScalarExpr};
 
@@ -312,7 +602,11 @@ impl PlanBuilder {
                 }
 
                 OrderBy {
-                    expr: AggregateCall::rewrite_scalar_using_call_names(&ob.expr, name_map),
+                    expr: AggregateCall::rewrite_scalar_for_aggregate_output(
+                        &ob.expr,
+                        name_map,
+                        group_names,
+                    ),
                     ascending: ob.ascending,
                 }
             })
@@ -372,6 +666,8 @@ mod tests {
     #[test]
     fn plan_for_simple_select_where_order_limit() {
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![id_col_t("id")],
             collections: vec![table_source("t")],
             joins: vec![],
@@ -381,6 +677,7 @@ mod tests {
                 right: ScalarExpr::Literal(Literal::Int(10)),
             }),
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![OrderBy {
                 expr: ScalarExpr::Column(col_t("id")),
@@ -388,6 +685,7 @@ mod tests {
             }],
             limit: Some(5),
             offset: Some(10),
+            set_operations: vec![],
         };
 
         let plan = PlanBuilder::from_analyzed(&aq).expect("plan");
@@ -430,9 +728,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn plan_for_select_distinct_dedups_after_project_before_limit() {
+        let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: true,
+            projection: vec![id_col_t("id")],
+            collections: vec![table_source("t")],
+            joins: vec![],
+            criteria: None,
+            group_by: vec![],
+            grouping_sets: vec![],
+            having: None,
+            order_by: vec![OrderBy {
+                expr: ScalarExpr::Column(col_t("id")),
+                ascending: true,
+            }],
+            limit: Some(5),
+            offset: None,
+            set_operations: vec![],
+        };
+
+        let plan = PlanBuilder::from_analyzed(&aq).expect("plan");
+        match plan {
+            LogicalPlan::Limit { input, .. } => match *input {
+                LogicalPlan::Distinct { input } => match *input {
+                    LogicalPlan::Project { input, .. } => {
+                        assert!(matches!(*input, LogicalPlan::Sort { .. }));
+                    }
+                    other => panic!("expected Project, got {other:?}"),
+                },
+                other => panic!("expected Distinct, got {other:?}"),
+            },
+            other => panic!("expected Limit root, got {other:?}"),
+        }
+    }
+
     #[test]
     fn plan_for_group_by_aggregate_and_having() {
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![
                 // SELECT t.category, SUM(t.amount) AS sum
                 AnalyzedIdentifier {
@@ -447,7 +783,8 @@ mod tests {
             collections: vec![table_source("t")],
             joins: vec![],
             criteria: None,
-            group_by: vec![col_t("category")],
+            group_by: vec![ScalarExpr::Column(col_t("category"))],
+            grouping_sets: vec![],
             having: Some(Predicate::Compare {
                 left: ScalarExpr::Function(Function {
                     name: "sum".into(),
@@ -460,6 +797,7 @@ mod tests {
             order_by: vec![],
             limit: None,
             offset: None,
+            set_operations: vec![],
         };
 
         let plan = PlanBuilder::from_analyzed(&aq).expect("plan");
@@ -471,8 +809,10 @@ mod tests {
                         LogicalPlan::Aggregate {
                             input,
                             group_keys,
+                            grouping_sets,
                             aggs,
                         } => {
+                            assert!(grouping_sets.is_empty());
                             assert_eq!(group_keys.len(), 1);
                             assert_eq!(aggs.len(), 1);
                             assert_eq!(aggs[0].func, "sum");
@@ -496,6 +836,8 @@ mod tests {
     fn planner_builds_cross_join_for_multiple_from_items() {
         // FROM a, b  (no explicit JOINs) → CROSS JOIN
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![AnalyzedIdentifier {
                 // any proj is fine; planner doesn't validate here
                 expression: ScalarExpr::Column(Column::WithCollection {
@@ -511,10 +853,12 @@ mod tests {
             joins: vec![],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             limit: None,
             offset: None,
+            set_operations: vec![],
         };
 
         let plan = PlanBuilder::from_analyzed(&aq).expect("planner should support FROM a, b");
@@ -565,6 +909,8 @@ mod tests {
     fn planner_no_longer_rejects_multiple_collections_without_joins() {
         // FROM a, b, c should be accepted and chained as CROSS JOINs
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![AnalyzedIdentifier {
                 expression: ScalarExpr::Column(Column::WithCollection {
                     collection: "a".into(),
@@ -579,10 +925,12 @@ mod tests {
             joins: vec![],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             limit: None,
             offset: None,
+            set_operations: vec![],
         };
 
         let plan = PlanBuilder::from_analyzed(&aq)
@@ -802,7 +1150,7 @@ mod join_shape_tests {
     use super::*;
     use crate::parser::analyzer::{AnalyzedIdentifier, AnalyzedJoin, AnalyzedSource};
     use crate::parser::ast::{
-        Column, ComparatorOp, JoinType, Literal, OrderBy, Predicate, ScalarExpr,
+        Column, ComparatorOp, JoinType, Literal, OrderBy, Predicate, ScalarExpr, Truth,
     };
     use crate::{Db, DbConfig, IdType, JsonPrimitive};
 
@@ -860,6 +1208,8 @@ mod join_shape_tests {
     #[test]
     fn plan_for_inner_join_then_where() {
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![
                 id_col("a", "id", JsonPrimitive::Int),
                 id_col("b", "name", JsonPrimitive::String),
@@ -871,10 +1221,12 @@ mod join_shape_tests {
                 right: ScalarExpr::Literal(Literal::Int(18)),
             }),
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![],
             limit: None,
             offset: None,
+            set_operations: vec![],
             joins: vec![analyzed_join(
                 JoinType::Inner,
                 table_source("b"),
@@ -924,13 +1276,68 @@ mod join_shape_tests {
         }
     }
 
+    #[test]
+    fn plan_for_unnest_expands_the_rows_before_it() {
+        let unnest = AnalyzedSource::Unnest {
+            visible: "t".into(),
+            expr: ScalarExpr::Column(col("p", "tags")),
+            column: "tag".into(),
+        };
+        let mut aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
+            projection: vec![id_col("t", "tag", JsonPrimitive::Any)],
+            collections: vec![table_source("p")],
+            criteria: None,
+            group_by: vec![],
+            grouping_sets: vec![],
+            having: None,
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            set_operations: vec![],
+            joins: vec![analyzed_join(
+                JoinType::Inner,
+                unnest.clone(),
+                Predicate::Const3(Truth::True),
+            )],
+        };
+
+        let plan = PlanBuilder::from_analyzed(&aq).expect("plan");
+        match plan {
+            LogicalPlan::Project { input, .. } => match *input {
+                LogicalPlan::Unnest {
+                    input,
+                    visible,
+                    column,
+                    ..
+                } => {
+                    assert_eq!((visible.as_str(), column.as_str()), ("t", "tag"));
+                    assert!(matches!(*input, LogicalPlan::Scan { .. }));
+                }
+                other => panic!("expected Unnest under Project, got {other:?}"),
+            },
+            other => panic!("expected Project root, got {other:?}"),
+        }
+
+        aq.joins[0].join_type = JoinType::Left;
+        assert!(PlanBuilder::from_analyzed(&aq).is_err());
+
+        aq.joins.clear();
+        aq.collections = vec![unnest];
+        assert!(PlanBuilder::from_analyzed(&aq).is_err());
+    }
+
     #[test]
     fn plan_for_left_join_chain_and_order_limit() {
         let aq = AnalyzedQuery {
+            ctes: vec![],
+            distinct: false,
             projection: vec![id_col("a", "id", JsonPrimitive::Int)],
             collections: vec![table_source("a")],
             criteria: None,
             group_by: vec![],
+            grouping_sets: vec![],
             having: None,
             order_by: vec![OrderBy {
                 expr: ScalarExpr::Column(col("a", "id")),
@@ -938,6 +1345,7 @@ mod join_shape_tests {
             }],
             limit: Some(10),
             offset: None,
+            set_operations: vec![],
             joins: vec![
                 analyzed_join(
                     JoinType::Left,
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use crate::parser::ast::{Case, Column, Function, Predicate, ScalarExpr, Window};
+
+/// A window the `Window` plan node computes, emitted under `name`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WindowCall {
+    pub name: String,
+    pub window: Window, // analyzed, qualified & folded
+}
+
+impl WindowCall {
+    /// Windows in `expr`, in order of first appearance and without duplicates.
+    /// Windows never nest and the ones inside subqueries belong to the subquery.
+    pub fn collect_in_scalar(expr: &ScalarExpr, windows: &mut Vec<Window>) {
+        match expr {
+            ScalarExpr::Window(window) if !windows.contains(window) => {
+                windows.push(window.clone());
+            }
+            ScalarExpr::Function(f) => {
+                for a in &f.args {
+                    Self::collect_in_scalar(a, windows);
+                }
+            }
+            ScalarExpr::Case(case) => {
+                for (condition, result) in &case.branches {
+                    Self::collect_in_predicate(condition, windows);
+                    Self::collect_in_scalar(result, windows);
+                }
+                if let Some(otherwise) = &case.otherwise {
+                    Self::collect_in_scalar(otherwise, windows);
+                }
+            }
+            ScalarExpr::Binary { left, right, .. } => {
+                Self::collect_in_scalar(left, windows);
+                Self::collect_in_scalar(right, windows);
+            }
+            ScalarExpr::Negate(expr) => Self::collect_in_scalar(expr, windows),
+            _ => {}
+        }
+    }
+
+    fn collect_in_predicate(predicate: &Predicate, windows: &mut Vec<Window>) {
+        match predicate {
+            Predicate::And(v) | Predicate::Or(v) => {
+                for p in v {
+                    Self::collect_in_predicate(p, windows);
+                }
+            }
+            Predicate::Not(p) => Self::collect_in_predicate(p, windows),
+            Predicate::Compare { left, right, .. } => {
+                Self::collect_in_scalar(left, windows);
+                Self::collect_in_scalar(right, windows);
+            }
+            Predicate::IsNull { expr, .. } | Predicate::InSubquery { expr, .. } => {
+                Self::collect_in_scalar(expr, windows);
+            }
+            Predicate::InList { expr, list, .. } => {
+                Self::collect_in_scalar(expr, windows);
+                for e in list {
+                    Self::collect_in_scalar(e, windows);
+                }
+            }
+            Predicate::Like { expr, pattern, .. } => {
+                Self::collect_in_scalar(expr, windows);
+                Self::collect_in_scalar(pattern, windows);
+            }
+            Predicate::Exists { .. } | Predicate::Const3(_) => {}
+        }
+    }
+
+    /// Rewrite an expression evaluated over `Window` output rows: each window
+    /// becomes a reference to the name it is emitted under.
+    pub fn rewrite_scalar(expr: &ScalarExpr, names: &HashMap<Window, String>) -> ScalarExpr {
+        match expr {
+            ScalarExpr::Window(window) => {
+                let name = names.get(window).expect("window must be named");
+                ScalarExpr::Column(Column::Name { name: name.clone() })
+            }
+            ScalarExpr::Function(f) => ScalarExpr::Function(Function {
+                args: f
+                    .args
+                    .iter()
+                    .map(|a| Self::rewrite_scalar(a, names))
+                    .collect(),
+                ..f.clone()
+            }),
+            ScalarExpr::Case(case) => ScalarExpr::Case(Case {
+                branches: case
+                    .branches
+                    .iter()
+                    .map(|(condition, result)| {
+                        (
+                            Self::rewrite_predicate(condition, names),
+                            Self::rewrite_scalar(result, names),
+                        )
+                    })
+                    .collect(),
+                otherwise: case
+                    .otherwise
+                    .as_deref()
+                    .map(|otherwise| Box::new(Self::rewrite_scalar(otherwise, names))),
+            }),
+            ScalarExpr::Binary { left, op, right } => ScalarExpr::Binary {
+                left: Box::new(Self::rewrite_scalar(left, names)),
+                op: *op,
+                right: Box::new(Self::rewrite_scalar(right, names)),
+            },
+            ScalarExpr::Negate(expr) => {
+                ScalarExpr::Negate(Box::new(Self::rewrite_scalar(expr, names)))
+            }
+            _ => expr.clone(),
+        }
+    }
+
+    fn rewrite_predicate(predicate: &Predicate, names: &HashMap<Window, String>) -> Predicate {
+        let scalar = |e: &ScalarExpr| Self::rewrite_scalar(e, names);
+        match predicate {
+            Predicate::And(v) => Predicate::And(
+                v.iter()
+                    .map(|p| Self::rewrite_predicate(p, names))
+                    .collect(),
+            ),
+            Predicate::Or(v) => Predicate::Or(
+                v.iter()
+                    .map(|p| Self::rewrite_predicate(p, names))
+                    .collect(),
+            ),
+            Predicate::Not(p) => Predicate::Not(Box::new(Self::rewrite_predicate(p, names))),
+            Predicate::Compare { left, op, right } => Predicate::Compare {
+                left: scalar(left),
+                op: *op,
+                right: scalar(right),
+            },
+            Predicate::IsNull { expr, negated } => Predicate::IsNull {
+                expr: scalar(expr),
+                negated: *negated,
+            },
+            Predicate::InList {
+                expr,
+                list,
+                negated,
+            } => Predicate::InList {
+                expr: scalar(expr),
+                list: list.iter().map(scalar).collect(),
+                negated: *negated,
+            },
+            Predicate::Like {
+                expr,
+                pattern,
+                negated,
+            } => Predicate::Like {
+                expr: scalar(expr),
+                pattern: scalar(pattern),
+                negated: *negated,
+            },
+            Predicate::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Predicate::InSubquery {
+                expr: scalar(expr),
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            Predicate::Exists { .. } | Predicate::Const3(_) => predicate.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        parser::{
+            QueryParser,
+            ast::{Column, ScalarExpr},
+        },
+        planner::window_call::WindowCall,
+    };
+
+    fn parse(text: &str) -> ScalarExpr {
+        ScalarExpr::parse(&mut QueryParser::new(text), false).expect("should parse")
+    }
+
+    #[test]
+    fn collects_each_window_once_and_rewrites_it_to_its_name() {
+        let expr = parse(
+            "CASE WHEN rank() OVER (ORDER BY x) = 1 THEN 0 ELSE rank() OVER (ORDER BY x) + lag(x) OVER () END",
+        );
+        let mut windows = vec![];
+        WindowCall::collect_in_scalar(&expr, &mut windows);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].function.name, "rank");
+        assert_eq!(windows[1].function.name, "lag");
+
+        let names = HashMap::from([
+            (windows[0].clone(), "rank_over".to_string()),
+            (windows[1].clone(), "lag_over".to_string()),
+        ]);
+        let rewritten = WindowCall::rewrite_scalar(&expr, &names);
+        let mut left = vec![];
+        WindowCall::collect_in_scalar(&rewritten, &mut left);
+        assert!(left.is_empty());
+        assert_eq!(
+            WindowCall::rewrite_scalar(&parse("lag(x) OVER ()"), &names),
+            ScalarExpr::Column(Column::Name {
+                name: "lag_over".into()
+            })
+        );
+    }
+}